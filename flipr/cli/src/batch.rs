@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use flipr::CancellationToken;
+
+use crate::ops::Op;
+use crate::process;
+
+/// One finished file, passed to the progress callback of [`BatchProcessor::run`].
+#[derive(Debug, Clone, Copy)]
+pub struct Progress<'a> {
+    /// Files finished so far, this one included.
+    pub done: usize,
+    pub total: usize,
+    /// Input file, relative to the input directory.
+    pub path: &'a Path,
+}
+
+/// Outcome of [`BatchProcessor::run`], with paths relative to the input
+/// directory in the order they finished.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchReport {
+    pub processed: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, String)>,
+    /// Files never started because the batch was cancelled, sorted.
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Applies one list of [`Op`]s to every PNG under a directory, mirroring the
+/// directory structure under the output directory.
+#[derive(Debug, Clone)]
+pub struct BatchProcessor {
+    input: PathBuf,
+    output: PathBuf,
+    ops: Vec<Op>,
+    template: String,
+    jobs: usize,
+    cancellation: CancellationToken,
+}
+
+impl BatchProcessor {
+    pub fn new(input: impl Into<PathBuf>, output: impl Into<PathBuf>, ops: Vec<Op>) -> Self {
+        Self {
+            input: input.into(),
+            output: output.into(),
+            ops,
+            template: "{name}".into(),
+            jobs: 0,
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    /// Sets the output file name, where `{stem}`, `{ext}` and `{name}` stand
+    /// for the input's file stem, extension and full name.
+    pub fn template(self, template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            ..self
+        }
+    }
+
+    /// Sets the number of worker threads, 0 meaning one per available core.
+    pub fn jobs(self, jobs: usize) -> Self {
+        Self { jobs, ..self }
+    }
+
+    /// Stops starting new files once `token` is cancelled; files already in
+    /// progress are finished.
+    pub fn cancellation(self, token: CancellationToken) -> Self {
+        Self {
+            cancellation: token,
+            ..self
+        }
+    }
+
+    /// Output path for the input file at `relative`.
+    pub fn output_path(&self, relative: &Path) -> PathBuf {
+        let part = |s: Option<&std::ffi::OsStr>| {
+            s.map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let name = self
+            .template
+            .replace("{stem}", &part(relative.file_stem()))
+            .replace("{ext}", &part(relative.extension()))
+            .replace("{name}", &part(relative.file_name()));
+
+        match relative.parent() {
+            Some(parent) => self.output.join(parent).join(name),
+            None => self.output.join(name),
+        }
+    }
+
+    /// Processes every file, reporting each one to `progress` as it finishes.
+    ///
+    /// Failures of single files are collected in the report; only an
+    /// unreadable input directory fails the whole batch.
+    pub fn run(&self, progress: impl Fn(Progress<'_>) + Sync) -> Result<BatchReport, String> {
+        let mut files = Vec::new();
+        collect_pngs(&self.input, Path::new(""), &mut files)?;
+        files.sort();
+
+        let workers = match self.jobs {
+            0 => thread::available_parallelism().map_or(1, usize::from),
+            jobs => jobs,
+        }
+        .min(files.len().max(1));
+
+        let next = AtomicUsize::new(0);
+        let report = Mutex::new(BatchReport::default());
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    while let Some(relative) = files.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        if self.cancellation.is_cancelled() {
+                            let mut report = report
+                                .lock()
+                                .expect("no worker panics while holding the lock");
+                            report.skipped.push(relative.clone());
+                            continue;
+                        }
+
+                        let outcome = self.process(relative);
+
+                        let mut report = report
+                            .lock()
+                            .expect("no worker panics while holding the lock");
+                        match outcome {
+                            Ok(()) => report.processed.push(relative.clone()),
+                            Err(error) => report.failed.push((relative.clone(), error)),
+                        }
+                        progress(Progress {
+                            done: report.processed.len() + report.failed.len(),
+                            total: files.len(),
+                            path: relative,
+                        });
+                    }
+                });
+            }
+        });
+
+        let mut report = report.into_inner().expect("workers have finished");
+        report.skipped.sort();
+        Ok(report)
+    }
+
+    fn process(&self, relative: &Path) -> Result<(), String> {
+        let output = self.output_path(relative);
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("cannot create {}: {e}", parent.display()))?;
+        }
+
+        process(&self.input.join(relative), &output, &self.ops)
+    }
+}
+
+/// Appends the paths of `.png` files under `root/relative`, relative to `root`.
+fn collect_pngs(root: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let dir = root.join(relative);
+    let entries =
+        std::fs::read_dir(&dir).map_err(|e| format!("cannot read {}: {e}", dir.display()))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("cannot read {}: {e}", dir.display()))?;
+        let path = relative.join(entry.file_name());
+        let is_png = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+
+        if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            collect_pngs(root, &path, files)?;
+        } else if is_png {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use flipr::CancellationToken;
+
+    use super::BatchProcessor;
+
+    #[test]
+    fn templates_fill_in_the_input_name() {
+        let batch = BatchProcessor::new("in", "out", Vec::new()).template("{stem}_small.{ext}");
+        assert_eq!(
+            batch.output_path(Path::new("a/b/photo.png")),
+            PathBuf::from("out/a/b/photo_small.png")
+        );
+        assert_eq!(
+            BatchProcessor::new("in", "out", Vec::new()).output_path(Path::new("x.png")),
+            PathBuf::from("out/x.png")
+        );
+    }
+
+    #[test]
+    fn cancelled_batches_skip_remaining_files() {
+        let dir = std::env::temp_dir().join(format!("flipr-cancel-{}", std::process::id()));
+        let input = dir.join("in");
+        std::fs::create_dir_all(&input).unwrap();
+        for name in ["a.png", "b.png", "c.png"] {
+            std::fs::write(input.join(name), b"not a png").unwrap();
+        }
+
+        let token = CancellationToken::new();
+        let report = BatchProcessor::new(&input, dir.join("out"), Vec::new())
+            .jobs(1)
+            .cancellation(token.clone())
+            .run(|_| token.cancel())
+            .unwrap();
+
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(
+            report.skipped,
+            [PathBuf::from("b.png"), PathBuf::from("c.png")]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,31 @@
+use std::path::Path;
+
+use flipr::{ImageBuffer, Rgba};
+use flipr_io::{Metadata, WithMetadata};
+
+/// Reads an 8-bit or 16-bit PNG of any color type as straight RGBA, turned
+/// upright by its EXIF orientation.
+pub fn load_png(path: &Path) -> Result<ImageBuffer<Rgba<u8>>, String> {
+    load_png_with_metadata(path).map(|loaded| loaded.image)
+}
+
+/// [`load_png`] together with the metadata of the file.
+pub fn load_png_with_metadata(path: &Path) -> Result<WithMetadata<ImageBuffer<Rgba<u8>>>, String> {
+    flipr_io::load_png_with_metadata(path)
+        .map_err(|e| format!("cannot read {}: {e}", path.display()))
+}
+
+/// Writes an 8-bit RGBA PNG.
+pub fn save_png(path: &Path, image: &ImageBuffer<Rgba<u8>>) -> Result<(), String> {
+    save_png_with_metadata(path, image, &Metadata::default())
+}
+
+/// Writes an 8-bit RGBA PNG recording `metadata`.
+pub fn save_png_with_metadata(
+    path: &Path,
+    image: &ImageBuffer<Rgba<u8>>,
+    metadata: &Metadata,
+) -> Result<(), String> {
+    flipr_io::write_png_with_metadata(path, image, metadata)
+        .map_err(|e| format!("cannot write {}: {e}", path.display()))
+}
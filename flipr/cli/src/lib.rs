@@ -0,0 +1,69 @@
+//! Building blocks of the `flipr-cli` tool: the `--ops` language, PNG
+//! loading and saving, batch processing of directory trees and, with the
+//! `watch` feature, re-processing on file changes.
+
+mod batch;
+mod io;
+mod ops;
+mod pipeline;
+#[cfg(feature = "watch")]
+mod watch;
+
+use std::path::Path;
+
+pub use batch::{BatchProcessor, BatchReport, Progress};
+use flipr::{Dither, Image, ImageBuffer, Layout, Rgba};
+use flipr_io::{Metadata, WithMetadata};
+pub use io::{load_png, load_png_with_metadata, save_png, save_png_with_metadata};
+pub use ops::{Op, parse_ops};
+pub use pipeline::IncrementalPipeline;
+#[cfg(feature = "watch")]
+pub use watch::watch;
+
+/// Loads the PNG at `path` as straight-alpha floats in `0.0..=1.0`, with
+/// the metadata of the file.
+pub(crate) fn load_float(path: &Path) -> Result<WithMetadata<ImageBuffer<Rgba<f32>>>, String> {
+    let source = load_png_with_metadata(path)?;
+    let (width, height) = (source.image.width(), source.image.height());
+    Ok(source
+        .map(|image| image.to_float())
+        .materialize(width, height))
+}
+
+/// Saves a float image as an 8-bit RGBA PNG recording `metadata`.
+pub(crate) fn save_float(
+    path: &Path,
+    image: &ImageBuffer<Rgba<f32>>,
+    metadata: &Metadata,
+) -> Result<(), String> {
+    let (width, height) = (image.width(), image.height());
+    let quantized = ImageBuffer::sample(
+        &image.clone().to_u8(Dither::None),
+        width,
+        height,
+        Layout::Interleaved,
+    );
+    save_png_with_metadata(path, &quantized, metadata)
+}
+
+/// Loads the PNG at `input`, applies `ops` in order and saves the result as
+/// an RGBA PNG at `output`, keeping the metadata of `input`.
+pub fn process(input: &Path, output: &Path, ops: &[Op]) -> Result<(), String> {
+    let result =
+        load_float(input)?.map(|source| ops.iter().fold(source, |image, op| op.apply(image)));
+    save_float(output, &result.image, &result.metadata)
+}
+
+/// Loads the PNG at `input`, applies `ops` in order and renders the result
+/// `width` characters wide for a terminal, in 24-bit color or, with
+/// `ascii`, as plain text.
+pub fn preview(input: &Path, ops: &[Op], width: usize, ascii: bool) -> Result<String, String> {
+    let result = ops
+        .iter()
+        .fold(load_float(input)?.image, |image, op| op.apply(image));
+    Ok(if ascii {
+        result.render_ascii(width)
+    } else {
+        result.render_truecolor(width)
+    })
+}
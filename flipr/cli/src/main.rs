@@ -0,0 +1,194 @@
+//! Command-line front end applying a list of flipr operations to PNG images.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use flipr_cli::{BatchProcessor, Op, preview, process};
+
+#[derive(Debug, Parser)]
+#[command(name = "flipr", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Applies operations to one image.
+    Process {
+        input: PathBuf,
+        output: PathBuf,
+        /// Comma-separated operations, e.g. "resize=800x600,blur=2.0,rotate=30".
+        #[arg(long, value_delimiter = ',')]
+        ops: Vec<Op>,
+        #[arg(long, value_enum, default_value_t = Backend::Cpu)]
+        backend: Backend,
+    },
+    /// Applies operations to one image and shows the result in the terminal.
+    Preview {
+        input: PathBuf,
+        #[arg(long, value_delimiter = ',')]
+        ops: Vec<Op>,
+        /// Width of the preview in characters.
+        #[arg(long, default_value_t = 80)]
+        width: usize,
+        /// Plain text instead of 24-bit color, for terminals without it.
+        #[arg(long)]
+        ascii: bool,
+    },
+    /// Applies operations to every PNG in a directory tree, in parallel.
+    Batch {
+        input: PathBuf,
+        output: PathBuf,
+        #[arg(long, value_delimiter = ',')]
+        ops: Vec<Op>,
+        /// Output file name; {stem}, {name} and {ext} refer to the input file.
+        #[arg(long, default_value = "{name}")]
+        template: String,
+        /// Number of worker threads, 0 for one per core.
+        #[arg(long, default_value_t = 0)]
+        jobs: usize,
+        #[arg(long, value_enum, default_value_t = Backend::Cpu)]
+        backend: Backend,
+    },
+    /// Re-processes an image whenever it or its pipeline script changes.
+    #[cfg(feature = "watch")]
+    Watch {
+        input: PathBuf,
+        output: PathBuf,
+        /// File listing the operations, separated by commas or newlines.
+        #[arg(long)]
+        script: PathBuf,
+        #[arg(long, value_enum, default_value_t = Backend::Cpu)]
+        backend: Backend,
+    },
+}
+
+fn batch(
+    input: &Path,
+    output: &Path,
+    ops: &[Op],
+    template: &str,
+    jobs: usize,
+) -> Result<(), String> {
+    let report = BatchProcessor::new(input, output, ops.to_vec())
+        .template(template)
+        .jobs(jobs)
+        .run(|progress| {
+            eprintln!(
+                "[{}/{}] {}",
+                progress.done,
+                progress.total,
+                progress.path.display()
+            )
+        })?;
+
+    for (path, error) in &report.failed {
+        eprintln!("failed: {}: {error}", path.display());
+    }
+    eprintln!(
+        "{} processed, {} failed",
+        report.processed.len(),
+        report.failed.len()
+    );
+
+    match report.failed.len() {
+        0 => Ok(()),
+        n => Err(format!(
+            "{n} of {} images failed",
+            n + report.processed.len()
+        )),
+    }
+}
+
+/// Where operations run; the CPU is the only backend flipr has so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Backend {
+    Cpu,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let outcome = match &cli.command {
+        Command::Process {
+            input,
+            output,
+            ops,
+            backend: Backend::Cpu,
+        } => process(input, output, ops),
+        Command::Preview {
+            input,
+            ops,
+            width,
+            ascii,
+        } => preview(input, ops, *width, *ascii).map(|text| print!("{text}")),
+        Command::Batch {
+            input,
+            output,
+            ops,
+            template,
+            jobs,
+            backend: Backend::Cpu,
+        } => batch(input, output, ops, template, *jobs),
+        #[cfg(feature = "watch")]
+        Command::Watch {
+            input,
+            output,
+            script,
+            backend: Backend::Cpu,
+        } => flipr_cli::watch(input, script, output, |message| eprintln!("{message}")),
+    };
+
+    match outcome {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use flipr::ToneMap;
+    use flipr_cli::Op;
+
+    use super::{Cli, Command};
+
+    #[test]
+    fn op_lists_are_parsed_in_order() {
+        let cli = Cli::try_parse_from([
+            "flipr",
+            "process",
+            "in.png",
+            "out.png",
+            "--ops",
+            "resize=800x600, blur=2.0,levels=0.1:0.9:1.2,tonemap=aces",
+        ])
+        .unwrap();
+
+        let Command::Process { ops, .. } = cli.command else {
+            panic!("expected the process subcommand");
+        };
+        assert_eq!(
+            ops,
+            [
+                Op::Resize(800, 600),
+                Op::Blur(2.0),
+                Op::Levels(0.1, 0.9, 1.2),
+                Op::ToneMap(ToneMap::Aces)
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_backends_are_rejected() {
+        assert!(
+            Cli::try_parse_from(["flipr", "process", "a.png", "b.png", "--backend", "gpu"])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::str::FromStr;
+
+use flipr::{
+    AdjustMode, AffineTransform, Image, ImageBuffer, Interpolation, Layout, Levels, Rgba, ToneMap,
+    from_fn, seam_carve,
+};
+use space::Place;
+
+/// One step of the `--ops` list, written `name=argument`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    /// `resize=WxH`, nearest-neighbour resampling.
+    Resize(usize, usize),
+    /// `carve=WxH`, content-aware resize by seam carving.
+    Carve(usize, usize),
+    /// `rotate=DEGREES`, bilinear rotation about the center, clockwise on
+    /// screen, keeping the size; corners that had no source turn transparent.
+    Rotate(f64),
+    /// `blur=SIGMA`, Gaussian blur.
+    Blur(f64),
+    /// `sharpen=SIGMA`, unsharp mask with amount 1.
+    Sharpen(f64),
+    /// `saturate=FACTOR`.
+    Saturate(f32),
+    /// `vibrance=FACTOR`.
+    Vibrance(f32),
+    /// `hue=DEGREES`.
+    Hue(f32),
+    /// `levels=BLACK:WHITE:GAMMA`, with black and white in `0..=1`.
+    Levels(f32, f32, f32),
+    /// `tonemap=reinhard|aces`.
+    ToneMap(ToneMap),
+}
+
+fn number<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid argument {value:?} for {name}"))
+}
+
+fn size(name: &str, value: &str) -> Result<(usize, usize), String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or_else(|| format!("{name} expects WIDTHxHEIGHT, got {value:?}"))?;
+    let (width, height) = (number(name, width)?, number(name, height)?);
+
+    if width == 0 || height == 0 {
+        return Err(format!("{name} needs a non-empty size"));
+    }
+    Ok((width, height))
+}
+
+impl FromStr for Op {
+    type Err = String;
+
+    fn from_str(op: &str) -> Result<Self, Self::Err> {
+        let (name, value) = op
+            .trim()
+            .split_once('=')
+            .ok_or_else(|| format!("operation {op:?} is not of the form name=argument"))?;
+
+        Ok(match name {
+            "resize" => {
+                let (width, height) = size(name, value)?;
+                Op::Resize(width, height)
+            }
+            "carve" => {
+                let (width, height) = size(name, value)?;
+                Op::Carve(width, height)
+            }
+            "rotate" => Op::Rotate(number(name, value)?),
+            "blur" => Op::Blur(number(name, value)?),
+            "sharpen" => Op::Sharpen(number(name, value)?),
+            "saturate" => Op::Saturate(number(name, value)?),
+            "vibrance" => Op::Vibrance(number(name, value)?),
+            "hue" => Op::Hue(number(name, value)?),
+            "levels" => match value.split(':').collect::<Vec<_>>()[..] {
+                [black, white, gamma] => {
+                    let (black, white, gamma) = (
+                        number(name, black)?,
+                        number(name, white)?,
+                        number(name, gamma)?,
+                    );
+                    if !(black < white && gamma > 0.0) {
+                        return Err("levels needs BLACK < WHITE and a positive GAMMA".into());
+                    }
+                    Op::Levels(black, white, gamma)
+                }
+                _ => return Err(format!("levels expects BLACK:WHITE:GAMMA, got {value:?}")),
+            },
+            "tonemap" => match value {
+                "reinhard" => Op::ToneMap(ToneMap::Reinhard),
+                "aces" => Op::ToneMap(ToneMap::Aces),
+                _ => {
+                    return Err(format!(
+                        "unknown tone map {value:?}, expected reinhard or aces"
+                    ));
+                }
+            },
+            _ => return Err(format!("unknown operation {name:?}")),
+        })
+    }
+}
+
+/// Parses a pipeline script: operations separated by commas or newlines,
+/// with `#` starting a comment that runs to the end of the line.
+pub fn parse_ops(script: &str) -> Result<Vec<Op>, String> {
+    script
+        .lines()
+        .map(|line| line.split_once('#').map_or(line, |(ops, _)| ops))
+        .flat_map(|line| line.split(','))
+        .filter(|op| !op.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+type Buffer = ImageBuffer<Rgba<f32>>;
+
+fn materialize(image: &impl Image<Pixel = Rgba<f32>>, like: &Buffer) -> Buffer {
+    ImageBuffer::sample(image, like.width(), like.height(), Layout::Interleaved)
+}
+
+impl Op {
+    /// Name of the operation in the `--ops` language.
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::Resize(..) => "resize",
+            Op::Carve(..) => "carve",
+            Op::Rotate(_) => "rotate",
+            Op::Blur(_) => "blur",
+            Op::Sharpen(_) => "sharpen",
+            Op::Saturate(_) => "saturate",
+            Op::Vibrance(_) => "vibrance",
+            Op::Hue(_) => "hue",
+            Op::Levels(..) => "levels",
+            Op::ToneMap(_) => "tonemap",
+        }
+    }
+
+    /// Applies the operation to a straight-alpha image in the `0.0..=1.0` range.
+    pub fn apply(self, image: Buffer) -> Buffer {
+        match self {
+            Op::Resize(width, height) => {
+                let (sx, sy) = (
+                    image.width() as f64 / width as f64,
+                    image.height() as f64 / height as f64,
+                );
+                let scaled = from_fn(|p: Place| {
+                    let (x, y) = (p.x().to_f64().unwrap_or(0.0), p.y().to_f64().unwrap_or(0.0));
+                    image.get(Place::new(x * sx, y * sy).expect("scaled coordinates are finite"))
+                });
+                ImageBuffer::sample(&scaled, width, height, Layout::Interleaved)
+            }
+            Op::Carve(width, height) => seam_carve(&image, width, height),
+            Op::Rotate(degrees) => {
+                let (width, height) = (image.width() as f64, image.height() as f64);
+                let center = Place::new(width / 2.0, height / 2.0).expect("sizes are finite");
+                let source = image.clone().continuize(Interpolation::Bilinear);
+                let bounded = from_fn(move |p: Place| {
+                    let (x, y) = (
+                        p.x().to_f64().unwrap_or(-1.0),
+                        p.y().to_f64().unwrap_or(-1.0),
+                    );
+                    if (0.0..width).contains(&x) && (0.0..height).contains(&y) {
+                        source.get(p)
+                    } else {
+                        Rgba::new(0.0, 0.0, 0.0, 0.0)
+                    }
+                });
+                let rotation = AffineTransform::rotation_about(&center, degrees.to_radians());
+                materialize(&bounded.transform(rotation), &image)
+            }
+            Op::Blur(sigma) => materialize(&image.clone().gaussian_blur(sigma), &image),
+            Op::Sharpen(sigma) => materialize(&image.clone().unsharp_mask(sigma, 1.0, 0.0), &image),
+            Op::Saturate(factor) => materialize(&image.clone().saturate(factor), &image),
+            Op::Vibrance(factor) => materialize(&image.clone().vibrance(factor), &image),
+            Op::Hue(degrees) => materialize(&image.clone().hue_rotate(degrees), &image),
+            Op::Levels(black, white, gamma) => materialize(
+                &image
+                    .clone()
+                    .adjust(Levels::new(black, white, gamma), AdjustMode::PerChannel),
+                &image,
+            ),
+            Op::ToneMap(op) => materialize(&image.clone().tone_map(op), &image),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flipr::{ImageBuffer, Layout, Rgba};
+
+    use super::{Op, parse_ops};
+
+    #[test]
+    fn malformed_ops_are_explained() {
+        let error = |op: &str| op.parse::<Op>().unwrap_err();
+        assert_eq!(error("shear=30"), "unknown operation \"shear\"");
+        assert!(error("blur").contains("name=argument"));
+        assert!(error("resize=0x5").contains("non-empty"));
+        assert!(error("levels=0.9:0.1:1").contains("BLACK < WHITE"));
+    }
+
+    #[test]
+    fn scripts_allow_newlines_and_comments() {
+        let script = "# tuning\nblur=1.5, saturate=1.2\n\nhue=30 # warmer\n";
+        assert_eq!(
+            parse_ops(script),
+            Ok(vec![Op::Blur(1.5), Op::Saturate(1.2), Op::Hue(30.0)])
+        );
+        assert!(parse_ops("blur=1\nshear=2").is_err());
+    }
+
+    #[test]
+    fn resize_changes_the_size() {
+        let image = ImageBuffer::filled(4, 4, Layout::Interleaved, Rgba::new(0.5, 0.5, 0.5, 1.0));
+        let resized = Op::Resize(6, 2).apply(image);
+        assert_eq!((resized.width(), resized.height()), (6, 2));
+        assert_eq!(resized.pixel(5, 1), Some(Rgba::new(0.5, 0.5, 0.5, 1.0)));
+    }
+
+    #[test]
+    fn rotate_turns_about_the_center() {
+        let image = ImageBuffer::from_fn(4, 4, Layout::Interleaved, |i, j| {
+            Rgba::new(i as f32 / 3.0, j as f32 / 3.0, 0.0, 1.0)
+        });
+        let rotated = Op::Rotate(90.0).apply(image.clone());
+        assert_eq!((rotated.width(), rotated.height()), (4, 4));
+        // A quarter turn clockwise moves the top-left pixel to the top right.
+        let (Rgba { r, g, .. }, top_right) = (image.pixel(0, 0).unwrap(), rotated.pixel(3, 0));
+        let Rgba { r: rr, g: rg, .. } = top_right.unwrap();
+        assert!((rr - r).abs() < 1e-5 && (rg - g).abs() < 1e-5);
+
+        let tilted = Op::Rotate(45.0).apply(image);
+        assert_eq!(tilted.pixel(0, 0).unwrap().a, 0.0);
+        assert_eq!(tilted.pixel(2, 2).unwrap().a, 1.0);
+    }
+}
@@ -0,0 +1,161 @@
+use flipr::{ExecutionObserver, FliprError, ImageBuffer, Rgba};
+
+use crate::ops::Op;
+
+type Buffer = ImageBuffer<Rgba<f32>>;
+
+/// Runs an [`Op`] list while keeping the output of every stage, so that a
+/// rerun with an edited list only recomputes the stages from the first
+/// changed operation on.
+#[derive(Debug, Clone)]
+pub struct IncrementalPipeline {
+    source: Buffer,
+    stages: Vec<(Op, Buffer)>,
+    reused: usize,
+}
+
+impl IncrementalPipeline {
+    pub fn new(source: Buffer) -> Self {
+        Self {
+            source,
+            stages: Vec::new(),
+            reused: 0,
+        }
+    }
+
+    /// Replaces the source image, invalidating every cached stage.
+    pub fn set_source(&mut self, source: Buffer) {
+        self.source = source;
+        self.stages.clear();
+    }
+
+    /// Applies `ops` to the source, reusing the cached stages of the longest
+    /// unchanged prefix.
+    pub fn run(&mut self, ops: &[Op]) -> &Buffer {
+        self.run_observed(ops, &())
+            .expect("the unit observer never cancels")
+    }
+
+    /// Like [`run`](Self::run), announcing every recomputed stage to
+    /// `observer` by its op name and stopping between stages once it is
+    /// cancelled. Stages finished before that stay cached.
+    pub fn run_observed(
+        &mut self,
+        ops: &[Op],
+        observer: &impl ExecutionObserver,
+    ) -> Result<&Buffer, FliprError> {
+        self.reused = self
+            .stages
+            .iter()
+            .zip(ops)
+            .take_while(|((cached, _), op)| cached == *op)
+            .count();
+        self.stages.truncate(self.reused);
+
+        for &op in &ops[self.reused..] {
+            if observer.is_cancelled() {
+                return Err(FliprError::Cancelled);
+            }
+            observer.stage(op.name());
+
+            let input = self.stages.last().map_or(&self.source, |(_, image)| image);
+            let output = op.apply(input.clone());
+            self.stages.push((op, output));
+        }
+
+        Ok(self.stages.last().map_or(&self.source, |(_, image)| image))
+    }
+
+    /// Number of stages the last [`run`](Self::run) took from the cache.
+    pub fn reused(&self) -> usize {
+        self.reused
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use flipr::{ExecutionObserver, FliprError, ImageBuffer, Layout, Rgba};
+
+    use super::IncrementalPipeline;
+    use crate::ops::Op;
+
+    fn gray(value: f32) -> ImageBuffer<Rgba<f32>> {
+        ImageBuffer::filled(
+            4,
+            3,
+            Layout::Interleaved,
+            Rgba::new(value, value, value, 1.0),
+        )
+    }
+
+    #[test]
+    fn unchanged_prefixes_are_reused() {
+        let mut pipeline = IncrementalPipeline::new(gray(0.5));
+        let first = pipeline
+            .run(&[Op::Resize(2, 2), Op::Levels(0.0, 1.0, 2.0)])
+            .clone();
+        assert_eq!(pipeline.reused(), 0);
+        assert_eq!((first.width(), first.height()), (2, 2));
+
+        pipeline.run(&[Op::Resize(2, 2), Op::Blur(1.0)]);
+        assert_eq!(pipeline.reused(), 1);
+
+        let again = pipeline
+            .run(&[Op::Resize(2, 2), Op::Levels(0.0, 1.0, 2.0)])
+            .clone();
+        assert_eq!(pipeline.reused(), 1);
+        assert_eq!(again, first);
+    }
+
+    #[test]
+    fn new_sources_invalidate_the_cache() {
+        let mut pipeline = IncrementalPipeline::new(gray(0.5));
+        pipeline.run(&[Op::Resize(2, 2)]);
+        pipeline.set_source(gray(0.25));
+
+        let result = pipeline.run(&[Op::Resize(2, 2)]).clone();
+        assert_eq!(pipeline.reused(), 0);
+        assert_eq!(result.pixel(1, 1), Some(Rgba::new(0.25, 0.25, 0.25, 1.0)));
+    }
+
+    #[test]
+    fn empty_lists_return_the_source() {
+        let mut pipeline = IncrementalPipeline::new(gray(0.5));
+        assert_eq!(*pipeline.run(&[]), gray(0.5));
+    }
+
+    /// Records stage names and cancels once `limit` stages have started.
+    struct Stages {
+        names: RefCell<Vec<String>>,
+        limit: usize,
+    }
+
+    impl ExecutionObserver for Stages {
+        fn stage(&self, name: &str) {
+            self.names.borrow_mut().push(name.to_owned());
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.names.borrow().len() >= self.limit
+        }
+    }
+
+    #[test]
+    fn observers_see_stages_and_can_cancel() {
+        let ops = [Op::Resize(2, 2), Op::Blur(1.0), Op::Levels(0.0, 1.0, 2.0)];
+        let mut pipeline = IncrementalPipeline::new(gray(0.5));
+
+        let observer = Stages {
+            names: RefCell::new(Vec::new()),
+            limit: 2,
+        };
+        let result = pipeline.run_observed(&ops, &observer).map(|_| ());
+        assert_eq!(result, Err(FliprError::Cancelled));
+        assert_eq!(*observer.names.borrow(), ["resize", "blur"]);
+
+        pipeline.run(&ops);
+        assert_eq!(pipeline.reused(), 2);
+    }
+}
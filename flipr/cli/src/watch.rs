@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use notify::{RecursiveMode, Watcher};
+
+use crate::ops::parse_ops;
+use crate::pipeline::IncrementalPipeline;
+use crate::{load_float, save_float};
+
+/// How long to gather events after the first one of a change, as editors
+/// often write a file in several steps.
+const SETTLE: Duration = Duration::from_millis(100);
+
+fn read_script(path: &Path) -> Result<Vec<crate::Op>, String> {
+    let script = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+    parse_ops(&script).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn absolute(path: &Path) -> Result<PathBuf, String> {
+    std::path::absolute(path).map_err(|e| format!("cannot resolve {}: {e}", path.display()))
+}
+
+/// Processes `input` with the operations in the `script` file, then re-runs
+/// whenever either file changes until the watcher fails.
+///
+/// Editing the script only recomputes the stages after the first changed
+/// operation. Errors while re-running, such as a half-written script, are
+/// passed to `log` and the previous output is kept.
+pub fn watch(
+    input: &Path,
+    script: &Path,
+    output: &Path,
+    mut log: impl FnMut(&str),
+) -> Result<(), String> {
+    let (input, script) = (absolute(input)?, absolute(script)?);
+    let (sender, events) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(sender).map_err(|e| format!("cannot watch files: {e}"))?;
+    // Watching starts before the first run so no change is missed, and
+    // watching the directories rather than the files survives editors that
+    // save by replacing the file.
+    for path in [&input, &script] {
+        let dir = path.parent().unwrap_or(Path::new("/"));
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("cannot watch {}: {e}", dir.display()))?;
+    }
+
+    let source = load_float(&input)?;
+    let mut metadata = source.metadata;
+    let mut pipeline = IncrementalPipeline::new(source.image);
+    let mut ops = read_script(&script)?;
+    save_float(output, pipeline.run(&ops), &metadata)?;
+    log(&format!("wrote {}", output.display()));
+
+    while let Ok(event) = events.recv() {
+        let mut changed = Vec::new();
+        let settled = Instant::now() + SETTLE;
+        let mut pending = Some(event);
+        while let Some(event) = pending {
+            match event {
+                Ok(event) if !event.kind.is_access() => changed.extend(event.paths),
+                Ok(_) => {}
+                Err(error) => log(&format!("watch error: {error}")),
+            }
+            pending = events
+                .recv_timeout(settled.saturating_duration_since(Instant::now()))
+                .ok();
+        }
+
+        let (source_changed, script_changed) =
+            (changed.contains(&input), changed.contains(&script));
+        if !source_changed && !script_changed {
+            continue;
+        }
+
+        let rerun = (|| {
+            if source_changed {
+                let source = load_float(&input)?;
+                metadata = source.metadata;
+                pipeline.set_source(source.image);
+            }
+            if script_changed {
+                ops = read_script(&script)?;
+            }
+            let result = pipeline.run(&ops);
+            save_float(output, result, &metadata)?;
+            Ok::<_, String>(pipeline.reused())
+        })();
+
+        match rerun {
+            Ok(reused) => log(&format!(
+                "wrote {} ({reused} of {} stages cached)",
+                output.display(),
+                ops.len()
+            )),
+            Err(error) => log(&error),
+        }
+    }
+
+    Err("file watcher stopped".into())
+}
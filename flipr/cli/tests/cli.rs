@@ -0,0 +1,211 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("flipr-cli-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_rgb(path: &Path, width: u32, height: u32) {
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path).unwrap()), width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let data: Vec<u8> = (0..width * height * 3)
+        .map(|n| (n * 7 % 256) as u8)
+        .collect();
+    encoder
+        .write_header()
+        .unwrap()
+        .write_image_data(&data)
+        .unwrap();
+}
+
+fn read_info(path: &Path) -> (u32, u32, png::ColorType) {
+    let reader = png::Decoder::new(BufReader::new(File::open(path).unwrap()))
+        .read_info()
+        .unwrap();
+    let info = reader.info();
+    (info.width, info.height, info.color_type)
+}
+
+fn flipr(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_flipr-cli"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn process_applies_the_op_list() {
+    let dir = temp_dir("process");
+    let (input, output) = (dir.join("in.png"), dir.join("out.png"));
+    write_rgb(&input, 12, 8);
+
+    let result = flipr(&[
+        "process",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        "--ops",
+        "resize=6x4,blur=0.5,saturate=1.5,levels=0:1:1.2",
+        "--backend",
+        "cpu",
+    ]);
+
+    assert!(
+        result.status.success(),
+        "{}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert_eq!(read_info(&output), (6, 4, png::ColorType::Rgba));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn process_keeps_metadata_and_turns_images_upright() {
+    let dir = temp_dir("metadata");
+    let (input, output) = (dir.join("in.png"), dir.join("out.png"));
+    let image = flipr::ImageBuffer::filled(6, 4, flipr::Layout::Interleaved, flipr::Gray(90u8));
+    let metadata = flipr_io::Metadata {
+        orientation: flipr_io::Orientation::Rotate270,
+        tags: [("Title".to_string(), "Harbour".to_string())].into(),
+        ..flipr_io::Metadata::default()
+    };
+    flipr_io::write_png_with_metadata(&input, &image, &metadata).unwrap();
+
+    let result = flipr(&[
+        "process",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        "--ops",
+        "blur=0.5",
+    ]);
+
+    assert!(
+        result.status.success(),
+        "{}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert_eq!(read_info(&output), (4, 6, png::ColorType::Rgba));
+    let saved = flipr_io::load_png_with_metadata(&output).unwrap().metadata;
+    assert_eq!(saved.orientation, flipr_io::Orientation::Normal);
+    assert_eq!(saved.tags, metadata.tags);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn preview_prints_the_result() {
+    let dir = temp_dir("preview");
+    let input = dir.join("in.png");
+    write_rgb(&input, 12, 8);
+
+    let path = input.to_str().unwrap();
+    let ascii = flipr(&[
+        "preview", path, "--ops", "blur=0.5", "--width", "6", "--ascii",
+    ]);
+    let text = String::from_utf8(ascii.stdout).unwrap();
+    assert_eq!(text.lines().map(str::len).collect::<Vec<_>>(), [6, 6]);
+
+    let color = flipr(&["preview", path, "--width", "6"]);
+    let text = String::from_utf8(color.stdout).unwrap();
+    assert_eq!(text.lines().count(), 2);
+    assert!(text.starts_with("\x1b[38;2;"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn bad_ops_fail_without_writing() {
+    let dir = temp_dir("bad-ops");
+    let (input, output) = (dir.join("in.png"), dir.join("out.png"));
+    write_rgb(&input, 4, 4);
+
+    let result = flipr(&[
+        "process",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        "--ops",
+        "shear=30",
+    ]);
+
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("unknown operation"));
+    assert!(!output.exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn batch_processes_a_tree_and_collects_failures() {
+    let dir = temp_dir("batch");
+    let (input, output) = (dir.join("in"), dir.join("out"));
+    std::fs::create_dir_all(input.join("nested")).unwrap();
+    write_rgb(&input.join("a.png"), 4, 4);
+    write_rgb(&input.join("nested/b.png"), 6, 2);
+    std::fs::write(input.join("broken.png"), b"not a png").unwrap();
+    std::fs::write(input.join("notes.txt"), b"ignored").unwrap();
+
+    let result = flipr(&[
+        "batch",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        "--ops",
+        "blur=0.5",
+        "--template",
+        "{stem}_blurred.{ext}",
+        "--jobs",
+        "2",
+    ]);
+
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(!result.status.success());
+    assert!(stderr.contains("2 processed, 1 failed"), "{stderr}");
+    assert!(stderr.contains("[3/3]"), "{stderr}");
+    assert_eq!(read_info(&output.join("a_blurred.png")).0, 4);
+    assert_eq!(read_info(&output.join("nested/b_blurred.png")).1, 2);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn watch_reprocesses_when_the_script_changes() {
+    use std::time::{Duration, Instant};
+
+    let dir = temp_dir("watch");
+    let (input, output, script) = (dir.join("in.png"), dir.join("out.png"), dir.join("ops.txt"));
+    write_rgb(&input, 8, 8);
+    std::fs::write(&script, "resize=4x4\n").unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_flipr-cli"))
+        .args(["watch", input.to_str().unwrap(), output.to_str().unwrap()])
+        .args(["--script", script.to_str().unwrap()])
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // The output may be caught half-written, so decoding errors just mean "not yet".
+    let width = || {
+        let file = File::open(&output).ok()?;
+        let reader = png::Decoder::new(BufReader::new(file)).read_info().ok()?;
+        Some(reader.info().width)
+    };
+    let wait_for_width = |expected: u32| {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(10) {
+            if width() == Some(expected) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        false
+    };
+
+    let first = wait_for_width(4);
+    std::fs::write(&script, "resize=4x4\nresize=2x2\n").unwrap();
+    let second = wait_for_width(2);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+    assert!(first && second);
+}
@@ -0,0 +1,192 @@
+//! Standard workloads at several resolutions, for catching performance
+//! regressions: `cargo bench -p flipr`.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use flipr::fft::{FrequencyFilter, convolve};
+use flipr::{
+    AffineTransform, Dither, DownsampleFilter, GaussianPyramid, Gray, Image, ImageBuffer,
+    Interpolation, Kernel, Layout, Rgb, ToneMap,
+};
+use space::Place;
+
+const SIZES: [usize; 3] = [32, 64, 128];
+
+fn source(size: usize) -> ImageBuffer<Rgb<u8>> {
+    ImageBuffer::from_fn(size, size, Layout::Interleaved, |i, j| {
+        Rgb::new((i * 7) as u8, (j * 13) as u8, (i ^ j) as u8)
+    })
+}
+
+fn gray(size: usize) -> ImageBuffer<Gray<f32>> {
+    ImageBuffer::from_fn(size, size, Layout::Interleaved, |i, j| {
+        Gray(((i * 31 + j * 17) % 256) as f32 / 255.0)
+    })
+}
+
+fn pointwise_chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pointwise_chain");
+    for size in SIZES {
+        let image = source(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &image, |b, image| {
+            b.iter(|| {
+                let chain = image
+                    .clone()
+                    .to_float()
+                    .saturate(1.2)
+                    .tone_map(ToneMap::Reinhard)
+                    .to_u8(Dither::Ordered);
+                black_box(ImageBuffer::sample(&chain, size, size, Layout::Interleaved))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn convolution_5x5(c: &mut Criterion) {
+    let mut group = c.benchmark_group("convolution_5x5");
+    for size in SIZES {
+        let image = source(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &image, |b, image| {
+            // σ = 0.6 gives a kernel radius of 2.
+            let blurred = image.clone().gaussian_blur(0.6);
+            b.iter(|| {
+                black_box(ImageBuffer::sample(
+                    &blurred,
+                    size,
+                    size,
+                    Layout::Interleaved,
+                ))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn convolution_31x31(c: &mut Criterion) {
+    let mut group = c.benchmark_group("convolution_31x31");
+    // Large enough for `Image::convolve` to take the FFT path.
+    let kernel = Kernel::new([[1.0; 31]; 31]).divided_by(961.0);
+    for size in SIZES {
+        let image = source(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &image, |b, image| {
+            b.iter(|| {
+                let convolved = image.clone().convolve(kernel);
+                black_box(ImageBuffer::sample(
+                    &convolved,
+                    size,
+                    size,
+                    Layout::Interleaved,
+                ))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn rotation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rotation");
+    for size in SIZES {
+        let image = source(size);
+        let center = Place::new(size as f64 / 2.0, size as f64 / 2.0).unwrap();
+        let rotation = AffineTransform::rotation_about(&center, 0.3);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &image, |b, image| {
+            b.iter(|| {
+                let rotated = image
+                    .clone()
+                    .continuize(Interpolation::Bilinear)
+                    .transform(rotation);
+                black_box(ImageBuffer::sample(
+                    &rotated,
+                    size,
+                    size,
+                    Layout::Interleaved,
+                ))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn resize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resize");
+    for size in SIZES {
+        let image = source(size);
+        for (name, factor) in [("down", 0.75), ("up", 1.5)] {
+            let resized_size = (size as f64 * factor) as usize;
+            let scaling = AffineTransform::scaling(factor, factor);
+            group.bench_with_input(BenchmarkId::new(name, size), &image, |b, image| {
+                b.iter(|| {
+                    let resized = image
+                        .clone()
+                        .continuize(Interpolation::Bilinear)
+                        .transform(scaling);
+                    black_box(ImageBuffer::sample(
+                        &resized,
+                        resized_size,
+                        resized_size,
+                        Layout::Interleaved,
+                    ))
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn fft_filtering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fft_filtering");
+    for size in SIZES {
+        let image = gray(size);
+        let kernel = ImageBuffer::from_fn(size, size, Layout::Interleaved, |i, j| {
+            Gray(if i.abs_diff(size / 2) <= 2 && j.abs_diff(size / 2) <= 2 {
+                1.0 / 25.0
+            } else {
+                0.0
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("convolve", size), &image, |b, image| {
+            b.iter(|| black_box(convolve(image, &kernel)))
+        });
+        group.bench_with_input(BenchmarkId::new("low_pass", size), &image, |b, image| {
+            let filter = FrequencyFilter::ButterworthLowPass {
+                cutoff: 0.1,
+                order: 2,
+            };
+            b.iter(|| black_box(filter.apply(image)))
+        });
+    }
+    group.finish();
+}
+
+fn downsample(c: &mut Criterion) {
+    let mut group = c.benchmark_group("downsample");
+    for size in SIZES {
+        let image = source(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &image, |b, image| {
+            b.iter(|| {
+                black_box(GaussianPyramid::new(
+                    image,
+                    size,
+                    size,
+                    3,
+                    DownsampleFilter::Binomial,
+                ))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    pointwise_chain,
+    convolution_5x5,
+    convolution_31x31,
+    rotation,
+    resize,
+    fft_filtering,
+    downsample
+);
+criterion_main!(benches);
@@ -0,0 +1,226 @@
+use space::{AffineMap, Offset, Place, Real, Scale};
+
+use crate::Image;
+
+fn finite(r: &Real) -> f64 {
+    r.to_f64().expect("a ratio of integers converts to f64")
+}
+
+/// Affine map of the plane in `f64`, `(x, y) ↦ (a·x + b·y + c, d·x + e·y + f)`
+/// for the rows `[[a, b, c], [d, e, f]]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AffineTransform {
+    rows: [[f64; 3]; 2],
+}
+
+impl Default for AffineTransform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl AffineTransform {
+    pub fn new(rows: [[f64; 3]; 2]) -> Self {
+        Self { rows }
+    }
+
+    pub fn identity() -> Self {
+        Self::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]])
+    }
+
+    pub fn rows(&self) -> [[f64; 3]; 2] {
+        self.rows
+    }
+
+    pub fn translation(dx: f64, dy: f64) -> Self {
+        Self::new([[1.0, 0.0, dx], [0.0, 1.0, dy]])
+    }
+
+    pub fn scaling(sx: f64, sy: f64) -> Self {
+        Self::new([[sx, 0.0, 0.0], [0.0, sy, 0.0]])
+    }
+
+    /// Rotation by `angle` radians about the origin, from the x axis toward
+    /// the y axis.
+    pub fn rotation(angle: f64) -> Self {
+        let (sin, cos) = libm::sincos(angle);
+        Self::new([[cos, -sin, 0.0], [sin, cos, 0.0]])
+    }
+
+    pub fn from_offset(offset: &Offset) -> Self {
+        Self::translation(finite(offset.dx()), finite(offset.dy()))
+    }
+
+    /// Scales x by `sx` and y by `sy` about the origin.
+    pub fn from_scale(sx: &Scale, sy: &Scale) -> Self {
+        Self::scaling(finite(sx.value()), finite(sy.value()))
+    }
+
+    /// Rounds an exact [`AffineMap`] to `f64`.
+    pub fn from_map(map: &AffineMap) -> Self {
+        let [[a, b], [d, e]] = map.linear().rows();
+        let translation = map.translation_part();
+
+        Self::new([
+            [finite(a), finite(b), finite(translation.x())],
+            [finite(d), finite(e), finite(translation.y())],
+        ])
+    }
+
+    /// Rotation by `angle` radians that keeps `center` in place.
+    pub fn rotation_about(center: &Place, angle: f64) -> Self {
+        let (cx, cy) = (finite(center.x()), finite(center.y()));
+        Self::translation(-cx, -cy)
+            .then(Self::rotation(angle))
+            .then(Self::translation(cx, cy))
+    }
+
+    /// Applies `self` first and `next` second.
+    pub fn then(self, next: Self) -> Self {
+        let [[a, b, c], [d, e, f]] = next.rows;
+        let [[p, q, r], [s, t, u]] = self.rows;
+
+        Self::new([
+            [a * p + b * s, a * q + b * t, a * r + b * u + c],
+            [d * p + e * s, d * q + e * t, d * r + e * u + f],
+        ])
+    }
+
+    /// The map undoing `self`, or `None` if it collapses the plane.
+    pub fn inverse(&self) -> Option<Self> {
+        let [[a, b, c], [d, e, f]] = self.rows;
+        let det = a * e - b * d;
+        if !det.is_normal() {
+            return None;
+        }
+
+        let (ia, ib, id, ie) = (e / det, -b / det, -d / det, a / det);
+        Some(Self::new([
+            [ia, ib, -(ia * c + ib * f)],
+            [id, ie, -(id * c + ie * f)],
+        ]))
+    }
+
+    pub fn transform_point(&self, x: f64, y: f64) -> (f64, f64) {
+        let [[a, b, c], [d, e, f]] = self.rows;
+        (a * x + b * y + c, d * x + e * y + f)
+    }
+
+    /// Maps `place`, or `None` if the result overflows `f64`.
+    pub fn transform_place(&self, place: &Place) -> Option<Place> {
+        let (x, y) = self.transform_point(finite(place.x()), finite(place.y()));
+        Place::new(x, y)
+    }
+}
+
+/// Image moved by an [`AffineTransform`], see [`Image::transform`].
+#[derive(Debug, Clone)]
+pub struct Transformed<I> {
+    image: I,
+    inverse: AffineTransform,
+}
+
+impl<I> Transformed<I> {
+    pub(crate) fn new(image: I, transform: AffineTransform) -> Self {
+        let inverse = transform
+            .inverse()
+            .expect("an image can only be moved by an invertible transform");
+        Self { image, inverse }
+    }
+}
+
+impl<I: Image> Image for Transformed<I> {
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let source = self
+            .inverse
+            .transform_place(&p)
+            .expect("transformed places stay finite");
+        self.image.get(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::FRAC_PI_2;
+
+    use proptest::array::uniform6;
+    use proptest::{prop_assert, proptest};
+    use space::{AffineMap, Offset, Place, Scale};
+
+    use super::AffineTransform;
+    use crate::{Gray, Image, from_fn};
+
+    fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+        (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9
+    }
+
+    fn coords(p: &Place) -> (f64, f64) {
+        (p.x().to_f64().unwrap(), p.y().to_f64().unwrap())
+    }
+
+    #[test]
+    fn space_types_build_transforms() {
+        let shift = AffineTransform::from_offset(&Offset::new(2.0, -1.0).unwrap());
+        let scale =
+            AffineTransform::from_scale(&Scale::new(3.0).unwrap(), &Scale::new(0.5).unwrap());
+        let p = Place::new(1.0, 4.0).unwrap();
+
+        assert_eq!(shift.transform_place(&p), Place::new(3.0, 3.0));
+        assert_eq!(scale.transform_place(&p), Place::new(3.0, 2.0));
+        assert_eq!(shift.then(scale).transform_place(&p), Place::new(9.0, 1.5));
+    }
+
+    #[test]
+    fn rotation_about_keeps_its_center() {
+        let center = Place::new(2.0, 1.0).unwrap();
+        let quarter = AffineTransform::rotation_about(&center, FRAC_PI_2);
+        assert!(close(
+            coords(&quarter.transform_place(&center).unwrap()),
+            (2.0, 1.0)
+        ));
+
+        let p = Place::new(3.0, 1.0).unwrap();
+        assert!(close(
+            coords(&quarter.transform_place(&p).unwrap()),
+            (2.0, 2.0)
+        ));
+    }
+
+    #[test]
+    fn exact_maps_round_to_the_same_transform() {
+        let map = AffineMap::scaling(Scale::new(2.0).unwrap(), Scale::new(4.0).unwrap())
+            .then(&AffineMap::translation(Offset::new(1.0, -1.0).unwrap()));
+        let transform = AffineTransform::from_map(&map);
+        assert_eq!(transform.transform_point(1.0, 1.0), (3.0, 3.0));
+    }
+
+    #[test]
+    fn singular_transforms_have_no_inverse() {
+        assert_eq!(AffineTransform::scaling(0.0, 1.0).inverse(), None);
+    }
+
+    #[test]
+    fn transformed_images_move_with_the_transform() {
+        let image = from_fn(|p: Place| Gray(p.x().to_f64().unwrap() as i32));
+        let moved = image.transform(AffineTransform::translation(10.0, 0.0));
+        assert_eq!(moved.get(Place::new(12.5, 0.0).unwrap()), Gray(2));
+    }
+
+    proptest! {
+        #[test]
+        fn inverse_undoes_the_transform(
+            [a, b, c, d, e, f] in uniform6(-10.0..10.0f64),
+            x in -100.0..100.0f64,
+            y in -100.0..100.0f64,
+        ) {
+            let transform = AffineTransform::new([[a, b, c], [d, e, f]]);
+            let well_conditioned = (a * e - b * d).abs() > 1e-3;
+            if let Some(inverse) = transform.inverse().filter(|_| well_conditioned) {
+                let (u, v) = transform.then(inverse).transform_point(x, y);
+                prop_assert!((u - x).abs() < 1e-6 && (v - y).abs() < 1e-6);
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Operators that extract structure from images rather than restyle them.
+
+mod background;
+mod corners;
+mod edges;
+mod features;
+mod flow;
+mod hough;
+mod metrics;
+mod segmentation;
+mod template;
+
+pub use background::{MixtureOfGaussians, RunningAverage};
+pub use corners::{HarrisResponse, fast_corners, harris_corners, harris_response};
+pub use edges::{Grad, Gradient, Magnitude, canny, gradient_magnitude, scharr, sobel};
+pub use features::{Descriptor, Feature, PATCH_RADIUS, describe, match_features};
+pub use flow::{Flow, FlowColors, LucasKanade, dense_flow, flow_colors, optical_flow};
+pub use hough::{Circle, HOUGH_ANGLES, Line, hough_circles, hough_lines, hough_space};
+pub use metrics::{SSIM_WINDOW, mse, psnr, ssim};
+pub use segmentation::{
+    Contour, DistanceMetric, distance_transform, find_contours, flood_fill, watershed,
+};
+pub use template::{MatchMethod, TemplateMatch, match_template};
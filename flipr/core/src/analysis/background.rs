@@ -0,0 +1,336 @@
+use alloc::vec::Vec;
+
+use crate::buffer::{ImageBuffer, Layout};
+use crate::color::SrgbChannel;
+use crate::pixel::Gray;
+
+const FOREGROUND: Gray<u8> = Gray(u8::MAX);
+const BACKGROUND: Gray<u8> = Gray(0);
+
+/// Smallest variance a Gaussian can shrink to, so that a perfectly static
+/// pixel still tolerates a little sensor noise.
+const MIN_VARIANCE: f32 = 1e-4;
+
+fn values<T: SrgbChannel>(frame: &ImageBuffer<Gray<T>>) -> impl Iterator<Item = f32> + '_ {
+    frame.pixels().map(|Gray(v)| v.normalized())
+}
+
+fn mask(width: usize, height: usize, foreground: &[bool]) -> ImageBuffer<Gray<u8>> {
+    ImageBuffer::from_fn(width, height, Layout::Interleaved, |i, j| {
+        if foreground[j * width + i] {
+            FOREGROUND
+        } else {
+            BACKGROUND
+        }
+    })
+}
+
+/// Background model that blends every frame into a running average and
+/// flags pixels far from it as foreground.
+///
+/// Cheap and adequate for static scenes with slow lighting changes; use
+/// [`MixtureOfGaussians`] where the background itself flickers or sways.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunningAverage {
+    learning_rate: f32,
+    threshold: f32,
+    background: Option<ImageBuffer<Gray<f32>>>,
+}
+
+impl RunningAverage {
+    /// Model mixing `learning_rate` of every frame into the background and
+    /// flagging pixels that differ from it by more than `threshold`, both
+    /// on the `0.0..=1.0` scale of [`SrgbChannel::normalized`].
+    pub fn new(learning_rate: f32, threshold: f32) -> Self {
+        Self {
+            learning_rate,
+            threshold,
+            background: None,
+        }
+    }
+
+    /// The background learned so far.
+    pub fn background(&self) -> Option<&ImageBuffer<Gray<f32>>> {
+        self.background.as_ref()
+    }
+
+    /// Foreground mask of `frame`, `255` for foreground and `0` for
+    /// background, after which `frame` is learned.
+    ///
+    /// The first frame, and the first after a change of size, becomes the
+    /// background and has no foreground.
+    pub fn apply<T: SrgbChannel>(&mut self, frame: &ImageBuffer<Gray<T>>) -> ImageBuffer<Gray<u8>> {
+        let (width, height) = (frame.width(), frame.height());
+        let background = match self.background.take() {
+            Some(b) if b.width() == width && b.height() == height => b,
+            _ => {
+                let first = ImageBuffer::from_fn(width, height, Layout::Interleaved, |i, j| {
+                    let Gray(v) = frame.pixel(i, j).expect("indices are in bounds");
+                    Gray(v.normalized())
+                });
+                self.background = Some(first);
+                return mask(width, height, &alloc::vec![false; width * height]);
+            }
+        };
+
+        let pairs: Vec<(f32, f32)> = values(frame)
+            .zip(background.pixels().map(|Gray(b)| b))
+            .collect();
+        let foreground: Vec<bool> = pairs
+            .iter()
+            .map(|&(v, b)| (v - b).abs() > self.threshold)
+            .collect();
+        self.background = Some(ImageBuffer::from_fn(
+            width,
+            height,
+            Layout::Interleaved,
+            |i, j| {
+                let (v, b) = pairs[j * width + i];
+                Gray(b + (v - b) * self.learning_rate)
+            },
+        ));
+
+        mask(width, height, &foreground)
+    }
+}
+
+/// One Gaussian of a pixel's [`MixtureOfGaussians`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Component {
+    weight: f32,
+    mean: f32,
+    variance: f32,
+}
+
+impl Component {
+    fn rank(&self) -> f32 {
+        self.weight / libm::sqrtf(self.variance)
+    }
+}
+
+/// Adaptive background model of Stauffer and Grimson: every pixel is a
+/// mixture of a few Gaussians, and the heaviest, narrowest ones make up the
+/// background.
+///
+/// A pixel alternating between several values, like leaves or a monitor,
+/// becomes background once each value has been seen often enough.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixtureOfGaussians {
+    learning_rate: f32,
+    components: usize,
+    match_sigmas: f32,
+    background_ratio: f32,
+    initial_sigma: f32,
+    size: (usize, usize),
+    pixels: Vec<Component>,
+}
+
+impl MixtureOfGaussians {
+    /// Model updating its weights and Gaussians by `learning_rate` per frame,
+    /// with 3 Gaussians per pixel, matches within 2.5 standard deviations
+    /// and 70 % of the weight counted as background.
+    pub fn new(learning_rate: f32) -> Self {
+        Self {
+            learning_rate,
+            components: 3,
+            match_sigmas: 2.5,
+            background_ratio: 0.7,
+            initial_sigma: 0.1,
+            size: (0, 0),
+            pixels: Vec::new(),
+        }
+    }
+
+    /// Gaussians per pixel; more can model busier backgrounds.
+    pub fn components(mut self, count: usize) -> Self {
+        self.components = count.max(1);
+        self.pixels.clear();
+        self
+    }
+
+    /// Share of the total weight, in `0.0..=1.0`, that the background
+    /// Gaussians must reach.
+    pub fn background_ratio(mut self, ratio: f32) -> Self {
+        self.background_ratio = ratio;
+        self
+    }
+
+    /// Standard deviation of a newly created Gaussian, on the `0.0..=1.0`
+    /// scale of [`SrgbChannel::normalized`].
+    pub fn initial_sigma(mut self, sigma: f32) -> Self {
+        self.initial_sigma = sigma;
+        self
+    }
+
+    fn fresh(&self, value: f32, weight: f32) -> Component {
+        Component {
+            weight,
+            mean: value,
+            variance: self.initial_sigma * self.initial_sigma,
+        }
+    }
+
+    /// Learns `value` into `mixture`, ordered by rank, and tells whether it
+    /// is foreground.
+    fn update(&self, mixture: &mut [Component], value: f32) -> bool {
+        let alpha = self.learning_rate;
+        let matched = mixture.iter().position(|c| {
+            let d = value - c.mean;
+            d * d < self.match_sigmas * self.match_sigmas * c.variance
+        });
+
+        // Background Gaussians are the best ranked ones up to the ratio.
+        let mut total = 0.0;
+        let background = mixture
+            .iter()
+            .take_while(|c| {
+                let below = total < self.background_ratio;
+                total += c.weight;
+                below
+            })
+            .count();
+        let foreground = matched.is_none_or(|k| k >= background);
+
+        for c in mixture.iter_mut() {
+            c.weight *= 1.0 - alpha;
+        }
+        match matched {
+            Some(k) => {
+                let c = &mut mixture[k];
+                let d = value - c.mean;
+                c.weight += alpha;
+                c.mean += alpha * d;
+                c.variance = (c.variance + alpha * (d * d - c.variance)).max(MIN_VARIANCE);
+            }
+            None => {
+                let last = mixture.len() - 1;
+                mixture[last] = self.fresh(value, alpha.max(1e-3));
+            }
+        }
+
+        let sum: f32 = mixture.iter().map(|c| c.weight).sum();
+        for c in mixture.iter_mut() {
+            c.weight /= sum;
+        }
+        mixture.sort_by(|a, b| b.rank().total_cmp(&a.rank()));
+
+        foreground
+    }
+
+    /// Foreground mask of `frame`, `255` for foreground and `0` for
+    /// background, after which `frame` is learned.
+    ///
+    /// The first frame, and the first after a change of size, initializes
+    /// every pixel and has no foreground.
+    pub fn apply<T: SrgbChannel>(&mut self, frame: &ImageBuffer<Gray<T>>) -> ImageBuffer<Gray<u8>> {
+        let (width, height) = (frame.width(), frame.height());
+        let k = self.components;
+
+        if self.size != (width, height) || self.pixels.len() != width * height * k {
+            let empty = Component {
+                weight: 0.0,
+                ..self.fresh(0.0, 0.0)
+            };
+            let mut pixels = Vec::with_capacity(width * height * k);
+            for value in values(frame) {
+                pixels.push(self.fresh(value, 1.0));
+                pixels.extend(core::iter::repeat_n(empty, k - 1));
+            }
+            self.size = (width, height);
+            self.pixels = pixels;
+            return mask(width, height, &alloc::vec![false; width * height]);
+        }
+
+        let mut pixels = core::mem::take(&mut self.pixels);
+        let foreground: Vec<bool> = pixels
+            .chunks_mut(k)
+            .zip(values(frame))
+            .map(|(mixture, value)| self.update(mixture, value))
+            .collect();
+        self.pixels = pixels;
+
+        mask(width, height, &foreground)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{MixtureOfGaussians, RunningAverage};
+    use crate::{Gray, ImageBuffer, Layout};
+
+    /// A textured scene with a bright 3×3 object at `(x, 4)`, if any.
+    fn frame(object: Option<usize>) -> ImageBuffer<Gray<u8>> {
+        ImageBuffer::from_fn(12, 10, Layout::Interleaved, |i, j| {
+            let inside = object.is_some_and(|x| (x..x + 3).contains(&i) && (4..7).contains(&j));
+            Gray(if inside {
+                240
+            } else {
+                (40 + 7 * i + 3 * j) as u8
+            })
+        })
+    }
+
+    fn foreground(mask: &ImageBuffer<Gray<u8>>) -> usize {
+        mask.pixels().filter(|&p| p == Gray(255)).count()
+    }
+
+    #[test]
+    fn running_average_flags_a_new_object() {
+        let mut model = RunningAverage::new(0.1, 0.1);
+        for _ in 0..5 {
+            assert_eq!(foreground(&model.apply(&frame(None))), 0);
+        }
+
+        let mask = model.apply(&frame(Some(2)));
+        assert_eq!(foreground(&mask), 9);
+        assert_eq!(mask.pixel(3, 5), Some(Gray(255)));
+        assert_eq!(mask.pixel(8, 5), Some(Gray(0)));
+    }
+
+    #[test]
+    fn running_average_absorbs_an_object_that_stays() {
+        let mut model = RunningAverage::new(0.5, 0.1);
+        model.apply(&frame(None));
+        let counts: Vec<_> = (0..6)
+            .map(|_| foreground(&model.apply(&frame(Some(5)))))
+            .collect();
+        assert_eq!(counts.first(), Some(&9));
+        assert_eq!(counts.last(), Some(&0));
+    }
+
+    #[test]
+    fn mixture_flags_a_moving_object() {
+        let mut model = MixtureOfGaussians::new(0.05);
+        for _ in 0..10 {
+            model.apply(&frame(None));
+        }
+
+        for x in [1, 4, 7] {
+            let mask = model.apply(&frame(Some(x)));
+            assert_eq!(foreground(&mask), 9);
+            assert_eq!(mask.pixel(x + 1, 5), Some(Gray(255)));
+        }
+    }
+
+    #[test]
+    fn mixture_learns_a_flickering_background() {
+        let flicker = |on: bool| {
+            ImageBuffer::from_fn(4, 4, Layout::Interleaved, move |i, _| {
+                Gray(if i == 0 && on { 0.9f32 } else { 0.2 })
+            })
+        };
+
+        let mut average = RunningAverage::new(0.05, 0.1);
+        let mut mixture = MixtureOfGaussians::new(0.05);
+        for n in 0..200 {
+            average.apply(&flicker(n % 2 == 0));
+            mixture.apply(&flicker(n % 2 == 0));
+        }
+
+        assert_eq!(foreground(&average.apply(&flicker(true))), 4);
+        assert_eq!(foreground(&mixture.apply(&flicker(true))), 0);
+        assert_eq!(foreground(&mixture.apply(&flicker(false))), 0);
+    }
+}
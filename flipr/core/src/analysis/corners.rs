@@ -0,0 +1,269 @@
+use alloc::vec::Vec;
+
+use space::{Offset, Place, Real};
+
+use crate::Image;
+use crate::analysis::edges::{Grad, sobel};
+use crate::arithmetic::Channel;
+use crate::buffer::cell_center;
+use crate::pixel::Gray;
+
+fn unit(n: i8) -> Real {
+    Real::from_f64(n as f64).expect("small integers are finite f64")
+}
+
+/// Harris corner response of a [`Gray`] image, see [`harris_response`].
+#[derive(Debug, Clone)]
+pub struct HarrisResponse<I> {
+    image: I,
+    k: f64,
+}
+
+/// Harris response `det M − k·(trace M)²` of `image` at every place, where `M`
+/// is the structure tensor: the sum of `[[dx², dx·dy], [dx·dy, dy²]]` over
+/// Sobel gradients at the 3×3 unit offsets around the place.
+///
+/// Corners score large and positive, edges negative and flat regions near
+/// zero; `k` is usually between `0.04` and `0.06`.
+pub fn harris_response<I>(image: I, k: f64) -> HarrisResponse<I> {
+    HarrisResponse { image, k }
+}
+
+impl<T, I> Image for HarrisResponse<I>
+where
+    T: Channel,
+    I: Image<Pixel = Gray<T>> + Clone,
+{
+    type Pixel = Gray<f32>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let gradients = sobel(self.image.clone());
+        let (mut xx, mut xy, mut yy) = (0.0, 0.0, 0.0);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let Grad { dx: gx, dy: gy } =
+                    gradients.get(&p + Offset::from_reals(unit(dx), unit(dy)));
+                let (gx, gy) = (gx as f64, gy as f64);
+                xx += gx * gx;
+                xy += gx * gy;
+                yy += gy * gy;
+            }
+        }
+
+        let (det, trace) = (xx * yy - xy * xy, xx + yy);
+        Gray((det - self.k * trace * trace) as f32)
+    }
+}
+
+/// Pixel centers whose score is above `threshold` and not below any of
+/// their 8 neighbors', strongest first.
+fn local_maxima(scores: &[f32], width: usize, height: usize, threshold: f32) -> Vec<(Place, f32)> {
+    let score = |i: usize, j: usize, di: isize, dj: isize| {
+        let (ni, nj) = (i.checked_add_signed(di)?, j.checked_add_signed(dj)?);
+        (ni < width && nj < height).then(|| scores[nj * width + ni])
+    };
+
+    let mut corners: Vec<(Place, f32)> = (0..height)
+        .flat_map(|j| (0..width).map(move |i| (i, j)))
+        .filter(|&(i, j)| {
+            let s = scores[j * width + i];
+            s > threshold
+                && (-1..=1)
+                    .flat_map(|dj| (-1..=1).map(move |di| (di, dj)))
+                    .filter_map(|(di, dj)| score(i, j, di, dj))
+                    .all(|n| n <= s)
+        })
+        .map(|(i, j)| (cell_center(i, j), scores[j * width + i]))
+        .collect();
+    corners.sort_by(|a, b| b.1.total_cmp(&a.1));
+    corners
+}
+
+/// Harris corners of the `width × height` pixel grid of `image`: pixel
+/// centers whose [`harris_response`] exceeds `threshold` and is a local
+/// maximum among its 8 neighbors, with their responses, strongest first.
+pub fn harris_corners<T, I>(
+    image: I,
+    width: usize,
+    height: usize,
+    k: f64,
+    threshold: f32,
+) -> Vec<(Place, f32)>
+where
+    T: Channel,
+    I: Image<Pixel = Gray<T>> + Clone,
+{
+    let response = harris_response(image, k);
+    let scores: Vec<f32> = (0..height)
+        .flat_map(|j| (0..width).map(move |i| (i, j)))
+        .map(|(i, j)| response.get(cell_center(i, j)).0)
+        .collect();
+
+    local_maxima(&scores, width, height, threshold)
+}
+
+/// The 16 pixels at distance 3 on a Bresenham circle, in order around it.
+const CIRCLE: [(i8, i8); 16] = [
+    (0, -3),
+    (1, -3),
+    (2, -2),
+    (3, -1),
+    (3, 0),
+    (3, 1),
+    (2, 2),
+    (1, 3),
+    (0, 3),
+    (-1, 3),
+    (-2, 2),
+    (-3, 1),
+    (-3, 0),
+    (-3, -1),
+    (-2, -2),
+    (-1, -3),
+];
+
+/// Contiguous circle pixels that must all be brighter or all darker.
+const ARC: usize = 9;
+
+/// FAST-9 score of a circle around a pixel of value `center`: the summed
+/// excess over `threshold` of the brighter or darker side, whichever forms an
+/// arc of at least 9 contiguous pixels, or `0` if neither does.
+fn fast_score(center: f64, ring: &[f64; 16], threshold: f64) -> f32 {
+    let side = |sign: f64| {
+        let passes = |k: usize| sign * (ring[k % 16] - center) > threshold;
+        let mut run = 0;
+        let longest = (0..16 + ARC).fold(0, |longest, k| {
+            run = if passes(k) { run + 1 } else { 0 };
+            usize::max(longest, run)
+        });
+        (longest >= ARC).then(|| {
+            (0..16)
+                .filter(|&k| passes(k))
+                .map(|k| (sign * (ring[k] - center) - threshold) as f32)
+                .sum::<f32>()
+        })
+    };
+
+    match (side(1.0), side(-1.0)) {
+        (Some(brighter), Some(darker)) => brighter.max(darker),
+        (brighter, darker) => brighter.or(darker).unwrap_or(0.0),
+    }
+}
+
+/// FAST-9 corners of the `width × height` pixel grid of `image`.
+///
+/// A pixel is a corner if 9 contiguous pixels of the radius-3 circle around
+/// it are all brighter or all darker than it by more than `threshold`, in
+/// channel units. Pixels closer than 3 to the border are skipped, and
+/// corners are thinned to local maxima of their score, strongest first.
+pub fn fast_corners<T, I>(
+    image: I,
+    width: usize,
+    height: usize,
+    threshold: f64,
+) -> Vec<(Place, f32)>
+where
+    T: Channel,
+    I: Image<Pixel = Gray<T>>,
+{
+    let value = |i: usize, j: usize, di: i8, dj: i8| {
+        let p = cell_center(i, j) + Offset::from_reals(unit(di), unit(dj));
+        image.get(p).0.to_f64()
+    };
+
+    let mut scores = Vec::with_capacity(width * height);
+    for j in 0..height {
+        for i in 0..width {
+            let inside = (3..width.saturating_sub(3)).contains(&i)
+                && (3..height.saturating_sub(3)).contains(&j);
+            let score = if inside {
+                let ring = CIRCLE.map(|(di, dj)| value(i, j, di, dj));
+                fast_score(value(i, j, 0, 0), &ring, threshold)
+            } else {
+                0.0
+            };
+            scores.push(score);
+        }
+    }
+
+    local_maxima(&scores, width, height, 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use proptest::{prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::{fast_corners, harris_corners, harris_response};
+    use crate::tests::place;
+    use crate::{Gray, Image, ImageBuffer, Layout, from_fn};
+
+    /// A bright square covering pixels `4..12` on a dark background.
+    fn square() -> ImageBuffer<Gray<u8>> {
+        ImageBuffer::from_fn(16, 16, Layout::Interleaved, |i, j| {
+            Gray(if (4..12).contains(&i) && (4..12).contains(&j) {
+                200
+            } else {
+                10
+            })
+        })
+    }
+
+    fn pixels(corners: &[(Place, f32)]) -> Vec<(f64, f64)> {
+        let mut pixels: Vec<_> = corners
+            .iter()
+            .map(|(p, _)| {
+                (
+                    p.x().to_f64().unwrap().floor(),
+                    p.y().to_f64().unwrap().floor(),
+                )
+            })
+            .collect();
+        pixels.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        pixels
+    }
+
+    proptest! {
+        #[test]
+        fn flat_images_have_no_response(p in place(), v: u8) {
+            prop_assert_eq!(harris_response(from_fn(move |_| Gray(v)), 0.05).get(p), Gray(0.0));
+        }
+    }
+
+    #[test]
+    fn harris_finds_the_corners_of_a_square() {
+        let corners = harris_corners(square(), 16, 16, 0.05, 1e9);
+        assert_eq!(corners.len(), 4, "{corners:?}");
+        for (x, y) in pixels(&corners) {
+            assert!([3.0, 4.0, 11.0, 12.0].contains(&x), "{x}");
+            assert!([3.0, 4.0, 11.0, 12.0].contains(&y), "{y}");
+        }
+        assert!(corners.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn harris_scores_edges_below_zero() {
+        let edge = harris_response(square(), 0.05).get(Place::new(8.5, 4.5).unwrap());
+        assert!(edge.0 < 0.0);
+    }
+
+    #[test]
+    fn fast_finds_the_corners_of_a_square() {
+        let corners = fast_corners(square(), 16, 16, 50.0);
+        assert_eq!(
+            pixels(&corners),
+            [(4.0, 4.0), (4.0, 11.0), (11.0, 4.0), (11.0, 11.0)]
+        );
+    }
+
+    #[test]
+    fn fast_ignores_edges_and_faint_corners() {
+        assert!(fast_corners(square(), 16, 16, 250.0).is_empty());
+        let stripe = ImageBuffer::from_fn(16, 16, Layout::Planar, |i, _| {
+            Gray(if i < 8 { 0u8 } else { 255 })
+        });
+        assert!(fast_corners(stripe, 16, 16, 50.0).is_empty());
+    }
+}
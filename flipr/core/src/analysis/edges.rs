@@ -0,0 +1,277 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use space::{Offset, Place, Real};
+
+use crate::Image;
+use crate::arithmetic::Channel;
+use crate::buffer::{ImageBuffer, Layout, cell_center};
+use crate::pixel::Gray;
+
+/// Image derivative at a place, as produced by [`sobel`] and [`scharr`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Grad {
+    pub dx: f32,
+    pub dy: f32,
+}
+
+impl Grad {
+    pub fn magnitude(self) -> f32 {
+        libm::hypotf(self.dx, self.dy)
+    }
+}
+
+/// 3×3 derivative kernel weights: `[side, center]` of the differencing column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Weights(f32, f32);
+
+const SOBEL: Weights = Weights(1.0, 2.0);
+const SCHARR: Weights = Weights(3.0, 10.0);
+
+/// Gradient of a [`Gray`] image estimated with a 3×3 kernel over unit steps.
+#[derive(Debug, Clone)]
+pub struct Gradient<I> {
+    image: I,
+    weights: Weights,
+}
+
+/// Horizontal and vertical Sobel derivatives of `image`.
+pub fn sobel<I>(image: I) -> Gradient<I> {
+    Gradient {
+        image,
+        weights: SOBEL,
+    }
+}
+
+/// Horizontal and vertical Scharr derivatives of `image`, which are more
+/// rotationally symmetric than [`sobel`].
+pub fn scharr<I>(image: I) -> Gradient<I> {
+    Gradient {
+        image,
+        weights: SCHARR,
+    }
+}
+
+fn unit(n: i8) -> Real {
+    Real::from_f64(n as f64).expect("small integers are finite f64")
+}
+
+impl<T, I> Image for Gradient<I>
+where
+    T: Channel,
+    I: Image<Pixel = Gray<T>>,
+{
+    type Pixel = Grad;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let at = |dx: i8, dy: i8| {
+            let Gray(v) = self.image.get(&p + Offset::from_reals(unit(dx), unit(dy)));
+            v.to_f64() as f32
+        };
+        let Weights(side, center) = self.weights;
+
+        let dx = side * (at(1, -1) - at(-1, -1))
+            + center * (at(1, 0) - at(-1, 0))
+            + side * (at(1, 1) - at(-1, 1));
+        let dy = side * (at(-1, 1) - at(-1, -1))
+            + center * (at(0, 1) - at(0, -1))
+            + side * (at(1, 1) - at(1, -1));
+
+        Grad { dx, dy }
+    }
+}
+
+/// Length of every gradient as a [`Gray`] image, see [`gradient_magnitude`].
+#[derive(Debug, Clone)]
+pub struct Magnitude<I>(I);
+
+/// Turns an image of [`Grad`]s into their Euclidean lengths.
+pub fn gradient_magnitude<I>(gradients: I) -> Magnitude<I>
+where
+    I: Image<Pixel = Grad>,
+{
+    Magnitude(gradients)
+}
+
+impl<I> Image for Magnitude<I>
+where
+    I: Image<Pixel = Grad>,
+{
+    type Pixel = Gray<f32>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        Gray(self.0.get(p).magnitude())
+    }
+}
+
+/// Neighbor offsets along the gradient direction, quantized to 45° steps.
+fn across_edge(g: Grad) -> (isize, isize) {
+    let angle = libm::atan2f(g.dy, g.dx).to_degrees();
+    let angle = if angle < 0.0 { angle + 180.0 } else { angle };
+
+    match angle {
+        a if !(22.5..157.5).contains(&a) => (1, 0),
+        a if a < 67.5 => (1, 1),
+        a if a < 112.5 => (0, 1),
+        _ => (-1, 1),
+    }
+}
+
+/// Canny edge detector over the `width × height` pixel grid of `image`.
+///
+/// Computes Sobel gradients at pixel centers, thins them with non-maximum
+/// suppression and keeps pixels above `high`, plus pixels above `low` that
+/// are 8-connected to them. Edges are `255`, everything else `0`. The input
+/// is not smoothed, so noisy images should be blurred first.
+///
+/// # Panics
+///
+/// Panics if `width` or `height` is zero.
+pub fn canny<T, I>(
+    image: I,
+    width: usize,
+    height: usize,
+    low: f32,
+    high: f32,
+) -> ImageBuffer<Gray<u8>>
+where
+    T: Channel,
+    I: Image<Pixel = Gray<T>>,
+{
+    assert!(width > 0 && height > 0, "canny needs at least one pixel");
+
+    let gradients = sobel(image);
+    let grads: Vec<Grad> = (0..height)
+        .flat_map(|j| (0..width).map(move |i| (i, j)))
+        .map(|(i, j)| gradients.get(cell_center(i, j)))
+        .collect();
+    let magnitude = |i: isize, j: isize| {
+        let inside = (0..width as isize).contains(&i) && (0..height as isize).contains(&j);
+        if inside {
+            grads[j as usize * width + i as usize].magnitude()
+        } else {
+            0.0
+        }
+    };
+
+    let mut strength = vec![0u8; width * height];
+    for j in 0..height {
+        for i in 0..width {
+            let g = grads[j * width + i];
+            let m = g.magnitude();
+            let (di, dj) = across_edge(g);
+            let (i, j) = (i as isize, j as isize);
+
+            // Ties along a plateau keep only the last pixel, so edges stay one pixel wide.
+            if m >= low && m > magnitude(i + di, j + dj) && m >= magnitude(i - di, j - dj) {
+                strength[j as usize * width + i as usize] = if m >= high { 2 } else { 1 };
+            }
+        }
+    }
+
+    let mut edges = vec![false; width * height];
+    let mut pending: Vec<usize> = (0..width * height).filter(|&k| strength[k] == 2).collect();
+    while let Some(k) = pending.pop() {
+        if edges[k] {
+            continue;
+        }
+        edges[k] = true;
+
+        let (i, j) = ((k % width) as isize, (k / width) as isize);
+        for (di, dj) in [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ] {
+            let (ni, nj) = (i + di, j + dj);
+            if (0..width as isize).contains(&ni) && (0..height as isize).contains(&nj) {
+                let n = nj as usize * width + ni as usize;
+                if strength[n] > 0 && !edges[n] {
+                    pending.push(n);
+                }
+            }
+        }
+    }
+
+    ImageBuffer::from_fn(width, height, Layout::Interleaved, |i, j| {
+        Gray(if edges[j * width + i] { u8::MAX } else { 0 })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::{Grad, canny, gradient_magnitude, scharr, sobel};
+    use crate::tests::place;
+    use crate::{Gray, Image, from_fn};
+
+    fn ramp(slope_x: f64, slope_y: f64) -> impl Image<Pixel = Gray<f64>> + Clone {
+        from_fn(move |p: Place| {
+            Gray(p.x().to_f64().unwrap() * slope_x + p.y().to_f64().unwrap() * slope_y)
+        })
+    }
+
+    fn step_at(x: f64) -> impl Image<Pixel = Gray<u8>> + Clone {
+        from_fn(move |p: Place| Gray(if p.x().to_f64().unwrap() < x { 0 } else { 200 }))
+    }
+
+    proptest! {
+        #[test]
+        fn flat_image_has_no_gradient(p in place(), v: u8) {
+            prop_assert_eq!(sobel(from_fn(move |_| Gray(v))).get(p), Grad::default());
+        }
+    }
+
+    #[test]
+    fn kernels_measure_linear_ramps() {
+        let p = Place::new(3.0, -2.0).unwrap();
+        assert_eq!(
+            sobel(ramp(1.0, 0.0)).get(p.clone()),
+            Grad { dx: 8.0, dy: 0.0 }
+        );
+        assert_eq!(
+            sobel(ramp(0.0, 0.5)).get(p.clone()),
+            Grad { dx: 0.0, dy: 4.0 }
+        );
+        assert_eq!(scharr(ramp(1.0, 0.0)).get(p), Grad { dx: 32.0, dy: 0.0 });
+    }
+
+    #[test]
+    fn magnitude_is_euclidean_length() {
+        let magnitude = gradient_magnitude(sobel(ramp(3.0, 4.0)));
+        assert_eq!(magnitude.get(Place::origin()), Gray(40.0));
+    }
+
+    #[test]
+    fn canny_finds_a_thin_vertical_edge() {
+        let edges = canny(step_at(4.0), 8, 5, 100.0, 400.0);
+        for j in 0..5 {
+            let row: Vec<u8> = (0..8).map(|i| edges.pixel(i, j).unwrap().0).collect();
+            assert_eq!(
+                row.iter().filter(|&&v| v == 255).count(),
+                1,
+                "row {j}: {row:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn canny_ignores_weak_edges_not_connected_to_strong_ones() {
+        let weak = from_fn(|p: Place| {
+            Gray(if p.x().to_f64().unwrap() < 4.0 {
+                0u8
+            } else {
+                20
+            })
+        });
+        let edges = canny(weak, 8, 5, 10.0, 400.0);
+        assert!((0..5).all(|j| (0..8).all(|i| edges.pixel(i, j) == Some(Gray(0)))));
+    }
+}
@@ -0,0 +1,192 @@
+use alloc::vec::Vec;
+
+use space::{Offset, Place};
+
+use crate::Image;
+use crate::arithmetic::Channel;
+use crate::noise::splitmix;
+use crate::pixel::Gray;
+
+/// Radius of the patch around a keypoint that [`describe`] samples.
+pub const PATCH_RADIUS: f64 = 15.0;
+
+/// 256-bit binary descriptor of the patch around a keypoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Descriptor(pub [u64; 4]);
+
+impl Descriptor {
+    /// Number of differing bits, the distance used by [`match_features`].
+    pub fn hamming(&self, other: &Descriptor) -> u32 {
+        self.0
+            .iter()
+            .zip(other.0)
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum()
+    }
+}
+
+/// Keypoint with its orientation and [`Descriptor`], see [`describe`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    pub place: Place,
+    /// Direction from the keypoint to the intensity centroid of its patch, in
+    /// radians from the x axis toward the y axis.
+    pub angle: f64,
+    pub descriptor: Descriptor,
+}
+
+/// Fixed offset of test point `n` of the pattern, within the patch radius and
+/// denser toward the center.
+fn pattern_point(n: u64) -> (f64, f64) {
+    let unit = |stream: u64| (splitmix(n * 4 + stream) >> 11) as f64 / (1u64 << 53) as f64;
+    let (x, y) = (unit(0) + unit(1) - 1.0, unit(2) + unit(3) - 1.0);
+    let scale = PATCH_RADIUS / libm::hypot(1.0, 1.0);
+    (x * scale, y * scale)
+}
+
+fn offset(x: f64, y: f64) -> Offset {
+    Offset::new(x, y).expect("patch offsets are finite")
+}
+
+/// Oriented BRIEF (ORB-style) features of `image` at `keypoints`, for example
+/// the places found by [`harris_corners`](super::harris_corners) or
+/// [`fast_corners`](super::fast_corners).
+///
+/// Each patch is oriented by its intensity centroid, then described by 256
+/// intensity comparisons between fixed pairs of points rotated by that angle,
+/// so descriptors survive rotation. Single samples are compared, so noisy
+/// images should be blurred first.
+pub fn describe<T, I>(image: &I, keypoints: impl IntoIterator<Item = Place>) -> Vec<Feature>
+where
+    T: Channel,
+    I: Image<Pixel = Gray<T>>,
+{
+    let radius = PATCH_RADIUS as i32;
+    keypoints
+        .into_iter()
+        .map(|place| {
+            let at = |x: f64, y: f64| image.get(&place + offset(x, y)).0.to_f64();
+
+            let (mut m10, mut m01) = (0.0, 0.0);
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    if dx * dx + dy * dy <= radius * radius {
+                        let v = at(dx as f64, dy as f64);
+                        m10 += dx as f64 * v;
+                        m01 += dy as f64 * v;
+                    }
+                }
+            }
+            let angle = libm::atan2(m01, m10);
+            let (sin, cos) = libm::sincos(angle);
+            let rotated = |(x, y): (f64, f64)| at(x * cos - y * sin, x * sin + y * cos);
+
+            let mut bits = [0u64; 4];
+            for n in 0..256 {
+                if rotated(pattern_point(2 * n)) < rotated(pattern_point(2 * n + 1)) {
+                    bits[n as usize / 64] |= 1 << (n % 64);
+                }
+            }
+
+            Feature {
+                place,
+                angle,
+                descriptor: Descriptor(bits),
+            }
+        })
+        .collect()
+}
+
+/// Matches every feature of `from` to its nearest feature of `to` by Hamming
+/// distance, brute force.
+///
+/// A match is kept only if its distance is below `ratio` times the distance
+/// to the second nearest feature (Lowe's ratio test, usually `0.7..0.8`),
+/// which drops ambiguous matches. Returns the matched places, ready to fit a
+/// transform from `from` to `to`.
+pub fn match_features(from: &[Feature], to: &[Feature], ratio: f64) -> Vec<(Place, Place)> {
+    from.iter()
+        .filter_map(|feature| {
+            let (mut best, mut second) = ((u32::MAX, None), u32::MAX);
+            for candidate in to {
+                let distance = feature.descriptor.hamming(&candidate.descriptor);
+                if distance < best.0 {
+                    second = best.0;
+                    best = (distance, Some(candidate));
+                } else if distance < second {
+                    second = distance;
+                }
+            }
+
+            let (distance, candidate) = best;
+            let distinct = second == u32::MAX || (distance as f64) < ratio * second as f64;
+            candidate
+                .filter(|_| distinct)
+                .map(|c| (feature.place.clone(), c.place.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::f64::consts::FRAC_PI_3;
+
+    use space::Place;
+
+    use super::{Descriptor, describe, match_features};
+    use crate::{AffineTransform, Gray, Image, Noise, noise};
+
+    fn texture() -> impl Image<Pixel = Gray<f32>> + Clone {
+        noise(Noise::Value { scale: 6.0 }, 7)
+    }
+
+    fn places() -> Vec<Place> {
+        [(10.0, 10.0), (40.0, 15.0), (25.0, 50.0), (70.0, 60.0)]
+            .map(|(x, y)| Place::new(x, y).unwrap())
+            .into()
+    }
+
+    #[test]
+    fn hamming_counts_differing_bits() {
+        let a = Descriptor([0b1011, 0, u64::MAX, 1]);
+        let b = Descriptor([0b0001, 0, 0, 1]);
+        assert_eq!(a.hamming(&b), 66);
+        assert_eq!(a.hamming(&a), 0);
+    }
+
+    #[test]
+    fn descriptors_survive_rotation() {
+        let center = Place::new(30.0, 30.0).unwrap();
+        let rotation = AffineTransform::rotation_about(&center, FRAC_PI_3);
+        let rotated = texture().transform(rotation);
+        let moved: Vec<_> = places()
+            .iter()
+            .map(|p| rotation.transform_place(p).unwrap())
+            .collect();
+
+        let before = describe(&texture(), places());
+        let after = describe(&rotated, moved);
+        for (a, b) in before.iter().zip(&after) {
+            assert!(a.descriptor.hamming(&b.descriptor) < 32, "{a:?} {b:?}");
+        }
+    }
+
+    #[test]
+    fn matches_follow_a_translation() {
+        let shift = AffineTransform::translation(5.0, -3.0);
+        let moved: Vec<_> = places()
+            .iter()
+            .map(|p| shift.transform_place(p).unwrap())
+            .collect();
+
+        let from = describe(&texture(), places());
+        let to = describe(&texture().transform(shift), moved.iter().rev().cloned());
+        let matches = match_features(&from, &to, 0.8);
+
+        assert_eq!(matches.len(), 4);
+        for (a, b) in matches {
+            assert_eq!(shift.transform_place(&a), Some(b));
+        }
+    }
+}
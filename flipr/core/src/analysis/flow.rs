@@ -0,0 +1,333 @@
+use alloc::vec::Vec;
+
+use space::{Offset, Place};
+
+use crate::Image;
+use crate::arithmetic::Channel;
+use crate::buffer::{ImageBuffer, Layout};
+use crate::pixel::{Gray, MultiChannelPixel, Rgb};
+use crate::pyramid::{DownsampleFilter, GaussianPyramid};
+
+/// Motion `[dx, dy]` of a pixel between two frames, as stored by [`dense_flow`].
+pub type Flow = MultiChannelPixel<f32, 2>;
+
+/// Settings of the pyramidal Lucas–Kanade tracker behind [`optical_flow`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LucasKanade {
+    /// Half the side of the square window whose pixels must move together.
+    pub radius: usize,
+    /// Pyramid levels; every level doubles the largest motion that can be
+    /// found, roughly `radius · 2^(levels − 1)` pixels.
+    pub levels: usize,
+    /// Refinement steps per level.
+    pub iterations: usize,
+}
+
+impl Default for LucasKanade {
+    fn default() -> Self {
+        Self {
+            radius: 7,
+            levels: 3,
+            iterations: 10,
+        }
+    }
+}
+
+/// One pyramid level as plain values, sampled bilinearly in level pixels.
+struct Level {
+    values: Vec<f32>,
+    width: usize,
+    height: usize,
+}
+
+impl Level {
+    fn new(buffer: &ImageBuffer<Gray<f32>>) -> Self {
+        Self {
+            values: buffer.pixels().map(|Gray(v)| v).collect(),
+            width: buffer.width(),
+            height: buffer.height(),
+        }
+    }
+
+    /// Value at `(x, y)`, with pixel centers at half-integers and the edge
+    /// pixels repeated outside.
+    fn at(&self, x: f32, y: f32) -> f32 {
+        let axis = |u: f32, len: usize| {
+            let u = (u - 0.5).clamp(0.0, (len - 1) as f32);
+            let lo = u as usize;
+            (lo, (lo + 1).min(len - 1), u - lo as f32)
+        };
+        let (x0, x1, tx) = axis(x, self.width);
+        let (y0, y1, ty) = axis(y, self.height);
+        let v = |i: usize, j: usize| self.values[j * self.width + i];
+
+        let top = v(x0, y0) + (v(x1, y0) - v(x0, y0)) * tx;
+        let bottom = v(x0, y1) + (v(x1, y1) - v(x0, y1)) * tx;
+        top + (bottom - top) * ty
+    }
+}
+
+fn levels<T, I>(image: &I, width: usize, height: usize, count: usize) -> Vec<Level>
+where
+    T: Channel,
+    I: Image<Pixel = Gray<T>>,
+{
+    let float = crate::from_fn(|p: Place| Gray(image.get(p).0.to_f64() as f32));
+    GaussianPyramid::new(&float, width, height, count, DownsampleFilter::Binomial)
+        .levels()
+        .iter()
+        .map(Level::new)
+        .collect()
+}
+
+/// Refines the motion `guess` of the window around `(x, y)` from `previous`
+/// to `next`, or `None` if the window has too little texture to tell.
+fn track(
+    previous: &Level,
+    next: &Level,
+    (x, y): (f32, f32),
+    guess: (f32, f32),
+    settings: &LucasKanade,
+) -> Option<(f32, f32)> {
+    let r = settings.radius as i32;
+    let window: Vec<(f32, f32, f32, f32, f32)> = (-r..=r)
+        .flat_map(|dy| (-r..=r).map(move |dx| (x + dx as f32, y + dy as f32)))
+        .map(|(u, v)| {
+            let ix = (previous.at(u + 1.0, v) - previous.at(u - 1.0, v)) / 2.0;
+            let iy = (previous.at(u, v + 1.0) - previous.at(u, v - 1.0)) / 2.0;
+            (u, v, previous.at(u, v), ix, iy)
+        })
+        .collect();
+
+    let (mut gxx, mut gxy, mut gyy) = (0.0, 0.0, 0.0);
+    for &(_, _, _, ix, iy) in &window {
+        gxx += ix * ix;
+        gxy += ix * iy;
+        gyy += iy * iy;
+    }
+    // The smaller eigenvalue of the structure tensor; a flat window or a
+    // straight edge cannot pin down the motion.
+    let trace = gxx + gyy;
+    let min_eigenvalue = (trace - libm::sqrtf((gxx - gyy) * (gxx - gyy) + 4.0 * gxy * gxy)) / 2.0;
+    if min_eigenvalue < 1e-4 * window.len() as f32 {
+        return None;
+    }
+    let det = gxx * gyy - gxy * gxy;
+
+    let mut flow = guess;
+    for _ in 0..settings.iterations {
+        let (mut bx, mut by) = (0.0, 0.0);
+        for &(u, v, value, ix, iy) in &window {
+            let difference = value - next.at(u + flow.0, v + flow.1);
+            bx += difference * ix;
+            by += difference * iy;
+        }
+
+        let step = ((gyy * bx - gxy * by) / det, (gxx * by - gxy * bx) / det);
+        flow = (flow.0 + step.0, flow.1 + step.1);
+        if step.0 * step.0 + step.1 * step.1 < 1e-4 {
+            break;
+        }
+    }
+    (flow.0.is_finite() && flow.1.is_finite()).then_some(flow)
+}
+
+/// Tracks `(x, y)` from the coarsest level down, doubling the motion found
+/// on each level into the guess for the next. Coarse levels too flat to
+/// track keep the current guess; only the finest level must succeed.
+fn track_pyramid(
+    previous: &[Level],
+    next: &[Level],
+    (x, y): (f32, f32),
+    settings: &LucasKanade,
+) -> Option<(f32, f32)> {
+    let mut guess = (0.0, 0.0);
+    for (level, (previous, next)) in previous.iter().zip(next).enumerate().skip(1).rev() {
+        let scale = (1 << level) as f32;
+        let flow = track(previous, next, (x / scale, y / scale), guess, settings).unwrap_or(guess);
+        guess = (flow.0 * 2.0, flow.1 * 2.0);
+    }
+    track(&previous[0], &next[0], (x, y), guess, settings)
+}
+
+/// Sparse Lucas–Kanade optical flow: how far each of `points` moved from
+/// `previous` to `next`, both sampled on a `width × height` pixel grid.
+///
+/// Each point is followed by the window of `2·radius + 1` pixels around it,
+/// coarse to fine through Gaussian pyramids, so motions larger than the
+/// window are found too. A point is `None` where its window is too flat or
+/// one-dimensional to track, such as on a straight edge.
+///
+/// # Panics
+///
+/// Panics if `width`, `height` or `settings.levels` is zero.
+pub fn optical_flow<T, I, J>(
+    previous: &I,
+    next: &J,
+    width: usize,
+    height: usize,
+    points: &[Place],
+    settings: &LucasKanade,
+) -> Vec<Option<Offset>>
+where
+    T: Channel,
+    I: Image<Pixel = Gray<T>>,
+    J: Image<Pixel = Gray<T>>,
+{
+    let (from, to) = (
+        levels(previous, width, height, settings.levels),
+        levels(next, width, height, settings.levels),
+    );
+
+    points
+        .iter()
+        .map(|p| {
+            let (x, y) = (p.x().to_f64()? as f32, p.y().to_f64()? as f32);
+            let (dx, dy) = track_pyramid(&from, &to, (x, y), settings)?;
+            Offset::new(dx as f64, dy as f64)
+        })
+        .collect()
+}
+
+/// Dense optical flow: the [`optical_flow`] of every pixel center, with zero
+/// motion where it cannot be tracked.
+///
+/// Render it with [`flow_colors`].
+pub fn dense_flow<T, I, J>(
+    previous: &I,
+    next: &J,
+    width: usize,
+    height: usize,
+    settings: &LucasKanade,
+) -> ImageBuffer<Flow>
+where
+    T: Channel,
+    I: Image<Pixel = Gray<T>>,
+    J: Image<Pixel = Gray<T>>,
+{
+    let (from, to) = (
+        levels(previous, width, height, settings.levels),
+        levels(next, width, height, settings.levels),
+    );
+
+    ImageBuffer::from_fn(width, height, Layout::Interleaved, |i, j| {
+        let center = (i as f32 + 0.5, j as f32 + 0.5);
+        let (dx, dy) = track_pyramid(&from, &to, center, settings).unwrap_or((0.0, 0.0));
+        MultiChannelPixel([dx, dy])
+    })
+}
+
+/// Color coding of a flow field, see [`flow_colors`].
+#[derive(Debug, Clone)]
+pub struct FlowColors<I> {
+    flow: I,
+    max_motion: f32,
+}
+
+/// Shows every [`Flow`] as a color: the hue is the direction of motion and
+/// the saturation its length relative to `max_motion`, so still pixels are
+/// white.
+pub fn flow_colors<I>(flow: I, max_motion: f32) -> FlowColors<I>
+where
+    I: Image<Pixel = Flow>,
+{
+    FlowColors { flow, max_motion }
+}
+
+impl<I> Image for FlowColors<I>
+where
+    I: Image<Pixel = Flow>,
+{
+    type Pixel = Rgb<f32>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let MultiChannelPixel([dx, dy]) = self.flow.get(p);
+        let saturation = (libm::hypotf(dx, dy) / self.max_motion).clamp(0.0, 1.0);
+        let hue = (libm::atan2f(dy, dx).to_degrees() + 360.0) % 360.0 / 60.0;
+
+        let channel = |n: f32| {
+            let k = (n + hue) % 6.0;
+            1.0 - saturation * (k.min(4.0 - k)).clamp(0.0, 1.0)
+        };
+        Rgb::new(channel(5.0), channel(3.0), channel(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use space::{Offset, Place};
+
+    use super::{LucasKanade, dense_flow, flow_colors, optical_flow};
+    use crate::{
+        AffineTransform, Gray, Image, ImageBuffer, Layout, MultiChannelPixel, Noise, Rgb, noise,
+    };
+
+    fn texture() -> impl Image<Pixel = Gray<f32>> + Clone {
+        noise(Noise::Value { scale: 6.0 }, 11)
+    }
+
+    fn moved(dx: f64, dy: f64) -> impl Image<Pixel = Gray<f32>> + Clone {
+        texture().transform(AffineTransform::translation(dx, dy))
+    }
+
+    fn points() -> Vec<Place> {
+        [(20.5, 20.5), (40.0, 25.0), (30.5, 44.0)]
+            .map(|(x, y)| Place::new(x, y).unwrap())
+            .into()
+    }
+
+    fn close(offset: &Offset, dx: f64, dy: f64, tolerance: f64) -> bool {
+        let (x, y) = (offset.dx().to_f64().unwrap(), offset.dy().to_f64().unwrap());
+        (x - dx).abs() < tolerance && (y - dy).abs() < tolerance
+    }
+
+    #[test]
+    fn small_motions_are_found_without_a_pyramid() {
+        let settings = LucasKanade {
+            levels: 1,
+            ..LucasKanade::default()
+        };
+        let flow = optical_flow(&texture(), &moved(1.5, -0.5), 64, 64, &points(), &settings);
+        for offset in flow {
+            assert!(close(&offset.unwrap(), 1.5, -0.5, 0.1));
+        }
+    }
+
+    #[test]
+    fn pyramids_find_large_motions() {
+        let settings = LucasKanade::default();
+        let flow = optical_flow(&texture(), &moved(9.0, 6.0), 64, 64, &points(), &settings);
+        for offset in flow {
+            assert!(close(&offset.unwrap(), 9.0, 6.0, 0.25));
+        }
+    }
+
+    #[test]
+    fn flat_windows_cannot_be_tracked() {
+        let flat = crate::from_fn(|_| Gray(0.5f32));
+        let flow = optical_flow(&flat, &flat, 32, 32, &points(), &LucasKanade::default());
+        assert!(flow.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn dense_flow_is_color_coded_by_direction() {
+        let settings = LucasKanade {
+            levels: 2,
+            ..LucasKanade::default()
+        };
+        let field = dense_flow(&texture(), &moved(2.0, 0.0), 40, 40, &settings);
+        let MultiChannelPixel([dx, dy]) = field.pixel(20, 20).unwrap();
+        assert!((dx - 2.0).abs() < 0.1 && dy.abs() < 0.1);
+
+        let still = ImageBuffer::filled(2, 2, Layout::Interleaved, MultiChannelPixel([0.0, 0.0]));
+        let right = ImageBuffer::filled(2, 2, Layout::Interleaved, MultiChannelPixel([4.0, 0.0]));
+        let p = Place::new(1.0, 1.0).unwrap();
+        assert_eq!(
+            flow_colors(still, 4.0).get(p.clone()),
+            Rgb::new(1.0, 1.0, 1.0)
+        );
+        assert_eq!(flow_colors(right, 4.0).get(p), Rgb::new(1.0, 0.0, 0.0));
+    }
+}
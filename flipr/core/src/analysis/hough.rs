@@ -0,0 +1,250 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use space::Place;
+
+use crate::arithmetic::Channel;
+use crate::buffer::{ImageBuffer, Layout, cell_center};
+use crate::pixel::Gray;
+
+/// Angle bins of [`hough_space`], one per degree of `0° ≤ θ < 180°`.
+pub const HOUGH_ANGLES: usize = 180;
+
+/// Straight line `x·cos θ + y·sin θ = ρ` found by [`hough_lines`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Line {
+    /// Signed distance of the line from the origin, to the nearest unit.
+    pub rho: f64,
+    /// Direction of the line's normal in radians, `0.0 ≤ θ < π`, to the
+    /// nearest degree.
+    pub theta: f64,
+    /// Edge pixels on the line.
+    pub votes: u32,
+}
+
+/// Circle found by [`hough_circles`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circle {
+    /// Center, at a pixel center.
+    pub center: Place,
+    pub radius: usize,
+    /// Edge pixels on the circle.
+    pub votes: u32,
+}
+
+/// Centers of the edge pixels of `edges`, nonzero meaning edge.
+fn edge_pixels<T: Channel>(edges: &ImageBuffer<Gray<T>>) -> Vec<(usize, usize)> {
+    let width = edges.width();
+    edges
+        .pixels()
+        .enumerate()
+        .filter(|(_, Gray(v))| v.to_f64() != 0.0)
+        .map(|(k, _)| (k % width, k / width))
+        .collect()
+}
+
+/// Largest `|ρ|` a line through the buffer can have, rounded up.
+fn max_rho(width: usize, height: usize) -> usize {
+    libm::ceil(libm::hypot(width as f64, height as f64)) as usize
+}
+
+/// Indices of the cells of the `width × height × depth` accumulator `votes`
+/// with at least `threshold` votes and no more than any of their up to 26
+/// neighbors, most votes first. Of equal neighbors only the first counts.
+fn peaks(votes: &[u32], [width, height, depth]: [usize; 3], threshold: u32) -> Vec<usize> {
+    let cell = |i: usize, j: usize, k: usize| (k * height + j) * width + i;
+    let mut peaks = Vec::new();
+    for k in 0..depth {
+        for j in 0..height {
+            for i in 0..width {
+                let index = cell(i, j, k);
+                let v = votes[index];
+                if v < threshold || v == 0 {
+                    continue;
+                }
+
+                let near = |n: usize, len: usize| n.saturating_sub(1)..=(n + 1).min(len - 1);
+                let beaten = near(k, depth).any(|nk| {
+                    near(j, height).any(|nj| {
+                        near(i, width).any(|ni| {
+                            let n = cell(ni, nj, nk);
+                            votes[n] > v || (votes[n] == v && n < index)
+                        })
+                    })
+                });
+                if !beaten {
+                    peaks.push(index);
+                }
+            }
+        }
+    }
+    peaks.sort_by(|&a, &b| votes[b].cmp(&votes[a]));
+    peaks
+}
+
+/// Hough accumulator of the lines through the edge pixels of `edges`, which
+/// are nonzero, such as the output of [`canny`](super::canny).
+///
+/// Column `n` holds the angle `θ = n°` and row `m` the distance
+/// `ρ = m − ⌈√(width² + height²)⌉`; every edge pixel center adds one vote
+/// to each line through it. Bright spots are lines, which makes the
+/// accumulator worth looking at when tuning [`hough_lines`].
+pub fn hough_space<T: Channel>(edges: &ImageBuffer<Gray<T>>) -> ImageBuffer<Gray<u32>> {
+    let offset = max_rho(edges.width(), edges.height());
+    let rows = 2 * offset + 1;
+    let angles: Vec<(f64, f64)> = (0..HOUGH_ANGLES)
+        .map(|n| libm::sincos((n as f64).to_radians()))
+        .collect();
+
+    let mut votes = alloc::vec![0u32; HOUGH_ANGLES * rows];
+    for (i, j) in edge_pixels(edges) {
+        let (x, y) = (i as f64 + 0.5, j as f64 + 0.5);
+        for (n, &(sin, cos)) in angles.iter().enumerate() {
+            let rho = libm::round(x * cos + y * sin) as isize + offset as isize;
+            votes[rho as usize * HOUGH_ANGLES + n] += 1;
+        }
+    }
+    ImageBuffer::from_fn(HOUGH_ANGLES, rows, Layout::Interleaved, |n, m| {
+        Gray(votes[m * HOUGH_ANGLES + n])
+    })
+}
+
+/// Straight lines through at least `threshold` edge pixels of `edges`,
+/// strongest first; the peaks of [`hough_space`], with nearly equal
+/// lines merged.
+pub fn hough_lines<T: Channel>(edges: &ImageBuffer<Gray<T>>, threshold: u32) -> Vec<Line> {
+    let offset = max_rho(edges.width(), edges.height()) as f64;
+    let space = hough_space(edges);
+    let votes: Vec<u32> = space.pixels().map(|Gray(v)| v).collect();
+
+    let mut lines: Vec<Line> = Vec::new();
+    for index in peaks(&votes, [HOUGH_ANGLES, space.height(), 1], threshold) {
+        let (n, rho) = (index % HOUGH_ANGLES, (index / HOUGH_ANGLES) as f64 - offset);
+        // A line whose rounding smeared it over neighboring bins, or that
+        // wraps around from θ = 180° with ρ negated, counts once.
+        let duplicate = lines.iter().any(|line| {
+            let m = libm::round(line.theta.to_degrees()) as usize;
+            let (gap, wrap) = (n.abs_diff(m), HOUGH_ANGLES - n.abs_diff(m));
+            (gap <= 2 && (rho - line.rho).abs() <= 2.0)
+                || (wrap <= 2 && (rho + line.rho).abs() <= 2.0)
+        });
+        if !duplicate {
+            lines.push(Line {
+                rho,
+                theta: (n as f64).to_radians(),
+                votes: votes[index],
+            });
+        }
+    }
+    lines
+}
+
+/// Pixel offsets of a circle of `radius` around the origin, each once.
+fn circle_offsets(radius: usize) -> Vec<(isize, isize)> {
+    let steps = 8 * radius.max(1);
+    let mut offsets: Vec<(isize, isize)> = (0..steps)
+        .map(|s| {
+            let (sin, cos) = libm::sincos(core::f64::consts::TAU * s as f64 / steps as f64);
+            let r = radius as f64;
+            (libm::round(r * cos) as isize, libm::round(r * sin) as isize)
+        })
+        .collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+    offsets
+}
+
+/// Circles with a radius in `radii` through at least `threshold` edge
+/// pixels of `edges`, which are nonzero, strongest first.
+///
+/// Every edge pixel votes for the centers of all circles of each radius
+/// through it, so the cost grows with the number of edge pixels times the
+/// sum of the radii. Centers are pixel centers inside the buffer.
+pub fn hough_circles<T: Channel>(
+    edges: &ImageBuffer<Gray<T>>,
+    radii: RangeInclusive<usize>,
+    threshold: u32,
+) -> Vec<Circle> {
+    let (width, height) = (edges.width(), edges.height());
+    let radii: Vec<usize> = radii.collect();
+    let points = edge_pixels(edges);
+
+    let mut votes = alloc::vec![0u32; width * height * radii.len()];
+    for (k, &radius) in radii.iter().enumerate() {
+        let offsets = circle_offsets(radius);
+        for &(i, j) in &points {
+            for &(di, dj) in &offsets {
+                if let (Some(ci), Some(cj)) = (i.checked_add_signed(di), j.checked_add_signed(dj))
+                    && ci < width
+                    && cj < height
+                {
+                    votes[(k * height + cj) * width + ci] += 1;
+                }
+            }
+        }
+    }
+
+    peaks(&votes, [width, height, radii.len()], threshold)
+        .into_iter()
+        .map(|index| Circle {
+            center: cell_center(index % width, index / width % height),
+            radius: radii[index / (width * height)],
+            votes: votes[index],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HOUGH_ANGLES, circle_offsets, hough_circles, hough_lines, hough_space};
+    use crate::{Gray, ImageBuffer, Layout};
+
+    #[test]
+    fn lines_are_found_in_normal_form() {
+        let edges = ImageBuffer::from_fn(30, 30, Layout::Interleaved, |i, j| {
+            Gray(u8::from(j == 7 || i == 20))
+        });
+        let lines = hough_lines(&edges, 25);
+        assert!(lines.len() >= 2);
+
+        let (mut horizontal, mut vertical) = (false, false);
+        for line in &lines[..2] {
+            assert!(line.votes >= 29);
+            let degrees = line.theta.to_degrees();
+            horizontal |= (degrees - 90.0).abs() <= 1.5 && (line.rho - 7.5).abs() <= 1.0;
+            // θ near 180° is the same vertical line with its normal flipped.
+            vertical |= (degrees <= 1.5 && (line.rho - 20.5).abs() <= 1.0)
+                || (degrees >= 178.5 && (line.rho + 20.5).abs() <= 1.0);
+        }
+        assert!(horizontal && vertical, "{lines:?}");
+    }
+
+    #[test]
+    fn hough_space_shows_one_vote_per_angle_and_edge_pixel() {
+        let mut edges = ImageBuffer::filled(8, 6, Layout::Interleaved, Gray(0u8));
+        edges.set_pixel(2, 3, Gray(1));
+        edges.set_pixel(5, 1, Gray(1));
+
+        let space = hough_space(&edges);
+        assert_eq!(space.width(), HOUGH_ANGLES);
+        assert_eq!(space.height(), 2 * 10 + 1);
+        let total: u32 = space.pixels().map(|Gray(v)| v).sum();
+        assert_eq!(total, 2 * HOUGH_ANGLES as u32);
+        assert!(hough_lines(&edges, 3).is_empty());
+    }
+
+    #[test]
+    fn circles_are_found_with_their_radius() {
+        let mut edges = ImageBuffer::filled(32, 28, Layout::Interleaved, Gray(0u8));
+        for (di, dj) in circle_offsets(6) {
+            edges.set_pixel((15 + di) as usize, (12 + dj) as usize, Gray(255));
+        }
+
+        let circles = hough_circles(&edges, 4..=8, 20);
+        let best = &circles[0];
+        assert_eq!(best.radius, 6);
+        assert_eq!(best.center.x().to_f64(), Some(15.5));
+        assert_eq!(best.center.y().to_f64(), Some(12.5));
+        assert_eq!(best.votes as usize, circle_offsets(6).len());
+    }
+}
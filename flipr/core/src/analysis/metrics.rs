@@ -0,0 +1,171 @@
+use crate::arithmetic::Channel;
+use crate::buffer::ImageBuffer;
+use crate::integral::SummedArea;
+use crate::pixel::Pixel;
+
+/// Side of the square windows [`ssim`] averages over.
+pub const SSIM_WINDOW: usize = 7;
+
+fn assert_same_size<P: Pixel>(a: &ImageBuffer<P>, b: &ImageBuffer<P>) {
+    assert!(
+        a.width() == b.width() && a.height() == b.height(),
+        "compared images differ in size: {}×{} and {}×{}",
+        a.width(),
+        a.height(),
+        b.width(),
+        b.height()
+    );
+}
+
+/// Channel `c` of every pixel of `image`, row by row.
+fn plane<P>(image: &ImageBuffer<P>, c: usize) -> impl Iterator<Item = f64> + '_
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    image.pixels().map(move |p| p.channel(c).to_f64())
+}
+
+/// Mean squared error, `Σ (a − b)² / n` over the `n` channel values of every
+/// pixel.
+///
+/// # Panics
+///
+/// Panics if the images differ in size or are empty.
+pub fn mse<P>(a: &ImageBuffer<P>, b: &ImageBuffer<P>) -> f64
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    assert_same_size(a, b);
+    assert!(a.width() * a.height() > 0, "compared images are empty");
+
+    let sum: f64 = (0..P::CHANNELS)
+        .flat_map(|c| plane(a, c).zip(plane(b, c)))
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum();
+    sum / (a.width() * a.height() * P::CHANNELS) as f64
+}
+
+/// Peak signal-to-noise ratio in decibels, `10 log₁₀(peak² / mse)`, where
+/// `peak` is the largest channel value, 255 for `u8` and 1 for `f32`.
+///
+/// Identical images give infinity.
+///
+/// # Panics
+///
+/// Panics if the images differ in size or are empty.
+pub fn psnr<P>(a: &ImageBuffer<P>, b: &ImageBuffer<P>, peak: f64) -> f64
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    10.0 * libm::log10(peak * peak / mse(a, b))
+}
+
+/// Mean structural similarity of `a` and `b`, in `-1.0..=1.0` with 1 for
+/// identical images.
+///
+/// Every [`SSIM_WINDOW`]-pixel square window inside the images (or the whole
+/// image, if smaller) scores
+///
+/// `(2 μa μb + c₁)(2 σab + c₂) / ((μa² + μb² + c₁)(σa² + σb² + c₂))`
+///
+/// from the means `μ`, variances `σ²` and covariance `σab` of its values, with
+/// `c₁ = (0.01 peak)²` and `c₂ = (0.03 peak)²`. The result is the average over
+/// all windows and channels. Unlike [`psnr`] it tracks local structure rather
+/// than uniform brightness shifts.
+///
+/// # Panics
+///
+/// Panics if the images differ in size or are empty.
+pub fn ssim<P>(a: &ImageBuffer<P>, b: &ImageBuffer<P>, peak: f64) -> f64
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    assert_same_size(a, b);
+    let (width, height) = (a.width(), a.height());
+    assert!(width * height > 0, "compared images are empty");
+
+    let (window_x, window_y) = (SSIM_WINDOW.min(width), SSIM_WINDOW.min(height));
+    let n = (window_x * window_y) as f64;
+    let (c1, c2) = ((0.01 * peak) * (0.01 * peak), (0.03 * peak) * (0.03 * peak));
+
+    let mut total = 0.0;
+    for c in 0..P::CHANNELS {
+        let tables = [
+            SummedArea::new(plane(a, c), width, height),
+            SummedArea::new(plane(b, c), width, height),
+            SummedArea::new(plane(a, c).map(|x| x * x), width, height),
+            SummedArea::new(plane(b, c).map(|y| y * y), width, height),
+            SummedArea::new(
+                plane(a, c).zip(plane(b, c)).map(|(x, y)| x * y),
+                width,
+                height,
+            ),
+        ];
+
+        for j in 0..=height - window_y {
+            for i in 0..=width - window_x {
+                let [sa, sb, saa, sbb, sab] = tables
+                    .each_ref()
+                    .map(|t| t.sum(i, j, window_x, window_y) / n);
+                let (var_a, var_b, covariance) = (saa - sa * sa, sbb - sb * sb, sab - sa * sb);
+
+                total += ((2.0 * sa * sb + c1) * (2.0 * covariance + c2))
+                    / ((sa * sa + sb * sb + c1) * (var_a + var_b + c2));
+            }
+        }
+    }
+
+    let windows = (width - window_x + 1) * (height - window_y + 1) * P::CHANNELS;
+    total / windows as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mse, psnr, ssim};
+    use crate::{Gray, ImageBuffer, Layout, Rgb};
+
+    fn ramp(offset: u8) -> ImageBuffer<Gray<u8>> {
+        ImageBuffer::from_fn(16, 12, Layout::Interleaved, |i, j| {
+            Gray((i * 8 + j * 4) as u8 + offset)
+        })
+    }
+
+    #[test]
+    fn metrics_follow_their_definitions() {
+        assert_eq!(mse(&ramp(0), &ramp(3)), 9.0);
+        assert!(
+            (psnr(&ramp(0), &ramp(3), 255.0) - 10.0 * libm::log10(255.0 * 255.0 / 9.0)).abs()
+                < 1e-9
+        );
+        assert_eq!(psnr(&ramp(0), &ramp(0), 255.0), f64::INFINITY);
+        assert!((ssim(&ramp(0), &ramp(0), 255.0) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ssim_prefers_shifted_structure_over_lost_structure() {
+        let flat = ImageBuffer::filled(16, 12, Layout::Interleaved, Gray(90u8));
+        let noisy = ImageBuffer::from_fn(16, 12, Layout::Interleaved, |i, j| {
+            Gray((i * 8 + j * 4) as u8 + if (i + j) % 2 == 0 { 20 } else { 0 })
+        });
+
+        let shifted = ssim(&ramp(0), &ramp(10), 255.0);
+        assert!(shifted > ssim(&ramp(0), &noisy, 255.0));
+        assert!(ssim(&ramp(0), &noisy, 255.0) > ssim(&ramp(0), &flat, 255.0));
+    }
+
+    #[test]
+    fn small_images_use_a_single_window() {
+        let a = ImageBuffer::from_fn(3, 2, Layout::Planar, |i, j| {
+            Rgb::new(i as f32 * 0.25, j as f32 * 0.5, 0.5)
+        });
+        let b = ImageBuffer::from_fn(3, 2, Layout::Interleaved, |i, j| {
+            Rgb::new(i as f32 * 0.25, j as f32 * 0.5, 0.5)
+        });
+        assert!((ssim(&a, &b, 1.0) - 1.0).abs() < 1e-12);
+        assert_eq!(mse(&a, &b), 0.0);
+    }
+}
@@ -0,0 +1,642 @@
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use space::Place;
+
+use crate::arithmetic::Channel;
+use crate::buffer::{ImageBuffer, Layout};
+use crate::pixel::{Gray, Pixel};
+use crate::static_image::clamped_index;
+
+/// How [`distance_transform`] measures the distance between pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DistanceMetric {
+    /// Exact straight-line distance between pixel centers.
+    #[default]
+    Euclidean,
+    /// 3-4 chamfer approximation of the Euclidean distance: `1` per straight
+    /// and `4/3` per diagonal step. Cheaper, and within 8 % of exact.
+    Chamfer,
+}
+
+/// Stands in for an infinite squared distance, finite so that differences
+/// of two of them stay numbers.
+const FAR: f64 = 1e20;
+
+/// Exact squared distances along one row or column, after Felzenszwalb and
+/// Huttenlocher: the lower envelope of the parabolas rooted at `f`.
+fn squared_distances(f: &[f64], out: &mut [f64]) {
+    let n = f.len();
+    let mut roots: Vec<usize> = Vec::with_capacity(n);
+    let mut bounds: Vec<f64> = Vec::with_capacity(n + 1);
+    roots.push(0);
+    bounds.push(-FAR);
+
+    let intersection = |p: usize, q: usize| {
+        let (p2, q2) = ((p * p) as f64, (q * q) as f64);
+        ((f[q] + q2) - (f[p] + p2)) / (2.0 * (q as f64 - p as f64))
+    };
+    for q in 1..n {
+        let mut s = intersection(roots[roots.len() - 1], q);
+        while roots.len() > 1 && s <= bounds[bounds.len() - 1] {
+            roots.pop();
+            bounds.pop();
+            s = intersection(roots[roots.len() - 1], q);
+        }
+        roots.push(q);
+        bounds.push(s);
+    }
+    bounds.push(FAR);
+
+    let mut k = 0;
+    for (q, out) in out.iter_mut().enumerate() {
+        while bounds[k + 1] < q as f64 {
+            k += 1;
+        }
+        let d = q as f64 - roots[k] as f64;
+        *out = d * d + f[roots[k]];
+    }
+}
+
+fn euclidean(background: &[bool], width: usize, height: usize) -> Vec<f32> {
+    let mut grid: Vec<f64> = background
+        .iter()
+        .map(|&b| if b { 0.0 } else { FAR })
+        .collect();
+
+    let mut line = Vec::with_capacity(width.max(height));
+    let mut out = alloc::vec![0.0; width.max(height)];
+    for i in 0..width {
+        line.clear();
+        line.extend((0..height).map(|j| grid[j * width + i]));
+        squared_distances(&line, &mut out[..height]);
+        for (j, &d) in out[..height].iter().enumerate() {
+            grid[j * width + i] = d;
+        }
+    }
+    for row in grid.chunks_mut(width.max(1)) {
+        line.clear();
+        line.extend_from_slice(row);
+        squared_distances(&line, &mut out[..width]);
+        row.copy_from_slice(&out[..width]);
+    }
+
+    grid.iter()
+        .map(|&d| {
+            if d >= FAR {
+                f32::INFINITY
+            } else {
+                libm::sqrt(d) as f32
+            }
+        })
+        .collect()
+}
+
+fn chamfer(background: &[bool], width: usize, height: usize) -> Vec<f32> {
+    const FORWARD: [(isize, isize, f32); 4] =
+        [(-1, -1, 4.0), (0, -1, 3.0), (1, -1, 4.0), (-1, 0, 3.0)];
+
+    let mut grid: Vec<f32> = background
+        .iter()
+        .map(|&b| if b { 0.0 } else { f32::INFINITY })
+        .collect();
+    let mut relax = |i: usize, j: usize, sign: isize| {
+        for (di, dj, cost) in FORWARD {
+            if let (Some(ni), Some(nj)) = (
+                i.checked_add_signed(di * sign),
+                j.checked_add_signed(dj * sign),
+            ) && ni < width
+                && nj < height
+            {
+                let candidate = grid[nj * width + ni] + cost;
+                let here = &mut grid[j * width + i];
+                *here = here.min(candidate);
+            }
+        }
+    };
+
+    for j in 0..height {
+        for i in 0..width {
+            relax(i, j, 1);
+        }
+    }
+    for j in (0..height).rev() {
+        for i in (0..width).rev() {
+            relax(i, j, -1);
+        }
+    }
+
+    grid.iter().map(|d| d / 3.0).collect()
+}
+
+/// Distance from every pixel of a binary `mask` to the nearest background
+/// pixel, measured between pixel centers.
+///
+/// Nonzero pixels are foreground; background pixels are at distance `0`.
+/// With no background pixel at all every distance is infinite. The ridges
+/// of the result are the skeleton of the shapes, and its maxima make good
+/// [`watershed`] markers for splitting touching blobs.
+pub fn distance_transform<T: Channel>(
+    mask: &ImageBuffer<Gray<T>>,
+    metric: DistanceMetric,
+) -> ImageBuffer<Gray<f32>> {
+    let (width, height) = (mask.width(), mask.height());
+    let background: Vec<bool> = mask.pixels().map(|Gray(v)| v.to_f64() == 0.0).collect();
+    let distances = match metric {
+        DistanceMetric::Euclidean => euclidean(&background, width, height),
+        DistanceMetric::Chamfer => chamfer(&background, width, height),
+    };
+    ImageBuffer::from_fn(width, height, Layout::Interleaved, |i, j| {
+        Gray(distances[j * width + i])
+    })
+}
+
+/// Pixel waiting to spread its label, lowest level first and oldest first
+/// among equals.
+struct Flood {
+    level: f64,
+    order: usize,
+    index: usize,
+}
+
+impl Ord for Flood {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .level
+            .total_cmp(&self.level)
+            .then(other.order.cmp(&self.order))
+    }
+}
+
+impl PartialOrd for Flood {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Flood {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Flood {}
+
+/// Marker-controlled watershed segmentation of the relief `elevation`.
+///
+/// Every nonzero pixel of `markers` is a seed labelled with its value. The
+/// basins are flooded from the seeds in order of rising water, each pixel
+/// taking the label of the first basin to reach it through its 4 neighbors,
+/// so the labels meet along the ridges of `elevation`. Every pixel connected
+/// to a seed ends up labelled; there are no separating watershed lines.
+///
+/// To split touching blobs of a mask, flood the negated
+/// [`distance_transform`] from one marker per blob.
+///
+/// # Panics
+///
+/// Panics if `elevation` and `markers` differ in size.
+pub fn watershed<T: Channel>(
+    elevation: &ImageBuffer<Gray<T>>,
+    markers: &ImageBuffer<Gray<u32>>,
+) -> ImageBuffer<Gray<u32>> {
+    let (width, height) = (elevation.width(), elevation.height());
+    assert!(
+        markers.width() == width && markers.height() == height,
+        "markers must be {width}×{height} like the elevation"
+    );
+
+    let levels: Vec<f64> = elevation.pixels().map(|Gray(v)| v.to_f64()).collect();
+    let mut labels: Vec<u32> = markers.pixels().map(|Gray(label)| label).collect();
+    let mut queue: BinaryHeap<Flood> = labels
+        .iter()
+        .enumerate()
+        .filter(|&(_, &label)| label != 0)
+        .map(|(index, _)| Flood {
+            level: levels[index],
+            order: index,
+            index,
+        })
+        .collect();
+
+    let mut order = labels.len();
+    while let Some(Flood { level, index, .. }) = queue.pop() {
+        let (i, j) = (index % width, index / width);
+        let neighbors = [
+            (i > 0).then(|| index - 1),
+            (i + 1 < width).then(|| index + 1),
+            (j > 0).then(|| index - width),
+            (j + 1 < height).then(|| index + width),
+        ];
+        for n in neighbors.into_iter().flatten() {
+            if labels[n] == 0 {
+                labels[n] = labels[index];
+                queue.push(Flood {
+                    level: levels[n].max(level),
+                    order,
+                    index: n,
+                });
+                order += 1;
+            }
+        }
+    }
+
+    ImageBuffer::from_fn(width, height, Layout::Interleaved, |i, j| {
+        Gray(labels[j * width + i])
+    })
+}
+
+/// Mask of the pixels reachable from `seed` through 4-connected neighbors
+/// whose every channel is within `tolerance` of the seed pixel's, `255`
+/// inside and `0` outside.
+///
+/// The pixel under `seed` is found like [`Image::get`](crate::Image::get)
+/// finds it, so a seed outside the buffer starts at the nearest edge pixel.
+pub fn flood_fill<P>(image: &ImageBuffer<P>, seed: &Place, tolerance: f64) -> ImageBuffer<Gray<u8>>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    let (width, height) = (image.width(), image.height());
+    let mut filled = alloc::vec![false; width * height];
+    if !filled.is_empty() {
+        let start = (
+            clamped_index(seed.x(), width),
+            clamped_index(seed.y(), height),
+        );
+        let reference = image
+            .pixel(start.0, start.1)
+            .expect("clamped indices are in bounds");
+        let similar = |p: P| {
+            (0..P::CHANNELS)
+                .all(|c| (p.channel(c).to_f64() - reference.channel(c).to_f64()).abs() <= tolerance)
+        };
+
+        let mut pending = alloc::vec![start];
+        filled[start.1 * width + start.0] = true;
+        while let Some((i, j)) = pending.pop() {
+            let neighbors = [
+                i.checked_sub(1).map(|ni| (ni, j)),
+                Some((i + 1, j)),
+                j.checked_sub(1).map(|nj| (i, nj)),
+                Some((i, j + 1)),
+            ];
+            for (ni, nj) in neighbors.into_iter().flatten() {
+                if let Some(p) = image.pixel(ni, nj)
+                    && !filled[nj * width + ni]
+                    && similar(p)
+                {
+                    filled[nj * width + ni] = true;
+                    pending.push((ni, nj));
+                }
+            }
+        }
+    }
+
+    ImageBuffer::from_fn(width, height, Layout::Interleaved, |i, j| {
+        Gray(if filled[j * width + i] { u8::MAX } else { 0 })
+    })
+}
+
+/// Boundary of one region of a mask, see [`find_contours`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    /// Corners of the boundary polygon, on the pixel grid lines, with the
+    /// foreground always on the right when walking them in order.
+    pub points: Vec<Place>,
+    /// Whether this is the inner boundary of a hole in the foreground.
+    pub hole: bool,
+    /// Index of the contour directly enclosing this one: the outer boundary
+    /// around a hole, or the hole around an island. Always smaller than the
+    /// contour's own index.
+    pub parent: Option<usize>,
+}
+
+/// Labels, from 1, the connected components of the pixels where `member`
+/// holds, leaving `0` elsewhere.
+fn components(
+    width: usize,
+    height: usize,
+    member: impl Fn(usize) -> bool,
+    diagonal: bool,
+) -> Vec<usize> {
+    let mut labels = alloc::vec![0; width * height];
+    let mut next = 0;
+    for start in 0..labels.len() {
+        if labels[start] != 0 || !member(start) {
+            continue;
+        }
+        next += 1;
+        labels[start] = next;
+        let mut pending = alloc::vec![start];
+        while let Some(index) = pending.pop() {
+            let (i, j) = ((index % width) as isize, (index / width) as isize);
+            for (di, dj) in [
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ] {
+                let (ni, nj) = (i + di, j + dj);
+                if (diagonal || di == 0 || dj == 0)
+                    && (0..width as isize).contains(&ni)
+                    && (0..height as isize).contains(&nj)
+                {
+                    let n = nj as usize * width + ni as usize;
+                    if labels[n] == 0 && member(n) {
+                        labels[n] = next;
+                        pending.push(n);
+                    }
+                }
+            }
+        }
+    }
+    labels
+}
+
+/// Walks the cracks around the region of `inside` whose first pixel in
+/// raster order is `(i, j)`, with the region on the right, and returns the
+/// corners. `diagonal` tells whether regions touching only at a corner are
+/// joined there.
+fn trace(
+    (i, j): (usize, usize),
+    inside: impl Fn(isize, isize) -> bool,
+    diagonal: bool,
+) -> Vec<Place> {
+    let corner = |(x, y): (isize, isize)| {
+        Place::new(x as f64, y as f64).expect("grid corners are finite f64")
+    };
+    // The pixel touching corner `(x, y)` on the quadrant `(sx, sy)` side.
+    let pixel = |(x, y): (isize, isize), (sx, sy): (isize, isize)| {
+        inside(x + (sx - 1) / 2, y + (sy - 1) / 2)
+    };
+
+    let start = (i as isize, j as isize);
+    let (mut vertex, mut direction) = (start, (1, 0));
+    let mut points = alloc::vec![corner(start)];
+    loop {
+        vertex = (vertex.0 + direction.0, vertex.1 + direction.1);
+        if vertex == start {
+            return points;
+        }
+
+        let (dx, dy) = direction;
+        let (right, left) = ((-dy, dx), (dy, -dx));
+        let ahead_left = pixel(vertex, (dx + left.0, dy + left.1));
+        let ahead_right = pixel(vertex, (dx + right.0, dy + right.1));
+        let turn = match (ahead_left, ahead_right) {
+            (true, false) if diagonal => left,
+            (_, false) => right,
+            (true, true) => left,
+            (false, true) => direction,
+        };
+        if turn != direction {
+            points.push(corner(vertex));
+            direction = turn;
+        }
+    }
+}
+
+fn labelled(
+    labels: &[usize],
+    label: usize,
+    width: usize,
+    height: usize,
+) -> impl Fn(isize, isize) -> bool + '_ {
+    move |x, y| {
+        (0..width as isize).contains(&x)
+            && (0..height as isize).contains(&y)
+            && labels[y as usize * width + x as usize] == label
+    }
+}
+
+/// Boundaries of the foreground regions of a binary `mask` and of the holes
+/// in them, as polygons through pixel corners.
+///
+/// Nonzero pixels are foreground and connect to all 8 neighbors; background
+/// pixels connect to their 4 neighbors, so a diagonal line of pixels is a
+/// single region that seals what it surrounds. Every foreground region has
+/// one outer contour and every hole one hole contour, in the raster order
+/// of their first pixels, so parents come before their children.
+pub fn find_contours<T: Channel>(mask: &ImageBuffer<Gray<T>>) -> Vec<Contour> {
+    let (width, height) = (mask.width(), mask.height());
+    let foreground: Vec<bool> = mask.pixels().map(|Gray(v)| v.to_f64() != 0.0).collect();
+    let regions = components(width, height, |k| foreground[k], true);
+    let gaps = components(width, height, |k| !foreground[k], false);
+
+    // Background touching the border is outside everything, not a hole.
+    let mut open = alloc::vec![false; width * height + 1];
+    for j in 0..height {
+        for i in 0..width {
+            if i == 0 || j == 0 || i + 1 == width || j + 1 == height {
+                open[gaps[j * width + i]] = true;
+            }
+        }
+    }
+
+    let mut region_contours: Vec<Option<usize>> = alloc::vec![None; width * height + 1];
+    let mut hole_contours: Vec<Option<usize>> = alloc::vec![None; width * height + 1];
+    let mut contours: Vec<Contour> = Vec::new();
+    for j in 0..height {
+        for i in 0..width {
+            let k = j * width + i;
+            let (region, gap) = (regions[k], gaps[k]);
+            if region != 0 && region_contours[region].is_none() {
+                // The pixel left of the region's first one is in whatever
+                // surrounds it.
+                let parent = (i > 0).then(|| gaps[k - 1]).and_then(|g| hole_contours[g]);
+                region_contours[region] = Some(contours.len());
+                contours.push(Contour {
+                    points: trace((i, j), labelled(&regions, region, width, height), true),
+                    hole: false,
+                    parent,
+                });
+            } else if gap != 0 && !open[gap] && hole_contours[gap].is_none() {
+                // A hole's first pixel sits right below its region.
+                let parent = region_contours[regions[k - width]];
+                let mut points = trace((i, j), labelled(&gaps, gap, width, height), false);
+                points[1..].reverse();
+                hole_contours[gap] = Some(contours.len());
+                contours.push(Contour {
+                    points,
+                    hole: true,
+                    parent,
+                });
+            }
+        }
+    }
+    contours
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use space::Place;
+
+    use super::{DistanceMetric, distance_transform, find_contours, flood_fill, watershed};
+    use crate::{Gray, ImageBuffer, Layout, Rgb};
+
+    fn hole_at(x: usize, y: usize) -> ImageBuffer<Gray<u8>> {
+        ImageBuffer::from_fn(9, 9, Layout::Interleaved, |i, j| {
+            Gray(if (i, j) == (x, y) { 0 } else { 1 })
+        })
+    }
+
+    #[test]
+    fn euclidean_distances_are_exact() {
+        let distances = distance_transform(&hole_at(1, 2), DistanceMetric::Euclidean);
+        assert_eq!(distances.pixel(1, 2), Some(Gray(0.0)));
+        assert_eq!(distances.pixel(4, 6), Some(Gray(5.0)));
+        assert_eq!(distances.pixel(8, 2), Some(Gray(7.0)));
+        assert_eq!(distances.pixel(2, 3), Some(Gray(core::f32::consts::SQRT_2)));
+    }
+
+    #[test]
+    fn chamfer_distances_weigh_diagonal_steps() {
+        let distances = distance_transform(&hole_at(4, 4), DistanceMetric::Chamfer);
+        assert_eq!(distances.pixel(5, 4), Some(Gray(1.0)));
+        assert_eq!(distances.pixel(5, 5), Some(Gray(4.0 / 3.0)));
+        assert_eq!(distances.pixel(8, 7), Some(Gray(5.0)));
+    }
+
+    #[test]
+    fn masks_without_background_are_infinitely_far() {
+        let full = ImageBuffer::filled(4, 3, Layout::Interleaved, Gray(255u8));
+        for metric in [DistanceMetric::Euclidean, DistanceMetric::Chamfer] {
+            let distances = distance_transform(&full, metric);
+            assert!(distances.pixels().all(|Gray(d)| d == f32::INFINITY));
+        }
+    }
+
+    #[test]
+    fn basins_meet_on_the_ridge() {
+        let ridge = ImageBuffer::from_fn(12, 5, Layout::Interleaved, |i, _| {
+            Gray(if i == 6 { 9u8 } else { 1 })
+        });
+        let mut markers = ImageBuffer::filled(12, 5, Layout::Interleaved, Gray(0u32));
+        markers.set_pixel(1, 2, Gray(1));
+        markers.set_pixel(10, 2, Gray(2));
+
+        let labels = watershed(&ridge, &markers);
+        for j in 0..5 {
+            for i in 0..12 {
+                let expected = if i < 6 { 1 } else { 2 };
+                assert_eq!(labels.pixel(i, j), Some(Gray(expected)), "({i}, {j})");
+            }
+        }
+    }
+
+    #[test]
+    fn touching_discs_are_split_at_their_neck() {
+        let disc = |i: usize, j: usize, cx: f64| {
+            let (x, y) = (i as f64 - cx, j as f64 - 8.0);
+            x * x + y * y <= 49.0
+        };
+        let mask = ImageBuffer::from_fn(29, 17, Layout::Interleaved, |i, j| {
+            Gray(u8::from(disc(i, j, 8.0) || disc(i, j, 20.0)))
+        });
+        let distances = distance_transform(&mask, DistanceMetric::Euclidean);
+        let relief = ImageBuffer::from_fn(29, 17, Layout::Interleaved, |i, j| {
+            Gray(-distances.pixel(i, j).unwrap().0)
+        });
+        let mut markers = ImageBuffer::filled(29, 17, Layout::Interleaved, Gray(0u32));
+        markers.set_pixel(8, 8, Gray(1));
+        markers.set_pixel(20, 8, Gray(2));
+
+        let labels = watershed(&relief, &markers);
+        for i in 1..14 {
+            assert_eq!(labels.pixel(i, 8), Some(Gray(1)), "({i}, 8)");
+        }
+        for i in 15..28 {
+            assert_eq!(labels.pixel(i, 8), Some(Gray(2)), "({i}, 8)");
+        }
+    }
+
+    fn mask(rows: &[&str]) -> ImageBuffer<Gray<u8>> {
+        ImageBuffer::from_fn(rows[0].len(), rows.len(), Layout::Interleaved, |i, j| {
+            Gray(u8::from(rows[j].as_bytes()[i] == b'#'))
+        })
+    }
+
+    fn corners(points: &[Place]) -> Vec<(f64, f64)> {
+        points
+            .iter()
+            .map(|p| (p.x().to_f64().unwrap(), p.y().to_f64().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn flood_fill_stops_at_walls_and_beyond_tolerance() {
+        let image = ImageBuffer::from_fn(8, 3, Layout::Interleaved, |i, _| {
+            Gray(match i {
+                4 => 200u8,
+                _ => 10 * i as u8,
+            })
+        });
+        let seed = Place::new(0.5, 1.5).unwrap();
+
+        let narrow = flood_fill(&image, &seed, 15.0);
+        let wide = flood_fill(&image, &seed, 35.0);
+        let count = |m: &ImageBuffer<Gray<u8>>| m.pixels().filter(|&p| p == Gray(255)).count();
+        assert_eq!(count(&narrow), 6);
+        assert_eq!(count(&wide), 12);
+        assert_eq!(wide.pixel(5, 1), Some(Gray(0)));
+    }
+
+    #[test]
+    fn flood_fill_compares_every_channel() {
+        let image = ImageBuffer::from_fn(4, 1, Layout::Interleaved, |i, _| {
+            Rgb::new(0.5f32, if i < 2 { 0.1 } else { 0.9 }, 0.5)
+        });
+        let filled = flood_fill(&image, &Place::new(-3.0, 0.5).unwrap(), 0.2);
+        let row: Vec<_> = filled.pixels().collect();
+        assert_eq!(row, [Gray(255), Gray(255), Gray(0), Gray(0)]);
+    }
+
+    #[test]
+    fn contours_outline_regions_clockwise() {
+        let contours = find_contours(&mask(&["....", ".##.", ".##.", "...."]));
+        assert_eq!(contours.len(), 1);
+        assert!(!contours[0].hole && contours[0].parent.is_none());
+        assert_eq!(
+            corners(&contours[0].points),
+            [(1.0, 1.0), (3.0, 1.0), (3.0, 3.0), (1.0, 3.0)]
+        );
+    }
+
+    #[test]
+    fn holes_and_islands_nest() {
+        let contours = find_contours(&mask(&[
+            "#######", //
+            "#.....#", "#.....#", "#..#..#", "#.....#", "#######",
+        ]));
+        let summary: Vec<_> = contours.iter().map(|c| (c.hole, c.parent)).collect();
+        assert_eq!(summary, [(false, None), (true, Some(0)), (false, Some(1))]);
+        assert_eq!(
+            corners(&contours[1].points),
+            [(1.0, 1.0), (1.0, 5.0), (6.0, 5.0), (6.0, 1.0)]
+        );
+        assert_eq!(
+            corners(&contours[2].points),
+            [(3.0, 3.0), (4.0, 3.0), (4.0, 4.0), (3.0, 4.0)]
+        );
+    }
+
+    #[test]
+    fn diagonal_neighbors_form_one_region_that_seals_holes() {
+        let contours = find_contours(&mask(&[
+            "......", //
+            "..#...", ".#.#..", "..#...", "......",
+        ]));
+        let summary: Vec<_> = contours.iter().map(|c| (c.hole, c.parent)).collect();
+        assert_eq!(summary, [(false, None), (true, Some(0))]);
+        assert_eq!(contours[0].points.len(), 12);
+        assert_eq!(contours[1].points.len(), 4);
+    }
+}
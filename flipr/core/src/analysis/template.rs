@@ -0,0 +1,240 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use space::{Offset, Place};
+
+use crate::Image;
+use crate::arithmetic::Channel;
+use crate::buffer::{ImageBuffer, cell_center};
+use crate::pixel::Gray;
+
+/// Similarity measure used by [`match_template`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchMethod {
+    /// Sum of squared differences; lower is better, `0` is a perfect match.
+    SquaredDifference,
+    /// Plain sum of products; higher is better but favours bright regions.
+    CrossCorrelation,
+    /// Zero-mean cross-correlation normalized to `-1.0..=1.0`; higher is better
+    /// and insensitive to brightness and contrast changes. Flat windows score `0`.
+    NormalizedCrossCorrelation,
+}
+
+impl MatchMethod {
+    /// Orders scores best first.
+    fn rank(self, a: f32, b: f32) -> Ordering {
+        match self {
+            MatchMethod::SquaredDifference => a.total_cmp(&b),
+            MatchMethod::CrossCorrelation | MatchMethod::NormalizedCrossCorrelation => {
+                b.total_cmp(&a)
+            }
+        }
+    }
+}
+
+/// Score of a template placed with its top-left corner at every place of an
+/// image, see [`match_template`].
+#[derive(Debug, Clone)]
+pub struct TemplateMatch<I> {
+    image: I,
+    cells: Vec<(Offset, f64)>,
+    method: MatchMethod,
+}
+
+/// Compares `template` against `image` at every placement of its top-left corner.
+///
+/// Template pixel `(i, j)` is compared with the image sampled at the center of
+/// the same cell relative to the placement, so integer places align the
+/// template with the image's pixel grid.
+pub fn match_template<I, T>(
+    image: I,
+    template: &ImageBuffer<Gray<T>>,
+    method: MatchMethod,
+) -> TemplateMatch<I>
+where
+    I: Image<Pixel = Gray<T>>,
+    T: Channel,
+{
+    let cells = (0..template.height())
+        .flat_map(|j| (0..template.width()).map(move |i| (i, j)))
+        .map(|(i, j)| {
+            let Gray(t) = template.pixel(i, j).expect("indices are in bounds");
+            (Place::origin().offset_to(cell_center(i, j)), t.to_f64())
+        })
+        .collect();
+
+    TemplateMatch {
+        image,
+        cells,
+        method,
+    }
+}
+
+impl<I, T> TemplateMatch<I>
+where
+    I: Image<Pixel = Gray<T>>,
+    T: Channel,
+{
+    fn score(&self, p: &Place) -> f32 {
+        let pairs = self.cells.iter().map(|(offset, t)| {
+            let Gray(v) = self.image.get(p + offset);
+            (v.to_f64(), *t)
+        });
+
+        let score = match self.method {
+            MatchMethod::SquaredDifference => pairs.map(|(v, t)| (v - t) * (v - t)).sum(),
+            MatchMethod::CrossCorrelation => pairs.map(|(v, t)| v * t).sum(),
+            MatchMethod::NormalizedCrossCorrelation => {
+                let pairs: Vec<_> = pairs.collect();
+                let n = pairs.len() as f64;
+                let mean_v = pairs.iter().map(|(v, _)| v).sum::<f64>() / n;
+                let mean_t = pairs.iter().map(|(_, t)| t).sum::<f64>() / n;
+                let (mut vt, mut vv, mut tt) = (0.0, 0.0, 0.0);
+                for (v, t) in pairs {
+                    let (v, t) = (v - mean_v, t - mean_t);
+                    vt += v * t;
+                    vv += v * v;
+                    tt += t * t;
+                }
+                let norm = libm::sqrt(vv * tt);
+                if norm == 0.0 { 0.0 } else { vt / norm }
+            }
+        };
+
+        score as f32
+    }
+
+    /// The `k` best-scoring integer placements with top-left corners in
+    /// `[0, width) × [0, height)`, best first, skipping any placement closer
+    /// than `min_distance` pixels to a better one. Placements scoring `NaN`,
+    /// such as windows over `NaN` pixels, are never returned.
+    pub fn best_matches(
+        &self,
+        width: usize,
+        height: usize,
+        k: usize,
+        min_distance: f64,
+    ) -> Vec<(Place, f32)> {
+        let mut candidates: Vec<((usize, usize), f32)> = (0..height)
+            .flat_map(|j| (0..width).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                let corner = Place::new(i as f64, j as f64).expect("indices are finite f64");
+                ((i, j), self.score(&corner))
+            })
+            .filter(|(_, score)| !score.is_nan())
+            .collect();
+        candidates.sort_by(|a, b| self.method.rank(a.1, b.1));
+
+        let mut kept: Vec<((usize, usize), f32)> = Vec::with_capacity(k);
+        for (at, score) in candidates {
+            if kept.len() == k {
+                break;
+            }
+            let far = kept.iter().all(|&((i, j), _)| {
+                let (di, dj) = (i as f64 - at.0 as f64, j as f64 - at.1 as f64);
+                di * di + dj * dj >= min_distance * min_distance
+            });
+            if far {
+                kept.push((at, score));
+            }
+        }
+
+        kept.into_iter()
+            .map(|((i, j), score)| {
+                let corner = Place::new(i as f64, j as f64).expect("indices are finite f64");
+                (corner, score)
+            })
+            .collect()
+    }
+}
+
+impl<I, T> Image for TemplateMatch<I>
+where
+    I: Image<Pixel = Gray<T>>,
+    T: Channel,
+{
+    type Pixel = Gray<f32>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        Gray(self.score(&p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use space::Place;
+
+    use super::{MatchMethod, match_template};
+    use crate::buffer::{ImageBuffer, Layout};
+    use crate::{Gray, Image};
+
+    fn scene() -> ImageBuffer<Gray<u8>> {
+        ImageBuffer::from_fn(10, 8, Layout::Interleaved, |i, j| {
+            let blob = |ci: usize, cj: usize| (i.abs_diff(ci) <= 1 && j.abs_diff(cj) <= 1) as u8;
+            let center = |ci: usize, cj: usize| (i == ci && j == cj) as u8;
+            Gray(10 + 200 * blob(2, 2) + 40 * center(2, 2) + 100 * blob(7, 5) + 20 * center(7, 5))
+        })
+    }
+
+    fn patch() -> ImageBuffer<Gray<u8>> {
+        ImageBuffer::from_fn(3, 3, Layout::Interleaved, |i, j| {
+            Gray(210 + (i == 1 && j == 1) as u8 * 40)
+        })
+    }
+
+    #[test]
+    fn squared_difference_is_zero_at_exact_match() {
+        let scores = match_template(scene(), &patch(), MatchMethod::SquaredDifference);
+        assert_eq!(scores.get(Place::new(1.0, 1.0).unwrap()), Gray(0.0));
+        assert!(scores.get(Place::new(2.0, 1.0).unwrap()).0 > 0.0);
+    }
+
+    #[test]
+    fn every_method_ranks_the_exact_match_first() {
+        for method in [
+            MatchMethod::SquaredDifference,
+            MatchMethod::CrossCorrelation,
+            MatchMethod::NormalizedCrossCorrelation,
+        ] {
+            let best = match_template(scene(), &patch(), method).best_matches(8, 6, 1, 0.0);
+            assert_eq!(best[0].0, Place::new(1.0, 1.0).unwrap(), "{method:?}");
+        }
+    }
+
+    #[test]
+    fn normalized_correlation_ignores_contrast() {
+        let scores = match_template(scene(), &patch(), MatchMethod::NormalizedCrossCorrelation);
+        let Gray(score) = scores.get(Place::new(1.0, 1.0).unwrap());
+        assert!((score - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn best_matches_respect_min_distance() {
+        let scores = match_template(scene(), &patch(), MatchMethod::NormalizedCrossCorrelation);
+        let best = scores.best_matches(8, 6, 2, 3.0);
+        assert_eq!(best.len(), 2);
+        assert_eq!(best[0].0, Place::new(1.0, 1.0).unwrap());
+        assert_eq!(best[1].0, Place::new(6.0, 4.0).unwrap());
+    }
+
+    #[test]
+    fn nan_scores_are_skipped() {
+        let scene = ImageBuffer::from_fn(10, 8, Layout::Interleaved, |i, j| {
+            let Gray(v) = scene().pixel(i, j).unwrap();
+            Gray(if i >= 8 { f32::NAN } else { f32::from(v) })
+        });
+        let patch = ImageBuffer::from_fn(3, 3, Layout::Interleaved, |i, j| {
+            Gray(f32::from(patch().pixel(i, j).unwrap().0))
+        });
+        for method in [
+            MatchMethod::SquaredDifference,
+            MatchMethod::CrossCorrelation,
+            MatchMethod::NormalizedCrossCorrelation,
+        ] {
+            let best = match_template(scene.clone(), &patch, method).best_matches(8, 6, 48, 0.0);
+            assert_eq!(best[0].0, Place::new(1.0, 1.0).unwrap(), "{method:?}");
+            assert_eq!(best.len(), 6 * 6, "{method:?}");
+            assert!(best.iter().all(|(_, score)| !score.is_nan()));
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use core::fmt;
+
+use crate::pixel::Pixel;
+
+/// What a pointwise operation does with a result that does not fit its
+/// channel type.
+///
+/// Float channels hold any finite result, so for them `Saturate` and `Wrap`
+/// are plain IEEE arithmetic, except that `NaN` becomes zero, and `Error` only
+/// reports results that became infinite or `NaN`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OverflowPolicy {
+    /// Results beyond the channel's bounds become the nearest bound.
+    #[default]
+    Saturate,
+    /// Results wrap around modulo the channel's range, like two's-complement
+    /// integer arithmetic.
+    Wrap,
+    /// Results are clamped to `min..=max`, such as the `16..=235` of video
+    /// range luma, and then saturated.
+    Clamp { min: f64, max: f64 },
+    /// Results beyond the channel's bounds are an [`Overflow`].
+    Error,
+}
+
+/// Result of a pointwise operation that did not fit its channel type under
+/// [`OverflowPolicy::Error`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Overflow {
+    /// The exact result.
+    pub value: f64,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} does not fit the channel type", self.value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Overflow {}
+
+/// Scalar type a pixel channel can be stored in.
+///
+/// Conversions from `f64` round to the nearest value and saturate at the bounds
+/// of integer types; `NaN` converts to zero.
+pub trait Channel: Copy {
+    fn to_f64(self) -> f64;
+
+    fn from_f64(value: f64) -> Self;
+
+    /// Converts the exact result of an operation, rounded to the nearest
+    /// value, following `policy`.
+    ///
+    /// `NaN` converts to zero under every policy but `Error`.
+    fn from_f64_with(value: f64, policy: OverflowPolicy) -> Result<Self, Overflow>;
+
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    fn wrapping_add(self, rhs: Self) -> Self;
+}
+
+/// Rounds half away from zero without relying on `std`.
+fn round(value: f64) -> f64 {
+    if value >= 0.0 {
+        ((value + 0.5) as i128) as f64
+    } else {
+        ((value - 0.5) as i128) as f64
+    }
+}
+
+macro_rules! integer_channel {
+    ($($t:ty),*) => {$(
+        impl Channel for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                round(value) as $t
+            }
+
+            fn from_f64_with(value: f64, policy: OverflowPolicy) -> Result<Self, Overflow> {
+                if value.is_nan() && policy != OverflowPolicy::Error {
+                    return Ok(0);
+                }
+                let (lo, hi) = (<$t>::MIN as f64, <$t>::MAX as f64);
+                match policy {
+                    OverflowPolicy::Saturate => Ok(Self::from_f64(value)),
+                    OverflowPolicy::Wrap => {
+                        let span = hi - lo + 1.0;
+                        let wrapped = libm::fmod(round(value) - lo, span);
+                        let wrapped = if wrapped < 0.0 { wrapped + span } else { wrapped };
+                        Ok((wrapped + lo) as $t)
+                    }
+                    OverflowPolicy::Clamp { min, max } => Ok(Self::from_f64(value.max(min).min(max))),
+                    OverflowPolicy::Error => {
+                        let rounded = round(value);
+                        if (lo..=hi).contains(&rounded) && !value.is_nan() {
+                            Ok(rounded as $t)
+                        } else {
+                            Err(Overflow { value })
+                        }
+                    }
+                }
+            }
+
+            fn saturating_add(self, rhs: Self) -> Self {
+                <$t>::saturating_add(self, rhs)
+            }
+
+            fn wrapping_add(self, rhs: Self) -> Self {
+                <$t>::wrapping_add(self, rhs)
+            }
+        }
+    )*};
+}
+
+macro_rules! float_channel {
+    ($($t:ty),*) => {$(
+        impl Channel for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                if value.is_nan() { 0.0 } else { value as $t }
+            }
+
+            fn from_f64_with(value: f64, policy: OverflowPolicy) -> Result<Self, Overflow> {
+                if value.is_nan() && policy != OverflowPolicy::Error {
+                    return Ok(0.0);
+                }
+                match policy {
+                    OverflowPolicy::Saturate | OverflowPolicy::Wrap => Ok(value as $t),
+                    OverflowPolicy::Clamp { min, max } => Ok(value.max(min).min(max) as $t),
+                    OverflowPolicy::Error => {
+                        let converted = value as $t;
+                        if converted.is_finite() {
+                            Ok(converted)
+                        } else {
+                            Err(Overflow { value })
+                        }
+                    }
+                }
+            }
+
+            fn saturating_add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+
+            fn wrapping_add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+        }
+    )*};
+}
+
+integer_channel!(u8, u16, u32, i8, i16, i32);
+float_channel!(f32, f64);
+
+/// Channel-wise addition with an explicit overflow policy.
+///
+/// For float channels both policies are plain IEEE addition.
+pub trait PixelAdd: Sized {
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    fn wrapping_add(self, rhs: Self) -> Self;
+
+    /// Adds the channels exactly and converts the sums following `policy`,
+    /// failing on the first channel that overflows under
+    /// [`OverflowPolicy::Error`].
+    fn add_with(self, rhs: Self, policy: OverflowPolicy) -> Result<Self, Overflow>;
+}
+
+/// Channel-wise multiplication by a factor, saturating on integer channels.
+pub trait PixelScale<F>: Sized {
+    fn scale(self, factor: F) -> Self;
+
+    /// Like [`scale`](Self::scale) with the products converted following
+    /// `policy`.
+    fn scale_with(self, factor: F, policy: OverflowPolicy) -> Result<Self, Overflow>;
+}
+
+/// Channel-wise linear interpolation, saturating on integer channels.
+///
+/// `t = 0` yields `self` and `t = 1` yields `other`.
+pub trait PixelLerp {
+    fn lerp(self, other: Self, t: f64) -> Self;
+}
+
+impl<P> PixelAdd for P
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    fn saturating_add(self, rhs: Self) -> Self {
+        self.zip_map(rhs, Channel::saturating_add)
+    }
+
+    fn wrapping_add(self, rhs: Self) -> Self {
+        self.zip_map(rhs, Channel::wrapping_add)
+    }
+
+    fn add_with(self, rhs: Self, policy: OverflowPolicy) -> Result<Self, Overflow> {
+        let mut error = None;
+        let sum = self.zip_map(rhs, |a, b| {
+            Channel::from_f64_with(a.to_f64() + b.to_f64(), policy).unwrap_or_else(|e| {
+                error.get_or_insert(e);
+                a
+            })
+        });
+        error.map_or(Ok(sum), Err)
+    }
+}
+
+impl<P> PixelScale<f64> for P
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    fn scale(self, factor: f64) -> Self {
+        self.map(|c| Channel::from_f64(c.to_f64() * factor))
+    }
+
+    fn scale_with(self, factor: f64, policy: OverflowPolicy) -> Result<Self, Overflow> {
+        let mut error = None;
+        let product = self.map(|c| {
+            Channel::from_f64_with(c.to_f64() * factor, policy).unwrap_or_else(|e| {
+                error.get_or_insert(e);
+                c
+            })
+        });
+        error.map_or(Ok(product), Err)
+    }
+}
+
+impl<P> PixelLerp for P
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    fn lerp(self, other: Self, t: f64) -> Self {
+        self.zip_map(other, |a, b| {
+            let (a, b) = (a.to_f64(), b.to_f64());
+            Channel::from_f64(a + (b - a) * t)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, proptest};
+
+    use super::{Channel, Overflow, OverflowPolicy, PixelAdd, PixelLerp, PixelScale};
+    use crate::pixel::{Gray, Rgb, Rgba};
+
+    #[test]
+    fn saturating_add_clips_at_bounds() {
+        let a = Rgb::new(250u8, 10, 0);
+        let b = Rgb::new(10u8, 10, 0);
+        assert_eq!(a.saturating_add(b), Rgb::new(255, 20, 0));
+        assert_eq!(Gray(-100i8).saturating_add(Gray(-100)), Gray(-128));
+    }
+
+    #[test]
+    fn wrapping_add_wraps_around() {
+        assert_eq!(Gray(250u8).wrapping_add(Gray(10)), Gray(4));
+    }
+
+    #[test]
+    fn float_add_is_plain_addition() {
+        let a = Rgba::new(0.5f32, 1.0, 2.0, 0.25);
+        assert_eq!(a.saturating_add(a), Rgba::new(1.0, 2.0, 4.0, 0.5));
+        assert_eq!(a.wrapping_add(a), a.saturating_add(a));
+    }
+
+    #[test]
+    fn policies_differ_only_beyond_the_bounds() {
+        let clamp = OverflowPolicy::Clamp {
+            min: 16.0,
+            max: 235.0,
+        };
+        let (a, b) = (Gray(250u8), Gray(10u8));
+        assert_eq!(a.add_with(b, OverflowPolicy::Saturate), Ok(Gray(255)));
+        assert_eq!(a.add_with(b, OverflowPolicy::Wrap), Ok(Gray(4)));
+        assert_eq!(a.add_with(b, clamp), Ok(Gray(235)));
+        assert_eq!(
+            a.add_with(b, OverflowPolicy::Error),
+            Err(Overflow { value: 260.0 })
+        );
+
+        for policy in [
+            OverflowPolicy::Saturate,
+            OverflowPolicy::Wrap,
+            OverflowPolicy::Error,
+        ] {
+            assert_eq!(Gray(250u8).add_with(Gray(5), policy), Ok(Gray(255)));
+            assert_eq!(Gray(-128i8).add_with(Gray(0), policy), Ok(Gray(-128)));
+        }
+        assert_eq!(
+            Gray(-128i8).add_with(Gray(-1), OverflowPolicy::Wrap),
+            Ok(Gray(127))
+        );
+        assert_eq!(
+            Gray(u32::MAX).add_with(Gray(2), OverflowPolicy::Wrap),
+            Ok(Gray(1))
+        );
+    }
+
+    #[test]
+    fn scale_with_follows_the_policy() {
+        let pixel = Rgb::new(100u8, 200, 20);
+        assert_eq!(
+            pixel.scale_with(1.5, OverflowPolicy::Saturate),
+            Ok(Rgb::new(150, 255, 30))
+        );
+        assert_eq!(
+            pixel.scale_with(1.5, OverflowPolicy::Wrap),
+            Ok(Rgb::new(150, 44, 30))
+        );
+        assert_eq!(
+            pixel.scale_with(-1.0, OverflowPolicy::Error),
+            Err(Overflow { value: -100.0 })
+        );
+        assert_eq!(
+            Gray(7u16).scale_with(f64::NAN, OverflowPolicy::Wrap),
+            Ok(Gray(0))
+        );
+        assert!(
+            Gray(7u16)
+                .scale_with(f64::NAN, OverflowPolicy::Error)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn float_channels_only_fail_on_infinity() {
+        let big = Gray(f32::MAX);
+        assert_eq!(big.add_with(Gray(1.0), OverflowPolicy::Error), Ok(big));
+        assert!(big.scale_with(2.0, OverflowPolicy::Error).is_err());
+        assert_eq!(
+            Gray(2.0f32).add_with(Gray(0.5), OverflowPolicy::Clamp { min: 0.0, max: 1.0 }),
+            Ok(Gray(1.0))
+        );
+    }
+
+    #[test]
+    fn nan_converts_to_zero_unless_it_is_an_error() {
+        let clamp = OverflowPolicy::Clamp {
+            min: 16.0,
+            max: 235.0,
+        };
+        assert_eq!(u8::from_f64_with(f64::NAN, clamp), Ok(0));
+        assert_eq!(f32::from_f64_with(f64::NAN, clamp), Ok(0.0));
+        assert_eq!(
+            f32::from_f64_with(f64::NAN, OverflowPolicy::Saturate),
+            Ok(0.0)
+        );
+        assert_eq!(f32::from_f64_with(f64::NAN, OverflowPolicy::Wrap), Ok(0.0));
+        assert_eq!(f32::from_f64(f64::NAN), 0.0);
+        assert!(f32::from_f64_with(f64::NAN, OverflowPolicy::Error).is_err());
+    }
+
+    #[test]
+    fn scale_rounds_and_saturates() {
+        assert_eq!(Rgb::new(10u8, 100, 200).scale(1.5), Rgb::new(15, 150, 255));
+        assert_eq!(Gray(3u8).scale(0.5), Gray(2));
+        assert_eq!(Gray(100u8).scale(-1.0), Gray(0));
+        assert_eq!(Gray(1u16).scale(f64::NAN), Gray(0));
+    }
+
+    #[test]
+    fn lerp_hits_midpoint() {
+        assert_eq!(Gray(0u8).lerp(Gray(255), 0.5), Gray(128));
+        assert_eq!(Gray(0.0f64).lerp(Gray(2.0), 0.25), Gray(0.5));
+    }
+
+    proptest! {
+        #[test]
+        fn u8_round_trips_through_f64(c: u8) {
+            prop_assert_eq!(u8::from_f64(c.to_f64()), c);
+        }
+
+        #[test]
+        fn lerp_endpoints_are_inputs(a: u8, b: u8) {
+            prop_assert_eq!(Gray(a).lerp(Gray(b), 0.0), Gray(a));
+            prop_assert_eq!(Gray(a).lerp(Gray(b), 1.0), Gray(b));
+        }
+
+        #[test]
+        fn saturating_add_is_commutative(a: [u8; 3], b: [u8; 3]) {
+            let (a, b) = (Rgb::new(a[0], a[1], a[2]), Rgb::new(b[0], b[1], b[2]));
+            prop_assert_eq!(a.saturating_add(b), b.saturating_add(a));
+        }
+
+        #[test]
+        fn policies_agree_with_integer_arithmetic(a: i16, b: i16) {
+            prop_assert_eq!(Gray(a).add_with(Gray(b), OverflowPolicy::Saturate), Ok(Gray(a.saturating_add(b))));
+            prop_assert_eq!(Gray(a).add_with(Gray(b), OverflowPolicy::Wrap), Ok(Gray(a.wrapping_add(b))));
+            prop_assert_eq!(
+                Gray(a).add_with(Gray(b), OverflowPolicy::Error).ok(),
+                a.checked_add(b).map(Gray)
+            );
+        }
+
+        #[test]
+        fn scale_by_one_is_identity(c: i16) {
+            prop_assert_eq!(Gray(c).scale(1.0), Gray(c));
+        }
+    }
+}
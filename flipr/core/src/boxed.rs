@@ -0,0 +1,136 @@
+use alloc::sync::Arc;
+use core::fmt;
+
+use space::Place;
+
+use crate::Image;
+use crate::buffer::ImageBuffer;
+use crate::from_fn::from_fn;
+use crate::pixel::Pixel;
+use crate::zip::ZipWith;
+
+/// Image of any type with pixels `P`, see [`Image::boxed`].
+///
+/// Lets images built from different chains of operations share one type, so
+/// they can be stored together or assembled at runtime, for example from a
+/// script. Cloning shares the underlying image.
+pub struct BoxedImage<'a, P> {
+    image: Arc<dyn Image<Pixel = P> + 'a>,
+}
+
+impl<'a, P> BoxedImage<'a, P> {
+    pub(crate) fn new(image: impl Image<Pixel = P> + 'a) -> Self {
+        Self {
+            image: Arc::new(image),
+        }
+    }
+
+    /// Applies `f` to every pixel, keeping the result boxed.
+    pub fn map<Q>(self, f: impl Fn(P) -> Q + 'a) -> BoxedImage<'a, Q>
+    where
+        P: 'a,
+    {
+        BoxedImage::new(from_fn(move |p| f(self.get(p))))
+    }
+
+    /// Like [`Image::zip_with`], keeping the result boxed.
+    pub fn zip_with<Q, R>(
+        self,
+        other: BoxedImage<'a, Q>,
+        f: impl Fn(P, Q) -> R + 'a,
+    ) -> BoxedImage<'a, R>
+    where
+        P: 'a,
+        Q: 'a,
+    {
+        BoxedImage::new(ZipWith::new(self, other, f))
+    }
+}
+
+impl<P> Clone for BoxedImage<'_, P> {
+    fn clone(&self) -> Self {
+        Self {
+            image: Arc::clone(&self.image),
+        }
+    }
+}
+
+impl<P> fmt::Debug for BoxedImage<'_, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedImage").finish_non_exhaustive()
+    }
+}
+
+impl<P> Image for BoxedImage<'_, P> {
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.image.get(p)
+    }
+
+    fn as_buffer(&self) -> Option<&ImageBuffer<P>>
+    where
+        P: Pixel,
+    {
+        self.image.as_buffer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use proptest::{prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::BoxedImage;
+    use crate::tests::place;
+    use crate::{Gray, Image, ImageBuffer, Layout, Rgb, ToneMap, from_fn};
+
+    fn source() -> BoxedImage<'static, Rgb<f32>> {
+        from_fn(|p: Place| {
+            let x = p.x().to_f64().unwrap() as f32;
+            Rgb::new(x / 4.0, 0.5, 1.0 - x / 4.0)
+        })
+        .boxed()
+    }
+
+    /// Builds a pipeline from step names, as a script loader would.
+    fn assemble(steps: &[&str]) -> BoxedImage<'static, Rgb<f32>> {
+        steps.iter().fold(source(), |image, step| match *step {
+            "saturate" => image.saturate(1.5).boxed(),
+            "blur" => image.gaussian_blur(0.8).boxed(),
+            "tonemap" => image.tone_map(ToneMap::Reinhard).boxed(),
+            _ => image,
+        })
+    }
+
+    #[test]
+    fn runtime_pipelines_match_static_ones() {
+        let dynamic = assemble(&["saturate", "tonemap"]);
+        let fixed = source().saturate(1.5).tone_map(ToneMap::Reinhard);
+        assert_eq!(
+            ImageBuffer::sample(&dynamic, 4, 2, Layout::Interleaved),
+            ImageBuffer::sample(&fixed, 4, 2, Layout::Interleaved)
+        );
+    }
+
+    #[test]
+    fn different_chains_share_a_type() {
+        let images: Vec<BoxedImage<'_, Rgb<f32>>> = alloc::vec![
+            source(),
+            assemble(&["blur"]),
+            source().unsharp_mask(1.0, 1.0, 0.0).boxed(),
+        ];
+        assert_eq!(images.len(), 3);
+    }
+
+    proptest! {
+        #[test]
+        fn map_and_zip_act_per_pixel(p in place()) {
+            let gray = source().map(|c| Gray(c.g));
+            let sum = gray.clone().zip_with(gray.clone(), |a, b| a.0 + b.0);
+            prop_assert_eq!(sum.get(p.clone()), 2.0 * gray.get(p).0);
+        }
+    }
+}
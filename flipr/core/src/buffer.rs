@@ -0,0 +1,292 @@
+use alloc::vec::Vec;
+
+use space::{Place, Real};
+
+use crate::Image;
+use crate::pixel::Pixel;
+use crate::static_image::clamped_index;
+
+/// How the channels of an [`ImageBuffer`] are arranged in memory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// One array of whole pixels, the way most file formats store them.
+    #[default]
+    Interleaved,
+    /// One array per channel, the way SIMD and GPU code prefers them.
+    Planar,
+}
+
+#[derive(Clone)]
+enum Storage<P: Pixel> {
+    Interleaved(Vec<P>),
+    Planar(Vec<Vec<P::Scalar>>),
+}
+
+/// Heap-allocated `width × height` grid of pixels.
+///
+/// Like [`StaticImage`](crate::StaticImage), pixel `(i, j)` covers the unit cell
+/// `[i, i + 1) × [j, j + 1)` and places outside of the grid are clamped to the
+/// nearest edge pixel. Pixels are stored row-major in either [`Layout`];
+/// equality compares pixels only, regardless of layout.
+#[derive(Clone)]
+pub struct ImageBuffer<P: Pixel> {
+    width: usize,
+    height: usize,
+    storage: Storage<P>,
+}
+
+impl<P: Pixel> ImageBuffer<P> {
+    /// Creates a buffer by asking `f` for the pixel at every `(i, j)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is zero.
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        layout: Layout,
+        mut f: impl FnMut(usize, usize) -> P,
+    ) -> Self {
+        assert!(
+            width > 0 && height > 0,
+            "ImageBuffer must have at least one pixel"
+        );
+
+        let pixels = (0..height)
+            .flat_map(|j| (0..width).map(move |i| (i, j)))
+            .map(|(i, j)| f(i, j))
+            .collect();
+
+        Self {
+            width,
+            height,
+            storage: Storage::Interleaved(pixels),
+        }
+        .into_layout(layout)
+    }
+
+    /// Creates a buffer with every pixel set to `pixel`.
+    pub fn filled(width: usize, height: usize, layout: Layout, pixel: P) -> Self {
+        Self::from_fn(width, height, layout, |_, _| pixel)
+    }
+
+    /// Samples `image` at the center of every pixel cell.
+    pub fn sample<I>(image: &I, width: usize, height: usize, layout: Layout) -> Self
+    where
+        I: Image<Pixel = P>,
+    {
+        Self::from_fn(width, height, layout, |i, j| image.get(cell_center(i, j)))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn layout(&self) -> Layout {
+        match self.storage {
+            Storage::Interleaved(_) => Layout::Interleaved,
+            Storage::Planar(_) => Layout::Planar,
+        }
+    }
+
+    fn index(&self, i: usize, j: usize) -> Option<usize> {
+        (i < self.width && j < self.height).then_some(j * self.width + i)
+    }
+
+    pub fn pixel(&self, i: usize, j: usize) -> Option<P> {
+        let index = self.index(i, j)?;
+
+        Some(match &self.storage {
+            Storage::Interleaved(pixels) => pixels[index],
+            Storage::Planar(planes) => P::from_channels(|c| planes[c][index]),
+        })
+    }
+
+    /// Overwrites the pixel at `(i, j)`, returning `false` if it is out of bounds.
+    pub fn set_pixel(&mut self, i: usize, j: usize, pixel: P) -> bool {
+        let Some(index) = self.index(i, j) else {
+            return false;
+        };
+
+        match &mut self.storage {
+            Storage::Interleaved(pixels) => pixels[index] = pixel,
+            Storage::Planar(planes) => {
+                for (c, plane) in planes.iter_mut().enumerate() {
+                    plane[index] = pixel.channel(c);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Row-major pixels, if the buffer is interleaved.
+    pub fn as_interleaved(&self) -> Option<&[P]> {
+        match &self.storage {
+            Storage::Interleaved(pixels) => Some(pixels),
+            Storage::Planar(_) => None,
+        }
+    }
+
+    /// Row-major values of channel `c`, if the buffer is planar.
+    pub fn plane(&self, c: usize) -> Option<&[P::Scalar]> {
+        match &self.storage {
+            Storage::Interleaved(_) => None,
+            Storage::Planar(planes) => planes.get(c).map(Vec::as_slice),
+        }
+    }
+
+    /// Interleaved buffer over `pixels`, which hold exactly `width × height`
+    /// pixels in row-major order.
+    pub(crate) fn from_interleaved(width: usize, height: usize, pixels: Vec<P>) -> Self {
+        debug_assert_eq!(pixels.len(), width * height);
+        Self {
+            width,
+            height,
+            storage: Storage::Interleaved(pixels),
+        }
+    }
+
+    /// The pixel storage, if the buffer is interleaved.
+    pub(crate) fn into_interleaved(self) -> Option<Vec<P>> {
+        match self.storage {
+            Storage::Interleaved(pixels) => Some(pixels),
+            Storage::Planar(_) => None,
+        }
+    }
+
+    /// Rearranges the pixels into `layout`, which is free if it already matches.
+    pub fn into_layout(self, layout: Layout) -> Self {
+        let storage = match (self.storage, layout) {
+            (storage @ Storage::Interleaved(_), Layout::Interleaved)
+            | (storage @ Storage::Planar(_), Layout::Planar) => storage,
+            (Storage::Interleaved(pixels), Layout::Planar) => Storage::Planar(
+                (0..P::CHANNELS)
+                    .map(|c| pixels.iter().map(|p| p.channel(c)).collect())
+                    .collect(),
+            ),
+            (Storage::Planar(planes), Layout::Interleaved) => Storage::Interleaved(
+                (0..self.width * self.height)
+                    .map(|index| P::from_channels(|c| planes[c][index]))
+                    .collect(),
+            ),
+        };
+
+        Self { storage, ..self }
+    }
+}
+
+impl<P: Pixel + PartialEq> PartialEq for ImageBuffer<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && (0..self.height)
+                .flat_map(|j| (0..self.width).map(move |i| (i, j)))
+                .all(|(i, j)| self.pixel(i, j) == other.pixel(i, j))
+    }
+}
+
+impl<P: Pixel + Eq> Eq for ImageBuffer<P> {}
+
+impl<P: Pixel> core::fmt::Debug for ImageBuffer<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ImageBuffer")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("layout", &self.layout())
+            .finish_non_exhaustive()
+    }
+}
+
+pub(crate) fn cell_center(i: usize, j: usize) -> Place {
+    let half = Real::from_f64(0.5).expect("0.5 is a finite f64");
+    let coord = |n: usize| Real::from_f64(n as f64).expect("pixel indices are finite f64") + &half;
+
+    Place::from_reals(coord(i), coord(j))
+}
+
+impl<P: Pixel> Image for ImageBuffer<P> {
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let i = clamped_index(p.x(), self.width);
+        let j = clamped_index(p.y(), self.height);
+
+        self.pixel(i, j).expect("clamped indices are in bounds")
+    }
+
+    fn as_buffer(&self) -> Option<&ImageBuffer<P>> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::{ImageBuffer, Layout};
+    use crate::tests::place;
+    use crate::{Gray, Image, Rgb, from_fn};
+
+    fn gradient(layout: Layout) -> ImageBuffer<Rgb<u8>> {
+        ImageBuffer::from_fn(3, 2, layout, |i, j| {
+            Rgb::new(i as u8, j as u8, (i + j) as u8)
+        })
+    }
+
+    #[test]
+    fn planar_buffer_stores_one_plane_per_channel() {
+        let buffer = gradient(Layout::Planar);
+        assert_eq!(buffer.plane(0), Some(&[0, 1, 2, 0, 1, 2][..]));
+        assert_eq!(buffer.plane(2), Some(&[0, 1, 2, 1, 2, 3][..]));
+        assert_eq!(buffer.plane(3), None);
+        assert_eq!(buffer.as_interleaved(), None);
+    }
+
+    #[test]
+    fn interleaved_buffer_stores_whole_pixels() {
+        let buffer = gradient(Layout::Interleaved);
+        assert_eq!(buffer.as_interleaved().map(<[_]>::len), Some(6));
+        assert_eq!(buffer.plane(0), None);
+    }
+
+    #[test]
+    fn layout_conversions_round_trip() {
+        let interleaved = gradient(Layout::Interleaved);
+        let planar = interleaved.clone().into_layout(Layout::Planar);
+        assert_eq!(planar.layout(), Layout::Planar);
+        assert_eq!(planar.plane(1), gradient(Layout::Planar).plane(1));
+        assert_eq!(planar.into_layout(Layout::Interleaved), interleaved);
+    }
+
+    #[test]
+    fn set_pixel_writes_every_plane() {
+        let mut buffer = gradient(Layout::Planar);
+        assert!(buffer.set_pixel(1, 1, Rgb::new(7, 8, 9)));
+        assert!(!buffer.set_pixel(3, 0, Rgb::new(7, 8, 9)));
+        assert_eq!(buffer.pixel(1, 1), Some(Rgb::new(7, 8, 9)));
+        assert_eq!(buffer.get(Place::new(1.5, 1.5).unwrap()), Rgb::new(7, 8, 9));
+    }
+
+    #[test]
+    fn sample_reads_cell_centers() {
+        let image = from_fn(|p: Place| Gray(p.x().to_f64().unwrap() * 2.0));
+        let buffer = ImageBuffer::sample(&image, 2, 1, Layout::Interleaved);
+        assert_eq!(buffer.as_interleaved(), Some(&[Gray(1.0), Gray(3.0)][..]));
+    }
+
+    proptest! {
+        #[test]
+        fn layouts_sample_identically(p in place()) {
+            prop_assert_eq!(
+                gradient(Layout::Planar).get(p.clone()),
+                gradient(Layout::Interleaved).get(p)
+            );
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use alloc::vec::Vec;
+
+use space::Place;
+
+use crate::analysis::{gradient_magnitude, sobel};
+use crate::arithmetic::{Channel, PixelLerp};
+use crate::buffer::{ImageBuffer, cell_center};
+use crate::pixel::{Gray, Pixel};
+use crate::{Image, from_fn};
+
+/// Resizes `image` to `target_width × target_height` by removing or duplicating
+/// the connected seams of lowest gradient energy, so that salient content keeps
+/// its proportions while uniform regions absorb the change.
+///
+/// Vertical seams are handled first, then horizontal ones. Energy is the Sobel
+/// gradient magnitude of the mean of the color channels. Inserted seams blend
+/// each seam pixel with its right (or lower) neighbour.
+///
+/// # Panics
+///
+/// Panics if `target_width` or `target_height` is zero.
+pub fn seam_carve<P>(
+    image: &ImageBuffer<P>,
+    target_width: usize,
+    target_height: usize,
+) -> ImageBuffer<P>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    assert!(
+        target_width > 0 && target_height > 0,
+        "seam carving needs a non-empty target"
+    );
+
+    let resized = resize_width(image.clone(), target_width);
+    transpose(&resize_width(transpose(&resized), target_height))
+}
+
+fn resize_width<P>(mut image: ImageBuffer<P>, target: usize) -> ImageBuffer<P>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    while image.width() > target {
+        let seam = vertical_seam(&image);
+        image = remove_seam(&image, &seam);
+    }
+
+    while image.width() < target {
+        let count = (target - image.width()).min(image.width());
+        image = insert_seams(&image, count);
+    }
+
+    image
+}
+
+fn energy<P>(image: &ImageBuffer<P>) -> Vec<f64>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    let colors = P::CHANNELS.min(3);
+    let gray = from_fn(|p: Place| {
+        let pixel = image.get(p);
+        Gray((0..colors).map(|c| pixel.channel(c).to_f64()).sum::<f64>() / colors as f64)
+    });
+    let magnitude = gradient_magnitude(sobel(gray));
+
+    (0..image.height())
+        .flat_map(|j| (0..image.width()).map(move |i| (i, j)))
+        .map(|(i, j)| magnitude.get(cell_center(i, j)).0 as f64)
+        .collect()
+}
+
+/// Column of the minimal 8-connected top-to-bottom path in every row.
+fn vertical_seam<P>(image: &ImageBuffer<P>) -> Vec<usize>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    let (width, height) = (image.width(), image.height());
+    let mut cost = energy(image);
+
+    for j in 1..height {
+        for i in 0..width {
+            let above = (i.saturating_sub(1)..=(i + 1).min(width - 1))
+                .map(|k| cost[(j - 1) * width + k])
+                .fold(f64::INFINITY, f64::min);
+            cost[j * width + i] += above;
+        }
+    }
+
+    let argmin = |range: core::ops::RangeInclusive<usize>, j: usize| {
+        range
+            .min_by(|&a, &b| cost[j * width + a].total_cmp(&cost[j * width + b]))
+            .expect("rows are not empty")
+    };
+
+    let mut seam = Vec::with_capacity(height);
+    seam.push(argmin(0..=width - 1, height - 1));
+    for j in (0..height - 1).rev() {
+        let i = *seam.last().expect("seam is not empty");
+        seam.push(argmin(i.saturating_sub(1)..=(i + 1).min(width - 1), j));
+    }
+    seam.reverse();
+    seam
+}
+
+fn remove_seam<P: Pixel>(image: &ImageBuffer<P>, seam: &[usize]) -> ImageBuffer<P> {
+    ImageBuffer::from_fn(image.width() - 1, image.height(), image.layout(), |i, j| {
+        let i = if i < seam[j] { i } else { i + 1 };
+        image.pixel(i, j).expect("indices are in bounds")
+    })
+}
+
+/// Duplicates the `count` lowest-energy seams found by successive removal.
+fn insert_seams<P>(image: &ImageBuffer<P>, count: usize) -> ImageBuffer<P>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    let (width, height) = (image.width(), image.height());
+    let mut columns: Vec<Vec<usize>> = (0..height).map(|_| (0..width).collect()).collect();
+    let mut duplicated = alloc::vec![false; width * height];
+    let mut working = image.clone();
+
+    for _ in 0..count {
+        let seam = vertical_seam(&working);
+        for (j, &i) in seam.iter().enumerate() {
+            duplicated[j * width + columns[j].remove(i)] = true;
+        }
+        if working.width() > 1 {
+            working = remove_seam(&working, &seam);
+        }
+    }
+
+    let rows: Vec<Vec<P>> = (0..height)
+        .map(|j| {
+            let pixel = |i: usize| {
+                image
+                    .pixel(i.min(width - 1), j)
+                    .expect("indices are in bounds")
+            };
+            (0..width)
+                .flat_map(|i| {
+                    let copy = duplicated[j * width + i].then(|| pixel(i).lerp(pixel(i + 1), 0.5));
+                    core::iter::once(pixel(i)).chain(copy)
+                })
+                .collect()
+        })
+        .collect();
+
+    ImageBuffer::from_fn(width + count, height, image.layout(), |i, j| rows[j][i])
+}
+
+fn transpose<P: Pixel>(image: &ImageBuffer<P>) -> ImageBuffer<P> {
+    ImageBuffer::from_fn(image.height(), image.width(), image.layout(), |i, j| {
+        image.pixel(j, i).expect("indices are in bounds")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::seam_carve;
+    use crate::buffer::{ImageBuffer, Layout};
+    use crate::{Gray, Rgb};
+
+    /// Flat background with a bright 2×2 square at (6, 2).
+    fn scene() -> ImageBuffer<Rgb<u8>> {
+        ImageBuffer::from_fn(10, 6, Layout::Interleaved, |i, j| {
+            let on = (6..8).contains(&i) && (2..4).contains(&j);
+            if on {
+                Rgb::new(250, 40, 40)
+            } else {
+                Rgb::new(20, 20, 20)
+            }
+        })
+    }
+
+    fn count(image: &ImageBuffer<Rgb<u8>>, pixel: Rgb<u8>) -> usize {
+        (0..image.height())
+            .flat_map(|j| (0..image.width()).map(move |i| (i, j)))
+            .filter(|&(i, j)| image.pixel(i, j) == Some(pixel))
+            .count()
+    }
+
+    #[test]
+    fn shrinking_removes_background_and_keeps_the_object() {
+        let carved = seam_carve(&scene(), 6, 4);
+        assert_eq!((carved.width(), carved.height()), (6, 4));
+        assert_eq!(count(&carved, Rgb::new(250, 40, 40)), 4);
+    }
+
+    #[test]
+    fn enlarging_keeps_the_object_intact() {
+        let carved = seam_carve(&scene(), 14, 9);
+        assert_eq!((carved.width(), carved.height()), (14, 9));
+        assert_eq!(count(&carved, Rgb::new(250, 40, 40)), 4);
+    }
+
+    #[test]
+    fn enlarging_beyond_double_width_works_in_rounds() {
+        let image = ImageBuffer::from_fn(2, 1, Layout::Planar, |i, _| Gray(i as u8 * 100));
+        let carved = seam_carve(&image, 7, 1);
+        assert_eq!(carved.width(), 7);
+        assert_eq!(carved.layout(), Layout::Planar);
+    }
+
+    #[test]
+    fn same_size_is_identity() {
+        assert_eq!(seam_carve(&scene(), 10, 6), scene());
+    }
+}
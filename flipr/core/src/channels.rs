@@ -0,0 +1,118 @@
+use space::Place;
+
+use crate::Image;
+use crate::pixel::{Gray, Pixel, Rgb};
+
+/// Single channel of an image as a [`Gray`] image, see [`Image::select_channel`].
+#[derive(Debug, Clone)]
+pub struct SelectChannel<I> {
+    image: I,
+    index: usize,
+}
+
+impl<I> SelectChannel<I> {
+    pub(crate) fn new(image: I, index: usize) -> Self {
+        Self { image, index }
+    }
+}
+
+impl<I> Image for SelectChannel<I>
+where
+    I: Image,
+    I::Pixel: Pixel,
+{
+    type Pixel = Gray<<I::Pixel as Pixel>::Scalar>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        Gray(self.image.get(p).channel(self.index))
+    }
+}
+
+/// Recombines three [`Gray`] images into an [`Rgb`] one, see [`merge_channels`].
+#[derive(Debug, Clone)]
+pub struct MergeChannels<R, G, B> {
+    r: R,
+    g: G,
+    b: B,
+}
+
+/// Builds an [`Rgb`] image from separate red, green and blue [`Gray`] images.
+///
+/// This is the inverse of [`Image::split_channels`], so a channel can be
+/// filtered on its own and put back.
+pub fn merge_channels<T, R, G, B>(r: R, g: G, b: B) -> MergeChannels<R, G, B>
+where
+    R: Image<Pixel = Gray<T>>,
+    G: Image<Pixel = Gray<T>>,
+    B: Image<Pixel = Gray<T>>,
+{
+    MergeChannels { r, g, b }
+}
+
+impl<T, R, G, B> Image for MergeChannels<R, G, B>
+where
+    R: Image<Pixel = Gray<T>>,
+    G: Image<Pixel = Gray<T>>,
+    B: Image<Pixel = Gray<T>>,
+{
+    type Pixel = Rgb<T>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        Rgb {
+            r: self.r.get(p.clone()).0,
+            g: self.g.get(p.clone()).0,
+            b: self.b.get(p).0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::merge_channels;
+    use crate::tests::place;
+    use crate::{Gray, Image, Rgb, Rgba, from_fn};
+
+    fn image() -> impl Image<Pixel = Rgb<f64>> + Clone {
+        from_fn(|p: Place| {
+            let (x, y) = (p.x().to_f64().unwrap(), p.y().to_f64().unwrap());
+            Rgb::new(x, y, x * y)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn split_then_merge_is_identity(p in place()) {
+            let (r, g, b) = image().split_channels();
+            prop_assert_eq!(merge_channels(r, g, b).get(p.clone()), image().get(p));
+        }
+
+        #[test]
+        fn split_channels_select_each_component(p in place()) {
+            let (r, g, b) = image().split_channels();
+            let pixel = image().get(p.clone());
+            prop_assert_eq!(r.get(p.clone()), Gray(pixel.r));
+            prop_assert_eq!(g.get(p.clone()), Gray(pixel.g));
+            prop_assert_eq!(b.get(p), Gray(pixel.b));
+        }
+    }
+
+    #[test]
+    fn channels_can_be_filtered_separately() {
+        let (r, g, b) = image().split_channels();
+        let dimmed = from_fn(move |p| Gray(b.get(p).0 / 2.0));
+        let merged = merge_channels(r, g, dimmed);
+        assert_eq!(
+            merged.get(Place::new(2.0, 3.0).unwrap()),
+            Rgb::new(2.0, 3.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn select_channel_reaches_alpha() {
+        let alpha = from_fn(|_| Rgba::new(1u8, 2, 3, 4)).select_channel(3);
+        assert_eq!(alpha.get(Place::origin()), Gray(4));
+    }
+}
@@ -0,0 +1,347 @@
+use space::Place;
+
+use crate::Image;
+use crate::pixel::{MapChannels, Pixel, Rgb};
+
+type Matrix = [[f64; 3]; 3];
+
+/// RGB color space with a D65 white point, defined by its primaries and
+/// transfer function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// IEC 61966-2-1 sRGB.
+    Srgb,
+    /// DCI-P3 primaries with the sRGB transfer function.
+    DisplayP3,
+    /// Adobe RGB (1998), a pure power curve with exponent `563 / 256`.
+    AdobeRgb,
+}
+
+impl ColorSpace {
+    /// Converts an encoded channel value in `0.0..=1.0` to linear light.
+    ///
+    /// Values outside of the range are extended by symmetry about zero.
+    pub fn decode(self, c: f32) -> f32 {
+        match self {
+            ColorSpace::Srgb | ColorSpace::DisplayP3 => srgb_decode(c),
+            ColorSpace::AdobeRgb => signed_pow(c, 563.0 / 256.0),
+        }
+    }
+
+    /// Converts a linear channel value back to its encoding, the inverse of
+    /// [`ColorSpace::decode`].
+    pub fn encode(self, c: f32) -> f32 {
+        match self {
+            ColorSpace::Srgb | ColorSpace::DisplayP3 => srgb_encode(c),
+            ColorSpace::AdobeRgb => signed_pow(c, 256.0 / 563.0),
+        }
+    }
+
+    /// Matrix from linear RGB to CIE XYZ.
+    fn to_xyz(self) -> Matrix {
+        match self {
+            ColorSpace::Srgb => [
+                [0.4124564, 0.3575761, 0.1804375],
+                [0.2126729, 0.7151522, 0.0721750],
+                [0.0193339, 0.1191920, 0.9503041],
+            ],
+            ColorSpace::DisplayP3 => [
+                [0.4865709, 0.2656677, 0.1982173],
+                [0.2289746, 0.6917385, 0.0792869],
+                [0.0000000, 0.0451134, 1.0439444],
+            ],
+            ColorSpace::AdobeRgb => [
+                [0.5767309, 0.1855540, 0.1881852],
+                [0.2973769, 0.6273491, 0.0752741],
+                [0.0270343, 0.0706872, 0.9911085],
+            ],
+        }
+    }
+
+    /// Re-expresses an encoded color of `self` as an encoded color of `to`.
+    ///
+    /// Colors outside of the gamut of `to` come out with channels outside of
+    /// `0.0..=1.0`; clamping is left to the caller.
+    pub fn convert(self, to: ColorSpace, color: Rgb<f32>) -> Rgb<f32> {
+        if self == to {
+            return color;
+        }
+
+        let linear = [color.r, color.g, color.b].map(|c| self.decode(c) as f64);
+        let matrix = multiply(&invert(&to.to_xyz()), &self.to_xyz());
+        let [r, g, b] = apply(&matrix, linear).map(|c| to.encode(c as f32));
+
+        Rgb::new(r, g, b)
+    }
+}
+
+/// Power curve mirrored through the origin, so out-of-gamut negative values
+/// survive a round trip.
+fn signed_pow(c: f32, exponent: f32) -> f32 {
+    libm::copysignf(libm::powf(c.abs(), exponent), c)
+}
+
+pub(crate) fn srgb_decode(c: f32) -> f32 {
+    if c.abs() <= 0.04045 {
+        c / 12.92
+    } else {
+        libm::copysignf(libm::powf((c.abs() + 0.055) / 1.055, 2.4), c)
+    }
+}
+
+pub(crate) fn srgb_encode(c: f32) -> f32 {
+    if c.abs() <= 0.0031308 {
+        c * 12.92
+    } else {
+        libm::copysignf(1.055 * libm::powf(c.abs(), 1.0 / 2.4) - 0.055, c)
+    }
+}
+
+fn apply(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    core::array::from_fn(|i| core::array::from_fn(|j| (0..3).map(|k| a[i][k] * b[k][j]).sum()))
+}
+
+fn invert(m: &Matrix) -> Matrix {
+    let cofactor = |i: usize, j: usize| {
+        let (r0, r1) = ((i + 1) % 3, (i + 2) % 3);
+        let (c0, c1) = ((j + 1) % 3, (j + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let det: f64 = (0..3).map(|j| m[0][j] * cofactor(0, j)).sum();
+
+    core::array::from_fn(|i| core::array::from_fn(|j| cofactor(j, i) / det))
+}
+
+/// Converts an [`Rgb`] image between color spaces, see
+/// [`Image::convert_color_space`].
+#[derive(Debug, Clone)]
+pub struct ConvertColorSpace<I> {
+    image: I,
+    from: ColorSpace,
+    to: ColorSpace,
+}
+
+impl<I> ConvertColorSpace<I> {
+    pub(crate) fn new(image: I, from: ColorSpace, to: ColorSpace) -> Self {
+        Self { image, from, to }
+    }
+}
+
+impl<I> Image for ConvertColorSpace<I>
+where
+    I: Image<Pixel = Rgb<f32>>,
+{
+    type Pixel = Rgb<f32>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.from.convert(self.to, self.image.get(p))
+    }
+}
+
+/// Channel type holding sRGB-encoded values, see [`Image::linearize`].
+pub trait SrgbChannel: Copy {
+    /// The value mapped to `0.0..=1.0` without decoding, as used for alpha.
+    fn normalized(self) -> f32;
+
+    /// The value decoded to linear light, reading `lut` where the channel type
+    /// is small enough to have one.
+    fn decoded(self, lut: &[f32; 256]) -> f32;
+}
+
+impl SrgbChannel for u8 {
+    fn normalized(self) -> f32 {
+        self as f32 / u8::MAX as f32
+    }
+
+    fn decoded(self, lut: &[f32; 256]) -> f32 {
+        lut[self as usize]
+    }
+}
+
+impl SrgbChannel for u16 {
+    fn normalized(self) -> f32 {
+        self as f32 / u16::MAX as f32
+    }
+
+    fn decoded(self, _: &[f32; 256]) -> f32 {
+        srgb_decode(self.normalized())
+    }
+}
+
+impl SrgbChannel for f32 {
+    fn normalized(self) -> f32 {
+        self
+    }
+
+    fn decoded(self, _: &[f32; 256]) -> f32 {
+        srgb_decode(self)
+    }
+}
+
+/// Decodes sRGB color channels to linear `f32` light, see [`Image::linearize`].
+#[derive(Debug, Clone)]
+pub struct Linearize<I> {
+    image: I,
+    lut: [f32; 256],
+}
+
+impl<I> Linearize<I> {
+    pub(crate) fn new(image: I) -> Self {
+        Self {
+            image,
+            lut: core::array::from_fn(|v| srgb_decode(v as f32 / u8::MAX as f32)),
+        }
+    }
+}
+
+impl<I> Image for Linearize<I>
+where
+    I: Image,
+    I::Pixel: MapChannels<f32>,
+    <I::Pixel as Pixel>::Scalar: SrgbChannel,
+{
+    type Pixel = <I::Pixel as MapChannels<f32>>::Output;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let pixel = self.image.get(p);
+        let linear = pixel.map_channels(|c| c.decoded(&self.lut));
+
+        // Color channels come first, so alpha keeps its normalized value.
+        let mut index = 0;
+        pixel.map_channels(SrgbChannel::normalized).map_color(|_| {
+            index += 1;
+            linear.channel(index - 1)
+        })
+    }
+}
+
+/// Encodes linear `f32` color channels with the sRGB transfer function, see
+/// [`Image::delinearize`].
+#[derive(Debug, Clone)]
+pub struct Delinearize<I>(I);
+
+impl<I> Delinearize<I> {
+    pub(crate) fn new(image: I) -> Self {
+        Self(image)
+    }
+}
+
+impl<I> Image for Delinearize<I>
+where
+    I: Image,
+    I::Pixel: Pixel<Scalar = f32>,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.0.get(p).map_color(srgb_encode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, proptest};
+    use space::Place;
+
+    use super::ColorSpace;
+    use crate::{Dither, Gray, Image, Rgb, Rgba, from_fn};
+
+    const SPACES: [ColorSpace; 3] = [
+        ColorSpace::Srgb,
+        ColorSpace::DisplayP3,
+        ColorSpace::AdobeRgb,
+    ];
+
+    fn close(a: Rgb<f32>, b: Rgb<f32>) -> bool {
+        (a.r - b.r).abs() < 5e-4 && (a.g - b.g).abs() < 5e-4 && (a.b - b.b).abs() < 5e-4
+    }
+
+    #[test]
+    fn white_and_black_are_shared() {
+        for from in SPACES {
+            for to in SPACES {
+                let white = from.convert(to, Rgb::new(1.0, 1.0, 1.0));
+                assert!(
+                    close(white, Rgb::new(1.0, 1.0, 1.0)),
+                    "{from:?} -> {to:?}: {white:?}"
+                );
+                assert!(close(
+                    from.convert(to, Rgb::new(0.0, 0.0, 0.0)),
+                    Rgb::new(0.0, 0.0, 0.0)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn wide_gamuts_contain_srgb_but_not_the_reverse() {
+        let red = Rgb::new(1.0, 0.0, 0.0);
+        let in_p3 = ColorSpace::Srgb.convert(ColorSpace::DisplayP3, red);
+        assert!(
+            [in_p3.r, in_p3.g, in_p3.b]
+                .iter()
+                .all(|c| (0.0..=1.0).contains(c))
+        );
+        assert!(in_p3.r < 1.0);
+        assert!(ColorSpace::DisplayP3.convert(ColorSpace::Srgb, red).r > 1.0);
+        assert!(
+            ColorSpace::AdobeRgb
+                .convert(ColorSpace::Srgb, Rgb::new(0.0, 1.0, 0.0))
+                .r
+                < 0.0
+        );
+    }
+
+    #[test]
+    fn linearize_keeps_alpha_linear() {
+        let image = from_fn(|_| Rgba::new(0u8, 128, 255, 128)).linearize();
+        let Rgba { r, g, b, a } = image.get(Place::new(0.0, 0.0).unwrap());
+        assert_eq!((r, b), (0.0, 1.0));
+        assert!((g - 0.2158605).abs() < 1e-6);
+        assert_eq!(a, 128.0 / 255.0);
+    }
+
+    #[test]
+    fn linear_mid_gray_encodes_brighter() {
+        let image = from_fn(|_| Gray(0.5f32)).delinearize().to_u8(Dither::None);
+        assert_eq!(image.get(Place::new(0.0, 0.0).unwrap()), Gray(188));
+    }
+
+    proptest! {
+        #[test]
+        fn u8_round_trips_through_linear_light(v: u8) {
+            let image = from_fn(move |_| Rgb::new(v, v, v)).linearize().delinearize().to_u8(Dither::None);
+            prop_assert!(image.get(Place::new(0.0, 0.0).unwrap()) == Rgb::new(v, v, v));
+        }
+
+        #[test]
+        fn lut_matches_direct_decoding(v: u8) {
+            let lut = from_fn(move |_| Gray(v)).linearize();
+            let direct = from_fn(move |_| Gray(v as f32 / 255.0)).linearize();
+            let p = Place::new(0.0, 0.0).unwrap();
+            prop_assert!(lut.get(p.clone()) == direct.get(p));
+        }
+
+        #[test]
+        fn conversions_round_trip(r in 0.0..=1.0f32, g in 0.0..=1.0f32, b in 0.0..=1.0f32) {
+            for from in SPACES {
+                for to in SPACES {
+                    let color = Rgb::new(r, g, b);
+                    let back = to.convert(from, from.convert(to, color));
+                    prop_assert!(close(back, color), "{:?} -> {:?}: {:?}", from, to, back);
+                }
+            }
+        }
+
+        #[test]
+        fn transfer_functions_round_trip(c in 0.0..=1.0f32) {
+            for space in SPACES {
+                prop_assert!((space.encode(space.decode(c)) - c).abs() < 1e-5);
+            }
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use alloc::vec::Vec;
+
+use space::Place;
+
+use crate::Image;
+use crate::arithmetic::PixelLerp;
+use crate::color::SrgbChannel;
+use crate::generators::{ColorStop, check_stops, color_at};
+use crate::pixel::{Gray, Pixel, Rgb};
+
+/// Viridis at ten evenly spaced points, from matplotlib.
+const VIRIDIS: [[u8; 3]; 10] = [
+    [0x44, 0x01, 0x54],
+    [0x48, 0x28, 0x78],
+    [0x3e, 0x49, 0x89],
+    [0x31, 0x68, 0x8e],
+    [0x26, 0x82, 0x8e],
+    [0x1f, 0x9e, 0x89],
+    [0x35, 0xb7, 0x79],
+    [0x6e, 0xce, 0x58],
+    [0xb5, 0xde, 0x2b],
+    [0xfd, 0xe7, 0x25],
+];
+
+/// Magma at ten evenly spaced points, from matplotlib.
+const MAGMA: [[u8; 3]; 10] = [
+    [0x00, 0x00, 0x04],
+    [0x18, 0x0f, 0x3d],
+    [0x44, 0x0f, 0x76],
+    [0x72, 0x1f, 0x81],
+    [0x9e, 0x2f, 0x7f],
+    [0xcd, 0x40, 0x71],
+    [0xf1, 0x60, 0x5d],
+    [0xfd, 0x96, 0x68],
+    [0xfe, 0xca, 0x8d],
+    [0xfc, 0xfd, 0xbf],
+];
+
+/// Inferno at ten evenly spaced points, from matplotlib.
+const INFERNO: [[u8; 3]; 10] = [
+    [0x00, 0x00, 0x04],
+    [0x1b, 0x0c, 0x41],
+    [0x4a, 0x0c, 0x6b],
+    [0x78, 0x1c, 0x6d],
+    [0xa5, 0x2c, 0x60],
+    [0xcf, 0x44, 0x46],
+    [0xed, 0x69, 0x25],
+    [0xfb, 0x9b, 0x06],
+    [0xf7, 0xd1, 0x3d],
+    [0xfc, 0xff, 0xa4],
+];
+
+/// Plasma at ten evenly spaced points, from matplotlib.
+const PLASMA: [[u8; 3]; 10] = [
+    [0x0d, 0x08, 0x87],
+    [0x46, 0x03, 0x9f],
+    [0x72, 0x01, 0xa8],
+    [0x9c, 0x17, 0x9e],
+    [0xbd, 0x37, 0x86],
+    [0xd8, 0x57, 0x6b],
+    [0xed, 0x79, 0x53],
+    [0xfb, 0x9f, 0x3a],
+    [0xfd, 0xca, 0x26],
+    [0xf0, 0xf9, 0x21],
+];
+
+/// Mapping of values in `0.0..=1.0` to colors, for showing single-channel
+/// data such as differences, depth or probabilities.
+///
+/// Viridis, magma, inferno and plasma are perceptually uniform: equal
+/// steps in value look like equal steps in color, and they stay readable
+/// in grayscale and to most color-blind viewers. Jet is not, and invents
+/// edges where its hue changes quickly, but many people are used to
+/// reading it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Colormap {
+    /// Dark blue through green to yellow.
+    Viridis,
+    /// Black through purple and orange to pale yellow.
+    Magma,
+    /// Black through purple, red and orange to pale yellow.
+    Inferno,
+    /// Dark blue through purple and orange to yellow.
+    Plasma,
+    /// Dark blue through cyan, yellow and red to dark red.
+    Jet,
+    /// Colors interpolated between stops sorted by position, best built
+    /// with [`Colormap::gradient`], which checks them.
+    Gradient(Vec<ColorStop<Rgb<u8>>>),
+}
+
+impl Colormap {
+    /// Map interpolating between user-defined `stops`, such as
+    /// `[(0.0, black), (0.5, red), (1.0, white)]`, and repeating the first
+    /// and last stop beyond them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stops` is empty or not sorted by position.
+    pub fn gradient(stops: Vec<ColorStop<Rgb<u8>>>) -> Self {
+        check_stops(&stops);
+        Colormap::Gradient(stops)
+    }
+
+    /// sRGB color of `t`, which is clamped to `0.0..=1.0`; `NaN` maps like
+    /// `0.0`.
+    ///
+    /// The built-in perceptually uniform maps interpolate ten points of
+    /// matplotlib's tables linearly, within a few levels of the originals.
+    pub fn color(&self, t: f32) -> Rgb<u8> {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let table = match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Magma => &MAGMA,
+            Colormap::Inferno => &INFERNO,
+            Colormap::Plasma => &PLASMA,
+            Colormap::Gradient(stops) => return color_at(stops, t as f64),
+            Colormap::Jet => {
+                let ramp = |center: f32| (1.5 - (4.0 * t - center).abs()).clamp(0.0, 1.0);
+                let byte = |v: f32| libm::roundf(v * 255.0) as u8;
+                return Rgb::new(byte(ramp(3.0)), byte(ramp(2.0)), byte(ramp(1.0)));
+            }
+        };
+
+        let position = t * (table.len() - 1) as f32;
+        let lower = (position as usize).min(table.len() - 2);
+        let [r0, g0, b0] = table[lower];
+        let [r1, g1, b1] = table[lower + 1];
+        Rgb::new(r0, g0, b0).lerp(Rgb::new(r1, g1, b1), (position - lower as f32) as f64)
+    }
+
+    /// [`color`](Self::color) of a gray value.
+    pub fn apply(&self, Gray(v): Gray<f32>) -> Rgb<u8> {
+        self.color(v)
+    }
+}
+
+/// Single-channel image shown through a [`Colormap`], see
+/// [`Image::apply_colormap`].
+#[derive(Debug, Clone)]
+pub struct ApplyColormap<I> {
+    image: I,
+    colormap: Colormap,
+    low: f32,
+    high: f32,
+}
+
+impl<I> ApplyColormap<I> {
+    pub(crate) fn new(image: I, colormap: Colormap) -> Self {
+        Self {
+            image,
+            colormap,
+            low: 0.0,
+            high: 1.0,
+        }
+    }
+
+    /// Stretches `low..=high` over the whole map instead of `0.0..=1.0`,
+    /// both on the scale of [`SrgbChannel::normalized`], for data such as
+    /// depth that fills only part of its range.
+    pub fn range(mut self, low: f32, high: f32) -> Self {
+        self.low = low;
+        self.high = high;
+        self
+    }
+}
+
+impl<I, T> Image for ApplyColormap<I>
+where
+    I: Image<Pixel = Gray<T>>,
+    T: SrgbChannel,
+{
+    type Pixel = Rgb<u8>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let Gray(v) = self.image.get(p);
+        let t = (v.normalized() - self.low) / (self.high - self.low);
+        self.colormap.color(t)
+    }
+}
+
+/// Heatmap of the differences between two images, see
+/// [`Image::diff_visualize`].
+#[derive(Debug, Clone)]
+pub struct DiffVisualized<I, J> {
+    first: I,
+    second: J,
+    amplification: f32,
+    colormap: Colormap,
+}
+
+impl<I, J> DiffVisualized<I, J> {
+    pub(crate) fn new(first: I, second: J, amplification: f32) -> Self {
+        Self {
+            first,
+            second,
+            amplification,
+            colormap: Colormap::Magma,
+        }
+    }
+
+    /// Shows the differences with `colormap` instead of magma.
+    pub fn colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+}
+
+impl<I, J> Image for DiffVisualized<I, J>
+where
+    I: Image,
+    J: Image<Pixel = I::Pixel>,
+    I::Pixel: Pixel,
+    <I::Pixel as Pixel>::Scalar: SrgbChannel,
+{
+    type Pixel = Rgb<u8>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (a, b) = (self.first.get(p.clone()), self.second.get(p));
+        let difference = (0..<I::Pixel as Pixel>::CHANNELS)
+            .map(|c| (a.channel(c).normalized() - b.channel(c).normalized()).abs())
+            .fold(0.0, f32::max);
+        self.colormap.color(difference * self.amplification)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, proptest};
+    use space::Place;
+
+    use super::Colormap;
+    use crate::{Gray, Image, ImageBuffer, Layout, Rgb, Rgba, from_fn};
+
+    #[test]
+    fn endpoints_match_matplotlib() {
+        assert_eq!(Colormap::Viridis.color(0.0), Rgb::new(68, 1, 84));
+        assert_eq!(Colormap::Viridis.color(1.0), Rgb::new(253, 231, 37));
+        assert_eq!(Colormap::Magma.color(0.0), Rgb::new(0, 0, 4));
+        assert_eq!(Colormap::Magma.color(1.0), Rgb::new(252, 253, 191));
+        assert_eq!(
+            Colormap::Viridis.color(-3.0),
+            Colormap::Viridis.color(f32::NAN)
+        );
+        assert_eq!(Colormap::Magma.apply(Gray(7.0)), Colormap::Magma.color(1.0));
+    }
+
+    #[test]
+    fn jet_runs_from_blue_to_red() {
+        assert_eq!(Colormap::Jet.color(0.0), Rgb::new(0, 0, 128));
+        assert_eq!(Colormap::Jet.color(0.5), Rgb::new(128, 255, 128));
+        assert_eq!(Colormap::Jet.color(1.0), Rgb::new(128, 0, 0));
+    }
+
+    #[test]
+    fn differences_are_amplified_before_mapping() {
+        let base = from_fn(|_| Rgba::new(0.5f32, 0.5, 0.5, 1.0));
+        let edited = from_fn(|p: Place| {
+            let x = p.x().to_f64().unwrap() as f32;
+            Rgba::new(0.5, 0.5 + 0.0625 * x, 0.5, 1.0)
+        });
+        let p = |x: f64| Place::new(x, 0.5).unwrap();
+
+        let heat = base.diff_visualize(edited, 2.0);
+        assert_eq!(heat.get(p(0.0)), Colormap::Magma.color(0.0));
+        assert_eq!(heat.get(p(4.0)), Colormap::Magma.color(0.5));
+        assert_eq!(heat.get(p(50.0)), Colormap::Magma.color(1.0));
+
+        let jet = base.diff_visualize(edited, 2.0).colormap(Colormap::Jet);
+        assert_eq!(jet.get(p(4.0)), Rgb::new(128, 255, 128));
+    }
+
+    #[test]
+    fn depth_maps_are_stretched_over_the_map() {
+        let depth = ImageBuffer::from_fn(3, 1, Layout::Interleaved, |i, _| {
+            Gray([1000u16, 2000, 40000][i])
+        });
+        let low = 1000.0 / u16::MAX as f32;
+        let high = 2000.0 / u16::MAX as f32;
+        let colors = depth.apply_colormap(Colormap::Inferno).range(low, high);
+
+        let buffer = ImageBuffer::sample(&colors, 3, 1, Layout::Interleaved);
+        assert_eq!(buffer.pixel(0, 0), Some(Rgb::new(0, 0, 4)));
+        assert_eq!(buffer.pixel(1, 0), Some(Rgb::new(252, 255, 164)));
+        assert_eq!(buffer.pixel(2, 0), buffer.pixel(1, 0));
+    }
+
+    #[test]
+    fn gradients_interpolate_user_stops() {
+        let map = Colormap::gradient(alloc::vec![
+            (0.25, Rgb::new(0, 0, 0)),
+            (0.75, Rgb::new(200, 100, 0)),
+        ]);
+        assert_eq!(map.color(0.0), Rgb::new(0, 0, 0));
+        assert_eq!(map.color(0.5), Rgb::new(100, 50, 0));
+        assert_eq!(map.color(1.0), Rgb::new(200, 100, 0));
+
+        let mask = from_fn(|p: Place| {
+            Gray(if p.x().to_f64().unwrap() < 0.0 {
+                0.0f32
+            } else {
+                1.0
+            })
+        });
+        let shown = mask.apply_colormap(map);
+        assert_eq!(
+            shown.get(Place::new(1.0, 0.0).unwrap()),
+            Rgb::new(200, 100, 0)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "sorted")]
+    fn unsorted_stops_are_rejected() {
+        Colormap::gradient(alloc::vec![
+            (0.5, Rgb::new(0, 0, 0)),
+            (0.1, Rgb::new(1, 1, 1))
+        ]);
+    }
+
+    proptest! {
+        #[test]
+        fn viridis_brightens_monotonically(a in 0.0f32..=1.0, b in 0.0f32..=1.0) {
+            let luma = |t: f32| {
+                let Rgb { r, g, b } = Colormap::Viridis.color(t);
+                0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32
+            };
+            let (lo, hi) = if a < b { (a, b) } else { (b, a) };
+            prop_assert!(luma(lo) <= luma(hi) + 1.0);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use alloc::vec::Vec;
+
+use space::Place;
+
+use crate::Image;
+use crate::lut::Lut;
+use crate::pixel::Pixel;
+
+/// Monotone transfer curve on `0.0..=1.0`, the shape of a photographic adjustment.
+pub trait ToneCurve {
+    fn apply(&self, x: f32) -> f32;
+
+    /// Tabulates the curve for `u8` channels, see [`Image::apply_lut`].
+    fn to_lut(&self) -> Lut {
+        Lut::from_fn(|v| {
+            let y = self.apply(v as f32 / u8::MAX as f32).clamp(0.0, 1.0);
+            libm::roundf(y * u8::MAX as f32) as u8
+        })
+    }
+}
+
+/// Input levels: maps `black` to 0 and `white` to 1, bending midtones with `gamma`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Levels {
+    black: f32,
+    white: f32,
+    gamma: f32,
+}
+
+impl Levels {
+    /// Values of `gamma` above 1 brighten the midtones.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `black < white` and `gamma` is positive.
+    pub fn new(black: f32, white: f32, gamma: f32) -> Self {
+        assert!(black < white, "black point must be below white point");
+        assert!(gamma > 0.0, "gamma must be positive");
+        Self {
+            black,
+            white,
+            gamma,
+        }
+    }
+}
+
+impl ToneCurve for Levels {
+    fn apply(&self, x: f32) -> f32 {
+        let t = ((x - self.black) / (self.white - self.black)).clamp(0.0, 1.0);
+        libm::powf(t, 1.0 / self.gamma)
+    }
+}
+
+/// Curve through control points, interpolated by a monotone cubic spline so
+/// it never overshoots between them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve {
+    points: Vec<(f32, f32)>,
+    tangents: Vec<f32>,
+}
+
+impl Curve {
+    /// Builds the Fritsch–Carlson spline through `points`, which are constant
+    /// beyond the first and last one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` is empty or its inputs are not strictly increasing.
+    pub fn new(points: Vec<(f32, f32)>) -> Self {
+        assert!(
+            !points.is_empty(),
+            "a curve needs at least one control point"
+        );
+        assert!(
+            points.windows(2).all(|w| w[0].0 < w[1].0),
+            "control points must have strictly increasing inputs"
+        );
+
+        let secants: Vec<f32> = points
+            .windows(2)
+            .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+            .collect();
+
+        let mut tangents: Vec<f32> = (0..points.len())
+            .map(
+                |k| match (k.checked_sub(1).map(|k| secants[k]), secants.get(k)) {
+                    (Some(before), Some(&after)) if before * after > 0.0 => (before + after) / 2.0,
+                    (Some(_), Some(_)) | (None, None) => 0.0,
+                    (Some(only), None) | (None, Some(&only)) => only,
+                },
+            )
+            .collect();
+
+        // Limit tangents so each segment stays monotone.
+        for (k, &secant) in secants.iter().enumerate() {
+            if secant == 0.0 {
+                tangents[k] = 0.0;
+                tangents[k + 1] = 0.0;
+                continue;
+            }
+            let (a, b) = (tangents[k] / secant, tangents[k + 1] / secant);
+            let norm = libm::hypotf(a, b);
+            if norm > 3.0 {
+                tangents[k] = 3.0 * a / norm * secant;
+                tangents[k + 1] = 3.0 * b / norm * secant;
+            }
+        }
+
+        Self { points, tangents }
+    }
+}
+
+impl ToneCurve for Curve {
+    fn apply(&self, x: f32) -> f32 {
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        if x <= first.0 {
+            return first.1;
+        }
+        if x >= last.0 {
+            return last.1;
+        }
+
+        let k = self.points.partition_point(|&(px, _)| px <= x) - 1;
+        let ((x0, y0), (x1, y1)) = (self.points[k], self.points[k + 1]);
+        let h = x1 - x0;
+        let t = (x - x0) / h;
+        let (t2, t3) = (t * t, t * t * t);
+
+        (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+            + (t3 - 2.0 * t2 + t) * h * self.tangents[k]
+            + (-2.0 * t3 + 3.0 * t2) * y1
+            + (t3 - t2) * h * self.tangents[k + 1]
+    }
+}
+
+/// Which values of a pixel a [`ToneCurve`] reshapes, see [`Image::adjust`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AdjustMode {
+    /// Every color channel independently, which may shift hues.
+    #[default]
+    PerChannel,
+    /// Rec. 709 luminance, scaling the color channels to match and so
+    /// preserving their ratios.
+    Luminance,
+}
+
+/// Applies a [`ToneCurve`] to an `f32` image, see [`Image::adjust`].
+#[derive(Debug, Clone)]
+pub struct Adjusted<I, C> {
+    image: I,
+    curve: C,
+    mode: AdjustMode,
+}
+
+impl<I, C> Adjusted<I, C> {
+    pub(crate) fn new(image: I, curve: C, mode: AdjustMode) -> Self {
+        Self { image, curve, mode }
+    }
+}
+
+/// Rec. 709 luminance of the color channels, or the only channel of a gray pixel.
+pub(crate) fn luminance<P: Pixel<Scalar = f32>>(pixel: P) -> f32 {
+    if P::CHANNELS < 3 {
+        return pixel.channel(0);
+    }
+    0.2126 * pixel.channel(0) + 0.7152 * pixel.channel(1) + 0.0722 * pixel.channel(2)
+}
+
+impl<I, C> Image for Adjusted<I, C>
+where
+    I: Image,
+    I::Pixel: Pixel<Scalar = f32>,
+    C: ToneCurve,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let pixel = self.image.get(p);
+
+        match self.mode {
+            AdjustMode::PerChannel => pixel.map_color(|c| self.curve.apply(c)),
+            AdjustMode::Luminance => {
+                let before = luminance(pixel);
+                let after = self.curve.apply(before);
+                if before > 0.0 {
+                    pixel.map_color(|c| c * after / before)
+                } else {
+                    pixel.map_color(|_| after)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use proptest::{prop_assert, proptest};
+    use space::Place;
+
+    use super::{AdjustMode, Curve, Levels, ToneCurve};
+    use crate::{Gray, Image, Rgb, from_fn};
+
+    fn s_curve() -> Curve {
+        Curve::new(vec![(0.0, 0.0), (0.25, 0.15), (0.75, 0.85), (1.0, 1.0)])
+    }
+
+    #[test]
+    fn levels_stretch_and_bend() {
+        let levels = Levels::new(0.2, 0.6, 2.0);
+        assert_eq!(levels.apply(0.1), 0.0);
+        assert_eq!(levels.apply(0.6), 1.0);
+        assert_eq!(levels.apply(0.3), 0.5);
+        assert_eq!(Levels::new(0.0, 1.0, 1.0).to_lut(), crate::Lut::identity());
+    }
+
+    #[test]
+    fn curve_passes_through_control_points() {
+        let curve = s_curve();
+        for (x, y) in [(0.0, 0.0), (0.25, 0.15), (0.75, 0.85), (1.0, 1.0)] {
+            assert!((curve.apply(x) - y).abs() < 1e-6);
+        }
+        assert_eq!(curve.apply(-1.0), 0.0);
+    }
+
+    #[test]
+    fn flat_segments_do_not_overshoot() {
+        let curve = Curve::new(vec![(0.0, 0.0), (0.4, 0.5), (0.6, 0.5), (1.0, 1.0)]);
+        assert_eq!(curve.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn luminance_mode_preserves_channel_ratios() {
+        let image = from_fn(|_| Rgb::new(0.2f32, 0.4, 0.1))
+            .adjust(Levels::new(0.0, 0.5, 1.0), AdjustMode::Luminance);
+        let Rgb { r, g, b } = image.get(Place::new(0.0, 0.0).unwrap());
+        assert!((g / r - 2.0).abs() < 1e-5 && (r / b - 2.0).abs() < 1e-5);
+
+        let per_channel =
+            from_fn(|_| Gray(0.2f32)).adjust(Levels::new(0.0, 0.5, 1.0), AdjustMode::PerChannel);
+        assert_eq!(per_channel.get(Place::new(0.0, 0.0).unwrap()), Gray(0.4));
+    }
+
+    proptest! {
+        #[test]
+        fn curve_is_monotone(a in 0.0..=1.0f32, b in 0.0..=1.0f32) {
+            let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+            let curve = Curve::new(vec![(0.0, 0.0), (0.1, 0.6), (0.2, 0.65), (1.0, 1.0)]);
+            prop_assert!(curve.apply(lo) <= curve.apply(hi) + 1e-6);
+        }
+    }
+}
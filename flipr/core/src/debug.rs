@@ -0,0 +1,327 @@
+//! Hooks for looking inside a pipeline while debugging it.
+
+use space::Place;
+
+use crate::Image;
+use crate::buffer::ImageBuffer;
+use crate::pixel::Pixel;
+
+/// Image calling a function with every place it is sampled at and the pixel
+/// found there, see [`Image::inspect`].
+///
+/// Adapters that read a buffer source whole, such as large convolutions, do
+/// not sample it.
+#[derive(Debug, Clone)]
+pub struct Inspect<I, F> {
+    image: I,
+    f: F,
+}
+
+impl<I, F> Inspect<I, F> {
+    pub(crate) fn new(image: I, f: F) -> Self {
+        Self { image, f }
+    }
+}
+
+impl<I, F> Image for Inspect<I, F>
+where
+    I: Image,
+    F: Fn(&Place, &I::Pixel),
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let pixel = self.image.get(p.clone());
+        (self.f)(&p, &pixel);
+        pixel
+    }
+
+    fn as_buffer(&self) -> Option<&ImageBuffer<I::Pixel>>
+    where
+        I::Pixel: Pixel,
+    {
+        self.image.as_buffer()
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) use dump::dump;
+#[cfg(feature = "std")]
+pub use dump::{CollectSink, DebugSink, DirectorySink, StageDump, debug_enabled, set_debug};
+
+#[cfg(feature = "std")]
+mod dump {
+    use alloc::string::String;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use std::io::{self, Write};
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use crate::Image;
+    use crate::buffer::{ImageBuffer, Layout};
+    use crate::color::SrgbChannel;
+    use crate::pixel::Pixel;
+
+    static ENABLED: AtomicBool = AtomicBool::new(false);
+    static SINK: Mutex<Option<Arc<dyn DebugSink>>> = Mutex::new(None);
+
+    /// Turns stage dumps on with `sink` receiving them, or off with `None`.
+    ///
+    /// While off, [`Image::dump_stage`] costs nothing.
+    pub fn set_debug(sink: Option<Arc<dyn DebugSink>>) {
+        let mut current = SINK.lock().unwrap_or_else(|e| e.into_inner());
+        ENABLED.store(sink.is_some(), Ordering::Relaxed);
+        *current = sink;
+    }
+
+    /// Whether [`set_debug`] installed a sink.
+    pub fn debug_enabled() -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    /// Intermediate image of a pipeline, see [`Image::dump_stage`].
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct StageDump {
+        pub stage: String,
+        pub width: usize,
+        pub height: usize,
+        /// Channels per pixel.
+        pub channels: usize,
+        /// Channels of every pixel, row by row, on the `0.0..=1.0` scale of
+        /// [`SrgbChannel::normalized`].
+        pub values: Vec<f32>,
+    }
+
+    /// Receiver of the images dumped while debugging is on.
+    pub trait DebugSink: Send + Sync {
+        fn dump(&self, image: StageDump);
+    }
+
+    /// Sink keeping every dump in memory, for tests and interactive tools.
+    #[derive(Debug, Default)]
+    pub struct CollectSink(Mutex<Vec<StageDump>>);
+
+    impl CollectSink {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// The dumps received so far, oldest first, leaving the sink empty.
+        pub fn take(&self) -> Vec<StageDump> {
+            core::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()))
+        }
+    }
+
+    impl DebugSink for CollectSink {
+        fn dump(&self, image: StageDump) {
+            self.0.lock().unwrap_or_else(|e| e.into_inner()).push(image);
+        }
+    }
+
+    /// Sink writing every dump to `<directory>/<stage>.pam`, an 8-bit
+    /// Netpbm image most viewers open, replacing older dumps of the stage.
+    ///
+    /// Write errors do not fail the pipeline being debugged; the latest one
+    /// is kept for [`take_error`](Self::take_error).
+    #[derive(Debug)]
+    pub struct DirectorySink {
+        directory: PathBuf,
+        error: Mutex<Option<io::Error>>,
+    }
+
+    impl DirectorySink {
+        pub fn new(directory: impl Into<PathBuf>) -> Self {
+            Self {
+                directory: directory.into(),
+                error: Mutex::new(None),
+            }
+        }
+
+        /// The latest error writing a dump since the last call, if any.
+        pub fn take_error(&self) -> Option<io::Error> {
+            self.error.lock().unwrap_or_else(|e| e.into_inner()).take()
+        }
+
+        /// Path the dump of `stage` is written to.
+        pub fn path(&self, stage: &str) -> PathBuf {
+            let name: String = stage
+                .chars()
+                .map(|c| {
+                    if c.is_alphanumeric() || c == '-' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            self.directory.join(name + ".pam")
+        }
+
+        fn write(&self, image: &StageDump) -> io::Result<()> {
+            std::fs::create_dir_all(&self.directory)?;
+            let tuple_type = match image.channels {
+                1 => "TUPLTYPE GRAYSCALE\n",
+                2 => "TUPLTYPE GRAYSCALE_ALPHA\n",
+                3 => "TUPLTYPE RGB\n",
+                4 => "TUPLTYPE RGB_ALPHA\n",
+                _ => "",
+            };
+            let mut file = io::BufWriter::new(std::fs::File::create(self.path(&image.stage))?);
+            write!(
+                file,
+                "P7\nWIDTH {}\nHEIGHT {}\nDEPTH {}\nMAXVAL 255\n{tuple_type}ENDHDR\n",
+                image.width, image.height, image.channels
+            )?;
+            let bytes: Vec<u8> = image
+                .values
+                .iter()
+                .map(|v| (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8)
+                .collect();
+            file.write_all(&bytes)?;
+            file.flush()
+        }
+    }
+
+    impl DebugSink for DirectorySink {
+        fn dump(&self, image: StageDump) {
+            if let Err(error) = self.write(&image) {
+                *self.error.lock().unwrap_or_else(|e| e.into_inner()) = Some(error);
+            }
+        }
+    }
+
+    /// Samples `image` on a `width × height` grid and hands it to the sink
+    /// installed by [`set_debug`], if any.
+    pub(crate) fn dump<I>(image: &I, stage: &str, width: usize, height: usize)
+    where
+        I: Image,
+        I::Pixel: Pixel,
+        <I::Pixel as Pixel>::Scalar: SrgbChannel,
+    {
+        if !debug_enabled() {
+            return;
+        }
+        let Some(sink) = SINK.lock().unwrap_or_else(|e| e.into_inner()).clone() else {
+            return;
+        };
+
+        let buffer = ImageBuffer::sample(image, width, height, Layout::Interleaved);
+        let channels = <I::Pixel as Pixel>::CHANNELS;
+        let values = buffer
+            .pixels()
+            .flat_map(|p| (0..channels).map(move |c| p.channel(c).normalized()))
+            .collect();
+        sink.dump(StageDump {
+            stage: stage.into(),
+            width,
+            height,
+            channels,
+            values,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    use space::Place;
+
+    use crate::{Gray, Image, ImageBuffer, Layout, from_fn};
+
+    #[test]
+    fn inspect_sees_every_sample() {
+        let seen = RefCell::new(Vec::new());
+        let image = from_fn(|p: Place| Gray(p.x().to_f64().unwrap() as f32))
+            .inspect(|p: &Place, &Gray(v)| seen.borrow_mut().push((p.y().to_f64().unwrap(), v)));
+        let buffer = ImageBuffer::sample(&image, 2, 2, Layout::Interleaved);
+
+        assert_eq!(buffer.pixel(1, 0), Some(Gray(1.5)));
+        assert_eq!(
+            seen.into_inner(),
+            [(0.5, 0.5), (0.5, 1.5), (1.5, 0.5), (1.5, 1.5)]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn stages_are_dumped_only_while_debugging() {
+        use alloc::sync::Arc;
+
+        use super::{CollectSink, StageDump, set_debug};
+        use crate::Rgb;
+
+        let image = || from_fn(|p: Place| Rgb::new(p.x().to_f64().unwrap() as u8 * 255, 0, 0));
+        image().dump_stage("before", 2, 1);
+
+        let sink = Arc::new(CollectSink::new());
+        set_debug(Some(sink.clone()));
+        let sampled = image()
+            .dump_stage("ramp", 2, 1)
+            .get(Place::new(0.5, 0.5).unwrap());
+        set_debug(None);
+        image().dump_stage("after", 2, 1);
+
+        assert_eq!(sampled, Rgb::new(0, 0, 0));
+        assert_eq!(
+            sink.take(),
+            [StageDump {
+                stage: "ramp".into(),
+                width: 2,
+                height: 1,
+                channels: 3,
+                values: alloc::vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+            }]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn directory_sink_writes_pam_files() {
+        use super::{DebugSink, DirectorySink, StageDump};
+
+        let directory = std::env::temp_dir().join(format!("flipr-dump-{}", std::process::id()));
+        let sink = DirectorySink::new(&directory);
+        sink.dump(StageDump {
+            stage: "blur/3".into(),
+            width: 2,
+            height: 1,
+            channels: 1,
+            values: alloc::vec![0.0, 1.0],
+        });
+
+        let path = sink.path("blur/3");
+        assert!(path.ends_with("blur_3.pam"));
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        let header = "P7\nWIDTH 2\nHEIGHT 1\nDEPTH 1\nMAXVAL 255\nTUPLTYPE GRAYSCALE\nENDHDR\n";
+        assert_eq!(&bytes[..header.len()], header.as_bytes());
+        assert_eq!(&bytes[header.len()..], [0, 255]);
+        assert!(sink.take_error().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn directory_sink_keeps_write_errors() {
+        use super::{DebugSink, DirectorySink, StageDump};
+
+        // A file where the directory should be.
+        let file = std::env::temp_dir().join(format!("flipr-dump-file-{}", std::process::id()));
+        std::fs::write(&file, b"").unwrap();
+        let sink = DirectorySink::new(&file);
+        sink.dump(StageDump {
+            stage: "blur".into(),
+            width: 1,
+            height: 1,
+            channels: 1,
+            values: alloc::vec![0.5],
+        });
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(sink.take_error().is_some());
+        assert!(sink.take_error().is_none());
+    }
+}
@@ -0,0 +1,63 @@
+use space::{Offset, Place};
+
+use crate::Image;
+
+/// Image resampled through a displacement field, see [`Image::displace`].
+#[derive(Debug, Clone)]
+pub struct Displaced<I, F> {
+    image: I,
+    field: F,
+}
+
+impl<I, F> Displaced<I, F> {
+    pub(crate) fn new(image: I, field: F) -> Self {
+        Self { image, field }
+    }
+}
+
+impl<I, F> Image for Displaced<I, F>
+where
+    I: Image,
+    F: Image<Pixel = Offset>,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let offset = self.field.get(p.clone());
+        self.image.get(&p + offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, proptest};
+    use space::{Offset, Place};
+
+    use crate::tests::place;
+    use crate::{AffineTransform, Gray, Image, from_fn};
+
+    fn ramp() -> impl Image<Pixel = Gray<f64>> + Clone {
+        from_fn(|p: Place| Gray(p.x().to_f64().unwrap() + 10.0 * p.y().to_f64().unwrap()))
+    }
+
+    #[test]
+    fn every_place_reads_its_own_offset() {
+        let ripple = from_fn(|p: Place| {
+            let y = p.y().to_f64().unwrap();
+            Offset::new(if y < 0.0 { 2.0 } else { -1.0 }, 0.0).unwrap()
+        });
+        let displaced = ramp().displace(ripple);
+        assert_eq!(displaced.get(Place::new(3.0, -1.0).unwrap()), Gray(-5.0));
+        assert_eq!(displaced.get(Place::new(3.0, 1.0).unwrap()), Gray(12.0));
+    }
+
+    proptest! {
+        #[test]
+        fn a_constant_field_translates(p in place()) {
+            let shift = Offset::new(-2.5, 4.0).unwrap();
+            let displaced = ramp().displace(from_fn(move |_| shift.clone()));
+            let moved = ramp().transform(AffineTransform::translation(2.5, -4.0));
+            prop_assert_eq!(displaced.get(p.clone()), moved.get(p));
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use space::Place;
+
+use crate::Image;
+use crate::arithmetic::Overflow;
+use crate::buffer::{ImageBuffer, Layout, cell_center};
+use crate::lut::CubeError;
+use crate::pixel::Pixel;
+use crate::raw::RawError;
+
+/// Any error flipr can report, with optional context about where it happened.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FliprError {
+    Raw(RawError),
+    Cube(CubeError),
+    Overflow(Overflow),
+    /// Error raised by user code, such as a fallible pixel function.
+    Custom(String),
+    /// The computation was stopped by an
+    /// [`ExecutionObserver`](crate::ExecutionObserver).
+    Cancelled,
+    /// `source` annotated with the pixel and pipeline stage it happened in.
+    Context {
+        pixel: Option<(usize, usize)>,
+        stage: Option<String>,
+        source: Box<FliprError>,
+    },
+}
+
+impl FliprError {
+    pub fn custom(message: impl Into<String>) -> Self {
+        FliprError::Custom(message.into())
+    }
+
+    /// Records that the error happened while computing pixel `(i, j)`, unless
+    /// a pixel is already known.
+    pub fn at_pixel(self, i: usize, j: usize) -> Self {
+        match self {
+            FliprError::Context {
+                pixel: None,
+                stage,
+                source,
+            } => FliprError::Context {
+                pixel: Some((i, j)),
+                stage,
+                source,
+            },
+            context @ FliprError::Context { .. } => context,
+            error => FliprError::Context {
+                pixel: Some((i, j)),
+                stage: None,
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// Records the pipeline stage the error happened in, unless an inner
+    /// stage is already known.
+    pub fn in_stage(self, stage: impl Into<String>) -> Self {
+        match self {
+            FliprError::Context {
+                pixel,
+                stage: None,
+                source,
+            } => FliprError::Context {
+                pixel,
+                stage: Some(stage.into()),
+                source,
+            },
+            context @ FliprError::Context { .. } => context,
+            error => FliprError::Context {
+                pixel: None,
+                stage: Some(stage.into()),
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// The error without any context.
+    pub fn root_cause(&self) -> &FliprError {
+        match self {
+            FliprError::Context { source, .. } => source.root_cause(),
+            error => error,
+        }
+    }
+}
+
+impl fmt::Display for FliprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FliprError::Raw(error) => write!(f, "{error}"),
+            FliprError::Cube(error) => write!(f, "{error}"),
+            FliprError::Overflow(error) => write!(f, "{error}"),
+            FliprError::Custom(message) => write!(f, "{message}"),
+            FliprError::Cancelled => write!(f, "cancelled"),
+            FliprError::Context {
+                pixel,
+                stage,
+                source,
+            } => {
+                write!(f, "failed")?;
+                if let Some((i, j)) = pixel {
+                    write!(f, " at pixel ({i}, {j})")?;
+                }
+                if let Some(stage) = stage {
+                    write!(f, " in stage '{stage}'")?;
+                }
+                write!(f, ": {source}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FliprError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FliprError::Raw(error) => Some(error),
+            FliprError::Cube(error) => Some(error),
+            FliprError::Overflow(error) => Some(error),
+            FliprError::Custom(_) | FliprError::Cancelled => None,
+            FliprError::Context { source, .. } => Some(source.as_ref()),
+        }
+    }
+}
+
+impl From<RawError> for FliprError {
+    fn from(error: RawError) -> Self {
+        FliprError::Raw(error)
+    }
+}
+
+impl From<CubeError> for FliprError {
+    fn from(error: CubeError) -> Self {
+        FliprError::Cube(error)
+    }
+}
+
+impl From<Overflow> for FliprError {
+    fn from(error: Overflow) -> Self {
+        FliprError::Overflow(error)
+    }
+}
+
+impl From<String> for FliprError {
+    fn from(message: String) -> Self {
+        FliprError::Custom(message)
+    }
+}
+
+impl From<&str> for FliprError {
+    fn from(message: &str) -> Self {
+        FliprError::custom(message)
+    }
+}
+
+/// Image with fallible pixels whose errors are converted by a function, see
+/// [`Image::map_err`].
+#[derive(Debug, Clone)]
+pub struct MapErr<I, F> {
+    image: I,
+    f: F,
+}
+
+impl<I, F> MapErr<I, F> {
+    pub(crate) fn new(image: I, f: F) -> Self {
+        Self { image, f }
+    }
+}
+
+impl<I, F, P, E, E2> Image for MapErr<I, F>
+where
+    I: Image<Pixel = Result<P, E>>,
+    F: Fn(E) -> E2,
+{
+    type Pixel = Result<P, E2>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.image.get(p).map_err(&self.f)
+    }
+}
+
+impl<P: Pixel> ImageBuffer<P> {
+    /// Like [`sample`](Self::sample) for images with fallible pixels, stopping
+    /// at the first error and recording the pixel it happened at.
+    pub fn try_sample<I, E>(
+        image: &I,
+        width: usize,
+        height: usize,
+        layout: Layout,
+    ) -> Result<Self, FliprError>
+    where
+        I: Image<Pixel = Result<P, E>>,
+        E: Into<FliprError>,
+    {
+        let mut pixels = Vec::with_capacity(width * height);
+        for j in 0..height {
+            for i in 0..width {
+                let pixel = image.get(cell_center(i, j));
+                pixels.push(pixel.map_err(|error| error.into().at_pixel(i, j))?);
+            }
+        }
+
+        Ok(Self::from_fn(width, height, layout, |i, j| {
+            pixels[j * width + i]
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use space::Place;
+
+    use super::FliprError;
+    use crate::{CubeError, Gray, Image, ImageBuffer, Layout, OverflowPolicy, RawError, from_fn};
+
+    #[test]
+    fn context_reads_like_a_sentence() {
+        let error = FliprError::from(RawError::Empty)
+            .at_pixel(3, 4)
+            .in_stage("convolve");
+        assert_eq!(
+            error.to_string(),
+            "failed at pixel (3, 4) in stage 'convolve': image must have at least one pixel"
+        );
+        assert_eq!(error.root_cause(), &FliprError::Raw(RawError::Empty));
+    }
+
+    #[test]
+    fn innermost_context_wins() {
+        let error = FliprError::from(CubeError::MissingSize)
+            .in_stage("grade")
+            .at_pixel(1, 2)
+            .in_stage("pipeline")
+            .at_pixel(5, 6);
+        assert_eq!(
+            error.to_string(),
+            "failed at pixel (1, 2) in stage 'grade': missing or invalid LUT_3D_SIZE"
+        );
+    }
+
+    #[test]
+    fn try_sample_reports_the_first_failing_pixel() {
+        let image = from_fn(|p: Place| {
+            let x = p.x().to_f64().unwrap();
+            if x > 2.0 && p.y().to_f64().unwrap() > 1.0 {
+                Err("out of range")
+            } else {
+                Ok(Gray(x as u8))
+            }
+        })
+        .map_err(|e| FliprError::from(e).in_stage("threshold"));
+
+        let error = ImageBuffer::try_sample(&image, 4, 3, Layout::Interleaved).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "failed at pixel (2, 1) in stage 'threshold': out of range"
+        );
+
+        let buffer = ImageBuffer::try_sample(&image, 2, 3, Layout::Interleaved).unwrap();
+        assert_eq!(buffer.pixel(1, 2), Some(Gray(1)));
+    }
+
+    #[test]
+    fn overflowing_sums_fail_under_the_error_policy() {
+        let ramp = from_fn(|p: Place| Gray(p.x().to_f64().unwrap() as u8 * 60));
+        let sum = ramp.add_with(ramp, OverflowPolicy::Error);
+
+        let error = ImageBuffer::try_sample(&sum, 4, 1, Layout::Interleaved).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "failed at pixel (3, 0): 360 does not fit the channel type"
+        );
+        assert!(ImageBuffer::try_sample(&sum, 3, 1, Layout::Interleaved).is_ok());
+    }
+}
@@ -0,0 +1,324 @@
+use alloc::vec::Vec;
+
+use space::{Place, Real};
+
+use crate::affine::AffineTransform;
+use crate::homography::Homography;
+use crate::noise::splitmix;
+
+/// Settings of the RANSAC loop behind [`estimate_affine`] and
+/// [`estimate_homography`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ransac {
+    /// Number of random minimal samples to try.
+    pub iterations: usize,
+    /// Largest distance, in units of the target plane, between a mapped
+    /// point and its partner for the pair to count as an inlier.
+    pub tolerance: f64,
+    /// Seed of the sampling; equal seeds give equal estimates.
+    pub seed: u64,
+}
+
+impl Default for Ransac {
+    fn default() -> Self {
+        Self {
+            iterations: 500,
+            tolerance: 2.0,
+            seed: 0,
+        }
+    }
+}
+
+/// Transform fitted by [`estimate_affine`] or [`estimate_homography`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate<T> {
+    pub transform: T,
+    /// Whether each input pair agrees with `transform`, in input order.
+    pub inliers: Vec<bool>,
+}
+
+impl<T> Estimate<T> {
+    pub fn inlier_count(&self) -> usize {
+        self.inliers.iter().filter(|&&inlier| inlier).count()
+    }
+}
+
+type Pair = ((f64, f64), (f64, f64));
+
+fn coords(place: &Place) -> (f64, f64) {
+    let finite = |r: &Real| r.to_f64().expect("a ratio of integers converts to f64");
+    (finite(place.x()), finite(place.y()))
+}
+
+/// Solves `a · x = b` by Gaussian elimination with partial pivoting, or
+/// `None` if `a` is singular.
+fn solve<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]> {
+    for column in 0..N {
+        let pivot =
+            (column..N).max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))?;
+        if a[pivot][column].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+
+        for row in column + 1..N {
+            let factor = a[row][column] / a[column][column];
+            let pivot_row = a[column];
+            for (value, above) in a[row].iter_mut().zip(pivot_row).skip(column) {
+                *value -= factor * above;
+            }
+            b[row] -= factor * b[column];
+        }
+    }
+
+    let mut x = [0.0; N];
+    for row in (0..N).rev() {
+        let tail: f64 = (row + 1..N).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - tail) / a[row][row];
+    }
+    x.iter().all(|v| v.is_finite()).then_some(x)
+}
+
+/// Least-squares solution of the equations `row · x = value`, through the
+/// normal equations.
+fn least_squares<const N: usize>(
+    equations: impl Iterator<Item = ([f64; N], f64)>,
+) -> Option<[f64; N]> {
+    let (mut ata, mut atb) = ([[0.0; N]; N], [0.0; N]);
+    for (row, value) in equations {
+        for i in 0..N {
+            for j in 0..N {
+                ata[i][j] += row[i] * row[j];
+            }
+            atb[i] += row[i] * value;
+        }
+    }
+    solve(ata, atb)
+}
+
+/// Similarity moving the centroid of `points` to the origin with mean
+/// distance `√2`, which keeps the homography equations well conditioned.
+fn normalization(points: impl Iterator<Item = (f64, f64)> + Clone) -> Option<AffineTransform> {
+    let n = points.clone().count() as f64;
+    let (sx, sy) = points
+        .clone()
+        .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+    let (cx, cy) = (sx / n, sy / n);
+    let spread = points
+        .map(|(x, y)| libm::hypot(x - cx, y - cy))
+        .sum::<f64>()
+        / n;
+    if !spread.is_normal() {
+        return None;
+    }
+
+    let scale = core::f64::consts::SQRT_2 / spread;
+    Some(AffineTransform::translation(-cx, -cy).then(AffineTransform::scaling(scale, scale)))
+}
+
+fn fit_affine(pairs: &[Pair]) -> Option<AffineTransform> {
+    let row = |&((x, y), _): &Pair| [x, y, 1.0];
+    let top = least_squares(pairs.iter().map(|pair| (row(pair), pair.1.0)))?;
+    let middle = least_squares(pairs.iter().map(|pair| (row(pair), pair.1.1)))?;
+    Some(AffineTransform::new([top, middle]))
+}
+
+fn fit_homography(pairs: &[Pair]) -> Option<Homography> {
+    let from = normalization(pairs.iter().map(|&(p, _)| p))?;
+    let to = normalization(pairs.iter().map(|&(_, q)| q))?;
+
+    let equations = pairs.iter().flat_map(|&(p, q)| {
+        let (x, y) = from.transform_point(p.0, p.1);
+        let (u, v) = to.transform_point(q.0, q.1);
+        [
+            ([x, y, 1.0, 0.0, 0.0, 0.0, -x * u, -y * u], u),
+            ([0.0, 0.0, 0.0, x, y, 1.0, -x * v, -y * v], v),
+        ]
+    });
+    let [a, b, c, d, e, f, g, h] = least_squares(equations)?;
+
+    let normalized = Homography::new([[a, b, c], [d, e, f], [g, h, 1.0]]);
+    let unnormalize = Homography::from(to).inverse()?;
+    let homography = Homography::from(from).then(normalized).then(unnormalize);
+    homography.inverse().map(|_| homography)
+}
+
+/// Runs RANSAC: fits `fit` to random `minimal`-sized samples, keeps the fit
+/// with the most inliers and refits it to all of them.
+fn ransac<T>(
+    pairs: &[(Place, Place)],
+    settings: &Ransac,
+    minimal: usize,
+    fit: impl Fn(&[Pair]) -> Option<T>,
+    error: impl Fn(&T, Pair) -> Option<f64>,
+) -> Option<Estimate<T>> {
+    let pairs: Vec<Pair> = pairs.iter().map(|(p, q)| (coords(p), coords(q))).collect();
+    if pairs.len() < minimal {
+        return None;
+    }
+
+    let inliers = |transform: &T| -> Vec<bool> {
+        pairs
+            .iter()
+            .map(|&pair| error(transform, pair).is_some_and(|e| e <= settings.tolerance))
+            .collect()
+    };
+    let count = |inliers: &[bool]| inliers.iter().filter(|&&inlier| inlier).count();
+
+    let mut state = settings.seed;
+    let mut best: Option<Vec<bool>> = None;
+    for _ in 0..settings.iterations {
+        let mut sample: Vec<usize> = Vec::with_capacity(minimal);
+        while sample.len() < minimal {
+            state = splitmix(state);
+            let index = (state % pairs.len() as u64) as usize;
+            if !sample.contains(&index) {
+                sample.push(index);
+            }
+        }
+
+        let chosen: Vec<Pair> = sample.iter().map(|&k| pairs[k]).collect();
+        if let Some(candidate) = fit(&chosen).map(|transform| inliers(&transform))
+            && best
+                .as_ref()
+                .is_none_or(|best| count(&candidate) > count(best))
+        {
+            best = Some(candidate);
+        }
+    }
+
+    let best = best?;
+    let kept: Vec<Pair> = pairs
+        .iter()
+        .zip(&best)
+        .filter(|(_, inlier)| **inlier)
+        .map(|(&pair, _)| pair)
+        .collect();
+    let transform = fit(&kept)?;
+    let refined = inliers(&transform);
+    let inliers = if count(&refined) >= count(&best) {
+        refined
+    } else {
+        best
+    };
+
+    Some(Estimate { transform, inliers })
+}
+
+fn distance((x, y): (f64, f64), (u, v): (f64, f64)) -> f64 {
+    libm::hypot(x - u, y - v)
+}
+
+/// Affine transform mapping the first place of each pair onto the second,
+/// fitted robustly with RANSAC so that wrong matches are ignored.
+///
+/// Returns `None` for fewer than 3 pairs or if every sample is degenerate,
+/// for example collinear. Feed the result to [`Image::transform`](crate::Image::transform).
+pub fn estimate_affine(
+    pairs: &[(Place, Place)],
+    settings: &Ransac,
+) -> Option<Estimate<AffineTransform>> {
+    ransac(pairs, settings, 3, fit_affine, |transform, (p, q)| {
+        Some(distance(transform.transform_point(p.0, p.1), q))
+    })
+}
+
+/// Homography mapping the first place of each pair onto the second, fitted
+/// robustly with RANSAC by the normalized direct linear transform.
+///
+/// Returns `None` for fewer than 4 pairs or if every sample is degenerate.
+/// Feed the result to [`Image::warp`](crate::Image::warp).
+pub fn estimate_homography(
+    pairs: &[(Place, Place)],
+    settings: &Ransac,
+) -> Option<Estimate<Homography>> {
+    ransac(pairs, settings, 4, fit_homography, |homography, (p, q)| {
+        Some(distance(homography.transform_point(p.0, p.1)?, q))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use space::Place;
+
+    use super::{Ransac, estimate_affine, estimate_homography};
+    use crate::{AffineTransform, Homography};
+
+    fn grid() -> Vec<(f64, f64)> {
+        (0..6)
+            .flat_map(|j| (0..6).map(move |i| (i as f64 * 7.0 + (j % 2) as f64, j as f64 * 5.0)))
+            .collect()
+    }
+
+    fn pairs(map: impl Fn(f64, f64) -> (f64, f64)) -> Vec<(Place, Place)> {
+        grid()
+            .into_iter()
+            .map(|(x, y)| {
+                let (u, v) = map(x, y);
+                (Place::new(x, y).unwrap(), Place::new(u, v).unwrap())
+            })
+            .collect()
+    }
+
+    /// Replaces every fourth target with a far-away wrong match.
+    fn with_outliers(mut pairs: Vec<(Place, Place)>) -> Vec<(Place, Place)> {
+        for (k, pair) in pairs.iter_mut().enumerate().filter(|(k, _)| k % 4 == 0) {
+            pair.1 = Place::new(100.0 + k as f64 * 13.0, -50.0 - k as f64 * 3.0).unwrap();
+        }
+        pairs
+    }
+
+    fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+        (a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6
+    }
+
+    #[test]
+    fn affine_fit_ignores_outliers() {
+        let truth = AffineTransform::rotation(0.4)
+            .then(AffineTransform::scaling(1.5, 0.8))
+            .then(AffineTransform::translation(12.0, -3.0));
+        let pairs = with_outliers(pairs(|x, y| truth.transform_point(x, y)));
+
+        let estimate = estimate_affine(&pairs, &Ransac::default()).unwrap();
+        assert_eq!(estimate.inlier_count(), 27);
+        assert!(estimate.inliers.iter().step_by(4).all(|inlier| !inlier));
+        for (x, y) in grid() {
+            assert!(close(
+                estimate.transform.transform_point(x, y),
+                truth.transform_point(x, y)
+            ));
+        }
+    }
+
+    #[test]
+    fn homography_fit_recovers_perspective() {
+        let truth = Homography::new([[0.9, 0.1, 5.0], [-0.05, 1.1, -2.0], [0.001, 0.002, 1.0]]);
+        let pairs = with_outliers(pairs(|x, y| truth.transform_point(x, y).unwrap()));
+
+        let estimate = estimate_homography(&pairs, &Ransac::default()).unwrap();
+        assert_eq!(estimate.inlier_count(), 27);
+        for (x, y) in grid() {
+            assert!(close(
+                estimate.transform.transform_point(x, y).unwrap(),
+                truth.transform_point(x, y).unwrap()
+            ));
+        }
+    }
+
+    #[test]
+    fn too_few_or_degenerate_pairs_give_no_estimate() {
+        let settings = Ransac::default();
+        let line: Vec<_> = (0..5)
+            .map(|k| {
+                let p = Place::new(k as f64, k as f64).unwrap();
+                (p.clone(), p)
+            })
+            .collect();
+        assert_eq!(estimate_affine(&line[..2], &settings), None);
+        assert_eq!(estimate_affine(&line, &settings), None);
+        assert_eq!(estimate_homography(&line, &settings), None);
+    }
+}
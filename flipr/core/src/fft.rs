@@ -0,0 +1,486 @@
+//! Discrete Fourier transforms of [`Gray<f32>`] buffers and frequency-domain filtering.
+//!
+//! Power-of-two sizes use an iterative radix-2 transform; other sizes go through
+//! Bluestein's algorithm, so every size runs in `O(n log n)`.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+use core::ops::{Add, Mul, Sub};
+
+use crate::buffer::{ImageBuffer, Layout};
+use crate::pixel::Gray;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    /// `e^(iθ)`.
+    pub fn from_angle(theta: f64) -> Self {
+        Self::new(libm::cos(theta), libm::sin(theta))
+    }
+
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    pub fn norm(self) -> f64 {
+        libm::hypot(self.re, self.im)
+    }
+
+    pub fn scale(self, k: f64) -> Self {
+        Self::new(self.re * k, self.im * k)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Complex::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+/// Row-major `width × height` grid of complex values, typically a spectrum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplexPlane {
+    width: usize,
+    height: usize,
+    data: Vec<Complex>,
+}
+
+impl ComplexPlane {
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        mut f: impl FnMut(usize, usize) -> Complex,
+    ) -> Self {
+        let data = (0..height)
+            .flat_map(|v| (0..width).map(move |u| (u, v)))
+            .map(|(u, v)| f(u, v))
+            .collect();
+
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, u: usize, v: usize) -> Option<Complex> {
+        (u < self.width && v < self.height).then(|| self.data[v * self.width + u])
+    }
+
+    /// Multiplies element-wise with `other`, which is convolution in the spatial domain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the planes differ in size.
+    pub fn multiply(&self, other: &ComplexPlane) -> ComplexPlane {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "spectra must have the same size"
+        );
+
+        let data = self
+            .data
+            .iter()
+            .zip(&other.data)
+            .map(|(&a, &b)| a * b)
+            .collect();
+        Self { data, ..*self }
+    }
+
+    fn transform(&mut self, inverse: bool) {
+        let (width, height) = (self.width, self.height);
+
+        for row in self.data.chunks_mut(width) {
+            dft(row, inverse);
+        }
+
+        let mut column = vec![Complex::default(); height];
+        for u in 0..width {
+            for (v, c) in column.iter_mut().enumerate() {
+                *c = self.data[v * width + u];
+            }
+            dft(&mut column, inverse);
+            for (v, c) in column.iter().enumerate() {
+                self.data[v * width + u] = *c;
+            }
+        }
+    }
+}
+
+/// In-place unnormalized DFT, or its inverse scaled by `1 / n`.
+fn dft(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    if n <= 1 {
+        return;
+    }
+
+    if n.is_power_of_two() {
+        radix2(data, inverse);
+    } else {
+        bluestein(data, inverse);
+    }
+
+    if inverse {
+        let k = 1.0 / n as f64;
+        data.iter_mut().for_each(|c| *c = c.scale(k));
+    }
+}
+
+fn radix2(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    let bits = n.trailing_zeros();
+
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let step = Complex::from_angle(sign * 2.0 * PI / len as f64);
+        for chunk in data.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            let mut w = Complex::new(1.0, 0.0);
+            for (a, b) in lo.iter_mut().zip(hi) {
+                let t = w * *b;
+                *b = *a - t;
+                *a = *a + t;
+                w = w * step;
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// Expresses a DFT of any length as a power-of-two circular convolution.
+fn bluestein(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    let m = (2 * n - 1).next_power_of_two();
+    let sign = if inverse { 1.0 } else { -1.0 };
+
+    // k² mod 2n keeps the chirp angle exact for large k.
+    let chirp: Vec<Complex> = (0..n)
+        .map(|k| {
+            let k2 = (k * k) % (2 * n);
+            Complex::from_angle(sign * PI * k2 as f64 / n as f64)
+        })
+        .collect();
+
+    let mut a = vec![Complex::default(); m];
+    for (k, (x, w)) in data.iter().zip(&chirp).enumerate() {
+        a[k] = *x * *w;
+    }
+
+    let mut b = vec![Complex::default(); m];
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+
+    radix2(&mut a, false);
+    radix2(&mut b, false);
+    for (x, y) in a.iter_mut().zip(&b) {
+        *x = *x * *y;
+    }
+    radix2(&mut a, true);
+
+    let k = 1.0 / m as f64;
+    for (i, x) in data.iter_mut().enumerate() {
+        *x = (a[i] * chirp[i]).scale(k);
+    }
+}
+
+/// Spectrum of a grayscale buffer, with the zero frequency at `(0, 0)`.
+pub fn fft2(image: &ImageBuffer<Gray<f32>>) -> ComplexPlane {
+    let mut plane = ComplexPlane::from_fn(image.width(), image.height(), |u, v| {
+        let Gray(value) = image.pixel(u, v).expect("indices are in bounds");
+        Complex::new(value as f64, 0.0)
+    });
+    plane.transform(false);
+    plane
+}
+
+/// Inverse of [`fft2`], keeping the real part.
+///
+/// # Panics
+///
+/// Panics if the plane is empty.
+pub fn ifft2(spectrum: &ComplexPlane) -> ImageBuffer<Gray<f32>> {
+    let mut plane = spectrum.clone();
+    plane.transform(true);
+    ImageBuffer::from_fn(plane.width, plane.height, Layout::Interleaved, |u, v| {
+        Gray(plane.data[v * plane.width + u].re as f32)
+    })
+}
+
+/// Circular convolution of `image` with `kernel`, both of the same size, with
+/// the kernel's origin at `(width / 2, height / 2)`.
+///
+/// # Panics
+///
+/// Panics if the sizes differ.
+pub fn convolve(
+    image: &ImageBuffer<Gray<f32>>,
+    kernel: &ImageBuffer<Gray<f32>>,
+) -> ImageBuffer<Gray<f32>> {
+    let (width, height) = (image.width(), image.height());
+    assert!(
+        kernel.width() == width && kernel.height() == height,
+        "kernel must have the size of the image"
+    );
+
+    let centered = ImageBuffer::from_fn(width, height, Layout::Interleaved, |u, v| {
+        let x = (u + width / 2) % width;
+        let y = (v + height / 2) % height;
+        kernel.pixel(x, y).expect("indices are in bounds")
+    });
+
+    ifft2(&fft2(image).multiply(&fft2(&centered)))
+}
+
+/// Linear convolution of the row-major `width × height` planes with the
+/// `kernel_width`-wide row-major `weights`, centered on the middle weight,
+/// with edge values repeated outside like an [`ImageBuffer`] does.
+///
+/// The planes are padded to powers of two large enough that the circular
+/// convolution never wraps around, and the kernel is transformed once.
+pub(crate) fn convolve_clamped(
+    planes: &[Vec<f64>],
+    width: usize,
+    height: usize,
+    weights: &[f64],
+    kernel_width: usize,
+) -> Vec<Vec<f64>> {
+    let kernel_height = weights.len() / kernel_width;
+    let (rx, ry) = (kernel_width / 2, kernel_height / 2);
+    let (pw, ph) = (
+        (width + 2 * rx).next_power_of_two(),
+        (height + 2 * ry).next_power_of_two(),
+    );
+
+    let mut kernel = ComplexPlane::from_fn(pw, ph, |u, v| {
+        let (i, j) = ((u + rx) % pw, (v + ry) % ph);
+        if i < kernel_width && j < kernel_height {
+            Complex::new(weights[j * kernel_width + i], 0.0)
+        } else {
+            Complex::default()
+        }
+    });
+    kernel.transform(false);
+
+    planes
+        .iter()
+        .map(|plane| {
+            let clamp = |n: usize, r: usize, len: usize| n.saturating_sub(r).min(len - 1);
+            let mut padded = ComplexPlane::from_fn(pw, ph, |u, v| {
+                let (x, y) = (clamp(u, rx, width), clamp(v, ry, height));
+                Complex::new(plane[y * width + x], 0.0)
+            });
+            padded.transform(false);
+            let mut product = padded.multiply(&kernel);
+            product.transform(true);
+
+            (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| product.data[(y + ry) * pw + x + rx].re)
+                .collect()
+        })
+        .collect()
+}
+
+/// Radially symmetric frequency response, with cutoffs in cycles per pixel
+/// (`0.0..=0.5` covers everything up to Nyquist).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrequencyFilter {
+    IdealLowPass { cutoff: f64 },
+    IdealHighPass { cutoff: f64 },
+    ButterworthLowPass { cutoff: f64, order: u32 },
+    ButterworthHighPass { cutoff: f64, order: u32 },
+}
+
+impl FrequencyFilter {
+    /// Gain at radial frequency `d`, in cycles per pixel.
+    pub fn gain(self, d: f64) -> f64 {
+        let butterworth =
+            |cutoff: f64, order: u32| 1.0 / (1.0 + libm::pow(d / cutoff, 2.0 * order as f64));
+
+        match self {
+            FrequencyFilter::IdealLowPass { cutoff } => (d <= cutoff) as u8 as f64,
+            FrequencyFilter::IdealHighPass { cutoff } => (d > cutoff) as u8 as f64,
+            FrequencyFilter::ButterworthLowPass { cutoff, order } => butterworth(cutoff, order),
+            FrequencyFilter::ButterworthHighPass { cutoff, order } => {
+                1.0 - butterworth(cutoff, order)
+            }
+        }
+    }
+
+    /// Filters `image` by scaling its spectrum with [`FrequencyFilter::gain`].
+    pub fn apply(self, image: &ImageBuffer<Gray<f32>>) -> ImageBuffer<Gray<f32>> {
+        let (width, height) = (image.width(), image.height());
+        let frequency = |k: usize, n: usize| {
+            let k = if k > n / 2 {
+                k as f64 - n as f64
+            } else {
+                k as f64
+            };
+            k / n as f64
+        };
+        let response = ComplexPlane::from_fn(width, height, |u, v| {
+            let d = libm::hypot(frequency(u, width), frequency(v, height));
+            Complex::new(self.gain(d), 0.0)
+        });
+
+        ifft2(&fft2(image).multiply(&response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::collection::vec;
+    use proptest::{prop_assert, proptest};
+
+    use super::{Complex, FrequencyFilter, convolve, fft2, ifft2};
+    use crate::buffer::{ImageBuffer, Layout};
+    use crate::pixel::Gray;
+
+    fn buffer(width: usize, values: &[f32]) -> ImageBuffer<Gray<f32>> {
+        ImageBuffer::from_fn(width, values.len() / width, Layout::Interleaved, |i, j| {
+            Gray(values[j * width + i])
+        })
+    }
+
+    fn close(a: &ImageBuffer<Gray<f32>>, b: &ImageBuffer<Gray<f32>>, tolerance: f32) -> bool {
+        a.as_interleaved()
+            .unwrap()
+            .iter()
+            .zip(b.as_interleaved().unwrap())
+            .all(|(x, y)| (x.0 - y.0).abs() <= tolerance)
+    }
+
+    proptest! {
+        #[test]
+        fn inverse_undoes_forward(width in 1usize..9, values in vec(-100.0..100.0f32, 1..64)) {
+            let height = values.len() / width;
+            proptest::prop_assume!(height > 0);
+            let image = buffer(width, &values[..width * height]);
+            prop_assert!(close(&ifft2(&fft2(&image)), &image, 1e-3));
+        }
+    }
+
+    #[test]
+    fn dc_term_is_the_sum() {
+        let image = buffer(3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let dc = fft2(&image).get(0, 0).unwrap();
+        assert!((dc.re - 21.0).abs() < 1e-9 && dc.im.abs() < 1e-9);
+    }
+
+    #[test]
+    fn matches_naive_dft_for_odd_sizes() {
+        let values = [3.0, -1.0, 4.0, 1.0, -5.0];
+        let spectrum = fft2(&buffer(5, &values));
+        for u in 0..5 {
+            let expected = values
+                .iter()
+                .enumerate()
+                .fold(Complex::default(), |acc, (x, &v)| {
+                    let theta = -2.0 * core::f64::consts::PI * (u * x) as f64 / 5.0;
+                    acc + Complex::from_angle(theta).scale(v as f64)
+                });
+            assert!((spectrum.get(u, 0).unwrap() - expected).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn convolving_with_centered_impulse_is_identity() {
+        let image = buffer(
+            4,
+            &[
+                1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0,
+            ],
+        );
+        let impulse = ImageBuffer::from_fn(4, 3, Layout::Interleaved, |i, j| {
+            Gray(if (i, j) == (2, 1) { 1.0 } else { 0.0 })
+        });
+        assert!(close(&convolve(&image, &impulse), &image, 1e-4));
+    }
+
+    #[test]
+    fn convolving_with_shifted_impulse_shifts_circularly() {
+        let image = buffer(4, &[1.0, 2.0, 3.0, 4.0]);
+        let impulse = buffer(4, &[0.0, 0.0, 0.0, 1.0]);
+        assert!(close(
+            &convolve(&image, &impulse),
+            &buffer(4, &[4.0, 1.0, 2.0, 3.0]),
+            1e-4
+        ));
+    }
+
+    #[test]
+    fn low_pass_keeps_dc_and_high_pass_removes_it() {
+        let image = buffer(4, &[1.0, 3.0, 1.0, 3.0, 3.0, 1.0, 3.0, 1.0]);
+        let mean = buffer(4, &[2.0; 8]);
+        let detail = buffer(4, &[-1.0, 1.0, -1.0, 1.0, 1.0, -1.0, 1.0, -1.0]);
+
+        let low = FrequencyFilter::IdealLowPass { cutoff: 0.1 }.apply(&image);
+        let high = FrequencyFilter::IdealHighPass { cutoff: 0.1 }.apply(&image);
+        assert!(close(&low, &mean, 1e-4));
+        assert!(close(&high, &detail, 1e-4));
+    }
+
+    #[test]
+    fn butterworth_is_half_power_at_cutoff() {
+        let filter = FrequencyFilter::ButterworthLowPass {
+            cutoff: 0.2,
+            order: 2,
+        };
+        assert!((filter.gain(0.2) - 0.5).abs() < 1e-12);
+        assert_eq!(filter.gain(0.0), 1.0);
+        let high = FrequencyFilter::ButterworthHighPass {
+            cutoff: 0.2,
+            order: 2,
+        };
+        assert!((high.gain(0.3) + filter.gain(0.3) - 1.0).abs() < 1e-12);
+    }
+}
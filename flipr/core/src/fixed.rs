@@ -0,0 +1,341 @@
+use space::{Offset, Place, Real};
+
+use crate::Image;
+use crate::arithmetic::{Overflow, OverflowPolicy, PixelScale};
+use crate::kernel::Kernel;
+use crate::pixel::{Pixel, with_sums};
+
+/// Fixed-point number format of [`Fixed`] factors and [`FixedKernel`]
+/// weights, for targets without a floating-point unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedFormat {
+    /// 8 integer and 8 fractional bits: values in `-128.0..128.0` in steps
+    /// of `1/256`.
+    Q8_8,
+    /// 16 integer and 16 fractional bits: values in `-32768.0..32768.0` in
+    /// steps of `1/65536`.
+    Q16_16,
+}
+
+impl FixedFormat {
+    /// Number of fractional bits.
+    pub const fn frac_bits(self) -> u32 {
+        match self {
+            FixedFormat::Q8_8 => 8,
+            FixedFormat::Q16_16 => 16,
+        }
+    }
+
+    /// Smallest and largest raw value.
+    const fn raw_bounds(self) -> (i32, i32) {
+        match self {
+            FixedFormat::Q8_8 => (i16::MIN as i32, i16::MAX as i32),
+            FixedFormat::Q16_16 => (i32::MIN, i32::MAX),
+        }
+    }
+
+    /// `value` in this format, rounded to the nearest step and saturated at
+    /// the bounds.
+    const fn quantize(self, value: f64) -> i32 {
+        let scaled = value * (1u32 << self.frac_bits()) as f64;
+        let rounded = if scaled < 0.0 {
+            scaled - 0.5
+        } else {
+            scaled + 0.5
+        };
+        let (lo, hi) = self.raw_bounds();
+        if rounded <= lo as f64 {
+            lo
+        } else if rounded >= hi as f64 {
+            hi
+        } else {
+            rounded as i32
+        }
+    }
+
+    /// Divides a product by one step, rounding half up.
+    const fn rescale(self, product: i64) -> i64 {
+        let bits = self.frac_bits();
+        (product + (1 << (bits - 1))) >> bits
+    }
+}
+
+/// Channel type whose values the fixed-point path reads and writes as
+/// integers, without a detour through `f64`.
+pub trait FixedChannel: Copy {
+    fn to_i64(self) -> i64;
+
+    /// Converts `value` following `policy`.
+    fn from_i64_with(value: i64, policy: OverflowPolicy) -> Result<Self, Overflow>;
+}
+
+macro_rules! fixed_channel {
+    ($($t:ty),*) => {$(
+        impl FixedChannel for $t {
+            fn to_i64(self) -> i64 {
+                self as i64
+            }
+
+            fn from_i64_with(value: i64, policy: OverflowPolicy) -> Result<Self, Overflow> {
+                let (lo, hi) = (<$t>::MIN as i64, <$t>::MAX as i64);
+                match policy {
+                    OverflowPolicy::Saturate => Ok(value.clamp(lo, hi) as $t),
+                    OverflowPolicy::Wrap => Ok(value as $t),
+                    OverflowPolicy::Clamp { min, max } => {
+                        let (min, max) = (min as i64, max as i64);
+                        Ok(value.max(min).min(max).clamp(lo, hi) as $t)
+                    }
+                    OverflowPolicy::Error => <$t>::try_from(value).map_err(|_| Overflow {
+                        value: value as f64,
+                    }),
+                }
+            }
+        }
+    )*};
+}
+
+fixed_channel!(u8, u16, u32, i8, i16, i32);
+
+/// Fixed-point factor for [`PixelScale`], so that integer pixels are
+/// scaled with integer arithmetic alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixed {
+    raw: i32,
+    format: FixedFormat,
+}
+
+impl Fixed {
+    /// `value` rounded to the nearest step of `format` and saturated at its
+    /// bounds; usable in constants, so the conversion can happen at compile
+    /// time.
+    pub const fn from_f64(value: f64, format: FixedFormat) -> Self {
+        Self {
+            raw: format.quantize(value),
+            format,
+        }
+    }
+
+    /// Raw value with [`frac_bits`](FixedFormat::frac_bits) fractional bits.
+    pub fn raw(self) -> i32 {
+        self.raw
+    }
+
+    pub fn format(self) -> FixedFormat {
+        self.format
+    }
+
+    fn mul<T: FixedChannel>(self, c: T) -> i64 {
+        self.format.rescale(c.to_i64() * self.raw as i64)
+    }
+}
+
+impl<P> PixelScale<Fixed> for P
+where
+    P: Pixel,
+    P::Scalar: FixedChannel,
+{
+    fn scale(self, factor: Fixed) -> Self {
+        self.map(|c| {
+            FixedChannel::from_i64_with(factor.mul(c), OverflowPolicy::Saturate)
+                .expect("saturation cannot overflow")
+        })
+    }
+
+    fn scale_with(self, factor: Fixed, policy: OverflowPolicy) -> Result<Self, Overflow> {
+        let mut error = None;
+        let product = self.map(|c| {
+            FixedChannel::from_i64_with(factor.mul(c), policy).unwrap_or_else(|e| {
+                error.get_or_insert(e);
+                c
+            })
+        });
+        error.map_or(Ok(product), Err)
+    }
+}
+
+/// [`Kernel`] with its weights quantized to a [`FixedFormat`], see
+/// [`Kernel::quantize`] and [`Image::convolve_fixed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedKernel<const W: usize, const H: usize> {
+    weights: [[i32; W]; H],
+    format: FixedFormat,
+}
+
+impl<const W: usize, const H: usize> FixedKernel<W, H> {
+    /// Raw weights with [`frac_bits`](FixedFormat::frac_bits) fractional
+    /// bits.
+    pub fn weights(&self) -> &[[i32; W]; H] {
+        &self.weights
+    }
+
+    pub fn format(&self) -> FixedFormat {
+        self.format
+    }
+}
+
+impl<const W: usize, const H: usize> Kernel<W, H> {
+    /// The kernel with every weight rounded to the nearest step of `format`
+    /// and saturated at its bounds; usable in constants.
+    ///
+    /// Rounding moves each weight by at most half a step, so a convolution
+    /// with the quantized kernel differs from the exact one by at most
+    /// [`quantization_error`](Self::quantization_error) times the largest
+    /// channel magnitude under the kernel, plus half a unit from rounding
+    /// the result. That is at most `W·H / 2^(frac_bits + 1)` times the
+    /// largest magnitude: `0.018` of full scale for a 3×3 kernel in Q8.8,
+    /// and `0.00007` in Q16.16.
+    pub const fn quantize(&self, format: FixedFormat) -> FixedKernel<W, H> {
+        let mut weights = [[0; W]; H];
+        let mut j = 0;
+        while j < H {
+            let mut i = 0;
+            while i < W {
+                weights[j][i] = format.quantize(self.weights()[j][i]);
+                i += 1;
+            }
+            j += 1;
+        }
+        FixedKernel { weights, format }
+    }
+
+    /// Sum of the magnitudes by which [`quantize`](Self::quantize) moves the
+    /// weights, the bound on the relative error of a quantized convolution.
+    pub fn quantization_error(&self, format: FixedFormat) -> f64 {
+        let step = (1u32 << format.frac_bits()) as f64;
+        let quantized = self.quantize(format);
+        self.weights()
+            .iter()
+            .flatten()
+            .zip(quantized.weights.iter().flatten())
+            .map(|(&w, &q)| (w - q as f64 / step).abs())
+            .sum()
+    }
+}
+
+/// Image convolved with a [`FixedKernel`] in integer arithmetic, see
+/// [`Image::convolve_fixed`].
+#[derive(Debug, Clone)]
+pub struct FixedConvolved<I, const W: usize, const H: usize> {
+    image: I,
+    kernel: FixedKernel<W, H>,
+}
+
+impl<I, const W: usize, const H: usize> FixedConvolved<I, W, H> {
+    pub(crate) fn new(image: I, kernel: FixedKernel<W, H>) -> Self {
+        Self { image, kernel }
+    }
+}
+
+impl<I, const W: usize, const H: usize> Image for FixedConvolved<I, W, H>
+where
+    I: Image,
+    I::Pixel: Pixel,
+    <I::Pixel as Pixel>::Scalar: FixedChannel,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (rx, ry) = ((W / 2) as i64, (H / 2) as i64);
+
+        with_sums::<I::Pixel, i64, _>(|sums| {
+            for (j, row) in self.kernel.weights.iter().enumerate() {
+                for (i, &weight) in row.iter().enumerate() {
+                    // Convolution reads the source mirrored around `p`.
+                    let offset =
+                        Offset::from_reals(Real::from(rx - i as i64), Real::from(ry - j as i64));
+                    let sample = self.image.get(&p + offset);
+                    for (c, sum) in sums.iter_mut().enumerate() {
+                        *sum += sample.channel(c).to_i64() * weight as i64;
+                    }
+                }
+            }
+
+            Pixel::from_channels(|c| {
+                let value = self.kernel.format.rescale(sums[c]);
+                FixedChannel::from_i64_with(value, OverflowPolicy::Saturate)
+                    .expect("saturation cannot overflow")
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::{Fixed, FixedFormat};
+    use crate::tests::place;
+    use crate::{
+        Gray, Image, ImageBuffer, Kernel, Layout, Overflow, OverflowPolicy, PixelScale, Rgb,
+        from_fn, kernel,
+    };
+
+    const BLUR: Kernel<3, 3> = kernel!([[1, 2, 1], [2, 4, 2], [1, 2, 1]] / 16);
+    const THIRDS: Kernel<3, 1> = kernel!([[1, 1, 1]] / 3);
+
+    #[test]
+    fn weights_round_to_the_nearest_step() {
+        let blur = BLUR.quantize(FixedFormat::Q8_8);
+        assert_eq!(blur.weights()[1], [32, 64, 32]);
+        assert_eq!(BLUR.quantization_error(FixedFormat::Q8_8), 0.0);
+
+        let thirds = THIRDS.quantize(FixedFormat::Q8_8);
+        assert_eq!(thirds.weights(), &[[85, 85, 85]]);
+        let error = THIRDS.quantization_error(FixedFormat::Q8_8);
+        assert!(error > 0.0 && error <= 3.0 / 512.0);
+
+        let huge = kernel!([[-1000, 0, 1000]]).quantize(FixedFormat::Q8_8);
+        assert_eq!(huge.weights(), &[[i16::MIN as i32, 0, i16::MAX as i32]]);
+    }
+
+    #[test]
+    fn fixed_factors_scale_like_float_factors() {
+        let pixel = Rgb::new(10u8, 100, 200);
+        let factor = Fixed::from_f64(1.5, FixedFormat::Q8_8);
+        assert_eq!(factor.raw(), 384);
+        assert_eq!(pixel.scale(factor), pixel.scale(1.5));
+        assert_eq!(
+            Gray(200u8).scale_with(factor, OverflowPolicy::Error),
+            Err(Overflow { value: 300.0 })
+        );
+        assert_eq!(
+            Gray(200u8).scale_with(factor, OverflowPolicy::Wrap),
+            Ok(Gray(44))
+        );
+        let negative = Fixed::from_f64(-0.5, FixedFormat::Q16_16);
+        assert_eq!(Gray(-7i16).scale(negative), Gray(4));
+    }
+
+    #[test]
+    fn fixed_convolution_stays_within_the_documented_bound() {
+        let image = ImageBuffer::from_fn(9, 7, Layout::Interleaved, |i, j| {
+            Gray(((i * 53 + j * 29) % 256) as u8)
+        });
+        for format in [FixedFormat::Q8_8, FixedFormat::Q16_16] {
+            let bound = THIRDS.quantization_error(format) * 255.0 + 1.0;
+            let fixed = image.clone().convolve_fixed(THIRDS.quantize(format));
+            let exact = image.clone().convolve(THIRDS);
+            for j in 0..7 {
+                for i in 0..9 {
+                    let p = crate::buffer::cell_center(i, j);
+                    let (Gray(a), Gray(b)) = (fixed.get(p.clone()), exact.get(p));
+                    assert!((a as f64 - b as f64).abs() <= bound, "{a} vs {b}");
+                }
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn exact_kernels_convolve_exactly(p in place(), v: [u8; 2]) {
+            let image = from_fn(move |q: Place| {
+                Gray(if q.x().to_f64().unwrap() < 0.0 { v[0] } else { v[1] })
+            });
+            let fixed = image.convolve_fixed(BLUR.quantize(FixedFormat::Q16_16));
+            prop_assert_eq!(fixed.get(p.clone()), image.convolve(BLUR).get(p.clone()));
+            let Gray(value) = fixed.get(p);
+            prop_assert!(value >= v[0].min(v[1]) && value <= v[0].max(v[1]));
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use space::Place;
+
+use crate::Image;
+
+/// Image whose pixels are computed by a closure, see [`from_fn`].
+#[derive(Debug, Clone, Copy)]
+pub struct FromFn<F>(F);
+
+/// Creates an image sampling the given closure at every place.
+pub fn from_fn<P, F>(f: F) -> FromFn<F>
+where
+    F: Fn(Place) -> P,
+{
+    FromFn(f)
+}
+
+impl<P, F> Image for FromFn<F>
+where
+    F: Fn(Place) -> P,
+{
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        (self.0)(p)
+    }
+}
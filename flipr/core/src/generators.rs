@@ -0,0 +1,456 @@
+//! Source images that need no input: gradients, checkerboards, rings and
+//! color bars, for tests, demos and calibration.
+
+use alloc::vec::Vec;
+
+use space::{Offset, Place, Real};
+
+use crate::Image;
+use crate::arithmetic::PixelLerp;
+use crate::pixel::Rgb;
+
+/// Position along a gradient, in `0.0..=1.0`, paired with the color there.
+pub type ColorStop<P> = (f32, P);
+
+pub(crate) fn check_stops<P>(stops: &[ColorStop<P>]) {
+    assert!(
+        !stops.is_empty(),
+        "a gradient needs at least one color stop"
+    );
+    assert!(
+        stops.windows(2).all(|w| w[0].0 <= w[1].0),
+        "color stops must be sorted by position"
+    );
+}
+
+/// Color at `t`, interpolated between the surrounding stops and clamped to the
+/// first and last one.
+pub(crate) fn color_at<P: PixelLerp + Copy>(stops: &[ColorStop<P>], t: f64) -> P {
+    let after = stops.partition_point(|&(position, _)| (position as f64) <= t);
+
+    match (after.checked_sub(1).map(|i| stops[i]), stops.get(after)) {
+        (Some((_, color)), None) | (None, Some(&(_, color))) => color,
+        (Some((p0, c0)), Some(&(p1, c1))) => c0.lerp(c1, (t - p0 as f64) / (p1 - p0) as f64),
+        (None, None) => unreachable!("stops are not empty"),
+    }
+}
+
+fn coords(p: &Place) -> (f64, f64) {
+    (p.x().to_f64().unwrap_or(0.0), p.y().to_f64().unwrap_or(0.0))
+}
+
+/// Gradient along the line from `start` to `end`, see [`linear_gradient`].
+#[derive(Debug, Clone)]
+pub struct LinearGradient<P> {
+    start: (f64, f64),
+    end: (f64, f64),
+    stops: Vec<ColorStop<P>>,
+}
+
+/// Creates a gradient whose stop positions run from `0.0` at `start` to `1.0`
+/// at `end`, constant along lines perpendicular to it.
+///
+/// # Panics
+///
+/// Panics if `stops` is empty or not sorted by position.
+pub fn linear_gradient<P>(start: Place, end: Place, stops: Vec<ColorStop<P>>) -> LinearGradient<P> {
+    check_stops(&stops);
+    LinearGradient {
+        start: coords(&start),
+        end: coords(&end),
+        stops,
+    }
+}
+
+impl<P: PixelLerp + Copy> Image for LinearGradient<P> {
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (x, y) = coords(&p);
+        let (dx, dy) = (self.end.0 - self.start.0, self.end.1 - self.start.1);
+        let length = dx * dx + dy * dy;
+        let t = if length == 0.0 {
+            0.0
+        } else {
+            ((x - self.start.0) * dx + (y - self.start.1) * dy) / length
+        };
+
+        color_at(&self.stops, t)
+    }
+}
+
+/// Gradient around a center, see [`radial_gradient`].
+#[derive(Debug, Clone)]
+pub struct RadialGradient<P> {
+    center: (f64, f64),
+    radius: f64,
+    stops: Vec<ColorStop<P>>,
+}
+
+/// Creates a gradient whose stop positions run from `0.0` at `center` to `1.0`
+/// at distance `radius` from it.
+///
+/// # Panics
+///
+/// Panics if `stops` is empty or not sorted by position.
+pub fn radial_gradient<P>(
+    center: Place,
+    radius: Real,
+    stops: Vec<ColorStop<P>>,
+) -> RadialGradient<P> {
+    check_stops(&stops);
+    RadialGradient {
+        center: coords(&center),
+        radius: radius.to_f64().unwrap_or(0.0),
+        stops,
+    }
+}
+
+impl<P: PixelLerp + Copy> Image for RadialGradient<P> {
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (x, y) = coords(&p);
+        let distance = libm::hypot(x - self.center.0, y - self.center.1);
+        let t = if self.radius == 0.0 {
+            1.0
+        } else {
+            distance / self.radius
+        };
+
+        color_at(&self.stops, t)
+    }
+}
+
+/// Radial lens distortion as a displacement field, see [`barrel_distortion`]
+/// and [`pincushion_distortion`].
+#[derive(Debug, Clone)]
+pub struct RadialDistortion {
+    center: (f64, f64),
+    radius: f64,
+    k: f64,
+}
+
+fn radial_distortion(center: Place, radius: Real, k: f64) -> RadialDistortion {
+    let radius = radius.to_f64().unwrap_or(0.0);
+    assert!(radius > 0.0, "distortion radius must be positive");
+    RadialDistortion {
+        center: coords(&center),
+        radius,
+        k,
+    }
+}
+
+/// Field for [`Image::displace`] that bows straight lines outward, like a
+/// wide-angle lens: a place at distance `r` from `center` reads the source
+/// at distance `r·(1 + strength·(r / radius)²)`.
+///
+/// Displacing by a [`pincushion_distortion`] of the same strength roughly
+/// undoes it, which is how lens distortion is corrected.
+///
+/// # Panics
+///
+/// Panics if `radius` is not positive.
+pub fn barrel_distortion(center: Place, radius: Real, strength: f64) -> RadialDistortion {
+    radial_distortion(center, radius, strength)
+}
+
+/// Field for [`Image::displace`] that bows straight lines inward, like a
+/// telephoto lens: the opposite of [`barrel_distortion`].
+///
+/// # Panics
+///
+/// Panics if `radius` is not positive.
+pub fn pincushion_distortion(center: Place, radius: Real, strength: f64) -> RadialDistortion {
+    radial_distortion(center, radius, -strength)
+}
+
+impl Image for RadialDistortion {
+    type Pixel = Offset;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (x, y) = coords(&p);
+        let (dx, dy) = (x - self.center.0, y - self.center.1);
+        let scale = self.k * (dx * dx + dy * dy) / (self.radius * self.radius);
+        Offset::new(dx * scale, dy * scale).unwrap_or_else(Offset::zero)
+    }
+}
+
+/// Alternating squares of side `size`, see [`checkerboard`].
+#[derive(Debug, Clone)]
+pub struct Checkerboard<P> {
+    size: Real,
+    even: P,
+    odd: P,
+}
+
+/// Creates a checkerboard whose square at the origin is `even`.
+///
+/// Square membership is decided exactly on [`Real`] coordinates, so edges stay
+/// crisp under any transform.
+///
+/// # Panics
+///
+/// Panics if `size` is not positive.
+pub fn checkerboard<P>(size: Real, even: P, odd: P) -> Checkerboard<P> {
+    assert!(
+        size > Real::zero(),
+        "checkerboard squares must have a positive size"
+    );
+    Checkerboard { size, even, odd }
+}
+
+impl<P: Copy> Image for Checkerboard<P> {
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let square = |c: &Real| (c / &self.size).floor().to_i64().unwrap_or(0);
+
+        if (square(p.x()) + square(p.y())).rem_euclid(2) == 0 {
+            self.even
+        } else {
+            self.odd
+        }
+    }
+}
+
+/// Alternating rings of width `spacing`, see [`concentric_circles`].
+#[derive(Debug, Clone)]
+pub struct ConcentricCircles<P> {
+    center: (f64, f64),
+    spacing: f64,
+    even: P,
+    odd: P,
+}
+
+/// Creates rings around `center` whose innermost disc is `even`.
+///
+/// # Panics
+///
+/// Panics if `spacing` is not positive.
+pub fn concentric_circles<P>(
+    center: Place,
+    spacing: Real,
+    even: P,
+    odd: P,
+) -> ConcentricCircles<P> {
+    assert!(spacing > Real::zero(), "ring spacing must be positive");
+    ConcentricCircles {
+        center: coords(&center),
+        spacing: spacing.to_f64().unwrap_or(1.0),
+        even,
+        odd,
+    }
+}
+
+impl<P: Copy> Image for ConcentricCircles<P> {
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (x, y) = coords(&p);
+        let ring = libm::floor(libm::hypot(x - self.center.0, y - self.center.1) / self.spacing);
+
+        if ring % 2.0 == 0.0 {
+            self.even
+        } else {
+            self.odd
+        }
+    }
+}
+
+/// SMPTE-style color bars filling `[0, width) × [0, height)`, see [`color_bars`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorBars {
+    width: f64,
+    height: f64,
+}
+
+/// Creates an approximation of SMPTE ECR 1-1978 bars.
+///
+/// The top two thirds hold the seven 75% bars, a thin strip holds their
+/// reversed castellations, and the bottom quarter holds -I, 100% white, +Q and
+/// black. Places outside of the frame are clamped to its edge.
+///
+/// # Panics
+///
+/// Panics if `width` or `height` is not positive.
+pub fn color_bars(width: Real, height: Real) -> ColorBars {
+    assert!(
+        width > Real::zero() && height > Real::zero(),
+        "color bars need a non-empty frame"
+    );
+    ColorBars {
+        width: width.to_f64().unwrap_or(1.0),
+        height: height.to_f64().unwrap_or(1.0),
+    }
+}
+
+impl Image for ColorBars {
+    type Pixel = Rgb<u8>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        const BARS: [Rgb<u8>; 7] = [
+            Rgb {
+                r: 191,
+                g: 191,
+                b: 191,
+            },
+            Rgb {
+                r: 191,
+                g: 191,
+                b: 0,
+            },
+            Rgb {
+                r: 0,
+                g: 191,
+                b: 191,
+            },
+            Rgb { r: 0, g: 191, b: 0 },
+            Rgb {
+                r: 191,
+                g: 0,
+                b: 191,
+            },
+            Rgb { r: 191, g: 0, b: 0 },
+            Rgb { r: 0, g: 0, b: 191 },
+        ];
+        const BLACK: Rgb<u8> = Rgb {
+            r: 16,
+            g: 16,
+            b: 16,
+        };
+
+        let (x, y) = coords(&p);
+        let u = (x / self.width).clamp(0.0, 1.0 - f64::EPSILON);
+        let v = (y / self.height).clamp(0.0, 1.0);
+        let bar = (u * 7.0) as usize;
+
+        if v < 2.0 / 3.0 {
+            BARS[bar]
+        } else if v < 0.75 {
+            if bar.is_multiple_of(2) {
+                BARS[6 - bar]
+            } else {
+                BLACK
+            }
+        } else {
+            // Each of the first three bottom patches spans 5/4 of a top bar.
+            match (u * 7.0 / 1.25) as usize {
+                0 => Rgb::new(0, 33, 76),
+                1 => Rgb::new(235, 235, 235),
+                2 => Rgb::new(50, 0, 106),
+                _ => BLACK,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use proptest::{prop_assert, proptest};
+    use space::{Offset, Place, Real};
+
+    use super::{
+        barrel_distortion, checkerboard, color_bars, concentric_circles, linear_gradient,
+        pincushion_distortion, radial_gradient,
+    };
+    use crate::tests::place;
+    use crate::{Gray, Image, Rgb};
+
+    fn at(x: f64, y: f64) -> Place {
+        Place::new(x, y).unwrap()
+    }
+
+    fn real(value: f64) -> Real {
+        Real::from_f64(value).unwrap()
+    }
+
+    #[test]
+    fn linear_gradient_interpolates_between_stops() {
+        let image = linear_gradient(
+            at(0.0, 0.0),
+            at(10.0, 0.0),
+            vec![(0.0, Gray(0.0f32)), (0.5, Gray(1.0)), (1.0, Gray(0.0))],
+        );
+        assert_eq!(image.get(at(2.5, 7.0)), Gray(0.5));
+        assert_eq!(image.get(at(5.0, -3.0)), Gray(1.0));
+        assert_eq!(image.get(at(-4.0, 0.0)), Gray(0.0));
+        assert_eq!(image.get(at(40.0, 0.0)), Gray(0.0));
+    }
+
+    #[test]
+    fn radial_gradient_depends_on_distance_only() {
+        let image = radial_gradient(
+            at(1.0, 1.0),
+            real(5.0),
+            vec![(0.0, Rgb::new(0u8, 0, 0)), (1.0, Rgb::new(200, 100, 50))],
+        );
+        assert_eq!(image.get(at(1.0, 1.0)), Rgb::new(0, 0, 0));
+        assert_eq!(image.get(at(4.0, 5.0)), Rgb::new(200, 100, 50));
+        assert_eq!(image.get(at(1.0, 3.5)), image.get(at(3.5, 1.0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "sorted")]
+    fn unsorted_stops_are_rejected() {
+        linear_gradient(
+            at(0.0, 0.0),
+            at(1.0, 0.0),
+            vec![(1.0, Gray(0u8)), (0.0, Gray(1))],
+        );
+    }
+
+    #[test]
+    fn checkerboard_alternates_across_the_origin() {
+        let image = checkerboard(real(2.0), 'a', 'b');
+        assert_eq!(image.get(at(0.5, 0.5)), 'a');
+        assert_eq!(image.get(at(2.5, 0.5)), 'b');
+        assert_eq!(image.get(at(-0.5, 0.5)), 'b');
+        assert_eq!(image.get(at(-0.5, -0.5)), 'a');
+    }
+
+    #[test]
+    fn circles_alternate_with_radius() {
+        let image = concentric_circles(at(0.0, 0.0), real(1.0), 0, 1);
+        assert_eq!(image.get(at(0.5, 0.0)), 0);
+        assert_eq!(image.get(at(0.0, 1.5)), 1);
+        assert_eq!(image.get(at(-1.8, -1.8)), 0);
+    }
+
+    #[test]
+    fn color_bars_follow_the_smpte_order() {
+        let image = color_bars(real(70.0), real(12.0));
+        assert_eq!(image.get(at(5.0, 1.0)), Rgb::new(191, 191, 191));
+        assert_eq!(image.get(at(65.0, 1.0)), Rgb::new(0, 0, 191));
+        assert_eq!(image.get(at(5.0, 8.5)), Rgb::new(0, 0, 191));
+        assert_eq!(image.get(at(15.0, 8.5)), Rgb::new(16, 16, 16));
+        assert_eq!(image.get(at(5.0, 11.0)), Rgb::new(0, 33, 76));
+        assert_eq!(image.get(at(69.0, 11.0)), Rgb::new(16, 16, 16));
+    }
+
+    #[test]
+    fn lens_distortion_grows_with_the_square_of_the_radius() {
+        let barrel = barrel_distortion(at(10.0, 10.0), real(5.0), 0.2);
+        let offset = |field: &dyn Image<Pixel = Offset>, x, y| {
+            let o = field.get(at(x, y));
+            (o.dx().to_f64().unwrap(), o.dy().to_f64().unwrap())
+        };
+        assert_eq!(offset(&barrel, 10.0, 10.0), (0.0, 0.0));
+        assert_eq!(offset(&barrel, 15.0, 10.0), (1.0, 0.0));
+        assert_eq!(offset(&barrel, 10.0, 0.0), (0.0, -8.0));
+
+        let pincushion = pincushion_distortion(at(10.0, 10.0), real(5.0), 0.2);
+        assert_eq!(offset(&pincushion, 15.0, 10.0), (-1.0, 0.0));
+    }
+
+    proptest! {
+        #[test]
+        fn checkerboard_flips_when_shifted_by_one_square(p in place()) {
+            let image = checkerboard(real(3.0), true, false);
+            let shifted = Place::from_reals(p.x() + real(3.0), p.y().clone());
+            prop_assert!(image.get(p) != image.get(shifted));
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use space::{Grid, Place, Rounding};
+
+use crate::buffer::{ImageBuffer, cell_center};
+use crate::pixel::Pixel;
+use crate::view::ImageView;
+
+/// Dimensions of a pixel grid, in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Size {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Size {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+
+    /// Number of pixels.
+    pub fn area(self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.area() == 0
+    }
+
+    pub fn contains(self, coord: Coord) -> bool {
+        coord.x < self.width && coord.y < self.height
+    }
+
+    /// Row-major index of `coord`, if it is inside the grid.
+    pub fn index(self, coord: Coord) -> Option<usize> {
+        self.contains(coord).then(|| coord.y * self.width + coord.x)
+    }
+
+    /// Every coordinate of the grid in row-major order.
+    pub fn coords(self) -> impl Iterator<Item = Coord> {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| Coord::new(x, y)))
+    }
+}
+
+impl From<(usize, usize)> for Size {
+    fn from((width, height): (usize, usize)) -> Self {
+        Self::new(width, height)
+    }
+}
+
+impl From<Size> for (usize, usize) {
+    fn from(size: Size) -> Self {
+        (size.width, size.height)
+    }
+}
+
+/// Pixel `(x, y)` of a grid, covering the cell `[x, x + 1) × [y, y + 1)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Coord {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// Place at the center of the pixel cell, where buffers sample images.
+    pub fn center(self) -> Place {
+        cell_center(self.x, self.y)
+    }
+
+    /// Pixel whose cell contains `place`, or `None` if `place` has a negative
+    /// or unrepresentably large coordinate.
+    pub fn containing(place: &Place) -> Option<Self> {
+        let (x, y) = Grid::unit().place_to_index(place, Rounding::Floor)?;
+        Some(Self::new(
+            usize::try_from(x).ok()?,
+            usize::try_from(y).ok()?,
+        ))
+    }
+
+    /// Moves by `(dx, dy)`, or `None` if a coordinate would leave `0..size`.
+    pub fn checked_offset(self, dx: isize, dy: isize, size: Size) -> Option<Self> {
+        let coord = Self::new(
+            self.x.checked_add_signed(dx)?,
+            self.y.checked_add_signed(dy)?,
+        );
+        size.contains(coord).then_some(coord)
+    }
+}
+
+impl From<(usize, usize)> for Coord {
+    fn from((x, y): (usize, usize)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<Coord> for (usize, usize) {
+    fn from(coord: Coord) -> Self {
+        (coord.x, coord.y)
+    }
+}
+
+impl<P: Pixel> ImageBuffer<P> {
+    pub fn size(&self) -> Size {
+        Size::new(self.width(), self.height())
+    }
+}
+
+impl<P: Copy> ImageView<'_, P> {
+    pub fn size(&self) -> Size {
+        Size::new(self.width(), self.height())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::{Coord, Size};
+    use crate::tests::place;
+
+    #[test]
+    fn coords_are_row_major_indices() {
+        let size = Size::new(3, 2);
+        let indices: Vec<_> = size.coords().map(|c| size.index(c)).collect();
+        assert_eq!(indices, (0..6).map(Some).collect::<Vec<_>>());
+        assert_eq!(size.index(Coord::new(3, 0)), None);
+        assert!(Size::new(0, 5).is_empty());
+    }
+
+    #[test]
+    fn offsets_stay_inside_the_grid() {
+        let size = Size::new(4, 4);
+        let coord = Coord::new(1, 3);
+        assert_eq!(coord.checked_offset(2, -3, size), Some(Coord::new(3, 0)));
+        assert_eq!(coord.checked_offset(-2, 0, size), None);
+        assert_eq!(coord.checked_offset(0, 1, size), None);
+    }
+
+    #[test]
+    fn negative_places_have_no_pixel() {
+        assert_eq!(Coord::containing(&Place::new(-0.5, 2.0).unwrap()), None);
+        assert_eq!(
+            Coord::containing(&Place::new(2.999, 0.0).unwrap()),
+            Some(Coord::new(2, 0))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn centers_lie_in_their_own_cell(x in 0..1000usize, y in 0..1000usize) {
+            let coord = Coord::new(x, y);
+            prop_assert_eq!(Coord::containing(&coord.center()), Some(coord));
+        }
+
+        #[test]
+        fn containing_cells_hold_the_place(p in place()) {
+            let (x, y) = (p.x().to_f64().unwrap(), p.y().to_f64().unwrap());
+            match Coord::containing(&p) {
+                Some(c) => prop_assert!(
+                    (c.x as f64..c.x as f64 + 1.0).contains(&x)
+                        && (c.y as f64..c.y as f64 + 1.0).contains(&y)
+                ),
+                None => prop_assert!(x < 0.0 || y < 0.0),
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use space::Place;
+
+use crate::Image;
+use crate::affine::AffineTransform;
+
+/// Projective map of the plane in `f64`, `(x, y) ↦ (u / w, v / w)` for
+/// `(u, v, w) = rows · (x, y, 1)`.
+///
+/// Unlike an [`AffineTransform`] it can model a change of perspective, such
+/// as two photos of a flat scene taken from different directions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Homography {
+    rows: [[f64; 3]; 3],
+}
+
+impl Default for Homography {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl From<AffineTransform> for Homography {
+    fn from(transform: AffineTransform) -> Self {
+        let [top, middle] = transform.rows();
+        Self::new([top, middle, [0.0, 0.0, 1.0]])
+    }
+}
+
+impl Homography {
+    pub fn new(rows: [[f64; 3]; 3]) -> Self {
+        Self { rows }
+    }
+
+    pub fn identity() -> Self {
+        Self::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    pub fn rows(&self) -> [[f64; 3]; 3] {
+        self.rows
+    }
+
+    /// Applies `self` first and `next` second.
+    pub fn then(self, next: Self) -> Self {
+        let (a, b) = (next.rows, self.rows);
+        Self::new(core::array::from_fn(|i| {
+            core::array::from_fn(|j| (0..3).map(|k| a[i][k] * b[k][j]).sum())
+        }))
+    }
+
+    /// The map undoing `self`, or `None` if it collapses the plane.
+    pub fn inverse(&self) -> Option<Self> {
+        let m = self.rows;
+        let cofactor = |i: usize, j: usize| {
+            let (r0, r1) = ((i + 1) % 3, (i + 2) % 3);
+            let (c0, c1) = ((j + 1) % 3, (j + 2) % 3);
+            m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+        };
+        let det: f64 = (0..3).map(|j| m[0][j] * cofactor(0, j)).sum();
+        if !det.is_normal() {
+            return None;
+        }
+
+        Some(Self::new(core::array::from_fn(|i| {
+            core::array::from_fn(|j| cofactor(j, i) / det)
+        })))
+    }
+
+    /// Maps `(x, y)`, or `None` if it lands on the line at infinity.
+    pub fn transform_point(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        let [u, v, w] = self.rows.map(|[a, b, c]| a * x + b * y + c);
+        (w != 0.0).then(|| (u / w, v / w))
+    }
+
+    /// Maps `place`, or `None` if the result is at infinity or overflows `f64`.
+    pub fn transform_place(&self, place: &Place) -> Option<Place> {
+        let (x, y) = (place.x().to_f64()?, place.y().to_f64()?);
+        let (x, y) = self.transform_point(x, y)?;
+        Place::new(x, y)
+    }
+}
+
+/// Image moved by a [`Homography`], see [`Image::warp`].
+#[derive(Debug, Clone)]
+pub struct Warped<I> {
+    image: I,
+    inverse: Homography,
+}
+
+impl<I> Warped<I> {
+    pub(crate) fn new(image: I, homography: Homography) -> Self {
+        let inverse = homography
+            .inverse()
+            .expect("an image can only be warped by an invertible homography");
+        Self { image, inverse }
+    }
+}
+
+impl<I: Image> Image for Warped<I> {
+    type Pixel = Option<I::Pixel>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let source = self.inverse.transform_place(&p)?;
+        Some(self.image.get(source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::array::uniform3;
+    use proptest::{prop_assert, proptest};
+    use space::Place;
+
+    use super::Homography;
+    use crate::{AffineTransform, Gray, Image, from_fn};
+
+    fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+        (a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6
+    }
+
+    #[test]
+    fn affine_transforms_are_homographies() {
+        let affine = AffineTransform::rotation(0.3).then(AffineTransform::translation(2.0, -1.0));
+        let homography = Homography::from(affine);
+        assert_eq!(
+            homography.transform_point(3.0, 4.0),
+            Some(affine.transform_point(3.0, 4.0))
+        );
+    }
+
+    #[test]
+    fn perspective_sends_a_line_to_infinity() {
+        let tilt = Homography::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.5, 1.0]]);
+        assert_eq!(tilt.transform_point(1.0, -2.0), None);
+        assert_eq!(tilt.transform_point(2.0, 2.0), Some((1.0, 1.0)));
+    }
+
+    #[test]
+    fn warped_images_follow_the_homography() {
+        let image = from_fn(|p: Place| Gray(p.x().to_f64().unwrap() as i32));
+        let tilt = Homography::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.5, 1.0]]);
+        let warped = image.warp(tilt);
+        assert_eq!(warped.get(Place::new(1.5, 1.0).unwrap()), Some(Gray(3)));
+    }
+
+    proptest! {
+        #[test]
+        fn inverse_undoes_the_homography(
+            [r0, r1, r2] in [uniform3(-5.0..5.0f64), uniform3(-5.0..5.0f64), uniform3(-0.01..0.01f64)],
+            x in -10.0..10.0f64,
+            y in -10.0..10.0f64,
+        ) {
+            let homography = Homography::new([r0, r1, [r2[0], r2[1], 1.0]]);
+            let det = r0[0] * r1[1] - r0[1] * r1[0];
+            if let Some(inverse) = homography.inverse().filter(|_| det.abs() > 1e-2)
+                && let Some((u, v)) = homography.transform_point(x, y)
+            {
+                let back = inverse.transform_point(u, v).unwrap();
+                prop_assert!(close(back, (x, y)), "{:?}", back);
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use space::Place;
+
+use crate::Image;
+use crate::pixel::Pixel;
+
+type Matrix = [[f32; 3]; 3];
+
+/// Rec. 709 luma weights, rounded as in the CSS filter effects matrices.
+pub(crate) const LUMA: [f32; 3] = [0.213, 0.715, 0.072];
+
+/// Color adjustment applied by a [`HueSaturation`] adapter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HueSaturationOp {
+    /// Rotates hues by the angle in degrees around the gray axis, keeping luma.
+    HueRotate(f32),
+    /// Scales the distance from gray by the factor: 0 desaturates, 1 is a no-op.
+    Saturate(f32),
+    /// Like [`HueSaturationOp::Saturate`], but weaker on colors that are already
+    /// saturated, so skin tones and skies don't clip first.
+    Vibrance(f32),
+}
+
+fn saturate_matrix(s: f32) -> Matrix {
+    core::array::from_fn(|i| {
+        core::array::from_fn(|j| {
+            let identity = if i == j { 1.0 } else { 0.0 };
+            LUMA[j] + (identity - LUMA[j]) * s
+        })
+    })
+}
+
+fn hue_rotate_matrix(degrees: f32) -> Matrix {
+    let (sin, cos) = libm::sincosf(degrees.to_radians());
+    let [l0, l1, l2] = LUMA;
+
+    [
+        [
+            l0 + cos * (1.0 - l0) - sin * l0,
+            l1 - cos * l1 - sin * l1,
+            l2 - cos * l2 + sin * (1.0 - l2),
+        ],
+        [
+            l0 - cos * l0 + sin * 0.143,
+            l1 + cos * (1.0 - l1) + sin * 0.140,
+            l2 - cos * l2 - sin * 0.283,
+        ],
+        [
+            l0 - cos * l0 - sin * (1.0 - l0),
+            l1 - cos * l1 + sin * l1,
+            l2 + cos * (1.0 - l2) + sin * l2,
+        ],
+    ]
+}
+
+impl HueSaturationOp {
+    pub fn apply(self, rgb: [f32; 3]) -> [f32; 3] {
+        let matrix = match self {
+            HueSaturationOp::HueRotate(degrees) => hue_rotate_matrix(degrees),
+            HueSaturationOp::Saturate(factor) => saturate_matrix(factor),
+            HueSaturationOp::Vibrance(factor) => {
+                let max = rgb.iter().copied().fold(f32::MIN, f32::max);
+                let min = rgb.iter().copied().fold(f32::MAX, f32::min);
+                let saturation = (max - min).clamp(0.0, 1.0);
+                saturate_matrix(1.0 + (factor - 1.0) * (1.0 - saturation))
+            }
+        };
+
+        matrix.map(|row| row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2])
+    }
+}
+
+/// Hue and saturation adjustment of an `f32` color image, see
+/// [`Image::hue_rotate`], [`Image::saturate`] and [`Image::vibrance`].
+///
+/// Pixels with fewer than three channels pass through unchanged; alpha is kept.
+#[derive(Debug, Clone)]
+pub struct HueSaturation<I> {
+    image: I,
+    op: HueSaturationOp,
+}
+
+impl<I> HueSaturation<I> {
+    pub(crate) fn new(image: I, op: HueSaturationOp) -> Self {
+        Self { image, op }
+    }
+}
+
+impl<I> Image for HueSaturation<I>
+where
+    I: Image,
+    I::Pixel: Pixel<Scalar = f32>,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let pixel = self.image.get(p);
+        if <I::Pixel as Pixel>::CHANNELS < 3 {
+            return pixel;
+        }
+
+        let rgb = self
+            .op
+            .apply([pixel.channel(0), pixel.channel(1), pixel.channel(2)]);
+        Pixel::from_channels(|c| if c < 3 { rgb[c] } else { pixel.channel(c) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, proptest};
+    use space::Place;
+
+    use super::{HueSaturationOp, LUMA};
+    use crate::{Gray, Image, Rgb, Rgba, from_fn};
+
+    fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-4)
+    }
+
+    fn luma(rgb: [f32; 3]) -> f32 {
+        LUMA[0] * rgb[0] + LUMA[1] * rgb[1] + LUMA[2] * rgb[2]
+    }
+
+    #[test]
+    fn zero_saturation_gives_gray() {
+        let image = from_fn(|_| Rgba::new(0.9f32, 0.2, 0.4, 0.5)).saturate(0.0);
+        let Rgba { r, g, b, a } = image.get(Place::new(0.0, 0.0).unwrap());
+        assert!((r - g).abs() < 1e-6 && (g - b).abs() < 1e-6);
+        assert_eq!(a, 0.5);
+    }
+
+    #[test]
+    fn full_turn_is_identity_and_gray_is_unchanged() {
+        let color = [0.9, 0.2, 0.4];
+        assert!(close(HueSaturationOp::HueRotate(360.0).apply(color), color));
+        assert!(close(
+            HueSaturationOp::HueRotate(77.0).apply([0.3; 3]),
+            [0.3; 3]
+        ));
+        let image = from_fn(|_| Gray(0.3f32)).hue_rotate(90.0);
+        assert_eq!(image.get(Place::new(0.0, 0.0).unwrap()), Gray(0.3));
+    }
+
+    #[test]
+    fn vibrance_favours_muted_colors() {
+        let boost = |rgb: [f32; 3]| {
+            let out = HueSaturationOp::Vibrance(2.0).apply(rgb);
+            (out[0] - out[2]) / (rgb[0] - rgb[2])
+        };
+        assert!(boost([0.55, 0.5, 0.45]) > boost([1.0, 0.5, 0.0]));
+        assert!(close(
+            HueSaturationOp::Vibrance(2.0).apply([1.0, 0.0, 0.0]),
+            [1.0, 0.0, 0.0]
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn hue_rotation_keeps_luma(r in 0.0..=1.0f32, g in 0.0..=1.0f32, b in 0.0..=1.0f32, degrees in -360.0..360.0f32) {
+            let rotated = from_fn(move |_| Rgb::new(r, g, b)).hue_rotate(degrees).get(Place::new(0.0, 0.0).unwrap());
+            prop_assert!((luma([rotated.r, rotated.g, rotated.b]) - luma([r, g, b])).abs() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Summed-area table of a `width × height` grid of values, for sums over any
+/// rectangle in constant time.
+#[derive(Debug, Clone)]
+pub(crate) struct SummedArea {
+    table: Vec<f64>,
+    stride: usize,
+}
+
+impl SummedArea {
+    /// Table of `values`, given row by row.
+    pub(crate) fn new(mut values: impl Iterator<Item = f64>, width: usize, height: usize) -> Self {
+        // A zero row and column in front spare the edge cases.
+        let stride = width + 1;
+        let mut table = vec![0.0; stride * (height + 1)];
+
+        for j in 0..height {
+            let mut row = 0.0;
+            for i in 0..width {
+                row += values.next().expect("one value per pixel");
+                table[(j + 1) * stride + i + 1] = table[j * stride + i + 1] + row;
+            }
+        }
+        Self { table, stride }
+    }
+
+    /// Sum of the values in columns `i..i + w` of rows `j..j + h`.
+    pub(crate) fn sum(&self, i: usize, j: usize, w: usize, h: usize) -> f64 {
+        let (top, bottom) = (j * self.stride, (j + h) * self.stride);
+        let t = &self.table;
+        t[bottom + i + w] - t[top + i + w] - t[bottom + i] + t[top + i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SummedArea;
+
+    #[test]
+    fn rectangles_sum_their_values() {
+        let table = SummedArea::new((1..=12).map(f64::from), 4, 3);
+        assert_eq!(table.sum(0, 0, 4, 3), 78.0);
+        assert_eq!(table.sum(1, 1, 2, 2), 6.0 + 7.0 + 10.0 + 11.0);
+        assert_eq!(table.sum(3, 2, 1, 1), 12.0);
+        assert_eq!(table.sum(2, 0, 0, 3), 0.0);
+    }
+}
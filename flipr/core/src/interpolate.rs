@@ -0,0 +1,124 @@
+use space::{Place, Real};
+
+use crate::Image;
+use crate::arithmetic::{Channel, PixelLerp};
+use crate::buffer::ImageBuffer;
+use crate::pixel::Pixel;
+use crate::static_image::clamped_index;
+
+/// How an [`Interpolated`] buffer answers for places between pixel centers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// The pixel whose cell contains the place, exactly like sampling the
+    /// [`ImageBuffer`] itself.
+    #[default]
+    Nearest,
+    /// Linear blend of the four surrounding pixel centers.
+    Bilinear,
+}
+
+/// [`ImageBuffer`] turned back into a continuous image, see
+/// [`ImageBuffer::continuize`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interpolated<P: Pixel> {
+    buffer: ImageBuffer<P>,
+    interpolation: Interpolation,
+}
+
+impl<P: Pixel> ImageBuffer<P> {
+    /// Makes the buffer answer for every place with `interpolation`, so it
+    /// can be transformed in continuous space and sampled onto a new grid.
+    ///
+    /// Places outside of the grid are clamped to the edge pixels.
+    pub fn continuize(self, interpolation: Interpolation) -> Interpolated<P> {
+        Interpolated {
+            buffer: self,
+            interpolation,
+        }
+    }
+}
+
+impl<P: Pixel> Interpolated<P> {
+    pub fn buffer(&self) -> &ImageBuffer<P> {
+        &self.buffer
+    }
+
+    pub fn into_buffer(self) -> ImageBuffer<P> {
+        self.buffer
+    }
+}
+
+/// Surrounding pixel centers along one axis and the weight of the upper one.
+fn axis(r: &Real, len: usize) -> (usize, usize, f64) {
+    let u = match r.to_f64() {
+        Some(x) => (x - 0.5).clamp(0.0, (len - 1) as f64),
+        None => return (clamped_index(r, len), clamped_index(r, len), 0.0),
+    };
+    let lo = u as usize;
+    (lo, (lo + 1).min(len - 1), u - lo as f64)
+}
+
+impl<P> Image for Interpolated<P>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        match self.interpolation {
+            Interpolation::Nearest => self.buffer.get(p),
+            Interpolation::Bilinear => {
+                let (x0, x1, tx) = axis(p.x(), self.buffer.width());
+                let (y0, y1, ty) = axis(p.y(), self.buffer.height());
+                let pixel = |i, j| self.buffer.pixel(i, j).expect("indices are clamped");
+
+                let top = pixel(x0, y0).lerp(pixel(x1, y0), tx);
+                let bottom = pixel(x0, y1).lerp(pixel(x1, y1), tx);
+                top.lerp(bottom, ty)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::Interpolation;
+    use crate::tests::place;
+    use crate::{Gray, Image, ImageBuffer, Layout, Size};
+
+    fn ramp() -> ImageBuffer<Gray<f32>> {
+        ImageBuffer::from_fn(3, 2, Layout::Interleaved, |i, j| {
+            Gray(i as f32 * 10.0 + j as f32 * 100.0)
+        })
+    }
+
+    #[test]
+    fn bilinear_blends_between_centers() {
+        let image = ramp().continuize(Interpolation::Bilinear);
+        let at = |x, y| image.get(Place::new(x, y).unwrap()).0;
+        assert_eq!(at(0.5, 0.5), 0.0);
+        assert_eq!(at(1.0, 0.5), 5.0);
+        assert_eq!(at(1.5, 1.0), 60.0);
+        assert_eq!(at(-3.0, 9.0), 100.0);
+    }
+
+    #[test]
+    fn discretizing_a_continuized_buffer_round_trips() {
+        for interpolation in [Interpolation::Nearest, Interpolation::Bilinear] {
+            let image = ramp().continuize(interpolation);
+            assert_eq!(image.discretize(Size::new(3, 2), Layout::Planar), ramp());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn nearest_matches_the_buffer(p in place()) {
+            let image = ramp().continuize(Interpolation::Nearest);
+            prop_assert_eq!(image.get(p.clone()), ramp().get(p));
+        }
+    }
+}
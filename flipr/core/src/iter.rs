@@ -0,0 +1,247 @@
+use core::iter::FusedIterator;
+
+use crate::buffer::ImageBuffer;
+use crate::pixel::Pixel;
+use crate::view::ImageView;
+
+#[derive(Debug, Clone, Copy)]
+enum Source<'a, P: Pixel> {
+    Buffer(&'a ImageBuffer<P>),
+    View(ImageView<'a, P>),
+}
+
+impl<P: Pixel> Source<'_, P> {
+    fn width(&self) -> usize {
+        match self {
+            Source::Buffer(buffer) => buffer.width(),
+            Source::View(view) => view.width(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Source::Buffer(buffer) => buffer.width() * buffer.height(),
+            Source::View(view) => view.width() * view.height(),
+        }
+    }
+
+    fn pixel(&self, i: usize, j: usize) -> Option<P> {
+        match self {
+            Source::Buffer(buffer) => buffer.pixel(i, j),
+            Source::View(view) => view.pixel(i, j),
+        }
+    }
+}
+
+/// Iterator over `(i, j, pixel)` in row-major order, created by
+/// [`ImageBuffer::enumerate_pixels`] and [`ImageView::enumerate_pixels`].
+#[derive(Debug, Clone)]
+pub struct EnumeratePixels<'a, P: Pixel> {
+    source: Source<'a, P>,
+    next: usize,
+}
+
+impl<P: Pixel> Iterator for EnumeratePixels<'_, P> {
+    type Item = (usize, usize, P);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.source.len() {
+            return None;
+        }
+
+        let (i, j) = (
+            self.next % self.source.width(),
+            self.next / self.source.width(),
+        );
+        self.next += 1;
+
+        self.source.pixel(i, j).map(|p| (i, j, p))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.source.len() - self.next;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.next = self.next.saturating_add(n).min(self.source.len());
+        self.next()
+    }
+}
+
+impl<P: Pixel> ExactSizeIterator for EnumeratePixels<'_, P> {}
+
+impl<P: Pixel> FusedIterator for EnumeratePixels<'_, P> {}
+
+/// Iterator over pixels in row-major order, created by [`ImageBuffer::pixels`]
+/// and [`ImageView::pixels`].
+#[derive(Debug, Clone)]
+pub struct Pixels<'a, P: Pixel> {
+    inner: EnumeratePixels<'a, P>,
+}
+
+impl<P: Pixel> Iterator for Pixels<'_, P> {
+    type Item = P;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, _, p)| p)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth(n).map(|(_, _, p)| p)
+    }
+}
+
+impl<P: Pixel> ExactSizeIterator for Pixels<'_, P> {}
+
+impl<P: Pixel> FusedIterator for Pixels<'_, P> {}
+
+/// Iterator over the rows of an [`ImageView`] or interleaved [`ImageBuffer`]
+/// as slices of `width` pixels, top to bottom.
+#[derive(Debug, Clone)]
+pub struct Rows<'a, P> {
+    pixels: &'a [P],
+    width: usize,
+    stride: usize,
+    remaining: usize,
+}
+
+impl<'a, P> Iterator for Rows<'a, P> {
+    type Item = &'a [P];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let row = &self.pixels[..self.width];
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.pixels = &self.pixels[self.stride..];
+        }
+
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<P> ExactSizeIterator for Rows<'_, P> {}
+
+impl<P> FusedIterator for Rows<'_, P> {}
+
+impl<P: Pixel> ImageBuffer<P> {
+    /// Iterates over the pixels row by row, top to bottom, in either layout.
+    pub fn pixels(&self) -> Pixels<'_, P> {
+        Pixels {
+            inner: self.enumerate_pixels(),
+        }
+    }
+
+    /// Like [`pixels`](Self::pixels), but yields `(i, j, pixel)`.
+    pub fn enumerate_pixels(&self) -> EnumeratePixels<'_, P> {
+        let source = match self.view(0, 0, self.width(), self.height()) {
+            Some(view) => Source::View(view),
+            None => Source::Buffer(self),
+        };
+
+        EnumeratePixels { source, next: 0 }
+    }
+
+    /// Iterates over the rows as slices, if the buffer is interleaved.
+    pub fn rows(&self) -> Option<Rows<'_, P>> {
+        Some(self.view(0, 0, self.width(), self.height())?.rows())
+    }
+}
+
+impl<'a, P: Pixel> ImageView<'a, P> {
+    /// Iterates over the viewed pixels row by row, top to bottom.
+    pub fn pixels(&self) -> Pixels<'a, P> {
+        Pixels {
+            inner: self.enumerate_pixels(),
+        }
+    }
+
+    /// Like [`pixels`](Self::pixels), but yields `(i, j, pixel)` relative to
+    /// the view.
+    pub fn enumerate_pixels(&self) -> EnumeratePixels<'a, P> {
+        EnumeratePixels {
+            source: Source::View(*self),
+            next: 0,
+        }
+    }
+
+    /// Iterates over the rows as slices of `width` pixels, skipping the
+    /// padding between them.
+    pub fn rows(&self) -> Rows<'a, P> {
+        Rows {
+            pixels: self.as_slice(),
+            width: self.width(),
+            stride: self.stride(),
+            remaining: self.height(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{Gray, ImageBuffer, ImageView, Layout, Rgb};
+
+    fn gradient(layout: Layout) -> ImageBuffer<Rgb<u8>> {
+        ImageBuffer::from_fn(3, 2, layout, |i, j| {
+            Rgb::new(i as u8, j as u8, (i + j) as u8)
+        })
+    }
+
+    #[test]
+    fn pixels_are_row_major_in_both_layouts() {
+        for layout in [Layout::Interleaved, Layout::Planar] {
+            let buffer = gradient(layout);
+            let pixels: Vec<_> = buffer.pixels().map(|p| (p.r, p.g)).collect();
+            assert_eq!(pixels, [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+            assert_eq!(buffer.pixels().len(), 6);
+        }
+    }
+
+    #[test]
+    fn enumerated_coordinates_match_pixel() {
+        let buffer = gradient(Layout::Planar);
+        for (i, j, p) in buffer.enumerate_pixels() {
+            assert_eq!(buffer.pixel(i, j), Some(p));
+        }
+        assert_eq!(
+            buffer.enumerate_pixels().nth(4).map(|(i, j, _)| (i, j)),
+            Some((1, 1))
+        );
+        assert_eq!(buffer.enumerate_pixels().nth(6), None);
+    }
+
+    #[test]
+    fn rows_skip_view_padding() {
+        let data = [1, 2, 0, 3, 4, 0, 5, 6].map(Gray);
+        let view = ImageView::from_slice(&data, 2, 3, 3).unwrap();
+        let rows: Vec<_> = view.rows().collect();
+        assert_eq!(rows, [&data[0..2], &data[3..5], &data[6..8]]);
+        assert_eq!(
+            view.pixels().map(|p| p.0).collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn planar_buffers_have_no_row_slices() {
+        assert!(gradient(Layout::Planar).rows().is_none());
+        assert_eq!(
+            gradient(Layout::Interleaved).rows().map(|r| r.len()),
+            Some(2)
+        );
+    }
+}
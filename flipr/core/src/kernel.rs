@@ -0,0 +1,425 @@
+use alloc::vec::Vec;
+use core::cell::OnceCell;
+
+use space::{Offset, Place, Real};
+
+use crate::Image;
+use crate::arithmetic::Channel;
+use crate::buffer::ImageBuffer;
+use crate::hue::LUMA;
+use crate::pixel::{Pixel, with_sums};
+
+/// Convolution weights in `H` rows of `W`, centered on the middle weight.
+///
+/// Rows always have the same length, and [`new`](Self::new) rejects even
+/// dimensions when it is compiled, so a malformed kernel never reaches
+/// runtime. Usually written with [`kernel!`](crate::kernel!).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Kernel<const W: usize, const H: usize> {
+    weights: [[f64; W]; H],
+}
+
+impl<const W: usize, const H: usize> Kernel<W, H> {
+    /// Fails to compile unless `W` and `H` are odd.
+    pub const fn new(weights: [[f64; W]; H]) -> Self {
+        const {
+            assert!(
+                W % 2 == 1 && H % 2 == 1,
+                "a kernel needs an odd number of rows and columns"
+            )
+        };
+        Self { weights }
+    }
+
+    /// Every weight divided by `divisor`, usually their sum.
+    pub const fn divided_by(mut self, divisor: f64) -> Self {
+        let mut j = 0;
+        while j < H {
+            let mut i = 0;
+            while i < W {
+                self.weights[j][i] /= divisor;
+                i += 1;
+            }
+            j += 1;
+        }
+        self
+    }
+
+    pub const fn weights(&self) -> &[[f64; W]; H] {
+        &self.weights
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.weights.iter().flatten().sum()
+    }
+}
+
+/// Builds a [`Kernel`] from rows of numbers, optionally divided by a common
+/// factor, usable in constants:
+/// `const BLUR: Kernel<3, 3> = kernel!([[1, 2, 1], [2, 4, 2], [1, 2, 1]] / 16);`
+///
+/// Rows of different lengths and even dimensions are compile errors.
+#[macro_export]
+macro_rules! kernel {
+    ([$([$($weight:expr),+ $(,)?]),+ $(,)?] $(/ $divisor:expr)?) => {
+        $crate::Kernel::new([$([$(($weight) as f64),+]),+])
+            $(.divided_by(($divisor) as f64))?
+    };
+}
+
+/// Image convolved with a [`Kernel`] on a unit grid, see [`Image::convolve`].
+#[derive(Debug, Clone)]
+pub struct Convolved<I, const W: usize, const H: usize>
+where
+    I: Image,
+    I::Pixel: Pixel,
+{
+    image: I,
+    kernel: Kernel<W, H>,
+    /// The convolution over the extent of a buffer source, computed through
+    /// the FFT on the first sample for kernels of [`FFT_KERNEL_AREA`] weights
+    /// or more.
+    fft: OnceCell<Option<ImageBuffer<I::Pixel>>>,
+}
+
+impl<I, const W: usize, const H: usize> Convolved<I, W, H>
+where
+    I: Image,
+    I::Pixel: Pixel,
+    <I::Pixel as Pixel>::Scalar: Channel,
+{
+    pub(crate) fn new(image: I, kernel: Kernel<W, H>) -> Self {
+        Self {
+            image,
+            kernel,
+            fft: OnceCell::new(),
+        }
+    }
+
+    fn fft(&self) -> Option<&ImageBuffer<I::Pixel>> {
+        self.fft
+            .get_or_init(|| {
+                self.image
+                    .as_buffer()
+                    .filter(|_| W * H >= FFT_KERNEL_AREA)
+                    .map(|buffer| fft_convolve(buffer, &self.kernel))
+            })
+            .as_ref()
+    }
+}
+
+/// Index of the unit cell containing `r`, if it is in `0..len`.
+fn cell_index(r: &Real, len: usize) -> Option<usize> {
+    let i = r.floor().to_i64()?;
+    usize::try_from(i).ok().filter(|&i| i < len)
+}
+
+impl<I, const W: usize, const H: usize> Image for Convolved<I, W, H>
+where
+    I: Image,
+    I::Pixel: Pixel,
+    <I::Pixel as Pixel>::Scalar: Channel,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        // Outside the buffer the direct sum reads different edge pixels than
+        // the clamped FFT convolution did, so only places on it are looked up.
+        if let Some(fft) = self.fft()
+            && let Some(i) = cell_index(p.x(), fft.width())
+            && let Some(j) = cell_index(p.y(), fft.height())
+        {
+            return fft.pixel(i, j).expect("cell indices are in bounds");
+        }
+
+        let (rx, ry) = ((W / 2) as i64, (H / 2) as i64);
+        with_sums::<I::Pixel, f64, _>(|sums| {
+            for (j, row) in self.kernel.weights.iter().enumerate() {
+                for (i, weight) in row.iter().enumerate() {
+                    // Convolution reads the source mirrored around `p`.
+                    let offset =
+                        Offset::from_reals(Real::from(rx - i as i64), Real::from(ry - j as i64));
+                    let sample = self.image.get(&p + offset);
+                    for (c, sum) in sums.iter_mut().enumerate() {
+                        *sum += sample.channel(c).to_f64() * weight;
+                    }
+                }
+            }
+
+            Pixel::from_channels(|c| Channel::from_f64(sums[c]))
+        })
+    }
+}
+
+/// Image whose luma alone is convolved with a [`Kernel`], see
+/// [`Image::convolve_luma`].
+#[derive(Debug, Clone)]
+pub struct LumaConvolved<I, const W: usize, const H: usize> {
+    image: I,
+    kernel: Kernel<W, H>,
+}
+
+impl<I, const W: usize, const H: usize> LumaConvolved<I, W, H> {
+    pub(crate) fn new(image: I, kernel: Kernel<W, H>) -> Self {
+        Self { image, kernel }
+    }
+}
+
+/// Rec. 709 luma of the first three channels, or the first channel of
+/// pixels with fewer.
+fn luma<P>(pixel: P) -> f64
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    if P::CHANNELS < 3 {
+        return pixel.channel(0).to_f64();
+    }
+    (0..3)
+        .map(|c| LUMA[c] as f64 * pixel.channel(c).to_f64())
+        .sum()
+}
+
+impl<I, const W: usize, const H: usize> Image for LumaConvolved<I, W, H>
+where
+    I: Image,
+    I::Pixel: Pixel,
+    <I::Pixel as Pixel>::Scalar: Channel,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (rx, ry) = ((W / 2) as i64, (H / 2) as i64);
+
+        let mut convolved = 0.0;
+        for (j, row) in self.kernel.weights.iter().enumerate() {
+            for (i, weight) in row.iter().enumerate() {
+                let offset =
+                    Offset::from_reals(Real::from(rx - i as i64), Real::from(ry - j as i64));
+                convolved += luma(self.image.get(&p + offset)) * weight;
+            }
+        }
+
+        let pixel = self.image.get(p);
+        let shift = convolved - luma(pixel);
+        let colors = <I::Pixel as Pixel>::CHANNELS.min(3);
+        Pixel::from_channels(|c| {
+            let value = pixel.channel(c);
+            if c < colors {
+                Channel::from_f64(value.to_f64() + shift)
+            } else {
+                value
+            }
+        })
+    }
+}
+
+/// Kernel area from which [`Image::convolve`] convolves buffers through the
+/// FFT.
+pub const FFT_KERNEL_AREA: usize = 15 * 15;
+
+/// `image` convolved with `kernel` through the FFT, with edge pixels
+/// repeated outside like sampling the buffer does.
+fn fft_convolve<P, const W: usize, const H: usize>(
+    image: &ImageBuffer<P>,
+    kernel: &Kernel<W, H>,
+) -> ImageBuffer<P>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    let (width, height) = (image.width(), image.height());
+    let planes: Vec<Vec<f64>> = (0..P::CHANNELS)
+        .map(|c| image.pixels().map(|p| p.channel(c).to_f64()).collect())
+        .collect();
+    let weights: Vec<f64> = kernel.weights.iter().flatten().copied().collect();
+    let planes = crate::fft::convolve_clamped(&planes, width, height, &weights, W);
+
+    ImageBuffer::from_fn(width, height, image.layout(), |i, j| {
+        P::from_channels(|c| Channel::from_f64(planes[c][j * width + i]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use proptest::{prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::Kernel;
+    use crate::tests::place;
+    use crate::{Gray, Image, ImageBuffer, Layout, Rgb, Rgba, from_fn};
+
+    const BOX: Kernel<3, 3> = kernel!([[1, 1, 1], [1, 1, 1], [1, 1, 1]] / 9);
+
+    fn ramp() -> impl Image<Pixel = Gray<f32>> {
+        from_fn(|p: Place| Gray(p.x().to_f64().unwrap() as f32))
+    }
+
+    #[test]
+    fn kernels_are_built_at_compile_time() {
+        assert!((BOX.sum() - 1.0).abs() < 1e-12);
+        assert_eq!(
+            kernel!([[0, -1, 0], [-1, 5, -1], [0, -1, 0]]).weights()[1],
+            [-1.0, 5.0, -1.0]
+        );
+        assert_eq!(kernel!([[1.5]]).weights(), &[[1.5]]);
+    }
+
+    #[test]
+    fn convolution_mirrors_the_kernel() {
+        let difference = kernel!([[1, 0, -1]]);
+        let slope = ramp().convolve(difference);
+        assert_eq!(slope.get(Place::new(4.0, 0.0).unwrap()), Gray(2.0));
+    }
+
+    #[test]
+    fn color_channels_and_alpha_are_convolved_separately() {
+        let stripes = from_fn(|p: Place| {
+            let x = p.x().to_f64().unwrap();
+            Rgba::new(
+                x as f32,
+                10.0 - x as f32,
+                5.0,
+                if x < 0.0 { 0.0 } else { 1.0 },
+            )
+        });
+        let difference = kernel!([[1, 0, -1]]);
+        let at = Place::new(0.5, 0.0).unwrap();
+        assert_eq!(
+            stripes.convolve(difference).get(at.clone()),
+            Rgba::new(2.0, -2.0, 0.0, 1.0)
+        );
+        assert_eq!(
+            stripes.convolve(BOX).get(at),
+            Rgba::new(0.5, 9.5, 5.0, 2.0 / 3.0)
+        );
+    }
+
+    #[test]
+    fn luma_convolution_keeps_chroma() {
+        // Red and green of equal luma alternate every column.
+        let green = 0.213 / 0.715;
+        let stripes = from_fn(move |p: Place| {
+            if (p.x().to_f64().unwrap().floor() as i64).rem_euclid(2) == 0 {
+                Rgb::new(1.0f64, 0.0, 0.5)
+            } else {
+                Rgb::new(0.0, green, 0.5)
+            }
+        });
+        let at = Place::new(0.5, 0.5).unwrap();
+        let Rgb { r, g, b } = stripes.convolve_luma(BOX).get(at.clone());
+        assert!((r - 1.0).abs() < 1e-6 && g.abs() < 1e-6 && (b - 0.5).abs() < 1e-6);
+        assert_ne!(stripes.convolve(BOX).get(at).r, 1.0);
+
+        let step = from_fn(|p: Place| {
+            Gray(if p.x().to_f64().unwrap() < 0.0 {
+                0.0
+            } else {
+                9.0
+            })
+        });
+        let at = Place::new(-0.5, 0.5).unwrap();
+        assert_eq!(
+            step.convolve_luma(BOX).get(at.clone()),
+            step.convolve(BOX).get(at)
+        );
+    }
+
+    /// A lopsided 31×31 kernel, so that a flipped or shifted kernel shows.
+    fn large_kernel() -> Kernel<31, 31> {
+        let mut weights = [[0.0; 31]; 31];
+        for (j, row) in weights.iter_mut().enumerate() {
+            for (i, w) in row.iter_mut().enumerate() {
+                let (x, y) = (i as f64 - 15.0, j as f64 - 15.0);
+                *w = libm::exp(-(x * x + y * y) / 60.0) * (1.0 + x / 30.0);
+            }
+        }
+        let kernel = Kernel::new(weights);
+        kernel.divided_by(kernel.sum())
+    }
+
+    #[test]
+    fn large_kernels_over_buffers_match_direct_convolution() {
+        let image = ImageBuffer::from_fn(23, 17, Layout::Planar, |i, j| {
+            Rgb::new(
+                ((i * 37 + j * 11) % 50) as f32,
+                (i * j % 7) as f32,
+                if i < 8 { 100.0 } else { 0.0 },
+            )
+        });
+        let kernel = large_kernel();
+        // Not a buffer, so convolved by summing place by place.
+        let lazy = {
+            let image = image.clone();
+            from_fn(move |p: Place| image.get(p))
+        };
+
+        let fast = image.convolve(kernel);
+        let direct = lazy.convolve(kernel);
+        for (x, y) in [
+            (0.5, 0.5),
+            (11.5, 8.5),
+            (22.9, 16.0),
+            (-3.0, 5.5),
+            (30.0, 40.0),
+        ] {
+            let p = Place::new(x, y).unwrap();
+            let (a, b) = (fast.get(p.clone()), direct.get(p));
+            for (x, y) in [(a.r, b.r), (a.g, b.g), (a.b, b.b)] {
+                assert!((x - y).abs() < 1e-3, "{a:?} != {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn wrapped_buffers_take_the_fft_path_on_first_sample() {
+        let image = ImageBuffer::from_fn(19, 13, Layout::Interleaved, |i, j| {
+            Gray(((i * 7 + j * j * 3) % 40) as f32)
+        });
+        let mut weights = [[0.0; 15]; 17];
+        for (j, row) in weights.iter_mut().enumerate() {
+            for (i, w) in row.iter_mut().enumerate() {
+                *w = (1 + i + 2 * j) as f64 / 15.0 / 17.0 / 24.0;
+            }
+        }
+        let kernel = Kernel::new(weights);
+        let lazy = {
+            let image = image.clone();
+            from_fn(move |p: Place| image.get(p))
+        };
+
+        let samples = Cell::new(0);
+        let boxed = image.inspect(|_, _| samples.set(samples.get() + 1)).boxed();
+        let fast = boxed.convolve(kernel);
+        let direct = lazy.convolve(kernel);
+        for (x, y) in [(0.5, 0.5), (9.5, 6.5), (18.5, 12.5), (3.5, 11.5)] {
+            let p = Place::new(x, y).unwrap();
+            let (Gray(a), Gray(b)) = (fast.get(p.clone()), direct.get(p));
+            assert!((a - b).abs() < 1e-3, "{a} != {b}");
+        }
+        assert_eq!(samples.get(), 0);
+    }
+
+    #[test]
+    fn small_kernels_are_summed_directly() {
+        let image = ImageBuffer::from_fn(6, 4, Layout::Interleaved, |i, j| Gray((i * j) as u8));
+        let lazy = {
+            let image = image.clone();
+            from_fn(move |p: Place| image.get(p))
+        };
+        assert_eq!(
+            ImageBuffer::sample(&image.convolve(BOX), 6, 4, Layout::Interleaved),
+            ImageBuffer::sample(&lazy.convolve(BOX), 6, 4, Layout::Interleaved)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn normalized_kernels_keep_flat_images(p in place()) {
+            let flat = from_fn(|_| Gray(0.25f32)).convolve(BOX);
+            prop_assert_eq!(flat.get(p), Gray(0.25));
+        }
+    }
+}
@@ -1 +1,104 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod analysis;
+pub mod debug;
+pub mod fft;
+pub mod generators;
+#[cfg(feature = "proptest")]
+pub mod strategies;
+#[cfg(feature = "trace")]
+pub mod trace;
+
+mod affine;
+mod arithmetic;
+mod blur;
+mod boxed;
+mod buffer;
+mod carve;
+mod channels;
+mod color;
+mod colormap;
+mod curves;
+mod displace;
+mod error;
+mod estimate;
+mod fixed;
+mod from_fn;
+mod geometry;
+mod homography;
+mod hue;
+mod integral;
+mod interpolate;
+mod iter;
+mod kernel;
+mod lut;
+mod masked;
+mod montage;
+mod noise;
+mod normalize;
+mod observe;
+mod palette;
+mod pipeline;
+mod pixel;
+mod polar;
+mod pool;
+mod pyramid;
+mod raw;
+mod samples;
+mod stack;
+mod static_image;
+mod terminal;
+mod tile;
+mod tone;
 mod traits;
+mod view;
+mod volume;
+mod zip;
+
+pub use affine::{AffineTransform, Transformed};
+pub use arithmetic::{Channel, Overflow, OverflowPolicy, PixelAdd, PixelLerp, PixelScale};
+pub use blur::{GaussianBlur, UnsharpMask, fast_gaussian, gaussian_blur_iir};
+pub use boxed::BoxedImage;
+pub use buffer::{ImageBuffer, Layout};
+pub use carve::seam_carve;
+pub use channels::{MergeChannels, SelectChannel, merge_channels};
+pub use color::{ColorSpace, ConvertColorSpace, Delinearize, Linearize, SrgbChannel};
+pub use colormap::{ApplyColormap, Colormap, DiffVisualized};
+pub use curves::{AdjustMode, Adjusted, Curve, Levels, ToneCurve};
+pub use displace::Displaced;
+pub use error::{FliprError, MapErr};
+pub use estimate::{Estimate, Ransac, estimate_affine, estimate_homography};
+pub use fixed::{Fixed, FixedChannel, FixedConvolved, FixedFormat, FixedKernel};
+pub use from_fn::{FromFn, from_fn};
+pub use geometry::{Coord, Size};
+pub use homography::{Homography, Warped};
+pub use hue::{HueSaturation, HueSaturationOp};
+pub use interpolate::{Interpolated, Interpolation};
+pub use iter::{EnumeratePixels, Pixels, Rows};
+pub use kernel::{Convolved, FFT_KERNEL_AREA, Kernel, LumaConvolved};
+pub use lut::{ApplyLut, ApplyLut3d, CubeError, Lut, Lut3d};
+pub use masked::{MaskBlend, Masked};
+pub use montage::{Montage, montage};
+pub use noise::{Noise, NoiseImage, noise};
+pub use normalize::{Dither, Quantize, Quantized, ToFloat};
+pub use observe::{CancellationToken, ExecutionObserver};
+pub use palette::{Dithering, IndexedImage, Palette, quantize};
+pub use pixel::{Gray, MapChannels, MultiChannelPixel, Pixel, Rgb, Rgba};
+pub use polar::{FromPolar, PolarScale, ToPolar};
+pub use pool::BufferPool;
+pub use pyramid::{DownsampleFilter, GaussianPyramid, LaplacianPyramid};
+pub use raw::{ChannelOrder, RawError};
+pub use samples::Samples;
+pub use stack::{HStack, VStack};
+pub use static_image::StaticImage;
+pub use tile::Tiled;
+pub use tone::{ToneMap, ToneMapped};
+pub use traits::Image;
+pub use view::ImageView;
+pub use volume::{ImageStack, Slice, Volume};
+pub use zip::{AddWith, ZipWith};
+
+#[cfg(test)]
+pub mod tests;
@@ -0,0 +1,336 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use space::Place;
+
+use crate::Image;
+use crate::pixel::{Pixel, Rgb};
+
+/// Table mapping every `u8` value to another, collapsing any chain of
+/// per-channel `u8` operations into a single lookup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Lut([u8; 256]);
+
+impl Lut {
+    pub fn identity() -> Self {
+        Self::from_fn(|v| v)
+    }
+
+    /// Tabulates `f` once for every input value.
+    pub fn from_fn(f: impl Fn(u8) -> u8) -> Self {
+        Self(core::array::from_fn(|v| f(v as u8)))
+    }
+
+    pub fn get(&self, v: u8) -> u8 {
+        self.0[v as usize]
+    }
+
+    /// Table applying `self` first and `next` to its result.
+    pub fn then(&self, next: &Lut) -> Self {
+        Self::from_fn(|v| next.get(self.get(v)))
+    }
+}
+
+/// Applies a [`Lut`] to the color channels of a `u8` image, see [`Image::apply_lut`].
+#[derive(Debug, Clone)]
+pub struct ApplyLut<I> {
+    image: I,
+    lut: Lut,
+}
+
+impl<I> ApplyLut<I> {
+    pub(crate) fn new(image: I, lut: Lut) -> Self {
+        Self { image, lut }
+    }
+
+    /// Appends `next`, keeping a single table however long the chain gets.
+    pub fn then(self, next: &Lut) -> Self {
+        Self {
+            lut: self.lut.then(next),
+            ..self
+        }
+    }
+}
+
+impl<I> Image for ApplyLut<I>
+where
+    I: Image,
+    I::Pixel: Pixel<Scalar = u8>,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.image.get(p).map_color(|c| self.lut.get(c))
+    }
+}
+
+/// Reason a `.cube` file cannot be parsed, see [`Lut3d::parse_cube`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CubeError {
+    /// No `LUT_3D_SIZE` line, or a size below 2.
+    MissingSize,
+    /// The 1-based line could not be understood.
+    InvalidLine(usize),
+    /// The number of table entries is not the cube of the size.
+    WrongEntryCount { expected: usize, found: usize },
+}
+
+impl fmt::Display for CubeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CubeError::MissingSize => write!(f, "missing or invalid LUT_3D_SIZE"),
+            CubeError::InvalidLine(line) => write!(f, "invalid .cube line {line}"),
+            CubeError::WrongEntryCount { expected, found } => {
+                write!(f, "expected {expected} table entries, found {found}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CubeError {}
+
+/// Color cube sampled with trilinear interpolation, as used for color grading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut3d {
+    size: usize,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    /// Entries with red varying fastest, then green, then blue.
+    table: Vec<Rgb<f32>>,
+}
+
+impl Lut3d {
+    /// Tabulates `f` on a `size × size × size` grid over `0.0..=1.0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is below 2.
+    pub fn from_fn(size: usize, f: impl Fn(Rgb<f32>) -> Rgb<f32>) -> Self {
+        assert!(size >= 2, "a 3D LUT needs at least two points per axis");
+
+        let step = |i: usize| i as f32 / (size - 1) as f32;
+        let table = (0..size * size * size)
+            .map(|n| {
+                f(Rgb::new(
+                    step(n % size),
+                    step(n / size % size),
+                    step(n / (size * size)),
+                ))
+            })
+            .collect();
+
+        Self {
+            size,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            table,
+        }
+    }
+
+    /// Parses the text of an Adobe/Resolve `.cube` file with a 3D table.
+    pub fn parse_cube(text: &str) -> Result<Self, CubeError> {
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let invalid = || CubeError::InvalidLine(index + 1);
+            let triple = |words: core::str::SplitWhitespace<'_>| -> Result<[f32; 3], CubeError> {
+                let values = words
+                    .map(str::parse)
+                    .collect::<Result<Vec<f32>, _>>()
+                    .map_err(|_| invalid())?;
+                <[f32; 3]>::try_from(values).map_err(|_| invalid())
+            };
+
+            let mut words = line.split_whitespace();
+            match words.clone().next() {
+                None => {}
+                Some(word) if word.starts_with('#') => {}
+                Some("TITLE" | "LUT_1D_INPUT_RANGE" | "LUT_3D_INPUT_RANGE") => {}
+                Some("LUT_3D_SIZE") => {
+                    words.next();
+                    size = Some(
+                        words
+                            .next()
+                            .and_then(|s| s.parse::<usize>().ok())
+                            .ok_or_else(invalid)?,
+                    );
+                }
+                Some("DOMAIN_MIN") => {
+                    words.next();
+                    domain_min = triple(words)?;
+                }
+                Some("DOMAIN_MAX") => {
+                    words.next();
+                    domain_max = triple(words)?;
+                }
+                Some(_) => {
+                    let [r, g, b] = triple(words)?;
+                    table.push(Rgb::new(r, g, b));
+                }
+            }
+        }
+
+        let size = size
+            .filter(|&size| size >= 2)
+            .ok_or(CubeError::MissingSize)?;
+        if table.len() != size * size * size {
+            return Err(CubeError::WrongEntryCount {
+                expected: size * size * size,
+                found: table.len(),
+            });
+        }
+
+        Ok(Self {
+            size,
+            domain_min,
+            domain_max,
+            table,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Interpolates the table at `color`, clamping it to the domain first.
+    pub fn get(&self, color: Rgb<f32>) -> Rgb<f32> {
+        let last = (self.size - 1) as f32;
+        let axis = |c: usize, v: f32| {
+            let t = ((v - self.domain_min[c]) / (self.domain_max[c] - self.domain_min[c]))
+                .clamp(0.0, 1.0);
+            let x = t * last;
+            let i = (x as usize).min(self.size - 2);
+            (i, x - i as f32)
+        };
+        let (r, fr) = axis(0, color.r);
+        let (g, fg) = axis(1, color.g);
+        let (b, fb) = axis(2, color.b);
+
+        let entry = |dr: usize, dg: usize, db: usize| {
+            self.table[(b + db) * self.size * self.size + (g + dg) * self.size + r + dr]
+        };
+        let lerp = |a: Rgb<f32>, b: Rgb<f32>, t: f32| a.zip_map(b, |a, b| a + (b - a) * t);
+
+        let plane = |db: usize| {
+            lerp(
+                lerp(entry(0, 0, db), entry(1, 0, db), fr),
+                lerp(entry(0, 1, db), entry(1, 1, db), fr),
+                fg,
+            )
+        };
+        lerp(plane(0), plane(1), fb)
+    }
+}
+
+/// Grades an `Rgb<f32>` image through a [`Lut3d`], see [`Image::apply_lut3d`].
+#[derive(Debug, Clone)]
+pub struct ApplyLut3d<I> {
+    image: I,
+    lut: Lut3d,
+}
+
+impl<I> ApplyLut3d<I> {
+    pub(crate) fn new(image: I, lut: Lut3d) -> Self {
+        Self { image, lut }
+    }
+}
+
+impl<I> Image for ApplyLut3d<I>
+where
+    I: Image<Pixel = Rgb<f32>>,
+{
+    type Pixel = Rgb<f32>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.lut.get(self.image.get(p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::{CubeError, Lut, Lut3d};
+    use crate::tests::place;
+    use crate::{Gray, Image, Rgb, Rgba, from_fn};
+
+    const INVERT_CUBE: &str = "\
+# inverts every channel
+TITLE \"invert\"
+LUT_3D_SIZE 2
+
+1 1 1
+0 1 1
+1 0 1
+0 0 1
+1 1 0
+0 1 0
+1 0 0
+0 0 0
+";
+
+    #[test]
+    fn lut_applies_to_color_channels_only() {
+        let image = from_fn(|_| Rgba::new(10u8, 20, 30, 40))
+            .apply_lut(Lut::from_fn(|v| v.saturating_mul(2)));
+        assert_eq!(
+            image.get(Place::new(0.0, 0.0).unwrap()),
+            Rgba::new(20, 40, 60, 40)
+        );
+    }
+
+    #[test]
+    fn cube_file_is_parsed_and_interpolated() {
+        let lut = Lut3d::parse_cube(INVERT_CUBE).unwrap();
+        assert_eq!(lut.size(), 2);
+        assert_eq!(lut.get(Rgb::new(0.25, 1.0, 0.0)), Rgb::new(0.75, 0.0, 1.0));
+        assert_eq!(lut.get(Rgb::new(-3.0, 2.0, 0.5)), Rgb::new(1.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn malformed_cube_files_are_rejected() {
+        assert_eq!(Lut3d::parse_cube("0 0 0"), Err(CubeError::MissingSize));
+        assert_eq!(
+            Lut3d::parse_cube("LUT_3D_SIZE 2\n0 0 0\n"),
+            Err(CubeError::WrongEntryCount {
+                expected: 8,
+                found: 1
+            })
+        );
+        assert_eq!(
+            Lut3d::parse_cube("LUT_3D_SIZE 2\n0 zero 0\n"),
+            Err(CubeError::InvalidLine(2))
+        );
+    }
+
+    #[test]
+    fn identity_cube_is_exact_on_grid_points() {
+        let lut = Lut3d::from_fn(5, |c| c);
+        let image = from_fn(|_| Rgb::new(0.25f32, 0.5, 1.0)).apply_lut3d(lut);
+        assert_eq!(
+            image.get(Place::new(0.0, 0.0).unwrap()),
+            Rgb::new(0.25, 0.5, 1.0)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn chained_luts_match_chained_ops(v: u8, p in place()) {
+            let brighten = Lut::from_fn(|v| v.saturating_add(40));
+            let invert = Lut::from_fn(|v| 255 - v);
+            let image = from_fn(move |_| Gray(v)).apply_lut(brighten).then(&invert).then(&Lut::identity());
+            prop_assert_eq!(image.get(p), Gray(255 - v.saturating_add(40)));
+        }
+
+        #[test]
+        fn identity_cube_reproduces_colors(r in 0.0..=1.0f32, g in 0.0..=1.0f32, b in 0.0..=1.0f32) {
+            let out = Lut3d::from_fn(3, |c| c).get(Rgb::new(r, g, b));
+            prop_assert!((out.r - r).abs() < 1e-5 && (out.g - g).abs() < 1e-5 && (out.b - b).abs() < 1e-5);
+        }
+    }
+}
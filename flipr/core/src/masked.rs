@@ -0,0 +1,132 @@
+use space::Place;
+
+use crate::Image;
+use crate::arithmetic::PixelLerp;
+use crate::pixel::Gray;
+
+/// How a mask value selects between the source and the edited image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MaskBlend {
+    /// Any non-zero mask value selects the edited pixel.
+    #[default]
+    Binary,
+    /// The mask value, scaled to `0.0..=1.0`, interpolates from the source to
+    /// the edited pixel.
+    Feathered,
+}
+
+/// Edit restricted to a region of interest, see [`Image::masked`].
+#[derive(Debug, Clone)]
+pub struct Masked<I, J, M> {
+    source: I,
+    edited: J,
+    mask: M,
+    blend: MaskBlend,
+}
+
+impl<I, J, M> Masked<I, J, M> {
+    pub(crate) fn new(source: I, edited: J, mask: M, blend: MaskBlend) -> Self {
+        Self {
+            source,
+            edited,
+            mask,
+            blend,
+        }
+    }
+}
+
+impl<I, J, M> Image for Masked<I, J, M>
+where
+    I: Image,
+    I::Pixel: PixelLerp,
+    J: Image<Pixel = I::Pixel>,
+    M: Image<Pixel = Gray<u8>>,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        match (self.mask.get(p.clone()).0, self.blend) {
+            (0, _) => self.source.get(p),
+            (u8::MAX, _) | (_, MaskBlend::Binary) => self.edited.get(p),
+            (m, MaskBlend::Feathered) => {
+                let t = m as f64 / u8::MAX as f64;
+                self.source.get(p.clone()).lerp(self.edited.get(p), t)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::{Just, Strategy};
+    use proptest::{prop_assert_eq, prop_oneof, proptest};
+    use space::Place;
+
+    use super::MaskBlend;
+    use crate::tests::place;
+    use crate::{Gray, Image, Rgb, from_fn};
+
+    fn source() -> impl Image<Pixel = Rgb<u8>> + Clone {
+        from_fn(|_| Rgb::new(0u8, 100, 200))
+    }
+
+    fn invert<I: Image<Pixel = Rgb<u8>>>(image: I) -> impl Image<Pixel = Rgb<u8>> {
+        from_fn(move |p| {
+            let Rgb { r, g, b } = image.get(p);
+            Rgb::new(255 - r, 255 - g, 255 - b)
+        })
+    }
+
+    fn left_half() -> impl Image<Pixel = Gray<u8>> {
+        from_fn(|p: Place| {
+            Gray(if p.x().to_f64().unwrap() < 0.0 {
+                255
+            } else {
+                0
+            })
+        })
+    }
+
+    fn blend() -> impl Strategy<Value = MaskBlend> {
+        prop_oneof![Just(MaskBlend::Binary), Just(MaskBlend::Feathered)]
+    }
+
+    proptest! {
+        #[test]
+        fn empty_mask_passes_source_through(p in place(), blend in blend()) {
+            let image = source().masked(from_fn(|_| Gray(0u8)), invert, blend);
+            prop_assert_eq!(image.get(p.clone()), source().get(p));
+        }
+
+        #[test]
+        fn full_mask_applies_edit(p in place()) {
+            let image = source().masked(from_fn(|_| Gray(255u8)), invert, MaskBlend::Feathered);
+            prop_assert_eq!(image.get(p.clone()), invert(source()).get(p));
+        }
+    }
+
+    #[test]
+    fn edit_is_confined_to_mask() {
+        let image = source().masked(left_half(), invert, MaskBlend::Binary);
+        assert_eq!(
+            image.get(Place::new(-1.0, 0.0).unwrap()),
+            Rgb::new(255, 155, 55)
+        );
+        assert_eq!(
+            image.get(Place::new(1.0, 0.0).unwrap()),
+            Rgb::new(0, 100, 200)
+        );
+    }
+
+    #[test]
+    fn binary_mask_treats_any_nonzero_value_as_set() {
+        let image = source().masked(from_fn(|_| Gray(1u8)), invert, MaskBlend::Binary);
+        assert_eq!(image.get(Place::origin()), Rgb::new(255, 155, 55));
+    }
+
+    #[test]
+    fn feathered_mask_blends_proportionally() {
+        let image = source().masked(from_fn(|_| Gray(51u8)), invert, MaskBlend::Feathered);
+        assert_eq!(image.get(Place::origin()), Rgb::new(51, 111, 171));
+    }
+}
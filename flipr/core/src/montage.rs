@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+
+use space::{Offset, Place, Real};
+
+use crate::Image;
+
+/// Grid of same-typed images, see [`montage`].
+#[derive(Debug, Clone)]
+pub struct Montage<I: Image> {
+    images: Vec<I>,
+    columns: usize,
+    width: Real,
+    height: Real,
+    spacing: Real,
+    background: I::Pixel,
+}
+
+/// Lays `images` out row by row in a grid with `columns` columns.
+///
+/// Every image is shown through a `width × height` cell whose top-left corner is
+/// the image's origin. Cells are separated by `spacing`, and places in the gaps,
+/// left of or above the grid, or past the last image read as `background`.
+///
+/// # Panics
+///
+/// Panics if `columns` is zero, or if `width`, `height` or `spacing` is
+/// negative, or if the cells are empty.
+pub fn montage<I: Image>(
+    images: Vec<I>,
+    columns: usize,
+    width: Real,
+    height: Real,
+    spacing: Real,
+    background: I::Pixel,
+) -> Montage<I> {
+    assert!(columns > 0, "montage needs at least one column");
+    assert!(
+        width > Real::zero() && height > Real::zero(),
+        "montage cells must not be empty"
+    );
+    assert!(
+        spacing >= Real::zero(),
+        "montage spacing must not be negative"
+    );
+
+    Montage {
+        images,
+        columns,
+        width,
+        height,
+        spacing,
+        background,
+    }
+}
+
+/// Splits a coordinate into the index of its cell and the position within it,
+/// or `None` when it falls before the grid or into the spacing.
+fn cell(r: &Real, size: &Real, spacing: &Real) -> Option<(usize, Real)> {
+    let pitch = size + spacing;
+    let index = (r / &pitch).floor();
+    let local = r - &index * pitch;
+
+    if local >= *size {
+        return None;
+    }
+
+    Some((usize::try_from(index.to_i64()?).ok()?, local))
+}
+
+impl<I> Image for Montage<I>
+where
+    I: Image,
+    I::Pixel: Clone,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let Some((column, x)) = cell(p.x(), &self.width, &self.spacing) else {
+            return self.background.clone();
+        };
+        let Some((row, y)) = cell(p.y(), &self.height, &self.spacing) else {
+            return self.background.clone();
+        };
+
+        if column >= self.columns {
+            return self.background.clone();
+        }
+
+        match row
+            .checked_mul(self.columns)
+            .and_then(|i| i.checked_add(column))
+            .and_then(|i| self.images.get(i))
+        {
+            Some(image) => image.get(Place::origin() + Offset::from_reals(x, y)),
+            None => self.background.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use proptest::{prop_assert_eq, proptest};
+    use space::{Place, Real};
+
+    use super::montage;
+    use crate::tests::place;
+    use crate::{FromFn, Image, StaticImage, from_fn};
+
+    fn real(f: f64) -> Real {
+        Real::from_f64(f).unwrap()
+    }
+
+    fn at(x: f64, y: f64) -> Place {
+        Place::new(x, y).unwrap()
+    }
+
+    fn tagged(tag: i32) -> FromFn<impl Fn(Place) -> Option<(i32, Place)>> {
+        from_fn(move |p| Some((tag, p)))
+    }
+
+    fn grid(count: i32) -> impl Image<Pixel = Option<(i32, Place)>> {
+        let images: Vec<_> = (0..count).map(tagged).collect();
+        montage(images, 3, real(4.0), real(2.0), real(1.0), None)
+    }
+
+    #[test]
+    fn cells_are_laid_out_row_by_row() {
+        assert_eq!(grid(5).get(at(0.5, 0.5)), Some((0, at(0.5, 0.5))));
+        assert_eq!(grid(5).get(at(5.0, 1.0)), Some((1, at(0.0, 1.0))));
+        assert_eq!(grid(5).get(at(13.5, 0.0)), Some((2, at(3.5, 0.0))));
+        assert_eq!(grid(5).get(at(6.0, 3.5)), Some((4, at(1.0, 0.5))));
+    }
+
+    #[test]
+    fn spacing_reads_as_background() {
+        assert_eq!(grid(5).get(at(4.0, 0.0)), None);
+        assert_eq!(grid(5).get(at(0.0, 2.5)), None);
+    }
+
+    #[test]
+    fn places_outside_grid_read_as_background() {
+        assert_eq!(grid(5).get(at(-0.5, 0.0)), None);
+        assert_eq!(grid(5).get(at(0.0, -0.5)), None);
+        assert_eq!(grid(5).get(at(15.5, 0.0)), None);
+        assert_eq!(grid(5).get(at(11.0, 3.0)), None);
+    }
+
+    proptest! {
+        #[test]
+        fn empty_montage_is_background(p in place()) {
+            let images: Vec<StaticImage<Option<i32>, 1, 1>> = vec![];
+            let image = montage(images, 2, real(1.0), real(1.0), real(0.0), None);
+            prop_assert_eq!(image.get(p), None);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use space::Place;
+
+use crate::Image;
+use crate::pixel::Gray;
+
+/// Distribution sampled by a [`NoiseImage`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Noise {
+    /// Independent values in `0.0..1.0`, one per unit pixel cell.
+    Uniform,
+    /// Independent normally distributed values with mean zero, one per unit
+    /// pixel cell.
+    Gaussian { sigma: f32 },
+    /// Smoothly interpolated random values in `0.0..=1.0` on a lattice with
+    /// `scale` units between lattice points.
+    Value { scale: f32 },
+    /// Perlin gradient noise, roughly in `-1.0..=1.0`, on a lattice with
+    /// `scale` units between lattice points.
+    Perlin { scale: f32 },
+}
+
+/// Source image of deterministic noise, see [`noise`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseImage {
+    noise: Noise,
+    seed: u64,
+}
+
+/// Creates a noise image; equal seeds give equal images.
+///
+/// The image is stateless, so it can be sampled in any order or combined with
+/// another image through [`Image::zip_with`] to perturb it.
+pub fn noise(noise: Noise, seed: u64) -> NoiseImage {
+    NoiseImage { noise, seed }
+}
+
+impl NoiseImage {
+    fn hash(&self, i: i64, j: i64, stream: u64) -> u64 {
+        let mixed = splitmix(self.seed ^ splitmix(stream));
+        splitmix(mixed ^ splitmix(i as u64 ^ splitmix(j as u64)))
+    }
+
+    /// Uniform value in `0.0..1.0` attached to lattice point `(i, j)`.
+    fn unit(&self, i: i64, j: i64, stream: u64) -> f64 {
+        (self.hash(i, j, stream) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn value(&self, x: f64, y: f64) -> f64 {
+        let (i, j, tx, ty) = lattice(x, y);
+        let (u, v) = (smoothstep(tx), smoothstep(ty));
+        let top = lerp(self.unit(i, j, 0), self.unit(i + 1, j, 0), u);
+        let bottom = lerp(self.unit(i, j + 1, 0), self.unit(i + 1, j + 1, 0), u);
+        lerp(top, bottom, v)
+    }
+
+    fn perlin(&self, x: f64, y: f64) -> f64 {
+        let (i, j, tx, ty) = lattice(x, y);
+        let corner = |di: i64, dj: i64| {
+            let angle = self.unit(i + di, j + dj, 0) * core::f64::consts::TAU;
+            libm::cos(angle) * (tx - di as f64) + libm::sin(angle) * (ty - dj as f64)
+        };
+        let (u, v) = (fade(tx), fade(ty));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+        // Unit gradients bound the raw value by sqrt(1/2).
+        lerp(top, bottom, v) * core::f64::consts::SQRT_2
+    }
+}
+
+impl Image for NoiseImage {
+    type Pixel = Gray<f32>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let x = p.x().to_f64().unwrap_or(0.0);
+        let y = p.y().to_f64().unwrap_or(0.0);
+        let cell = || (libm::floor(x) as i64, libm::floor(y) as i64);
+
+        let value = match self.noise {
+            Noise::Uniform => {
+                let (i, j) = cell();
+                self.unit(i, j, 0)
+            }
+            Noise::Gaussian { sigma } => {
+                let (i, j) = cell();
+                // Box–Muller; `1 - u` keeps the logarithm finite.
+                let radius = libm::sqrt(-2.0 * libm::log(1.0 - self.unit(i, j, 0)));
+                let angle = self.unit(i, j, 1) * core::f64::consts::TAU;
+                radius * libm::cos(angle) * sigma as f64
+            }
+            Noise::Value { scale } => self.value(x / scale as f64, y / scale as f64),
+            Noise::Perlin { scale } => self.perlin(x / scale as f64, y / scale as f64),
+        };
+
+        Gray(value as f32)
+    }
+}
+
+pub(crate) fn splitmix(x: u64) -> u64 {
+    let x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Lattice cell containing `(x, y)` and the position within it.
+fn lattice(x: f64, y: f64) -> (i64, i64, f64, f64) {
+    let (fx, fy) = (libm::floor(x), libm::floor(y));
+    (fx as i64, fy as i64, x - fx, y - fy)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+fn smoothstep(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::{Noise, noise};
+    use crate::buffer::{ImageBuffer, Layout};
+    use crate::tests::place;
+    use crate::{Gray, Image, from_fn};
+
+    fn samples(noise: &impl Image<Pixel = Gray<f32>>) -> ImageBuffer<Gray<f32>> {
+        ImageBuffer::sample(noise, 64, 64, Layout::Interleaved)
+    }
+
+    fn mean_and_variance(buffer: &ImageBuffer<Gray<f32>>) -> (f64, f64) {
+        let values = buffer.as_interleaved().unwrap();
+        let n = values.len() as f64;
+        let mean = values.iter().map(|g| g.0 as f64).sum::<f64>() / n;
+        let variance = values
+            .iter()
+            .map(|g| (g.0 as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        (mean, variance)
+    }
+
+    #[test]
+    fn uniform_noise_has_uniform_moments() {
+        let (mean, variance) = mean_and_variance(&samples(&noise(Noise::Uniform, 7)));
+        assert!((mean - 0.5).abs() < 0.02, "mean {mean}");
+        assert!((variance - 1.0 / 12.0).abs() < 0.01, "variance {variance}");
+    }
+
+    #[test]
+    fn gaussian_noise_has_requested_sigma() {
+        let image = noise(Noise::Gaussian { sigma: 2.0 }, 7);
+        let (mean, variance) = mean_and_variance(&samples(&image));
+        assert!(mean.abs() < 0.1, "mean {mean}");
+        assert!((variance - 4.0).abs() < 0.3, "variance {variance}");
+    }
+
+    #[test]
+    fn seeds_give_different_images() {
+        assert_ne!(
+            samples(&noise(Noise::Uniform, 1)),
+            samples(&noise(Noise::Uniform, 2))
+        );
+    }
+
+    #[test]
+    fn noise_can_be_added_to_an_image() {
+        let flat = from_fn(|_| Gray(0.5f32));
+        let noisy = flat.zip_with(noise(Noise::Gaussian { sigma: 0.1 }, 3), |a, b| {
+            Gray(a.0 + b.0)
+        });
+        let (mean, variance) = mean_and_variance(&samples(&noisy));
+        assert!((mean - 0.5).abs() < 0.01, "mean {mean}");
+        assert!((variance - 0.01).abs() < 0.002, "variance {variance}");
+    }
+
+    #[test]
+    fn lattice_noise_interpolates_lattice_values() {
+        for image in [
+            noise(Noise::Value { scale: 4.0 }, 5),
+            noise(Noise::Perlin { scale: 4.0 }, 5),
+        ] {
+            let at = |x: f64| image.get(Place::new(x, 1.0).unwrap()).0;
+            assert!((at(2.0) - at(2.001)).abs() < 1e-3);
+        }
+
+        let perlin = noise(Noise::Perlin { scale: 4.0 }, 5);
+        assert_eq!(perlin.get(Place::new(8.0, 12.0).unwrap()), Gray(0.0));
+    }
+
+    proptest! {
+        #[test]
+        fn noise_is_deterministic(p in place()) {
+            let image = noise(Noise::Perlin { scale: 3.0 }, 11);
+            prop_assert_eq!(image.get(p.clone()), image.get(p));
+        }
+
+        #[test]
+        fn noise_stays_in_range(p in place()) {
+            let value = noise(Noise::Value { scale: 2.5 }, 11).get(p.clone()).0;
+            prop_assert!((0.0..=1.0).contains(&value));
+            let perlin = noise(Noise::Perlin { scale: 2.5 }, 11).get(p).0;
+            prop_assert!((-1.0..=1.0).contains(&perlin));
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use core::marker::PhantomData;
+
+use space::{Place, Real};
+
+use crate::Image;
+use crate::arithmetic::Channel;
+use crate::pixel::{MapChannels, Pixel};
+
+/// Quantization strategy used by [`Image::to_u8`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dither {
+    /// Rounds every channel to the nearest level.
+    #[default]
+    None,
+    /// Offsets the rounding threshold by a 4×4 Bayer matrix indexed by the
+    /// pixel cell, trading banding for a fixed, position-dependent pattern.
+    Ordered,
+}
+
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Rounding offset in `-0.5..0.5` of the Bayer matrix at pixel `(i, j)`.
+pub(crate) fn bayer_bias(i: usize, j: usize) -> f64 {
+    let level = BAYER[j % BAYER.len()][i % BAYER.len()] as f64;
+    (level + 0.5) / 16.0 - 0.5
+}
+
+fn cell(r: &Real) -> usize {
+    r.floor()
+        .to_i64()
+        .map_or(0, |i| i.rem_euclid(BAYER.len() as i64) as usize)
+}
+
+/// Integer channel whose full range maps onto `0.0..=1.0`.
+pub trait Quantized: Channel {
+    const MAX: f64;
+}
+
+impl Quantized for u8 {
+    const MAX: f64 = u8::MAX as f64;
+}
+
+impl Quantized for u16 {
+    const MAX: f64 = u16::MAX as f64;
+}
+
+/// Maps integer channels to `f32` in the `0.0..=1.0` range, see [`Image::to_float`].
+#[derive(Debug, Clone)]
+pub struct ToFloat<I>(I);
+
+impl<I> ToFloat<I> {
+    pub(crate) fn new(image: I) -> Self {
+        Self(image)
+    }
+}
+
+impl<I> Image for ToFloat<I>
+where
+    I: Image,
+    I::Pixel: MapChannels<f32>,
+    <I::Pixel as Pixel>::Scalar: Quantized,
+{
+    type Pixel = <I::Pixel as MapChannels<f32>>::Output;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.0
+            .get(p)
+            .map_channels(|c| (c.to_f64() / <I::Pixel as Pixel>::Scalar::MAX) as f32)
+    }
+}
+
+/// Quantizes `f32` channels in the `0.0..=1.0` range to the integer channel `T`,
+/// see [`Image::to_u8`] and [`Image::to_u16`].
+#[derive(Debug, Clone)]
+pub struct Quantize<I, T> {
+    image: I,
+    dither: Dither,
+    channel: PhantomData<T>,
+}
+
+impl<I, T> Quantize<I, T> {
+    pub(crate) fn new(image: I, dither: Dither) -> Self {
+        Self {
+            image,
+            dither,
+            channel: PhantomData,
+        }
+    }
+}
+
+impl<I, T> Image for Quantize<I, T>
+where
+    I: Image,
+    I::Pixel: MapChannels<T, Scalar = f32>,
+    T: Quantized,
+{
+    type Pixel = <I::Pixel as MapChannels<T>>::Output;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let bias = match self.dither {
+            Dither::None => 0.0,
+            Dither::Ordered => bayer_bias(cell(p.x()), cell(p.y())),
+        };
+
+        self.image.get(p).map_channels(|c| {
+            let c = (c as f64).clamp(0.0, 1.0);
+            T::from_f64(c * T::MAX + bias)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::Dither;
+    use crate::tests::place;
+    use crate::{Gray, Image, Rgb, from_fn};
+
+    proptest! {
+        #[test]
+        fn u8_round_trips_through_float(c: u8, p in place()) {
+            let image = from_fn(move |_| Rgb::new(c, 0, 255)).to_float().to_u8(Dither::None);
+            prop_assert_eq!(image.get(p), Rgb::new(c, 0, 255));
+        }
+
+        #[test]
+        fn float_is_normalized(c: u8, p in place()) {
+            let Gray(f) = from_fn(move |_| Gray(c)).to_float().get(p);
+            prop_assert!((0.0..=1.0).contains(&f));
+        }
+
+        #[test]
+        fn ordered_dither_stays_within_one_level(f in 0.0..=1.0f32, p in place()) {
+            let Gray(plain) = from_fn(move |_| Gray(f)).to_u8(Dither::None).get(p.clone());
+            let Gray(dithered) = from_fn(move |_| Gray(f)).to_u8(Dither::Ordered).get(p);
+            prop_assert!(plain.abs_diff(dithered) <= 1);
+        }
+    }
+
+    #[test]
+    fn u16_uses_its_full_range() {
+        let image = from_fn(|_| Gray(1000u16)).to_float();
+        let Gray(f) = image.get(Place::origin());
+        assert_eq!(f, 1000.0 / 65535.0);
+        assert_eq!(image.to_u16(Dither::None).get(Place::origin()), Gray(1000));
+        assert_eq!(
+            from_fn(|_| Gray(1.0f32))
+                .to_u16(Dither::Ordered)
+                .get(Place::origin()),
+            Gray(u16::MAX)
+        );
+    }
+
+    #[test]
+    fn out_of_range_floats_are_clamped() {
+        let image = from_fn(|_| Rgb::new(-0.5f32, 1.5, f32::NAN)).to_u8(Dither::None);
+        assert_eq!(image.get(Place::origin()), Rgb::new(0, 255, 0));
+    }
+
+    #[test]
+    fn ordered_dither_averages_to_the_input() {
+        let level = 100.25 / 255.0;
+        let image = from_fn(move |_| Gray(level)).to_u8(Dither::Ordered);
+        let sum: u32 = (0..4)
+            .flat_map(|y| (0..4).map(move |x| Place::new(x as f64, y as f64).unwrap()))
+            .map(|p| image.get(p).0 as u32)
+            .sum();
+        assert_eq!(sum, 1604);
+    }
+}
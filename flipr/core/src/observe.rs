@@ -0,0 +1,157 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Image;
+use crate::buffer::{ImageBuffer, Layout, cell_center};
+use crate::error::FliprError;
+use crate::pixel::Pixel;
+
+/// Callbacks for following a long computation and stopping it early, see
+/// [`ImageBuffer::sample_observed`].
+///
+/// Every method has a default that ignores the event, so observers only
+/// implement what they need.
+pub trait ExecutionObserver {
+    /// A named stage of the computation has started.
+    fn stage(&self, _name: &str) {}
+
+    /// `done` of `total` rows have been computed.
+    fn rows_completed(&self, _done: usize, _total: usize) {}
+
+    /// Asked between rows; returning `true` stops the computation with
+    /// [`FliprError::Cancelled`].
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+impl ExecutionObserver for () {}
+
+impl<O: ExecutionObserver + ?Sized> ExecutionObserver for &O {
+    fn stage(&self, name: &str) {
+        (**self).stage(name)
+    }
+
+    fn rows_completed(&self, done: usize, total: usize) {
+        (**self).rows_completed(done, total)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        (**self).is_cancelled()
+    }
+}
+
+/// Flag shared between clones, set from one thread to stop work observed on
+/// another.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl ExecutionObserver for CancellationToken {
+    fn is_cancelled(&self) -> bool {
+        CancellationToken::is_cancelled(self)
+    }
+}
+
+impl<P: Pixel> ImageBuffer<P> {
+    /// Like [`sample`](Self::sample), reporting every finished row to
+    /// `observer` and stopping between rows once it is cancelled.
+    pub fn sample_observed<I>(
+        image: &I,
+        width: usize,
+        height: usize,
+        layout: Layout,
+        observer: &impl ExecutionObserver,
+    ) -> Result<Self, FliprError>
+    where
+        I: Image<Pixel = P>,
+    {
+        let mut pixels = Vec::with_capacity(width * height);
+        for j in 0..height {
+            if observer.is_cancelled() {
+                return Err(FliprError::Cancelled);
+            }
+            pixels.extend((0..width).map(|i| image.get(cell_center(i, j))));
+            observer.rows_completed(j + 1, height);
+        }
+
+        Ok(Self::from_fn(width, height, layout, |i, j| {
+            pixels[j * width + i]
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use space::Place;
+
+    use super::{CancellationToken, ExecutionObserver};
+    use crate::{FliprError, Gray, ImageBuffer, Layout, from_fn};
+
+    /// Cancels itself after `limit` rows.
+    struct StopAfter {
+        rows: Cell<usize>,
+        limit: usize,
+    }
+
+    impl ExecutionObserver for StopAfter {
+        fn rows_completed(&self, done: usize, _total: usize) {
+            self.rows.set(done);
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.rows.get() >= self.limit
+        }
+    }
+
+    fn ramp() -> impl crate::Image<Pixel = Gray<u8>> {
+        from_fn(|p: Place| Gray(p.y().to_f64().unwrap() as u8))
+    }
+
+    #[test]
+    fn observed_sampling_matches_sample() {
+        let observed = ImageBuffer::sample_observed(&ramp(), 3, 4, Layout::Planar, &()).unwrap();
+        assert_eq!(observed, ImageBuffer::sample(&ramp(), 3, 4, Layout::Planar));
+    }
+
+    #[test]
+    fn cancellation_stops_between_rows() {
+        let observer = StopAfter {
+            rows: Cell::new(0),
+            limit: 2,
+        };
+        let result = ImageBuffer::sample_observed(&ramp(), 3, 4, Layout::Interleaved, &observer);
+        assert_eq!(result, Err(FliprError::Cancelled));
+        assert_eq!(observer.rows.get(), 2);
+    }
+
+    #[test]
+    fn tokens_cancel_every_clone() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(ImageBuffer::sample_observed(&ramp(), 2, 2, Layout::Interleaved, &clone).is_ok());
+
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert_eq!(
+            ImageBuffer::sample_observed(&ramp(), 2, 2, Layout::Interleaved, &clone),
+            Err(FliprError::Cancelled)
+        );
+    }
+}
@@ -0,0 +1,487 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use space::Place;
+
+use crate::Image;
+use crate::buffer::{ImageBuffer, Layout};
+use crate::normalize::bayer_bias;
+use crate::pixel::{Gray, Rgb};
+
+/// Error handling when mapping colors onto a [`Palette`], see [`quantize`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dithering {
+    /// Picks the nearest palette color for every pixel.
+    #[default]
+    None,
+    /// Offsets every pixel by a 4×4 Bayer pattern scaled to the palette spacing.
+    Ordered,
+    /// Diffuses each pixel's error onto its unvisited neighbours with the
+    /// Floyd–Steinberg weights, scanning rows left to right.
+    FloydSteinberg,
+}
+
+/// Up to 256 colors an indexed image can refer to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Palette {
+    colors: Vec<Rgb<u8>>,
+}
+
+impl Palette {
+    /// # Panics
+    ///
+    /// Panics if `colors` is empty or holds more than 256 entries.
+    pub fn new(colors: Vec<Rgb<u8>>) -> Self {
+        assert!(
+            (1..=256).contains(&colors.len()),
+            "a palette holds between 1 and 256 colors"
+        );
+        Self { colors }
+    }
+
+    /// Builds a palette of at most `size` colors by repeatedly splitting the
+    /// box of pixels with the widest channel range at its median.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not in `1..=256`.
+    pub fn median_cut(image: &ImageBuffer<Rgb<u8>>, size: usize) -> Self {
+        assert!(
+            (1..=256).contains(&size),
+            "a palette holds between 1 and 256 colors"
+        );
+
+        let mut boxes = vec![pixels(image).map(|c| [c.r, c.g, c.b]).collect::<Vec<_>>()];
+
+        while boxes.len() < size {
+            let widest = boxes
+                .iter()
+                .enumerate()
+                .map(|(index, colors)| (index, widest_channel(colors)))
+                .max_by_key(|&(_, (_, range))| range)
+                .filter(|&(_, (_, range))| range > 0);
+            let Some((index, (channel, _))) = widest else {
+                break;
+            };
+
+            let mut colors = boxes.swap_remove(index);
+            colors.sort_unstable_by_key(|c| c[channel]);
+            let upper = colors.split_off(colors.len() / 2);
+            boxes.extend([colors, upper]);
+        }
+
+        Self::new(
+            boxes
+                .iter()
+                .map(|colors| mean(colors.iter().map(|&c| (c, 1))))
+                .collect(),
+        )
+    }
+
+    /// Builds a palette of at most `size` colors by inserting every pixel into
+    /// an 8-level color octree and merging the least populated deepest nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not in `1..=256`.
+    pub fn octree(image: &ImageBuffer<Rgb<u8>>, size: usize) -> Self {
+        assert!(
+            (1..=256).contains(&size),
+            "a palette holds between 1 and 256 colors"
+        );
+
+        let mut tree = Octree::default();
+        for color in pixels(image) {
+            tree.insert(color);
+        }
+        tree.reduce(size);
+
+        Self::new(tree.leaves())
+    }
+
+    pub fn colors(&self) -> &[Rgb<u8>] {
+        &self.colors
+    }
+
+    /// Index of the color closest to `(r, g, b)` in Euclidean RGB distance.
+    pub fn nearest(&self, color: Rgb<u8>) -> u8 {
+        self.nearest_f64([color.r as f64, color.g as f64, color.b as f64])
+    }
+
+    fn nearest_f64(&self, color: [f64; 3]) -> u8 {
+        let distance = |c: &Rgb<u8>| {
+            let d = [
+                c.r as f64 - color[0],
+                c.g as f64 - color[1],
+                c.b as f64 - color[2],
+            ];
+            d[0] * d[0] + d[1] * d[1] + d[2] * d[2]
+        };
+
+        self.colors
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+            .map_or(0, |(index, _)| index as u8)
+    }
+
+    /// Mean distance from each color to its nearest neighbour, per channel, as
+    /// an estimate of the step between adjacent levels.
+    fn spacing(&self) -> f64 {
+        let distance = |a: &Rgb<u8>, b: &Rgb<u8>| {
+            let d = [
+                a.r as f64 - b.r as f64,
+                a.g as f64 - b.g as f64,
+                a.b as f64 - b.b as f64,
+            ];
+            libm::sqrt((d[0] * d[0] + d[1] * d[1] + d[2] * d[2]) / 3.0)
+        };
+        let nearest = |(index, a): (usize, &Rgb<u8>)| {
+            self.colors
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index)
+                .map(|(_, b)| distance(a, b))
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        match self.colors.len() {
+            1 => 0.0,
+            n => self.colors.iter().enumerate().map(nearest).sum::<f64>() / n as f64,
+        }
+    }
+}
+
+fn pixels(image: &ImageBuffer<Rgb<u8>>) -> impl Iterator<Item = Rgb<u8>> + '_ {
+    (0..image.height())
+        .flat_map(move |j| (0..image.width()).map(move |i| (i, j)))
+        .filter_map(|(i, j)| image.pixel(i, j))
+}
+
+fn widest_channel(colors: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|c| {
+            let values = colors.iter().map(|color| color[c]);
+            let range = values.clone().max().unwrap_or(0) - values.min().unwrap_or(0);
+            (c, range)
+        })
+        .max_by_key(|&(_, range)| range)
+        .expect("there are three channels")
+}
+
+/// Rounded mean of colors weighted by their pixel counts.
+fn mean(colors: impl Iterator<Item = ([u8; 3], u64)>) -> Rgb<u8> {
+    let (sum, count) = colors.fold(([0u64; 3], 0u64), |(sum, count), (c, n)| {
+        (
+            [
+                sum[0] + c[0] as u64 * n,
+                sum[1] + c[1] as u64 * n,
+                sum[2] + c[2] as u64 * n,
+            ],
+            count + n,
+        )
+    });
+
+    average(sum, count)
+}
+
+fn average(sum: [u64; 3], count: u64) -> Rgb<u8> {
+    let channel = |s: u64| ((s + count / 2) / count.max(1)) as u8;
+
+    Rgb::new(channel(sum[0]), channel(sum[1]), channel(sum[2]))
+}
+
+#[derive(Default)]
+struct OctreeNode {
+    children: [Option<usize>; 8],
+    count: u64,
+    sum: [u64; 3],
+}
+
+/// Arena-backed color octree; nodes without children are leaves.
+#[derive(Default)]
+struct Octree {
+    nodes: Vec<OctreeNode>,
+    /// Inner nodes per depth, the candidates for merging.
+    levels: [Vec<usize>; 8],
+    leaves: usize,
+}
+
+impl Octree {
+    fn insert(&mut self, color: Rgb<u8>) {
+        if self.nodes.is_empty() {
+            self.nodes.push(OctreeNode::default());
+        }
+
+        let mut node = 0;
+        for depth in 0..8 {
+            let bit = 7 - depth;
+            let octant = (((color.r >> bit) & 1) << 2
+                | ((color.g >> bit) & 1) << 1
+                | ((color.b >> bit) & 1)) as usize;
+
+            node = match self.nodes[node].children[octant] {
+                Some(child) => child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(OctreeNode::default());
+                    if self.nodes[node].children.iter().all(Option::is_none) {
+                        self.levels[depth].push(node);
+                        // The root starts out as a leaf of an empty tree.
+                        if depth > 0 {
+                            self.leaves -= 1;
+                        }
+                    }
+                    self.nodes[node].children[octant] = Some(child);
+                    self.leaves += 1;
+                    child
+                }
+            };
+        }
+
+        let leaf = &mut self.nodes[node];
+        leaf.count += 1;
+        leaf.sum[0] += color.r as u64;
+        leaf.sum[1] += color.g as u64;
+        leaf.sum[2] += color.b as u64;
+    }
+
+    /// Merges the children of the least populated deepest inner nodes until at
+    /// most `size` leaves remain.
+    fn reduce(&mut self, size: usize) {
+        while self.leaves > size {
+            let Some(level) = self.levels.iter_mut().rev().find(|level| !level.is_empty()) else {
+                break;
+            };
+
+            let nodes = &self.nodes;
+            let (position, _) = level
+                .iter()
+                .enumerate()
+                .min_by_key(|&(_, &node)| leaf_children_count(nodes, node))
+                .expect("level is not empty");
+            let node = level.swap_remove(position);
+
+            let children: Vec<usize> = self.nodes[node]
+                .children
+                .iter()
+                .flatten()
+                .copied()
+                .collect();
+            for &child in &children {
+                let (count, sum) = (self.nodes[child].count, self.nodes[child].sum);
+                let parent = &mut self.nodes[node];
+                parent.count += count;
+                (0..3).for_each(|c| parent.sum[c] += sum[c]);
+            }
+            self.nodes[node].children = [None; 8];
+            self.leaves = self.leaves + 1 - children.len();
+        }
+    }
+
+    /// Mean color of every populated leaf.
+    fn leaves(&self) -> Vec<Rgb<u8>> {
+        let mut stack = vec![0];
+        let mut colors = Vec::new();
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if node.children.iter().all(Option::is_none) {
+                colors.push(average(node.sum, node.count));
+            } else {
+                stack.extend(node.children.iter().flatten());
+            }
+        }
+
+        colors
+    }
+}
+
+/// Pixels of the subtree rooted at `node`, whose children are all leaves.
+fn leaf_children_count(nodes: &[OctreeNode], node: usize) -> u64 {
+    nodes[node]
+        .children
+        .iter()
+        .flatten()
+        .map(|&child| nodes[child].count)
+        .sum()
+}
+
+/// Image stored as indices into a [`Palette`], see [`quantize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedImage {
+    indices: ImageBuffer<Gray<u8>>,
+    palette: Palette,
+}
+
+impl IndexedImage {
+    pub fn indices(&self) -> &ImageBuffer<Gray<u8>> {
+        &self.indices
+    }
+
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+}
+
+impl Image for IndexedImage {
+    type Pixel = Rgb<u8>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.palette.colors[self.indices.get(p).0 as usize]
+    }
+}
+
+/// Maps every pixel of `image` to a color of `palette`.
+pub fn quantize(
+    image: &ImageBuffer<Rgb<u8>>,
+    palette: &Palette,
+    dithering: Dithering,
+) -> IndexedImage {
+    let (width, height) = (image.width(), image.height());
+    let color = |i: usize, j: usize| {
+        let c = image.pixel(i, j).expect("indices are in bounds");
+        [c.r as f64, c.g as f64, c.b as f64]
+    };
+
+    let indices = match dithering {
+        Dithering::None => ImageBuffer::from_fn(width, height, Layout::Interleaved, |i, j| {
+            Gray(palette.nearest_f64(color(i, j)))
+        }),
+        Dithering::Ordered => {
+            let spacing = palette.spacing();
+            ImageBuffer::from_fn(width, height, Layout::Interleaved, |i, j| {
+                let bias = bayer_bias(i, j) * spacing;
+                Gray(palette.nearest_f64(color(i, j).map(|c| c + bias)))
+            })
+        }
+        Dithering::FloydSteinberg => {
+            let mut error = vec![[0.0f64; 3]; width * height];
+            let mut indices = ImageBuffer::filled(width, height, Layout::Interleaved, Gray(0));
+
+            for j in 0..height {
+                for i in 0..width {
+                    let wanted = color(i, j);
+                    let wanted: [f64; 3] =
+                        core::array::from_fn(|c| wanted[c] + error[j * width + i][c]);
+                    let index = palette.nearest_f64(wanted);
+                    indices.set_pixel(i, j, Gray(index));
+
+                    let got = palette.colors[index as usize];
+                    let got = [got.r as f64, got.g as f64, got.b as f64];
+                    let neighbours = [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)];
+                    for (di, dj, weight) in neighbours {
+                        let (ni, nj) = (i as isize + di, j + dj);
+                        if ni < 0 || ni as usize >= width || nj >= height {
+                            continue;
+                        }
+                        let target = &mut error[nj * width + ni as usize];
+                        (0..3).for_each(|c| target[c] += (wanted[c] - got[c]) * weight / 16.0);
+                    }
+                }
+            }
+
+            indices
+        }
+    };
+
+    IndexedImage {
+        indices,
+        palette: palette.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::{Dithering, Palette, quantize};
+    use crate::Rgb;
+    use crate::buffer::{ImageBuffer, Layout};
+
+    fn quadrants() -> ImageBuffer<Rgb<u8>> {
+        ImageBuffer::from_fn(8, 8, Layout::Interleaved, |i, j| match (i < 4, j < 4) {
+            (true, true) => Rgb::new(250, 0, 0),
+            (false, true) => Rgb::new(0, 250, 0),
+            (true, false) => Rgb::new(0, 0, 250),
+            (false, false) => Rgb::new(30, 30, 30),
+        })
+    }
+
+    fn gray(level: u8) -> ImageBuffer<Rgb<u8>> {
+        ImageBuffer::filled(16, 16, Layout::Interleaved, Rgb::new(level, level, level))
+    }
+
+    fn black_and_white() -> Palette {
+        Palette::new(vec![Rgb::new(0, 0, 0), Rgb::new(255, 255, 255)])
+    }
+
+    fn white_fraction(image: &ImageBuffer<Rgb<u8>>, dithering: Dithering) -> f64 {
+        let indexed = quantize(image, &black_and_white(), dithering);
+        let white = indexed
+            .indices()
+            .as_interleaved()
+            .unwrap()
+            .iter()
+            .filter(|g| g.0 == 1)
+            .count();
+        white as f64 / (image.width() * image.height()) as f64
+    }
+
+    #[test]
+    fn palettes_recover_few_distinct_colors_exactly() {
+        for palette in [
+            Palette::median_cut(&quadrants(), 4),
+            Palette::octree(&quadrants(), 4),
+        ] {
+            let mut colors = palette.colors().to_vec();
+            colors.sort_by_key(|c| (c.r, c.g, c.b));
+            assert_eq!(
+                colors,
+                [
+                    Rgb::new(0, 0, 250),
+                    Rgb::new(0, 250, 0),
+                    Rgb::new(30, 30, 30),
+                    Rgb::new(250, 0, 0)
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn palettes_respect_the_requested_size() {
+        let image = ImageBuffer::from_fn(16, 16, Layout::Interleaved, |i, j| {
+            Rgb::new(i as u8 * 16, j as u8 * 16, (i * j) as u8)
+        });
+        assert_eq!(Palette::median_cut(&image, 10).colors().len(), 10);
+        assert!(Palette::octree(&image, 10).colors().len() <= 10);
+        assert_eq!(Palette::median_cut(&quadrants(), 16).colors().len(), 4);
+    }
+
+    #[test]
+    fn undithered_quantization_picks_the_nearest_color() {
+        assert_eq!(white_fraction(&gray(100), Dithering::None), 0.0);
+        assert_eq!(white_fraction(&gray(160), Dithering::None), 1.0);
+    }
+
+    #[test]
+    fn dithering_preserves_average_brightness() {
+        for dithering in [Dithering::Ordered, Dithering::FloydSteinberg] {
+            let fraction = white_fraction(&gray(64), dithering);
+            assert!((fraction - 0.25).abs() < 0.05, "{dithering:?}: {fraction}");
+        }
+    }
+
+    #[test]
+    fn indexed_image_samples_palette_colors() {
+        let indexed = quantize(
+            &quadrants(),
+            &Palette::median_cut(&quadrants(), 4),
+            Dithering::None,
+        );
+        assert_eq!(
+            ImageBuffer::sample(&indexed, 8, 8, Layout::Interleaved),
+            quadrants()
+        );
+    }
+}
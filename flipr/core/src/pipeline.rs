@@ -0,0 +1,56 @@
+/// Chains [`Image`](crate::Image) methods left to right, so
+/// `pipeline!(src => to_float() => saturate(1.2) => gaussian_blur(2.0))`
+/// reads in processing order and expands to
+/// `src.to_float().saturate(1.2).gaussian_blur(2.0)`.
+///
+/// Each step is a method call; a malformed chain is reported with the
+/// expected shape instead of a parser error deep inside the expansion.
+#[macro_export]
+macro_rules! pipeline {
+    ($source:expr $(=> $step:ident($($arg:expr),* $(,)?))* $(,)?) => {
+        $source $(.$step($($arg),*))*
+    };
+    ($($tokens:tt)*) => {
+        ::core::compile_error!(
+            "expected `pipeline!(source => step(args) => ...)`, where every step is an `Image` method"
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Dither, Image, ImageBuffer, Layout, Rgb, ToneMap};
+
+    fn source() -> ImageBuffer<Rgb<u8>> {
+        ImageBuffer::from_fn(4, 3, Layout::Interleaved, |i, j| {
+            Rgb::new((i * 60) as u8, (j * 80) as u8, 128)
+        })
+    }
+
+    #[test]
+    fn steps_apply_in_order() {
+        let piped = pipeline!(
+            source()
+                => to_float()
+                => saturate(1.2)
+                => tone_map(ToneMap::Reinhard)
+                => to_u8(Dither::None),
+        );
+        let nested = source()
+            .to_float()
+            .saturate(1.2)
+            .tone_map(ToneMap::Reinhard)
+            .to_u8(Dither::None);
+
+        assert_eq!(
+            ImageBuffer::sample(&piped, 4, 3, Layout::Interleaved),
+            ImageBuffer::sample(&nested, 4, 3, Layout::Interleaved)
+        );
+    }
+
+    #[test]
+    fn a_source_alone_is_a_pipeline() {
+        let image = source();
+        assert_eq!(pipeline!(image.clone()), image);
+    }
+}
@@ -0,0 +1,241 @@
+/// Pixel made of one or more channels of the same scalar type.
+pub trait Pixel: Copy {
+    type Scalar: Copy;
+
+    /// Number of channels, alpha included.
+    const CHANNELS: usize;
+
+    /// Returns the channel at `index`, in `r, g, b, a` order for color pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not below [`Pixel::CHANNELS`].
+    fn channel(self, index: usize) -> Self::Scalar;
+
+    /// Builds a pixel from its channels, asking `f` for each index in order.
+    fn from_channels(f: impl FnMut(usize) -> Self::Scalar) -> Self;
+
+    /// Applies `f` to every channel.
+    fn map(self, f: impl FnMut(Self::Scalar) -> Self::Scalar) -> Self;
+
+    /// Applies `f` to every color channel, leaving alpha untouched.
+    fn map_color(self, f: impl FnMut(Self::Scalar) -> Self::Scalar) -> Self {
+        self.map(f)
+    }
+
+    /// Combines corresponding channels of `self` and `other` with `f`.
+    fn zip_map(
+        self,
+        other: Self,
+        f: impl FnMut(Self::Scalar, Self::Scalar) -> Self::Scalar,
+    ) -> Self;
+}
+
+/// Calls `f` with one zeroed accumulator per channel of `P`, kept on the
+/// stack unless the pixel has more than four channels, so that sampling
+/// adapters summing many pixels do not allocate per sample.
+pub(crate) fn with_sums<P, T, R>(f: impl FnOnce(&mut [T]) -> R) -> R
+where
+    P: Pixel,
+    T: Copy + Default,
+{
+    if P::CHANNELS <= 4 {
+        f(&mut [T::default(); 4][..P::CHANNELS])
+    } else {
+        f(&mut alloc::vec![T::default(); P::CHANNELS])
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Gray<T>(pub T);
+
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Rgb<T> {
+    pub r: T,
+    pub g: T,
+    pub b: T,
+}
+
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Rgba<T> {
+    pub r: T,
+    pub g: T,
+    pub b: T,
+    pub a: T,
+}
+
+/// Pixel with `N` channels of equal standing, such as the bands of a
+/// multispectral image; no channel is treated as alpha.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct MultiChannelPixel<T, const N: usize>(pub [T; N]);
+
+impl<T> Gray<T> {
+    pub fn new(v: T) -> Self {
+        Self(v)
+    }
+}
+
+impl<T> Rgb<T> {
+    pub fn new(r: T, g: T, b: T) -> Self {
+        Self { r, g, b }
+    }
+}
+
+impl<T> Rgba<T> {
+    pub fn new(r: T, g: T, b: T, a: T) -> Self {
+        Self { r, g, b, a }
+    }
+}
+
+impl<T: Copy> Pixel for Gray<T> {
+    type Scalar = T;
+
+    const CHANNELS: usize = 1;
+
+    fn channel(self, index: usize) -> T {
+        match index {
+            0 => self.0,
+            _ => panic!("Gray has no channel {index}"),
+        }
+    }
+
+    fn from_channels(mut f: impl FnMut(usize) -> T) -> Self {
+        Self(f(0))
+    }
+
+    fn map(self, mut f: impl FnMut(T) -> T) -> Self {
+        Self(f(self.0))
+    }
+
+    fn zip_map(self, other: Self, mut f: impl FnMut(T, T) -> T) -> Self {
+        Self(f(self.0, other.0))
+    }
+}
+
+impl<T: Copy> Pixel for Rgb<T> {
+    type Scalar = T;
+
+    const CHANNELS: usize = 3;
+
+    fn channel(self, index: usize) -> T {
+        match index {
+            0 => self.r,
+            1 => self.g,
+            2 => self.b,
+            _ => panic!("Rgb has no channel {index}"),
+        }
+    }
+
+    fn from_channels(mut f: impl FnMut(usize) -> T) -> Self {
+        Self::new(f(0), f(1), f(2))
+    }
+
+    fn map(self, mut f: impl FnMut(T) -> T) -> Self {
+        Self::new(f(self.r), f(self.g), f(self.b))
+    }
+
+    fn zip_map(self, other: Self, mut f: impl FnMut(T, T) -> T) -> Self {
+        Self::new(f(self.r, other.r), f(self.g, other.g), f(self.b, other.b))
+    }
+}
+
+impl<T: Copy> Pixel for Rgba<T> {
+    type Scalar = T;
+
+    const CHANNELS: usize = 4;
+
+    fn channel(self, index: usize) -> T {
+        match index {
+            0 => self.r,
+            1 => self.g,
+            2 => self.b,
+            3 => self.a,
+            _ => panic!("Rgba has no channel {index}"),
+        }
+    }
+
+    fn from_channels(mut f: impl FnMut(usize) -> T) -> Self {
+        Self::new(f(0), f(1), f(2), f(3))
+    }
+
+    fn map(self, mut f: impl FnMut(T) -> T) -> Self {
+        Self::new(f(self.r), f(self.g), f(self.b), f(self.a))
+    }
+
+    fn map_color(self, mut f: impl FnMut(T) -> T) -> Self {
+        Self::new(f(self.r), f(self.g), f(self.b), self.a)
+    }
+
+    fn zip_map(self, other: Self, mut f: impl FnMut(T, T) -> T) -> Self {
+        Self::new(
+            f(self.r, other.r),
+            f(self.g, other.g),
+            f(self.b, other.b),
+            f(self.a, other.a),
+        )
+    }
+}
+
+impl<T: Copy, const N: usize> Pixel for MultiChannelPixel<T, N> {
+    type Scalar = T;
+
+    const CHANNELS: usize = N;
+
+    fn channel(self, index: usize) -> T {
+        match self.0.get(index) {
+            Some(&value) => value,
+            None => panic!("MultiChannelPixel<{N}> has no channel {index}"),
+        }
+    }
+
+    fn from_channels(f: impl FnMut(usize) -> T) -> Self {
+        Self(core::array::from_fn(f))
+    }
+
+    fn map(self, f: impl FnMut(T) -> T) -> Self {
+        Self(self.0.map(f))
+    }
+
+    fn zip_map(self, other: Self, mut f: impl FnMut(T, T) -> T) -> Self {
+        Self(core::array::from_fn(|c| f(self.0[c], other.0[c])))
+    }
+}
+
+/// Converts every channel of a pixel to another scalar type, keeping its layout.
+pub trait MapChannels<U>: Pixel {
+    type Output: Pixel<Scalar = U>;
+
+    fn map_channels(self, f: impl FnMut(Self::Scalar) -> U) -> Self::Output;
+}
+
+impl<T: Copy, U: Copy> MapChannels<U> for Gray<T> {
+    type Output = Gray<U>;
+
+    fn map_channels(self, mut f: impl FnMut(T) -> U) -> Gray<U> {
+        Gray(f(self.0))
+    }
+}
+
+impl<T: Copy, U: Copy> MapChannels<U> for Rgb<T> {
+    type Output = Rgb<U>;
+
+    fn map_channels(self, mut f: impl FnMut(T) -> U) -> Rgb<U> {
+        Rgb::new(f(self.r), f(self.g), f(self.b))
+    }
+}
+
+impl<T: Copy, U: Copy> MapChannels<U> for Rgba<T> {
+    type Output = Rgba<U>;
+
+    fn map_channels(self, mut f: impl FnMut(T) -> U) -> Rgba<U> {
+        Rgba::new(f(self.r), f(self.g), f(self.b), f(self.a))
+    }
+}
+
+impl<T: Copy, U: Copy, const N: usize> MapChannels<U> for MultiChannelPixel<T, N> {
+    type Output = MultiChannelPixel<U, N>;
+
+    fn map_channels(self, f: impl FnMut(T) -> U) -> MultiChannelPixel<U, N> {
+        MultiChannelPixel(self.0.map(f))
+    }
+}
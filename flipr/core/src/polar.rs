@@ -0,0 +1,186 @@
+use core::f64::consts::TAU;
+
+use space::Place;
+
+use crate::Image;
+
+/// How [`Image::to_polar`] and [`Image::from_polar`] lay out the distance
+/// `r` from the center and the angle `θ` around it.
+///
+/// The angle runs along y, clockwise on screen from the positive x axis, with
+/// `turn` places making one full turn; `360.0` gives one row per degree
+/// when sampled on a unit grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PolarScale {
+    /// x is `r` itself.
+    Linear { turn: f64 },
+    /// x is `turn / 2π · ln r`, which keeps small shapes square and turns
+    /// scaling about the center into a shift along x, just as rotation is a
+    /// shift along y.
+    Log { turn: f64 },
+}
+
+impl PolarScale {
+    fn turn(self) -> f64 {
+        match self {
+            Self::Linear { turn } | Self::Log { turn } => turn,
+        }
+    }
+
+    /// Distance from the center at polar x.
+    fn radius(self, x: f64) -> f64 {
+        match self {
+            Self::Linear { .. } => x,
+            // Far beyond any image, but still finite.
+            Self::Log { turn } => libm::exp((x * TAU / turn).min(700.0)),
+        }
+    }
+
+    /// Polar x of the distance `r` from the center.
+    fn x(self, r: f64) -> f64 {
+        match self {
+            Self::Linear { .. } => r,
+            Self::Log { turn } => turn / TAU * libm::log(r.max(f64::MIN_POSITIVE)),
+        }
+    }
+}
+
+fn coords(p: &Place) -> (f64, f64) {
+    (p.x().to_f64().unwrap_or(0.0), p.y().to_f64().unwrap_or(0.0))
+}
+
+/// Image unrolled around a center, see [`Image::to_polar`].
+#[derive(Debug, Clone)]
+pub struct ToPolar<I> {
+    image: I,
+    center: (f64, f64),
+    scale: PolarScale,
+}
+
+impl<I> ToPolar<I> {
+    pub(crate) fn new(image: I, center: Place, scale: PolarScale) -> Self {
+        Self {
+            image,
+            center: coords(&center),
+            scale,
+        }
+    }
+}
+
+impl<I: Image> Image for ToPolar<I> {
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (x, y) = coords(&p);
+        let r = self.scale.radius(x);
+        let (sin, cos) = libm::sincos(y * TAU / self.scale.turn());
+        let source = Place::new(self.center.0 + r * cos, self.center.1 + r * sin)
+            .expect("polar places map to finite places");
+        self.image.get(source)
+    }
+}
+
+/// Polar image rolled back up around a center, see [`Image::from_polar`].
+#[derive(Debug, Clone)]
+pub struct FromPolar<I> {
+    image: I,
+    center: (f64, f64),
+    scale: PolarScale,
+}
+
+impl<I> FromPolar<I> {
+    pub(crate) fn new(image: I, center: Place, scale: PolarScale) -> Self {
+        Self {
+            image,
+            center: coords(&center),
+            scale,
+        }
+    }
+}
+
+impl<I: Image> Image for FromPolar<I> {
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (x, y) = coords(&p);
+        let (dx, dy) = (x - self.center.0, y - self.center.1);
+        let angle = (libm::atan2(dy, dx) + TAU) % TAU;
+        let source = Place::new(
+            self.scale.x(libm::hypot(dx, dy)),
+            angle / TAU * self.scale.turn(),
+        )
+        .expect("finite places have finite polar coordinates");
+        self.image.get(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::{LN_2, TAU};
+
+    use proptest::{prop_assert, proptest};
+    use space::Place;
+
+    use super::PolarScale;
+    use crate::{AffineTransform, Gray, Image, from_fn};
+
+    fn at(x: f64, y: f64) -> Place {
+        Place::new(x, y).unwrap()
+    }
+
+    fn plane() -> impl Image<Pixel = Gray<f64>> + Clone {
+        from_fn(|p: Place| Gray(p.x().to_f64().unwrap() * 3.0 - p.y().to_f64().unwrap()))
+    }
+
+    #[test]
+    fn rings_unroll_into_columns() {
+        let rings = from_fn(|p: Place| {
+            let (x, y) = (p.x().to_f64().unwrap() - 4.0, p.y().to_f64().unwrap() - 2.0);
+            Gray(libm::round(libm::hypot(x, y)))
+        });
+        let polar = rings.to_polar(at(4.0, 2.0), PolarScale::Linear { turn: 360.0 });
+        for degrees in [0.0, 45.0, 170.0, 359.0] {
+            assert_eq!(polar.get(at(7.0, degrees)), Gray(7.0));
+        }
+    }
+
+    #[test]
+    fn angles_run_clockwise_on_screen() {
+        let polar = plane().to_polar(at(0.0, 0.0), PolarScale::Linear { turn: 4.0 });
+        assert_eq!(polar.get(at(2.0, 0.0)), Gray(6.0));
+        let Gray(down) = polar.get(at(2.0, 1.0));
+        assert!((down + 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn log_polar_turns_scaling_into_a_shift() {
+        let scale = PolarScale::Log { turn: 100.0 };
+        let origin = at(0.0, 0.0);
+        let polar = plane().to_polar(origin.clone(), scale);
+        let doubled = plane()
+            .transform(AffineTransform::scaling(2.0, 2.0))
+            .to_polar(origin, scale);
+
+        let shift = 100.0 / TAU * LN_2;
+        for (x, y) in [(10.0, 0.0), (30.0, 40.0), (-20.0, 77.0)] {
+            let Gray(a) = polar.get(at(x, y));
+            let Gray(b) = doubled.get(at(x + shift, y));
+            assert!((a - b).abs() < 1e-6, "{a} vs {b}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn from_polar_undoes_to_polar(x in -50.0..50.0f64, y in -50.0..50.0f64, log: bool) {
+            let scale = if log {
+                PolarScale::Log { turn: 90.0 }
+            } else {
+                PolarScale::Linear { turn: 90.0 }
+            };
+            let center = at(3.0, 4.0);
+            let round_trip = plane().to_polar(center.clone(), scale).from_polar(center, scale);
+            let (Gray(a), Gray(b)) = (round_trip.get(at(x, y)), plane().get(at(x, y)));
+            prop_assert!((a - b).abs() < 1e-6, "{} vs {}", a, b);
+        }
+    }
+}
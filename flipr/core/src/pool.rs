@@ -0,0 +1,137 @@
+use alloc::vec::Vec;
+use core::mem::size_of;
+
+use crate::Image;
+use crate::buffer::{ImageBuffer, cell_center};
+use crate::pixel::Pixel;
+
+/// Recycles the storage of interleaved [`ImageBuffer`]s, so pipelines that
+/// materialize many intermediates of similar size stop allocating once warm.
+///
+/// Idle storage is kept only while its total size stays within the budget;
+/// anything beyond that is freed when recycled.
+#[derive(Debug, Clone)]
+pub struct BufferPool<P> {
+    idle: Vec<Vec<P>>,
+    budget: usize,
+}
+
+impl<P: Pixel> BufferPool<P> {
+    /// Pool keeping at most `budget` bytes of idle pixel storage.
+    pub fn new(budget: usize) -> Self {
+        Self {
+            idle: Vec::new(),
+            budget,
+        }
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Bytes of pixel storage waiting to be reused.
+    pub fn idle_bytes(&self) -> usize {
+        self.idle
+            .iter()
+            .map(|v| v.capacity() * size_of::<P>())
+            .sum()
+    }
+
+    /// Like [`ImageBuffer::sample`] into an interleaved buffer, reusing the
+    /// smallest idle storage that is large enough.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is zero.
+    pub fn sample<I>(&mut self, image: &I, width: usize, height: usize) -> ImageBuffer<P>
+    where
+        I: Image<Pixel = P>,
+    {
+        assert!(
+            width > 0 && height > 0,
+            "ImageBuffer must have at least one pixel"
+        );
+
+        let len = width * height;
+        let mut pixels = self
+            .idle
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.capacity() >= len)
+            .min_by_key(|(_, v)| v.capacity())
+            .map(|(index, _)| index)
+            .map_or_else(
+                || Vec::with_capacity(len),
+                |index| self.idle.swap_remove(index),
+            );
+
+        pixels.extend(
+            (0..height).flat_map(|j| (0..width).map(move |i| image.get(cell_center(i, j)))),
+        );
+        ImageBuffer::from_interleaved(width, height, pixels)
+    }
+
+    /// Returns the storage of `buffer` to the pool if it is interleaved and
+    /// fits the budget, and frees it otherwise.
+    pub fn recycle(&mut self, buffer: ImageBuffer<P>) {
+        let Some(mut pixels) = buffer.into_interleaved() else {
+            return;
+        };
+
+        if self.idle_bytes() + pixels.capacity() * size_of::<P>() <= self.budget {
+            pixels.clear();
+            self.idle.push(pixels);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::size_of;
+
+    use space::Place;
+
+    use super::BufferPool;
+    use crate::{Gray, Image, ImageBuffer, Layout, from_fn};
+
+    fn ramp() -> impl Image<Pixel = Gray<u8>> {
+        from_fn(|p: Place| Gray((p.x().to_f64().unwrap() * 10.0) as u8))
+    }
+
+    #[test]
+    fn pooled_samples_match_fresh_ones() {
+        let mut pool = BufferPool::new(1 << 20);
+        let first = pool.sample(&ramp(), 8, 4);
+        pool.recycle(first);
+
+        let second = pool.sample(&ramp(), 5, 5);
+        assert_eq!(pool.idle_bytes(), 0);
+        assert_eq!(
+            second,
+            ImageBuffer::sample(&ramp(), 5, 5, Layout::Interleaved)
+        );
+    }
+
+    #[test]
+    fn storage_is_reused_without_allocating() {
+        let mut pool = BufferPool::new(1 << 20);
+        let buffer = pool.sample(&ramp(), 16, 16);
+        let address = buffer.as_interleaved().unwrap().as_ptr();
+        pool.recycle(buffer);
+
+        let reused = pool.sample(&ramp(), 4, 16);
+        assert_eq!(reused.as_interleaved().unwrap().as_ptr(), address);
+    }
+
+    #[test]
+    fn budget_limits_idle_storage() {
+        let fresh = || BufferPool::new(0).sample(&ramp(), 10, 8);
+        let mut pool = BufferPool::new(100 * size_of::<Gray<u8>>());
+        pool.recycle(fresh());
+        pool.recycle(fresh());
+        assert_eq!(pool.idle_bytes(), 80 * size_of::<Gray<u8>>());
+
+        pool.recycle(ImageBuffer::sample(&ramp(), 2, 2, Layout::Planar));
+        assert_eq!(pool.idle_bytes(), 80 * size_of::<Gray<u8>>());
+    }
+}
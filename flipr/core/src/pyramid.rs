@@ -0,0 +1,341 @@
+use alloc::vec::Vec;
+
+use crate::Image;
+use crate::arithmetic::Channel;
+use crate::buffer::{ImageBuffer, Layout};
+use crate::pixel::Pixel;
+
+/// Low-pass filter applied before every halving step of a [`GaussianPyramid`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DownsampleFilter {
+    /// Averages each 2×2 block.
+    Box,
+    /// Separable `[1, 3, 3, 1] / 8` binomial taps, smoother than [`Box`](Self::Box)
+    /// and the usual choice for blending.
+    #[default]
+    Binomial,
+}
+
+impl DownsampleFilter {
+    /// Taps as `(offset from 2i, weight)` pairs.
+    fn taps(self) -> &'static [(isize, f64)] {
+        match self {
+            DownsampleFilter::Box => &[(0, 0.5), (1, 0.5)],
+            DownsampleFilter::Binomial => &[(-1, 0.125), (0, 0.375), (1, 0.375), (2, 0.125)],
+        }
+    }
+}
+
+/// Sums `(pixel, weight)` pairs channel by channel.
+fn weighted<P>(terms: impl Iterator<Item = (P, f64)> + Clone) -> P
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    P::from_channels(|c| {
+        let sum = terms.clone().map(|(p, w)| p.channel(c).to_f64() * w).sum();
+        Channel::from_f64(sum)
+    })
+}
+
+fn clamp(n: isize, len: usize) -> usize {
+    n.clamp(0, len as isize - 1) as usize
+}
+
+fn pixel<P: Pixel>(buffer: &ImageBuffer<P>, i: usize, j: usize) -> P {
+    buffer.pixel(i, j).expect("indices are in bounds")
+}
+
+fn downsample<P>(fine: &ImageBuffer<P>, filter: DownsampleFilter) -> ImageBuffer<P>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    let (width, height) = (fine.width(), fine.height());
+    let taps = filter.taps();
+
+    ImageBuffer::from_fn(
+        width.div_ceil(2),
+        height.div_ceil(2),
+        fine.layout(),
+        |i, j| {
+            let terms = taps.iter().flat_map(|&(dj, wj)| {
+                taps.iter().map(move |&(di, wi)| {
+                    let x = clamp(2 * i as isize + di, width);
+                    let y = clamp(2 * j as isize + dj, height);
+                    (pixel(fine, x, y), wi * wj)
+                })
+            });
+            weighted(terms)
+        },
+    )
+}
+
+/// Bilinearly resamples `coarse` onto a `width × height` grid covering the same area.
+fn upsample<P>(coarse: &ImageBuffer<P>, width: usize, height: usize) -> ImageBuffer<P>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    let axis = |n: usize, fine_len: usize, coarse_len: usize| {
+        let u = ((n as f64 + 0.5) * coarse_len as f64 / fine_len as f64 - 0.5).max(0.0);
+        let lo = (u as usize).min(coarse_len - 1);
+        let hi = (lo + 1).min(coarse_len - 1);
+        (lo, hi, u - lo as f64)
+    };
+
+    ImageBuffer::from_fn(width, height, coarse.layout(), |i, j| {
+        let (x0, x1, tx) = axis(i, width, coarse.width());
+        let (y0, y1, ty) = axis(j, height, coarse.height());
+        let terms = [
+            (pixel(coarse, x0, y0), (1.0 - tx) * (1.0 - ty)),
+            (pixel(coarse, x1, y0), tx * (1.0 - ty)),
+            (pixel(coarse, x0, y1), (1.0 - tx) * ty),
+            (pixel(coarse, x1, y1), tx * ty),
+        ];
+        weighted(terms.into_iter())
+    })
+}
+
+/// Successively blurred and halved copies of an image, finest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianPyramid<P: Pixel> {
+    levels: Vec<ImageBuffer<P>>,
+}
+
+impl<P> GaussianPyramid<P>
+where
+    P: Pixel,
+    P::Scalar: Channel,
+{
+    /// Samples `image` on a `width × height` grid and halves it until `levels`
+    /// levels exist or the coarsest level is a single pixel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `levels`, `width` or `height` is zero.
+    pub fn new<I>(
+        image: &I,
+        width: usize,
+        height: usize,
+        levels: usize,
+        filter: DownsampleFilter,
+    ) -> Self
+    where
+        I: Image<Pixel = P>,
+    {
+        assert!(levels > 0, "a pyramid needs at least one level");
+
+        let mut pyramid = Vec::with_capacity(levels);
+        pyramid.push(ImageBuffer::sample(
+            image,
+            width,
+            height,
+            Layout::Interleaved,
+        ));
+
+        while let Some(last) = pyramid.last().filter(|_| pyramid.len() < levels) {
+            if last.width() == 1 && last.height() == 1 {
+                break;
+            }
+            pyramid.push(downsample(last, filter));
+        }
+
+        Self { levels: pyramid }
+    }
+
+    pub fn levels(&self) -> &[ImageBuffer<P>] {
+        &self.levels
+    }
+
+    pub fn level(&self, k: usize) -> Option<&ImageBuffer<P>> {
+        self.levels.get(k)
+    }
+}
+
+/// Band-pass decomposition of an image: every level holds the detail lost
+/// between two [`GaussianPyramid`] levels, and the last one the coarsest
+/// Gaussian level itself.
+///
+/// Differences are signed, so the pixel scalar is `f32`; convert integer images
+/// with [`Image::to_float`] first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaplacianPyramid<P: Pixel> {
+    levels: Vec<ImageBuffer<P>>,
+}
+
+impl<P> LaplacianPyramid<P>
+where
+    P: Pixel<Scalar = f32>,
+{
+    pub fn new<I>(
+        image: &I,
+        width: usize,
+        height: usize,
+        levels: usize,
+        filter: DownsampleFilter,
+    ) -> Self
+    where
+        I: Image<Pixel = P>,
+    {
+        Self::from_gaussian(&GaussianPyramid::new(image, width, height, levels, filter))
+    }
+
+    pub fn from_gaussian(gaussian: &GaussianPyramid<P>) -> Self {
+        let mut levels: Vec<_> = gaussian
+            .levels
+            .windows(2)
+            .map(|pair| {
+                let (fine, coarse) = (&pair[0], &pair[1]);
+                let predicted = upsample(coarse, fine.width(), fine.height());
+                ImageBuffer::from_fn(fine.width(), fine.height(), fine.layout(), |i, j| {
+                    pixel(fine, i, j).zip_map(pixel(&predicted, i, j), |a, b| a - b)
+                })
+            })
+            .collect();
+        levels.extend(gaussian.levels.last().cloned());
+
+        Self { levels }
+    }
+
+    /// Pyramid of the given band-pass levels, finest first, for example
+    /// the level-wise blend of several pyramids.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `levels` is empty or a level is not half the size of the
+    /// previous one, rounded up.
+    pub fn from_levels(levels: Vec<ImageBuffer<P>>) -> Self {
+        assert!(!levels.is_empty(), "a pyramid needs at least one level");
+        for pair in levels.windows(2) {
+            let (fine, coarse) = (&pair[0], &pair[1]);
+            assert!(
+                coarse.width() == fine.width().div_ceil(2)
+                    && coarse.height() == fine.height().div_ceil(2),
+                "pyramid level of {}×{} cannot follow one of {}×{}",
+                coarse.width(),
+                coarse.height(),
+                fine.width(),
+                fine.height()
+            );
+        }
+
+        Self { levels }
+    }
+
+    pub fn levels(&self) -> &[ImageBuffer<P>] {
+        &self.levels
+    }
+
+    pub fn level(&self, k: usize) -> Option<&ImageBuffer<P>> {
+        self.levels.get(k)
+    }
+
+    /// Collapses the pyramid back into a full-resolution image.
+    pub fn reconstruct(&self) -> ImageBuffer<P> {
+        let (coarsest, details) = self
+            .levels
+            .split_last()
+            .expect("a pyramid has at least one level");
+
+        details
+            .iter()
+            .rev()
+            .fold(coarsest.clone(), |coarse, detail| {
+                let predicted = upsample(&coarse, detail.width(), detail.height());
+                ImageBuffer::from_fn(detail.width(), detail.height(), detail.layout(), |i, j| {
+                    pixel(detail, i, j).zip_map(pixel(&predicted, i, j), |a, b| a + b)
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use space::Place;
+
+    use super::{DownsampleFilter, GaussianPyramid, LaplacianPyramid};
+    use crate::{Gray, Image, Rgb, from_fn};
+
+    fn checker() -> impl Image<Pixel = Rgb<f32>> {
+        from_fn(|p: Place| {
+            let (x, y) = (p.x().to_f64().unwrap(), p.y().to_f64().unwrap());
+            let on = ((x as i64 + y as i64) % 2 == 0) as u8 as f32;
+            Rgb::new(on, x as f32 / 10.0, y as f32 * y as f32)
+        })
+    }
+
+    #[test]
+    fn levels_halve_until_requested_count() {
+        let image = from_fn(|_| Gray(7u8));
+        let pyramid = GaussianPyramid::new(&image, 10, 5, 3, DownsampleFilter::Binomial);
+        let sizes: Vec<_> = pyramid
+            .levels()
+            .iter()
+            .map(|l| (l.width(), l.height()))
+            .collect();
+        assert_eq!(sizes, [(10, 5), (5, 3), (3, 2)]);
+    }
+
+    #[test]
+    fn levels_stop_at_a_single_pixel() {
+        let image = from_fn(|_| Gray(7u8));
+        let pyramid = GaussianPyramid::new(&image, 4, 3, 10, DownsampleFilter::Box);
+        assert_eq!(pyramid.levels().len(), 3);
+        assert_eq!(pyramid.level(2).map(|l| l.pixel(0, 0)), Some(Some(Gray(7))));
+    }
+
+    #[test]
+    fn box_filter_averages_blocks() {
+        let image = from_fn(|p: Place| Gray(p.x().to_f64().unwrap() as u8 * 10));
+        let pyramid = GaussianPyramid::new(&image, 4, 2, 2, DownsampleFilter::Box);
+        let coarse = pyramid.level(1).unwrap();
+        assert_eq!(coarse.pixel(0, 0), Some(Gray(5)));
+        assert_eq!(coarse.pixel(1, 0), Some(Gray(25)));
+    }
+
+    #[test]
+    fn laplacian_reconstructs_its_source() {
+        for filter in [DownsampleFilter::Box, DownsampleFilter::Binomial] {
+            let laplacian = LaplacianPyramid::new(&checker(), 13, 7, 4, filter);
+            let source = GaussianPyramid::new(&checker(), 13, 7, 1, filter);
+            let source = source.level(0).unwrap();
+            let rebuilt = laplacian.reconstruct();
+
+            for j in 0..7 {
+                for i in 0..13 {
+                    let (a, b) = (rebuilt.pixel(i, j).unwrap(), source.pixel(i, j).unwrap());
+                    assert!(
+                        (a.r - b.r).abs() < 1e-5
+                            && (a.g - b.g).abs() < 1e-5
+                            && (a.b - b.b).abs() < 1e-4
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pyramids_rebuild_from_their_levels() {
+        let laplacian = LaplacianPyramid::new(&checker(), 9, 6, 3, DownsampleFilter::Binomial);
+        let rebuilt = LaplacianPyramid::from_levels(laplacian.levels().to_vec());
+        assert_eq!(rebuilt, laplacian);
+
+        let mut levels = laplacian.levels().to_vec();
+        levels.swap(0, 1);
+        assert!(std::panic::catch_unwind(|| LaplacianPyramid::from_levels(levels)).is_err());
+    }
+
+    #[test]
+    fn laplacian_of_flat_image_has_no_detail() {
+        let image = from_fn(|_| Gray(0.5f32));
+        let laplacian = LaplacianPyramid::new(&image, 8, 8, 3, DownsampleFilter::Binomial);
+        let (coarsest, details) = laplacian.levels().split_last().unwrap();
+        assert!(
+            details
+                .iter()
+                .all(|l| l.as_interleaved().unwrap().iter().all(|&p| p == Gray(0.0)))
+        );
+        assert_eq!(coarsest.pixel(0, 0), Some(Gray(0.5)));
+    }
+}
@@ -0,0 +1,273 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::buffer::{ImageBuffer, Layout};
+use crate::pixel::Pixel;
+
+/// Order of the channels of one pixel in an interleaved byte buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelOrder {
+    Gray,
+    Rgb,
+    Bgr,
+    Rgba,
+    Bgra,
+}
+
+impl ChannelOrder {
+    /// For every byte of a pixel, the [`Pixel::channel`] index it holds.
+    fn channels(self) -> &'static [usize] {
+        match self {
+            ChannelOrder::Gray => &[0],
+            ChannelOrder::Rgb => &[0, 1, 2],
+            ChannelOrder::Bgr => &[2, 1, 0],
+            ChannelOrder::Rgba => &[0, 1, 2, 3],
+            ChannelOrder::Bgra => &[2, 1, 0, 3],
+        }
+    }
+
+    pub fn bytes_per_pixel(self) -> usize {
+        self.channels().len()
+    }
+}
+
+/// Reason a raw byte buffer cannot be converted, see [`ImageBuffer::from_raw`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawError {
+    /// Width or height is zero.
+    Empty,
+    /// The channel order does not have one byte per channel of the pixel type.
+    ChannelMismatch { pixel: usize, order: usize },
+    /// Rows would overlap.
+    StrideTooSmall { stride: usize, row: usize },
+    /// The buffer ends before the last pixel.
+    TooShort { len: usize, needed: usize },
+    /// The buffer would be longer than the address space.
+    TooLarge,
+}
+
+impl fmt::Display for RawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawError::Empty => write!(f, "image must have at least one pixel"),
+            RawError::ChannelMismatch { pixel, order } => {
+                write!(f, "pixel has {pixel} channels but the order has {order}")
+            }
+            RawError::StrideTooSmall { stride, row } => {
+                write!(
+                    f,
+                    "stride of {stride} bytes is shorter than a {row} byte row"
+                )
+            }
+            RawError::TooShort { len, needed } => {
+                write!(
+                    f,
+                    "buffer of {len} bytes is shorter than the {needed} needed"
+                )
+            }
+            RawError::TooLarge => write!(f, "buffer size overflows the address space"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RawError {}
+
+/// Checks the geometry of a raw buffer and returns its length in bytes.
+fn raw_length<P: Pixel>(
+    width: usize,
+    height: usize,
+    order: ChannelOrder,
+    stride: usize,
+) -> Result<usize, RawError> {
+    if width == 0 || height == 0 {
+        return Err(RawError::Empty);
+    }
+    if P::CHANNELS != order.bytes_per_pixel() {
+        return Err(RawError::ChannelMismatch {
+            pixel: P::CHANNELS,
+            order: order.bytes_per_pixel(),
+        });
+    }
+
+    let row = width
+        .checked_mul(order.bytes_per_pixel())
+        .ok_or(RawError::TooLarge)?;
+    if stride < row {
+        return Err(RawError::StrideTooSmall { stride, row });
+    }
+    stride
+        .checked_mul(height - 1)
+        .and_then(|rows| rows.checked_add(row))
+        .ok_or(RawError::TooLarge)
+}
+
+impl<P: Pixel<Scalar = u8>> ImageBuffer<P> {
+    /// Copies pixels out of an interleaved byte buffer whose rows start every
+    /// `stride` bytes, as handed over by camera SDKs and GUI toolkits.
+    ///
+    /// Padding after each row is ignored and the last row need not be padded.
+    pub fn from_raw(
+        bytes: &[u8],
+        width: usize,
+        height: usize,
+        stride: usize,
+        order: ChannelOrder,
+        layout: Layout,
+    ) -> Result<Self, RawError> {
+        let needed = raw_length::<P>(width, height, order, stride)?;
+        if bytes.len() < needed {
+            return Err(RawError::TooShort {
+                len: bytes.len(),
+                needed,
+            });
+        }
+
+        let channels = order.channels();
+        Ok(Self::from_fn(width, height, layout, |i, j| {
+            let start = j * stride + i * channels.len();
+            let pixel = &bytes[start..start + channels.len()];
+            P::from_channels(|c| {
+                pixel[channels
+                    .iter()
+                    .position(|&k| k == c)
+                    .expect("order covers every channel")]
+            })
+        }))
+    }
+
+    /// Writes the pixels into a new interleaved byte buffer with rows every
+    /// `stride` bytes, zero-filling the padding.
+    pub fn into_raw(self, order: ChannelOrder, stride: usize) -> Result<Vec<u8>, RawError> {
+        self.write_raw(order, stride)
+    }
+
+    /// Tightly packed interleaved bytes in `order`, see [`ImageBuffer::into_raw`].
+    pub fn as_bytes(&self, order: ChannelOrder) -> Result<Vec<u8>, RawError> {
+        self.write_raw(order, self.width() * order.bytes_per_pixel())
+    }
+
+    fn write_raw(&self, order: ChannelOrder, stride: usize) -> Result<Vec<u8>, RawError> {
+        let len = raw_length::<P>(self.width(), self.height(), order, stride)?;
+        let mut bytes = vec![0; len];
+
+        for j in 0..self.height() {
+            for i in 0..self.width() {
+                let pixel = self.pixel(i, j).expect("indices are in bounds");
+                let start = j * stride + i * order.bytes_per_pixel();
+                for (byte, &c) in bytes[start..].iter_mut().zip(order.channels()) {
+                    *byte = pixel.channel(c);
+                }
+            }
+        }
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use proptest::collection::vec as bytes;
+    use proptest::{prop_assert_eq, proptest};
+
+    use super::{ChannelOrder, RawError};
+    use crate::buffer::{ImageBuffer, Layout};
+    use crate::{Gray, Rgb, Rgba};
+
+    #[test]
+    fn bgr_with_padding_is_reordered() {
+        let bytes = [3, 2, 1, 6, 5, 4, 0xee, 9, 8, 7, 12, 11, 10];
+        let image =
+            ImageBuffer::<Rgb<u8>>::from_raw(&bytes, 2, 2, 7, ChannelOrder::Bgr, Layout::Planar)
+                .unwrap();
+        assert_eq!(image.pixel(1, 0), Some(Rgb::new(4, 5, 6)));
+        assert_eq!(image.pixel(0, 1), Some(Rgb::new(7, 8, 9)));
+        assert_eq!(image.layout(), Layout::Planar);
+    }
+
+    #[test]
+    fn invalid_geometry_is_reported() {
+        let from_raw = |len, stride, order| {
+            ImageBuffer::<Rgb<u8>>::from_raw(
+                &vec![0; len],
+                2,
+                2,
+                stride,
+                order,
+                Layout::Interleaved,
+            )
+        };
+        assert_eq!(
+            from_raw(12, 6, ChannelOrder::Rgba),
+            Err(RawError::ChannelMismatch { pixel: 3, order: 4 })
+        );
+        assert_eq!(
+            from_raw(12, 5, ChannelOrder::Rgb),
+            Err(RawError::StrideTooSmall { stride: 5, row: 6 })
+        );
+        assert_eq!(
+            from_raw(13, 8, ChannelOrder::Rgb),
+            Err(RawError::TooShort {
+                len: 13,
+                needed: 14
+            })
+        );
+        assert_eq!(
+            ImageBuffer::<Gray<u8>>::from_raw(
+                &[],
+                0,
+                1,
+                0,
+                ChannelOrder::Gray,
+                Layout::Interleaved
+            ),
+            Err(RawError::Empty)
+        );
+    }
+
+    #[test]
+    fn overflowing_geometry_is_reported() {
+        let from_raw = |width, height, stride| {
+            ImageBuffer::<Rgb<u8>>::from_raw(
+                &[0; 12],
+                width,
+                height,
+                stride,
+                ChannelOrder::Rgb,
+                Layout::Interleaved,
+            )
+        };
+        assert_eq!(from_raw(usize::MAX, 1, usize::MAX), Err(RawError::TooLarge));
+        assert_eq!(from_raw(1, usize::MAX, 3), Err(RawError::TooLarge));
+        assert_eq!(from_raw(1, 2, usize::MAX), Err(RawError::TooLarge));
+        assert_eq!(from_raw(1, 3, usize::MAX / 2), Err(RawError::TooLarge));
+
+        let image = ImageBuffer::filled(1, 2, Layout::Interleaved, Gray(0u8));
+        assert_eq!(
+            image.into_raw(ChannelOrder::Gray, usize::MAX),
+            Err(RawError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn export_zero_fills_padding() {
+        let image = ImageBuffer::filled(1, 2, Layout::Interleaved, Rgba::new(1, 2, 3, 4));
+        assert_eq!(
+            image.into_raw(ChannelOrder::Bgra, 6),
+            Ok(vec![3, 2, 1, 4, 0, 0, 3, 2, 1, 4])
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn bytes_round_trip(data in bytes(0..=255u8, 3 * 4 * 5)) {
+            let image =
+                ImageBuffer::<Rgb<u8>>::from_raw(&data, 4, 5, 12, ChannelOrder::Bgr, Layout::Interleaved)
+                    .unwrap();
+            prop_assert_eq!(image.as_bytes(ChannelOrder::Bgr), Ok(data));
+        }
+    }
+}
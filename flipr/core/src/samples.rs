@@ -0,0 +1,124 @@
+use core::iter::FusedIterator;
+
+use crate::Image;
+use crate::buffer::cell_center;
+
+/// Iterator over the pixel-cell centers of a `width × height` grid in
+/// row-major order, created by [`Image::samples`].
+#[derive(Debug, Clone)]
+pub struct Samples<'a, I> {
+    image: &'a I,
+    width: usize,
+    height: usize,
+    next: usize,
+}
+
+impl<'a, I> Samples<'a, I> {
+    pub(crate) fn new(image: &'a I, width: usize, height: usize) -> Self {
+        Self {
+            image,
+            width,
+            height,
+            next: 0,
+        }
+    }
+
+    fn total(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+impl<I: Image> Iterator for Samples<'_, I> {
+    type Item = I::Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.total() {
+            return None;
+        }
+
+        let (i, j) = (self.next % self.width, self.next / self.width);
+        self.next += 1;
+
+        Some(self.image.get(cell_center(i, j)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.total() - self.next;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.next = self.next.saturating_add(n).min(self.total());
+        self.next()
+    }
+}
+
+impl<I: Image> ExactSizeIterator for Samples<'_, I> {}
+
+impl<I: Image> FusedIterator for Samples<'_, I> {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+
+    use space::Place;
+
+    use crate::{Gray, Image, from_fn};
+
+    fn coordinates() -> impl Image<Pixel = (f64, f64)> {
+        from_fn(|p: Place| (p.x().to_f64().unwrap(), p.y().to_f64().unwrap()))
+    }
+
+    #[test]
+    fn samples_visit_cell_centers_row_major() {
+        let samples: Vec<_> = coordinates().samples(2, 2).collect();
+        assert_eq!(samples, [(0.5, 0.5), (1.5, 0.5), (0.5, 1.5), (1.5, 1.5)]);
+        assert_eq!(coordinates().samples(0, 4).count(), 0);
+    }
+
+    #[test]
+    fn fold_accumulates_in_row_major_order() {
+        let image = from_fn(|p: Place| {
+            Gray(p.x().to_f64().unwrap() as u32 + 10 * p.y().to_f64().unwrap() as u32)
+        });
+        assert_eq!(image.fold(3, 2, 0, |sum, p| sum + p.0), 36);
+        let order = image.fold(3, 2, Vec::new(), |mut seen, p| {
+            seen.push(p.0);
+            seen
+        });
+        assert_eq!(order, [0, 1, 2, 10, 11, 12]);
+    }
+
+    #[test]
+    fn try_fold_stops_at_the_first_error() {
+        let image = from_fn(|p: Place| Gray(p.x().to_f64().unwrap() as u8));
+        let mut visited = 0;
+        let result = image.try_fold(4, 4, 0u8, |sum, p| {
+            visited += 1;
+            sum.checked_add(p.0 * 100).ok_or(visited)
+        });
+        assert_eq!(result, Err(3));
+        assert_eq!(
+            image.try_fold(2, 1, 0u8, |sum, p| sum.checked_add(p.0).ok_or(())),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn predicates_stop_early() {
+        let visited = Cell::new(0);
+        let image = from_fn(|p: Place| {
+            visited.set(visited.get() + 1);
+            Gray(p.x().to_f64().unwrap() as u8 * 50)
+        });
+
+        assert!(image.any(5, 5, |p| p.0 > 100));
+        assert_eq!(visited.replace(0), 4);
+        assert_eq!(image.position(5, 5, |p| p.0 > 100), Some((3, 0)));
+        assert!(!image.all(5, 5, |p| p.0 < 100));
+        assert_eq!(visited.replace(0), 4 + 3);
+        assert_eq!(image.count(5, 5, |p| p.0 < 100), 10);
+        assert_eq!(image.position(5, 5, |p| p.0 > 250), None);
+    }
+}
@@ -0,0 +1,126 @@
+use space::{Offset, Place, Real};
+
+use crate::Image;
+
+/// Places `right` next to `left`, see [`Image::hstack`].
+#[derive(Debug, Clone)]
+pub struct HStack<A, B> {
+    left: A,
+    right: B,
+    width: Real,
+}
+
+impl<A, B> HStack<A, B> {
+    pub(crate) fn new(left: A, right: B, width: Real) -> Self {
+        Self { left, right, width }
+    }
+}
+
+impl<A, B> Image for HStack<A, B>
+where
+    A: Image,
+    B: Image<Pixel = A::Pixel>,
+{
+    type Pixel = A::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        if *p.x() < self.width {
+            self.left.get(p)
+        } else {
+            self.right
+                .get(p + Offset::from_reals(-&self.width, Real::zero()))
+        }
+    }
+}
+
+/// Places `bottom` below `top`, see [`Image::vstack`].
+#[derive(Debug, Clone)]
+pub struct VStack<A, B> {
+    top: A,
+    bottom: B,
+    height: Real,
+}
+
+impl<A, B> VStack<A, B> {
+    pub(crate) fn new(top: A, bottom: B, height: Real) -> Self {
+        Self {
+            top,
+            bottom,
+            height,
+        }
+    }
+}
+
+impl<A, B> Image for VStack<A, B>
+where
+    A: Image,
+    B: Image<Pixel = A::Pixel>,
+{
+    type Pixel = A::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        if *p.y() < self.height {
+            self.top.get(p)
+        } else {
+            self.bottom
+                .get(p + Offset::from_reals(Real::zero(), -&self.height))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, prop_assume, proptest};
+    use space::{Offset, Place, Real};
+
+    use crate::tests::{place, real};
+    use crate::{Image, from_fn};
+
+    fn coords() -> impl Image<Pixel = (Real, Real)> {
+        from_fn(|p: Place| (p.x().clone(), p.y().clone()))
+    }
+
+    proptest! {
+        #[test]
+        fn hstack_samples_left_before_width(p in place(), width in real()) {
+            prop_assume!(*p.x() < width);
+            let image = coords().hstack(from_fn(|_| unreachable!()), width);
+            prop_assert_eq!(image.get(p.clone()), coords().get(p));
+        }
+
+        #[test]
+        fn hstack_samples_right_shifted_from_width(p in place(), width in real()) {
+            prop_assume!(*p.x() >= width);
+            let image = from_fn(|_| unreachable!()).hstack(coords(), width.clone());
+            let shifted = &p + Offset::from_reals(-width, Real::zero());
+            prop_assert_eq!(image.get(p), coords().get(shifted));
+        }
+
+        #[test]
+        fn hstack_right_starts_at_its_origin(width in real(), y in real()) {
+            let image = from_fn(|_| unreachable!()).hstack(coords(), width.clone());
+            prop_assert_eq!(image.get(Place::from_reals(width, y.clone())), (Real::zero(), y));
+        }
+
+        #[test]
+        fn vstack_samples_top_before_height(p in place(), height in real()) {
+            prop_assume!(*p.y() < height);
+            let image = coords().vstack(from_fn(|_| unreachable!()), height);
+            prop_assert_eq!(image.get(p.clone()), coords().get(p));
+        }
+
+        #[test]
+        fn vstack_samples_bottom_shifted_from_height(p in place(), height in real()) {
+            prop_assume!(*p.y() >= height);
+            let image = from_fn(|_| unreachable!()).vstack(coords(), height.clone());
+            let shifted = &p + Offset::from_reals(Real::zero(), -height);
+            prop_assert_eq!(image.get(p), coords().get(shifted));
+        }
+
+        #[test]
+        fn vstack_bottom_starts_at_its_origin(height in real(), x in real()) {
+            let image = from_fn(|_| unreachable!()).vstack(coords(), height.clone());
+            prop_assert_eq!(image.get(Place::from_reals(x.clone(), height)), (x, Real::zero()));
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use space::{Place, Real};
+
+use crate::Image;
+
+/// Image backed by a fixed-size pixel array stored inline, without heap allocation.
+///
+/// Pixel `(i, j)` covers the unit cell `[i, i + 1) × [j, j + 1)`. Places outside
+/// of the array are clamped to the nearest edge pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticImage<P, const W: usize, const H: usize> {
+    rows: [[P; W]; H],
+}
+
+impl<P, const W: usize, const H: usize> StaticImage<P, W, H> {
+    const NON_EMPTY: () = assert!(W > 0 && H > 0, "StaticImage must have at least one pixel");
+
+    pub fn new(rows: [[P; W]; H]) -> Self {
+        let () = Self::NON_EMPTY;
+        Self { rows }
+    }
+
+    pub fn width(&self) -> usize {
+        W
+    }
+
+    pub fn height(&self) -> usize {
+        H
+    }
+
+    pub fn pixel(&self, i: usize, j: usize) -> Option<&P> {
+        self.rows.get(j)?.get(i)
+    }
+
+    pub fn pixel_mut(&mut self, i: usize, j: usize) -> Option<&mut P> {
+        self.rows.get_mut(j)?.get_mut(i)
+    }
+}
+
+impl<P: Copy, const W: usize, const H: usize> StaticImage<P, W, H> {
+    pub fn filled(pixel: P) -> Self {
+        Self::new([[pixel; W]; H])
+    }
+}
+
+/// Index of the unit cell containing `r`, clamped to `0..len`.
+pub(crate) fn clamped_index(r: &Real, len: usize) -> usize {
+    match r.floor().to_i64() {
+        Some(i) if i < 0 => 0,
+        Some(i) => (i as u64).min(len as u64 - 1) as usize,
+        None if *r < Real::zero() => 0,
+        None => len - 1,
+    }
+}
+
+impl<P: Clone, const W: usize, const H: usize> Image for StaticImage<P, W, H> {
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let i = clamped_index(p.x(), W);
+        let j = clamped_index(p.y(), H);
+
+        self.rows[j][i].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use space::Place;
+
+    use super::StaticImage;
+    use crate::Image;
+
+    fn image() -> StaticImage<u8, 3, 2> {
+        StaticImage::new([[1, 2, 3], [4, 5, 6]])
+    }
+
+    #[test]
+    fn place_inside_pixel_cell_samples_that_pixel() {
+        let place = Place::new(1.5, 1.25).unwrap();
+        assert_eq!(image().get(place), 5);
+    }
+
+    #[test]
+    fn pixel_cell_includes_its_top_left_corner() {
+        let place = Place::new(2.0, 0.0).unwrap();
+        assert_eq!(image().get(place), 3);
+    }
+
+    #[test]
+    fn places_outside_are_clamped_to_edge() {
+        assert_eq!(image().get(Place::new(-4.0, -0.5).unwrap()), 1);
+        assert_eq!(image().get(Place::new(10.0, 0.5).unwrap()), 3);
+        assert_eq!(image().get(Place::new(1e300, 1e300).unwrap()), 6);
+    }
+
+    #[test]
+    fn pixel_mut_writes_are_visible_through_get() {
+        let mut image = image();
+        *image.pixel_mut(0, 1).unwrap() = 9;
+        assert_eq!(image.get(Place::new(0.5, 1.5).unwrap()), 9);
+        assert_eq!(image.pixel(3, 0), None);
+    }
+}
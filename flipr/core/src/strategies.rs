@@ -0,0 +1,142 @@
+//! Proptest strategies for property-testing code built on flipr, enabled by
+//! the `proptest` feature.
+
+use proptest::prelude::{Just, Strategy, any, prop_oneof};
+use space::{Offset, Place, Real};
+
+use crate::affine::AffineTransform;
+use crate::buffer::{ImageBuffer, Layout};
+use crate::homography::Homography;
+use crate::pixel::{Gray, Pixel, Rgb, Rgba};
+
+/// Reals converted from finite `f64` values in `-1e3..1e3`, which keeps exact
+/// arithmetic on them fast.
+pub fn real() -> impl Strategy<Value = Real> {
+    (-1e3..1e3f64).prop_map(|f| Real::from_f64(f).expect("any finite f64 should be a valid Real"))
+}
+
+pub fn place() -> impl Strategy<Value = Place> {
+    (real(), real()).prop_map(|(x, y)| Place::from_reals(x, y))
+}
+
+pub fn offset() -> impl Strategy<Value = Offset> {
+    (real(), real()).prop_map(|(dx, dy)| Offset::from_reals(dx, dy))
+}
+
+/// Invertible affine transforms: a scaling by `0.25..4` per axis, possibly
+/// mirrored, a shear, a rotation and a translation by up to `1e3`, applied in
+/// that order.
+pub fn affine() -> impl Strategy<Value = AffineTransform> {
+    let scale = (0.25..4.0f64, any::<bool>()).prop_map(|(s, flip)| if flip { -s } else { s });
+    (
+        (scale.clone(), scale),
+        -1.0..1.0f64,
+        -core::f64::consts::PI..core::f64::consts::PI,
+        (-1e3..1e3f64, -1e3..1e3f64),
+    )
+        .prop_map(|((sx, sy), shear, angle, (dx, dy))| {
+            AffineTransform::scaling(sx, sy)
+                .then(AffineTransform::new([[1.0, shear, 0.0], [0.0, 1.0, 0.0]]))
+                .then(AffineTransform::rotation(angle))
+                .then(AffineTransform::translation(dx, dy))
+        })
+}
+
+/// Invertible homographies: an [`affine`] transform followed by a
+/// perspective change small enough that places within `1e2` of the origin
+/// stay in front of the camera.
+pub fn homography() -> impl Strategy<Value = Homography> {
+    (affine(), -1e-3..1e-3f64, -1e-3..1e-3f64).prop_map(|(affine, px, py)| {
+        Homography::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [px, py, 1.0]])
+            .then(Homography::from(affine))
+    })
+}
+
+pub fn layout() -> impl Strategy<Value = Layout> {
+    prop_oneof![Just(Layout::Interleaved), Just(Layout::Planar)]
+}
+
+/// Buffers of `1..=max_width` by `1..=max_height` pixels drawn from `pixel`, in
+/// either layout.
+pub fn image_buffer<P, S>(
+    pixel: S,
+    max_width: usize,
+    max_height: usize,
+) -> impl Strategy<Value = ImageBuffer<P>>
+where
+    P: Pixel + core::fmt::Debug,
+    S: Strategy<Value = P> + Clone,
+{
+    (1..=max_width.max(1), 1..=max_height.max(1), layout()).prop_flat_map(
+        move |(width, height, layout)| {
+            proptest::collection::vec(pixel.clone(), width * height).prop_map(move |pixels| {
+                ImageBuffer::from_fn(width, height, layout, |i, j| pixels[j * width + i])
+            })
+        },
+    )
+}
+
+pub fn gray_u8_image(
+    max_width: usize,
+    max_height: usize,
+) -> impl Strategy<Value = ImageBuffer<Gray<u8>>> {
+    image_buffer(any::<u8>().prop_map(Gray), max_width, max_height)
+}
+
+pub fn rgb_u8_image(
+    max_width: usize,
+    max_height: usize,
+) -> impl Strategy<Value = ImageBuffer<Rgb<u8>>> {
+    image_buffer(
+        any::<(u8, u8, u8)>().prop_map(|(r, g, b)| Rgb::new(r, g, b)),
+        max_width,
+        max_height,
+    )
+}
+
+pub fn rgba_u8_image(
+    max_width: usize,
+    max_height: usize,
+) -> impl Strategy<Value = ImageBuffer<Rgba<u8>>> {
+    image_buffer(
+        any::<(u8, u8, u8, u8)>().prop_map(|(r, g, b, a)| Rgba::new(r, g, b, a)),
+        max_width,
+        max_height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+
+    use super::{affine, gray_u8_image, homography, rgb_u8_image};
+    use crate::buffer::Layout;
+
+    proptest! {
+        #[test]
+        fn images_respect_size_bounds(image in rgb_u8_image(6, 3)) {
+            prop_assert!((1..=6).contains(&image.width()));
+            prop_assert!((1..=3).contains(&image.height()));
+        }
+
+        #[test]
+        fn generated_images_survive_layout_changes(image in gray_u8_image(5, 5)) {
+            prop_assert_eq!(image.clone().into_layout(Layout::Planar), image);
+        }
+
+        #[test]
+        fn generated_transforms_can_be_undone(
+            affine in affine(),
+            homography in homography(),
+            (x, y) in (-1e2..1e2f64, -1e2..1e2f64),
+        ) {
+            let (u, v) = affine.inverse().unwrap().transform_point(x, y);
+            let (u, v) = affine.transform_point(u, v);
+            prop_assert!((u - x).abs() < 1e-6 && (v - y).abs() < 1e-6);
+
+            let (u, v) = homography.transform_point(x, y).unwrap();
+            let (u, v) = homography.inverse().unwrap().transform_point(u, v).unwrap();
+            prop_assert!((u - x).abs() < 1e-6 && (v - y).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::buffer::ImageBuffer;
+use crate::color::SrgbChannel;
+use crate::hue::LUMA;
+use crate::pixel::Pixel;
+
+/// Characters of [`ImageBuffer::render_ascii`], darkest first.
+const RAMP: &[u8] = b" .:-=+*#%@";
+
+/// Straight color of `pixel` on the `0.0..=1.0` scale, composited over black:
+/// the first channel as gray for pixels with fewer than three channels, and
+/// the last channel as alpha for pixels with two or four.
+fn color<P>(pixel: P) -> [f32; 3]
+where
+    P: Pixel,
+    P::Scalar: SrgbChannel,
+{
+    let channel = |c: usize| pixel.channel(c).normalized();
+    let alpha = match P::CHANNELS {
+        2 | 4 => channel(P::CHANNELS - 1),
+        _ => 1.0,
+    };
+    let rgb = if P::CHANNELS < 3 {
+        [channel(0); 3]
+    } else {
+        [channel(0), channel(1), channel(2)]
+    };
+    rgb.map(|v| v * alpha)
+}
+
+impl<P> ImageBuffer<P>
+where
+    P: Pixel,
+    P::Scalar: SrgbChannel,
+{
+    /// Averages of the pixels under each cell of a `columns × rows` grid
+    /// laid over the buffer, row by row.
+    fn cells(&self, columns: usize, rows: usize) -> Vec<[f32; 3]> {
+        let span = |n: usize, cells: usize, len: usize| {
+            let start = n * len / cells;
+            start..((n + 1) * len / cells).max(start + 1)
+        };
+
+        let mut cells = Vec::with_capacity(columns * rows);
+        for r in 0..rows {
+            for c in 0..columns {
+                let mut sum = [0.0; 3];
+                let mut count = 0.0;
+                for j in span(r, rows, self.height()) {
+                    for i in span(c, columns, self.width()) {
+                        let rgb = color(self.pixel(i, j).expect("indices are in bounds"));
+                        for (s, v) in sum.iter_mut().zip(rgb) {
+                            *s += v;
+                        }
+                        count += 1.0;
+                    }
+                }
+                cells.push(sum.map(|s| s / count));
+            }
+        }
+        cells
+    }
+
+    /// Rows of a preview `width` cells wide keeping the aspect ratio, for
+    /// cells `aspect` times as tall as wide.
+    fn preview_rows(&self, width: usize, aspect: f64) -> usize {
+        let rows = self.height() as f64 * width as f64 / (self.width() as f64 * aspect);
+        (libm::round(rows) as usize).max(1)
+    }
+
+    /// The buffer as `width` columns of text, one line per row, brighter
+    /// pixels drawn with denser characters.
+    ///
+    /// Terminal characters are about twice as tall as wide, so the preview
+    /// has about half as many rows as columns per the buffer's aspect ratio.
+    /// Every character shows the average of the pixels under it.
+    pub fn render_ascii(&self, width: usize) -> String {
+        let width = width.max(1);
+        let rows = self.preview_rows(width, 2.0);
+        let cells = self.cells(width, rows);
+
+        let mut text = String::with_capacity((width + 1) * rows);
+        for row in cells.chunks(width) {
+            for rgb in row {
+                let luma: f32 = rgb.iter().zip(LUMA).map(|(v, w)| v * w).sum();
+                let index = libm::roundf(luma.clamp(0.0, 1.0) * (RAMP.len() - 1) as f32);
+                text.push(RAMP[index as usize] as char);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// The buffer as `width` columns of ANSI 24-bit color escapes, for
+    /// terminals with truecolor support.
+    ///
+    /// Every character is an upper half block whose foreground shows one
+    /// cell and whose background shows the cell below, so cells are square
+    /// and the preview keeps the buffer's aspect ratio. Each line ends by
+    /// resetting the colors.
+    pub fn render_truecolor(&self, width: usize) -> String {
+        let width = width.max(1);
+        let rows = self.preview_rows(width, 1.0);
+        let cells = self.cells(width, rows);
+        let byte = |v: f32| libm::roundf(v.clamp(0.0, 1.0) * 255.0) as u8;
+
+        let mut text = String::new();
+        let lines: Vec<&[[f32; 3]]> = cells.chunks(width).collect();
+        for pair in lines.chunks(2) {
+            for (c, top) in pair[0].iter().enumerate() {
+                let [r, g, b] = top.map(byte);
+                // Writing to a `String` cannot fail.
+                let _ = write!(text, "\x1b[38;2;{r};{g};{b}m");
+                if let Some(bottom) = pair.get(1) {
+                    let [r, g, b] = bottom[c].map(byte);
+                    let _ = write!(text, "\x1b[48;2;{r};{g};{b}m");
+                }
+                text.push('▀');
+            }
+            text.push_str("\x1b[0m\n");
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Gray, ImageBuffer, Layout, Rgb, Rgba};
+
+    #[test]
+    fn ascii_shows_brightness_at_half_height() {
+        let ramp =
+            ImageBuffer::from_fn(10, 4, Layout::Interleaved, |i, _| Gray((i * 255 / 9) as u8));
+        assert_eq!(ramp.render_ascii(10), " .:-=+*#%@\n .:-=+*#%@\n");
+        assert_eq!(ramp.render_ascii(5), " :=*%\n");
+    }
+
+    #[test]
+    fn ascii_composites_alpha_over_black() {
+        let white =
+            ImageBuffer::filled(2, 2, Layout::Interleaved, Rgba::new(1.0f32, 1.0, 1.0, 1.0));
+        let clear =
+            ImageBuffer::filled(2, 2, Layout::Interleaved, Rgba::new(1.0f32, 1.0, 1.0, 0.0));
+        assert_eq!(white.render_ascii(2), "@@\n");
+        assert_eq!(clear.render_ascii(2), "  \n");
+    }
+
+    #[test]
+    fn truecolor_pairs_rows_into_half_blocks() {
+        let image = ImageBuffer::from_fn(2, 3, Layout::Interleaved, |i, j| {
+            Rgb::new(if i == 0 { 255u8 } else { 0 }, (j * 100) as u8, 7)
+        });
+        assert_eq!(
+            image.render_truecolor(2),
+            "\x1b[38;2;255;0;7m\x1b[48;2;255;100;7m▀\x1b[38;2;0;0;7m\x1b[48;2;0;100;7m▀\x1b[0m\n\
+             \x1b[38;2;255;200;7m▀\x1b[38;2;0;200;7m▀\x1b[0m\n"
+        );
+    }
+}
@@ -0,0 +1,11 @@
+use proptest::prelude::Strategy;
+use space::{Place, Real};
+
+/// Generates Real values from a bounded range of f64, keeping tests fast.
+pub fn real() -> impl Strategy<Value = Real> {
+    (-1e3..1e3f64).prop_map(|f| Real::from_f64(f).expect("any finite f64 should be a valid Real"))
+}
+
+pub fn place() -> impl Strategy<Value = Place> {
+    (real(), real()).prop_map(|(x, y)| Place::from_reals(x, y))
+}
@@ -0,0 +1,179 @@
+use space::{Place, Real};
+
+use crate::Image;
+use crate::buffer::ImageBuffer;
+use crate::pixel::Pixel;
+
+/// How a [`Tiled`] buffer repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Repeat {
+    /// Copies side by side, `times` of them along each axis where given.
+    Wrap {
+        times_x: Option<usize>,
+        times_y: Option<usize>,
+    },
+    /// Copies flipped at every seam, forever.
+    Mirror,
+}
+
+/// [`ImageBuffer`] repeated across the plane, see [`ImageBuffer::tile`] and
+/// [`ImageBuffer::mirror_tile`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tiled<P: Pixel> {
+    buffer: ImageBuffer<P>,
+    repeat: Repeat,
+}
+
+impl<P: Pixel> ImageBuffer<P> {
+    /// Repeats the buffer `times_x` times to the right and `times_y` times
+    /// down from the origin, or forever along an axis given `None`; outside
+    /// the repeated area the edge pixels continue as usual.
+    ///
+    /// Repeating forever gives the buffer a periodic border, so that for
+    /// example [`Image::convolve`] wraps around instead of clamping.
+    pub fn tile(self, times_x: Option<usize>, times_y: Option<usize>) -> Tiled<P> {
+        Tiled {
+            buffer: self,
+            repeat: Repeat::Wrap { times_x, times_y },
+        }
+    }
+
+    /// Repeats the buffer forever, mirrored at every seam so that no edge
+    /// shows: every copy meets its neighbor at equal pixels.
+    pub fn mirror_tile(self) -> Tiled<P> {
+        Tiled {
+            buffer: self,
+            repeat: Repeat::Mirror,
+        }
+    }
+}
+
+impl<P: Pixel> Tiled<P> {
+    pub fn buffer(&self) -> &ImageBuffer<P> {
+        &self.buffer
+    }
+
+    pub fn into_buffer(self) -> ImageBuffer<P> {
+        self.buffer
+    }
+}
+
+/// Index of the unit cell containing `r`, saturating far away.
+fn cell(r: &Real) -> i64 {
+    r.floor().to_i64().unwrap_or(if *r < Real::zero() {
+        i64::MIN
+    } else {
+        i64::MAX
+    })
+}
+
+/// Pixel index along an axis of `len` pixels for cell `n`.
+fn wrap(n: i64, len: usize, times: Option<usize>) -> usize {
+    let len = len as i64;
+    let n = match times {
+        Some(times) => n.clamp(0, (times as i64).saturating_mul(len).max(1) - 1),
+        None => n,
+    };
+    n.rem_euclid(len) as usize
+}
+
+fn mirror(n: i64, len: usize) -> usize {
+    let len = len as i64;
+    let m = n.rem_euclid(2 * len);
+    (if m < len { m } else { 2 * len - 1 - m }) as usize
+}
+
+impl<P: Pixel> Image for Tiled<P> {
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (width, height) = (self.buffer.width(), self.buffer.height());
+        let (x, y) = (cell(p.x()), cell(p.y()));
+        let (i, j) = match self.repeat {
+            Repeat::Wrap { times_x, times_y } => {
+                (wrap(x, width, times_x), wrap(y, height, times_y))
+            }
+            Repeat::Mirror => (mirror(x, width), mirror(y, height)),
+        };
+        self.buffer
+            .pixel(i, j)
+            .expect("tiled indices are in bounds")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use proptest::{prop_assert_eq, proptest};
+    use space::Place;
+
+    use crate::fft::convolve;
+    use crate::tests::place;
+    use crate::{Gray, Image, ImageBuffer, Layout, kernel};
+
+    fn buffer() -> ImageBuffer<Gray<u8>> {
+        ImageBuffer::from_fn(3, 2, Layout::Interleaved, |i, j| Gray((10 * j + i) as u8))
+    }
+
+    fn row(image: &impl Image<Pixel = Gray<u8>>, y: f64, xs: core::ops::Range<i32>) -> Vec<u8> {
+        xs.map(|x| image.get(Place::new(x as f64 + 0.5, y).unwrap()).0)
+            .collect()
+    }
+
+    #[test]
+    fn tiles_repeat_a_given_number_of_times() {
+        let tiled = buffer().tile(Some(2), None);
+        assert_eq!(row(&tiled, 0.5, -2..9), [0, 0, 0, 1, 2, 0, 1, 2, 2, 2, 2]);
+        assert_eq!(row(&tiled, 3.5, 0..3), [10, 11, 12]);
+        assert_eq!(row(&tiled, -0.5, 0..3), [10, 11, 12]);
+    }
+
+    #[test]
+    fn mirrored_tiles_meet_at_equal_pixels() {
+        let tiled = buffer().mirror_tile();
+        assert_eq!(
+            row(&tiled, 0.5, -4..10),
+            [2, 2, 1, 0, 0, 1, 2, 2, 1, 0, 0, 1, 2, 2]
+        );
+        assert_eq!(row(&tiled, 2.5, 0..1), [10]);
+        assert_eq!(row(&tiled, 4.5, 0..1), [0]);
+    }
+
+    #[test]
+    fn periodic_borders_make_convolution_circular() {
+        let image = ImageBuffer::from_fn(5, 4, Layout::Interleaved, |i, j| {
+            Gray(((i * 7 + j * 3) % 5) as f32)
+        });
+        let blur = kernel!([[0, 1, 2], [1, 4, 0], [0, 3, 1]] / 12);
+        let periodic = ImageBuffer::sample(
+            &image.clone().tile(None, None).convolve(blur),
+            5,
+            4,
+            Layout::Interleaved,
+        );
+
+        let spread = ImageBuffer::from_fn(5, 4, Layout::Interleaved, |i, j| {
+            let (di, dj) = (i as isize - 2, j as isize - 2);
+            let weight = if di.abs() <= 1 && dj.abs() <= 1 {
+                blur.weights()[(dj + 1) as usize][(di + 1) as usize]
+            } else {
+                0.0
+            };
+            Gray(weight as f32)
+        });
+        let circular = convolve(&image, &spread);
+        for (Gray(a), Gray(b)) in periodic.pixels().zip(circular.pixels()) {
+            assert!((a - b).abs() < 1e-5, "{a} vs {b}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn endless_tiles_repeat_with_the_buffer_size(p in place()) {
+            let tiled = buffer().tile(None, None);
+            let shifted = &p + space::Offset::new(-6.0, 4.0).unwrap();
+            prop_assert_eq!(tiled.get(p), tiled.get(shifted));
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use space::Place;
+
+use crate::Image;
+use crate::pixel::Pixel;
+
+/// Operator compressing linear `f32` values into the displayable `0.0..=1.0` range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMap {
+    /// `c / (1 + c)`.
+    Reinhard,
+    /// Narkowicz's fit of the ACES filmic curve, clamped to `0.0..=1.0`.
+    Aces,
+    /// `c^(1 / gamma)`, for display encoding of values already in range.
+    Gamma(f32),
+}
+
+impl ToneMap {
+    pub fn apply(self, x: f32) -> f32 {
+        match self {
+            ToneMap::Reinhard => x / (1.0 + x),
+            ToneMap::Aces => {
+                let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                ((x * (a * x + b)) / (x * (c * x + d) + e)).clamp(0.0, 1.0)
+            }
+            ToneMap::Gamma(gamma) => libm::powf(x, 1.0 / gamma),
+        }
+    }
+}
+
+/// Applies a [`ToneMap`] to the color channels of an image, see [`Image::tone_map`].
+#[derive(Debug, Clone)]
+pub struct ToneMapped<I> {
+    image: I,
+    op: ToneMap,
+}
+
+impl<I> ToneMapped<I> {
+    pub(crate) fn new(image: I, op: ToneMap) -> Self {
+        Self { image, op }
+    }
+}
+
+impl<I> Image for ToneMapped<I>
+where
+    I: Image,
+    I::Pixel: Pixel<Scalar = f32>,
+{
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.image.get(p).map_color(|c| self.op.apply(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, proptest};
+    use space::Place;
+
+    use super::ToneMap;
+    use crate::{Image, Rgba, from_fn};
+
+    proptest! {
+        #[test]
+        fn reinhard_and_aces_map_hdr_into_unit_range(c in 0.0..1e6f32) {
+            for op in [ToneMap::Reinhard, ToneMap::Aces] {
+                prop_assert!((0.0..=1.0).contains(&op.apply(c)));
+            }
+        }
+
+        #[test]
+        fn reinhard_and_aces_are_monotonic(a in 0.0..1e3f32, b in 0.0..1e3f32) {
+            let (lo, hi) = if a < b { (a, b) } else { (b, a) };
+            for op in [ToneMap::Reinhard, ToneMap::Aces] {
+                prop_assert!(op.apply(lo) <= op.apply(hi));
+            }
+        }
+    }
+
+    #[test]
+    fn gamma_keeps_endpoints() {
+        assert_eq!(ToneMap::Gamma(2.2).apply(0.0), 0.0);
+        assert_eq!(ToneMap::Gamma(2.2).apply(1.0), 1.0);
+        assert_eq!(ToneMap::Gamma(2.0).apply(0.25), 0.5);
+    }
+
+    #[test]
+    fn alpha_is_not_tone_mapped() {
+        let image = from_fn(|_| Rgba::new(1.0f32, 3.0, 0.0, 4.0)).tone_map(ToneMap::Reinhard);
+        assert_eq!(image.get(Place::origin()), Rgba::new(0.5, 0.75, 0.0, 4.0));
+    }
+}
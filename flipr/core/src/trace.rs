@@ -0,0 +1,168 @@
+//! Opt-in sampling statistics, enabled by the `trace` feature.
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use space::Place;
+
+use crate::Image;
+use crate::buffer::ImageBuffer;
+use crate::pixel::Pixel;
+
+#[derive(Debug, Default)]
+struct Counters {
+    samples: AtomicU64,
+    nanos: AtomicU64,
+}
+
+type Stages = Vec<(String, Arc<Counters>)>;
+
+/// Collects statistics from every [`Traced`] stage created with it.
+///
+/// Clones share the same stages, so one tracer can be handed to every stage
+/// of a chain and the [`PipelineReport`] read once sampling is done.
+#[derive(Debug, Clone, Default)]
+pub struct Tracer {
+    stages: Arc<Mutex<Stages>>,
+}
+
+impl Tracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn register(&self, name: String) -> Arc<Counters> {
+        let counters = Arc::new(Counters::default());
+        self.stages
+            .lock()
+            .expect("tracer lock is not poisoned")
+            .push((name, counters.clone()));
+        counters
+    }
+
+    /// Snapshot of the statistics of every stage, in registration order.
+    pub fn report(&self) -> PipelineReport {
+        let stages = self.stages.lock().expect("tracer lock is not poisoned");
+
+        PipelineReport {
+            stages: stages
+                .iter()
+                .map(|(name, counters)| StageReport {
+                    name: name.clone(),
+                    samples: counters.samples.load(Ordering::Relaxed),
+                    time: Duration::from_nanos(counters.nanos.load(Ordering::Relaxed)),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Statistics of one [`Traced`] stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageReport {
+    pub name: String,
+    /// Number of places sampled.
+    pub samples: u64,
+    /// Time spent sampling, including every stage the traced one reads from.
+    pub time: Duration,
+}
+
+/// Statistics of every stage registered with a [`Tracer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineReport {
+    pub stages: Vec<StageReport>,
+}
+
+impl PipelineReport {
+    /// Stage with the most inclusive time.
+    pub fn slowest(&self) -> Option<&StageReport> {
+        self.stages.iter().max_by_key(|stage| stage.time)
+    }
+}
+
+impl fmt::Display for PipelineReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for stage in &self.stages {
+            writeln!(
+                f,
+                "{:<24} {:>10} samples {:>12.3?}",
+                stage.name, stage.samples, stage.time
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Image recording how often and how long it is sampled, see [`Image::traced`].
+///
+/// Every sample also runs inside a `tracing` span named `flipr::sample`.
+/// Adapters that read a buffer source whole, such as large convolutions,
+/// take no samples.
+#[derive(Debug, Clone)]
+pub struct Traced<I> {
+    image: I,
+    name: String,
+    counters: Arc<Counters>,
+}
+
+impl<I> Traced<I> {
+    pub(crate) fn new(image: I, name: &str, tracer: &Tracer) -> Self {
+        Self {
+            image,
+            name: name.into(),
+            counters: tracer.register(name.into()),
+        }
+    }
+}
+
+impl<I: Image> Image for Traced<I> {
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let _span = tracing::trace_span!("flipr::sample", stage = %self.name).entered();
+        let start = Instant::now();
+        let pixel = self.image.get(p);
+        let nanos = start.elapsed().as_nanos().try_into().unwrap_or(u64::MAX);
+
+        self.counters.samples.fetch_add(1, Ordering::Relaxed);
+        self.counters.nanos.fetch_add(nanos, Ordering::Relaxed);
+        pixel
+    }
+
+    fn as_buffer(&self) -> Option<&ImageBuffer<I::Pixel>>
+    where
+        I::Pixel: Pixel,
+    {
+        self.image.as_buffer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tracer;
+    use crate::buffer::{ImageBuffer, Layout};
+    use crate::{Gray, Image, from_fn};
+
+    #[test]
+    fn stages_count_their_samples() {
+        let tracer = Tracer::new();
+        let source = from_fn(|_| Gray(0.25f32)).traced("source", &tracer);
+        let blurred = source.gaussian_blur(0.5).traced("blur", &tracer);
+        ImageBuffer::sample(&blurred, 4, 3, Layout::Interleaved);
+
+        let report = tracer.report();
+        let samples: Vec<_> = report
+            .stages
+            .iter()
+            .map(|s| (s.name.as_str(), s.samples))
+            .collect();
+        assert_eq!(samples, [("source", 12 * 25), ("blur", 12)]);
+        assert_eq!(report.slowest().map(|s| s.name.as_str()), Some("blur"));
+        assert!(report.to_string().starts_with("source"));
+    }
+}
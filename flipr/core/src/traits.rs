@@ -1,7 +1,591 @@
-use space::Place;
+use space::{Offset, Place, Real};
+
+use crate::affine::{AffineTransform, Transformed};
+use crate::arithmetic::{Channel, OverflowPolicy, PixelAdd, PixelLerp};
+use crate::blur::{GaussianBlur, UnsharpMask};
+use crate::boxed::BoxedImage;
+use crate::buffer::{ImageBuffer, Layout};
+use crate::channels::SelectChannel;
+use crate::color::{ColorSpace, ConvertColorSpace, Delinearize, Linearize, SrgbChannel};
+use crate::colormap::{ApplyColormap, Colormap, DiffVisualized};
+use crate::curves::{AdjustMode, Adjusted, ToneCurve};
+use crate::debug::Inspect;
+use crate::displace::Displaced;
+use crate::error::MapErr;
+use crate::fixed::{FixedChannel, FixedConvolved, FixedKernel};
+use crate::geometry::Size;
+use crate::homography::{Homography, Warped};
+use crate::hue::{HueSaturation, HueSaturationOp};
+use crate::kernel::{Convolved, Kernel, LumaConvolved};
+use crate::lut::{ApplyLut, ApplyLut3d, Lut, Lut3d};
+use crate::masked::{MaskBlend, Masked};
+use crate::normalize::{Dither, Quantize, Quantized, ToFloat};
+use crate::pixel::{Gray, MapChannels, Pixel, Rgb};
+use crate::polar::{FromPolar, PolarScale, ToPolar};
+use crate::samples::Samples;
+use crate::stack::{HStack, VStack};
+use crate::tone::{ToneMap, ToneMapped};
+#[cfg(feature = "trace")]
+use crate::trace::{Traced, Tracer};
+use crate::zip::{AddWith, ZipWith};
 
 pub trait Image {
     type Pixel;
 
     fn get(&self, p: Place) -> Self::Pixel;
+
+    /// Samples the image onto a pixel grid, the counterpart of
+    /// [`ImageBuffer::continuize`].
+    fn discretize(&self, size: Size, layout: Layout) -> ImageBuffer<Self::Pixel>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel,
+    {
+        ImageBuffer::sample(self, size.width, size.height, layout)
+    }
+
+    /// The pixels of the image if it is an [`ImageBuffer`], so that adapters
+    /// can switch to algorithms working on whole buffers.
+    fn as_buffer(&self) -> Option<&ImageBuffer<Self::Pixel>>
+    where
+        Self::Pixel: Pixel,
+    {
+        None
+    }
+
+    /// Iterates over the samples at the pixel-cell centers of a
+    /// `width × height` grid, row by row, the same places
+    /// [`ImageBuffer::sample`](crate::ImageBuffer::sample) reads.
+    fn samples(&self, width: usize, height: usize) -> Samples<'_, Self>
+    where
+        Self: Sized,
+    {
+        Samples::new(self, width, height)
+    }
+
+    /// Accumulates the samples of a `width × height` grid with `f`, visiting
+    /// them in the row-major order of [`samples`](Self::samples).
+    fn fold<B>(&self, width: usize, height: usize, init: B, f: impl FnMut(B, Self::Pixel) -> B) -> B
+    where
+        Self: Sized,
+    {
+        self.samples(width, height).fold(init, f)
+    }
+
+    /// Like [`fold`](Self::fold), but stops sampling at the first error.
+    fn try_fold<B, E>(
+        &self,
+        width: usize,
+        height: usize,
+        init: B,
+        f: impl FnMut(B, Self::Pixel) -> Result<B, E>,
+    ) -> Result<B, E>
+    where
+        Self: Sized,
+    {
+        self.samples(width, height).try_fold(init, f)
+    }
+
+    /// Whether any sample of a `width × height` grid satisfies `pred`,
+    /// stopping at the first one that does.
+    fn any(&self, width: usize, height: usize, pred: impl FnMut(Self::Pixel) -> bool) -> bool
+    where
+        Self: Sized,
+    {
+        self.samples(width, height).any(pred)
+    }
+
+    /// Whether every sample of a `width × height` grid satisfies `pred`,
+    /// stopping at the first one that does not.
+    fn all(&self, width: usize, height: usize, pred: impl FnMut(Self::Pixel) -> bool) -> bool
+    where
+        Self: Sized,
+    {
+        self.samples(width, height).all(pred)
+    }
+
+    /// Number of samples of a `width × height` grid satisfying `pred`.
+    fn count(&self, width: usize, height: usize, mut pred: impl FnMut(Self::Pixel) -> bool) -> usize
+    where
+        Self: Sized,
+    {
+        self.samples(width, height)
+            .map(|p| usize::from(pred(p)))
+            .sum()
+    }
+
+    /// Pixel `(i, j)` of the first sample in row-major order that satisfies
+    /// `pred`.
+    fn position(
+        &self,
+        width: usize,
+        height: usize,
+        pred: impl FnMut(Self::Pixel) -> bool,
+    ) -> Option<(usize, usize)>
+    where
+        Self: Sized,
+    {
+        let n = self.samples(width, height).position(pred)?;
+        Some((n % width, n / width))
+    }
+
+    /// Places `other` to the right of `self`.
+    ///
+    /// Places with `x < width` are sampled from `self`, all others from `other`
+    /// shifted left by `width`, so that `other`'s origin lands at `(width, 0)`.
+    /// Both images are total, so there is nothing to fill where their extents
+    /// differ: each one answers for its own half-plane.
+    fn hstack<B>(self, other: B, width: Real) -> HStack<Self, B>
+    where
+        Self: Sized,
+        B: Image<Pixel = Self::Pixel>,
+    {
+        HStack::new(self, other, width)
+    }
+
+    /// Places `other` below `self`, the vertical counterpart of [`Image::hstack`].
+    fn vstack<B>(self, other: B, height: Real) -> VStack<Self, B>
+    where
+        Self: Sized,
+        B: Image<Pixel = Self::Pixel>,
+    {
+        VStack::new(self, other, height)
+    }
+
+    /// Converts `u8` or `u16` channels to `f32` in the `0.0..=1.0` convention.
+    fn to_float(self) -> ToFloat<Self>
+    where
+        Self: Sized,
+        Self::Pixel: MapChannels<f32>,
+        <Self::Pixel as Pixel>::Scalar: Quantized,
+    {
+        ToFloat::new(self)
+    }
+
+    /// Quantizes `f32` channels in the `0.0..=1.0` convention to `u8`.
+    ///
+    /// Values outside of the range are clamped and `NaN` becomes zero.
+    fn to_u8(self, dither: Dither) -> Quantize<Self, u8>
+    where
+        Self: Sized,
+        Self::Pixel: MapChannels<u8, Scalar = f32>,
+    {
+        Quantize::new(self, dither)
+    }
+
+    /// Quantizes `f32` channels in the `0.0..=1.0` convention to `u16`, see
+    /// [`Image::to_u8`].
+    fn to_u16(self, dither: Dither) -> Quantize<Self, u16>
+    where
+        Self: Sized,
+        Self::Pixel: MapChannels<u16, Scalar = f32>,
+    {
+        Quantize::new(self, dither)
+    }
+
+    /// Compresses linear `f32` color channels with a tone-mapping operator.
+    fn tone_map(self, op: ToneMap) -> ToneMapped<Self>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel<Scalar = f32>,
+    {
+        ToneMapped::new(self, op)
+    }
+
+    /// Decodes sRGB color channels to linear `f32` light, where blurring,
+    /// resampling and blending behave physically.
+    ///
+    /// `u8` channels are decoded through a 256-entry table; alpha is only
+    /// normalized to `0.0..=1.0`.
+    fn linearize(self) -> Linearize<Self>
+    where
+        Self: Sized,
+        Self::Pixel: MapChannels<f32>,
+        <Self::Pixel as Pixel>::Scalar: SrgbChannel,
+    {
+        Linearize::new(self)
+    }
+
+    /// Encodes linear `f32` color channels with the sRGB transfer function,
+    /// the inverse of [`Image::linearize`].
+    fn delinearize(self) -> Delinearize<Self>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel<Scalar = f32>,
+    {
+        Delinearize::new(self)
+    }
+
+    /// Re-expresses encoded `f32` colors of `from` in the color space `to`.
+    fn convert_color_space(self, from: ColorSpace, to: ColorSpace) -> ConvertColorSpace<Self>
+    where
+        Self: Sized + Image<Pixel = Rgb<f32>>,
+    {
+        ConvertColorSpace::new(self, from, to)
+    }
+
+    /// Blurs with a Gaussian of standard deviation `sigma` pixels, sampling
+    /// the source at unit steps out to `3σ`.
+    fn gaussian_blur(self, sigma: f64) -> GaussianBlur<Self>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel,
+        <Self::Pixel as Pixel>::Scalar: Channel,
+    {
+        GaussianBlur::new(self, sigma)
+    }
+
+    /// Convolves with `kernel`, sampling the source at unit steps around
+    /// every place; build kernels with [`kernel!`](crate::kernel!).
+    ///
+    /// Every channel, alpha included, is convolved on its own with the same
+    /// weights, so [`Rgb`] and [`Rgba`](crate::Rgba) images work like three
+    /// or four [`Gray`] ones.
+    ///
+    /// Kernels of [`FFT_KERNEL_AREA`](crate::FFT_KERNEL_AREA) weights or
+    /// more over an [`ImageBuffer`], also behind [`boxed`](Self::boxed) and
+    /// other wrappers that leave pixels alone, are convolved through the FFT
+    /// on the first sample, whose cost barely depends on the kernel size;
+    /// places on the buffer then read the result, which agrees with the
+    /// direct sum up to floating-point rounding.
+    fn convolve<const W: usize, const H: usize>(self, kernel: Kernel<W, H>) -> Convolved<Self, W, H>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel,
+        <Self::Pixel as Pixel>::Scalar: Channel,
+    {
+        Convolved::new(self, kernel)
+    }
+
+    /// Convolves with a kernel quantized by [`Kernel::quantize`] in integer
+    /// arithmetic alone, for targets without a floating-point unit.
+    ///
+    /// Results are rounded half up and saturated at the channel's bounds;
+    /// they differ from [`convolve`](Self::convolve) by at most the bound
+    /// documented on [`Kernel::quantize`].
+    fn convolve_fixed<const W: usize, const H: usize>(
+        self,
+        kernel: FixedKernel<W, H>,
+    ) -> FixedConvolved<Self, W, H>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel,
+        <Self::Pixel as Pixel>::Scalar: FixedChannel,
+    {
+        FixedConvolved::new(self, kernel)
+    }
+
+    /// Convolves only the Rec. 709 luma with `kernel` and shifts the color
+    /// channels by the change, leaving chroma and alpha alone, so that for
+    /// example sharpening adds no color fringes.
+    ///
+    /// Pixels with fewer than three channels are convolved like
+    /// [`convolve`](Self::convolve) does with their first channel.
+    fn convolve_luma<const W: usize, const H: usize>(
+        self,
+        kernel: Kernel<W, H>,
+    ) -> LumaConvolved<Self, W, H>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel,
+        <Self::Pixel as Pixel>::Scalar: Channel,
+    {
+        LumaConvolved::new(self, kernel)
+    }
+
+    /// Sharpens by adding `amount` times the difference between `self` and its
+    /// Gaussian blur of standard deviation `radius`.
+    ///
+    /// Channels whose difference is below `threshold`, in channel units, are
+    /// left alone so that noise and smooth gradients are not amplified.
+    fn unsharp_mask(self, radius: f64, amount: f64, threshold: f64) -> UnsharpMask<Self>
+    where
+        Self: Sized + Clone,
+        Self::Pixel: Pixel,
+        <Self::Pixel as Pixel>::Scalar: Channel,
+    {
+        UnsharpMask::new(self, radius, amount, threshold)
+    }
+
+    /// Reshapes the tones of an `f32` image with [`Levels`](crate::Levels) or a
+    /// [`Curve`](crate::Curve); `u8` images can use [`ToneCurve::to_lut`] instead.
+    fn adjust<C>(self, curve: C, mode: AdjustMode) -> Adjusted<Self, C>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel<Scalar = f32>,
+        C: ToneCurve,
+    {
+        Adjusted::new(self, curve, mode)
+    }
+
+    /// Rotates the hues of an `f32` color image by `degrees`, keeping luma.
+    fn hue_rotate(self, degrees: f32) -> HueSaturation<Self>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel<Scalar = f32>,
+    {
+        HueSaturation::new(self, HueSaturationOp::HueRotate(degrees))
+    }
+
+    /// Scales the saturation of an `f32` color image by `factor`.
+    fn saturate(self, factor: f32) -> HueSaturation<Self>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel<Scalar = f32>,
+    {
+        HueSaturation::new(self, HueSaturationOp::Saturate(factor))
+    }
+
+    /// Scales saturation by `factor`, tapering the effect on saturated colors.
+    fn vibrance(self, factor: f32) -> HueSaturation<Self>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel<Scalar = f32>,
+    {
+        HueSaturation::new(self, HueSaturationOp::Vibrance(factor))
+    }
+
+    /// Maps the color channels of a `u8` image through a [`Lut`].
+    fn apply_lut(self, lut: Lut) -> ApplyLut<Self>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel<Scalar = u8>,
+    {
+        ApplyLut::new(self, lut)
+    }
+
+    /// Grades an `Rgb<f32>` image through a 3D color cube.
+    fn apply_lut3d(self, lut: Lut3d) -> ApplyLut3d<Self>
+    where
+        Self: Sized + Image<Pixel = Rgb<f32>>,
+    {
+        ApplyLut3d::new(self, lut)
+    }
+
+    /// Extracts channel `index` as a [`Gray`](crate::Gray) image.
+    ///
+    /// Sampling panics if `index` is not below [`Pixel::CHANNELS`].
+    fn select_channel(self, index: usize) -> SelectChannel<Self>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel,
+    {
+        SelectChannel::new(self, index)
+    }
+
+    /// Splits an [`Rgb`] image into its red, green and blue channels, see
+    /// [`merge_channels`](crate::merge_channels) for the way back.
+    fn split_channels<T: Copy>(
+        self,
+    ) -> (
+        SelectChannel<Self>,
+        SelectChannel<Self>,
+        SelectChannel<Self>,
+    )
+    where
+        Self: Sized + Clone + Image<Pixel = Rgb<T>>,
+    {
+        (
+            SelectChannel::new(self.clone(), 0),
+            SelectChannel::new(self.clone(), 1),
+            SelectChannel::new(self, 2),
+        )
+    }
+
+    /// Applies `op` only where `mask` is set, passing `self` through elsewhere.
+    ///
+    /// `op` receives a copy of `self` and returns the edited image; `blend`
+    /// decides how intermediate mask values mix the two.
+    fn masked<M, J>(
+        self,
+        mask: M,
+        op: impl FnOnce(Self) -> J,
+        blend: MaskBlend,
+    ) -> Masked<Self, J, M>
+    where
+        Self: Sized + Clone,
+        Self::Pixel: PixelLerp,
+        J: Image<Pixel = Self::Pixel>,
+        M: Image<Pixel = Gray<u8>>,
+    {
+        let edited = op(self.clone());
+        Masked::new(self, edited, mask, blend)
+    }
+
+    /// Combines the pixels of `self` and `other` at every place with `f`.
+    fn zip_with<J, F, P>(self, other: J, f: F) -> ZipWith<Self, J, F>
+    where
+        Self: Sized,
+        J: Image,
+        F: Fn(Self::Pixel, J::Pixel) -> P,
+    {
+        ZipWith::new(self, other, f)
+    }
+
+    /// Adds `other` channel by channel, handling sums that do not fit the
+    /// channel type as `policy` says.
+    ///
+    /// Every pixel is a `Result` so that [`OverflowPolicy::Error`] can
+    /// report the place; [`ImageBuffer::try_sample`] stops at the first
+    /// overflow.
+    fn add_with<J>(self, other: J, policy: OverflowPolicy) -> AddWith<Self, J>
+    where
+        Self: Sized,
+        Self::Pixel: PixelAdd,
+        J: Image<Pixel = Self::Pixel>,
+    {
+        AddWith::new(self, other, policy)
+    }
+
+    /// Shows a single-channel image, such as a mask, depth map or heatmap,
+    /// in the colors of `colormap`.
+    ///
+    /// Values on the `0.0..=1.0` scale of [`SrgbChannel::normalized`] run
+    /// through the whole map, `f32` values as they are and integer ones
+    /// divided by their maximum; see [`ApplyColormap::range`] for others.
+    fn apply_colormap<T>(self, colormap: Colormap) -> ApplyColormap<Self>
+    where
+        Self: Sized + Image<Pixel = Gray<T>>,
+        T: SrgbChannel,
+    {
+        ApplyColormap::new(self, colormap)
+    }
+
+    /// Heatmap of how much `other` differs from `self`: the largest
+    /// difference between their channels on the `0.0..=1.0` scale of
+    /// [`SrgbChannel::normalized`], times `amplification`, shown with
+    /// [`Colormap::Magma`] or the map chosen with
+    /// [`DiffVisualized::colormap`].
+    ///
+    /// Identical pixels get the map's first color; with an amplification of
+    /// 10, differences of a tenth of the range and more get its last.
+    fn diff_visualize<J>(self, other: J, amplification: f32) -> DiffVisualized<Self, J>
+    where
+        Self: Sized,
+        Self::Pixel: Pixel,
+        <Self::Pixel as Pixel>::Scalar: SrgbChannel,
+        J: Image<Pixel = Self::Pixel>,
+    {
+        DiffVisualized::new(self, other, amplification)
+    }
+
+    /// Converts the errors of an image with fallible pixels, for example into
+    /// a [`FliprError`](crate::FliprError) naming the stage.
+    fn map_err<F, P, E, E2>(self, f: F) -> MapErr<Self, F>
+    where
+        Self: Sized + Image<Pixel = Result<P, E>>,
+        F: Fn(E) -> E2,
+    {
+        MapErr::new(self, f)
+    }
+
+    /// Moves the image by `transform`: the pixel at `p` ends up at
+    /// `transform.transform_place(p)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transform` has no inverse.
+    fn transform(self, transform: AffineTransform) -> Transformed<Self>
+    where
+        Self: Sized,
+    {
+        Transformed::new(self, transform)
+    }
+
+    /// Moves the image by `homography`, like [`transform`](Self::transform)
+    /// with perspective. Places whose source lies at infinity are `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `homography` has no inverse.
+    fn warp(self, homography: Homography) -> Warped<Self>
+    where
+        Self: Sized,
+    {
+        Warped::new(self, homography)
+    }
+
+    /// Unrolls the image around `center`: x becomes the distance from it and
+    /// y the angle, laid out by `scale`, so rotation about the center turns
+    /// into a vertical shift.
+    ///
+    /// Sample a buffer through [`ImageBuffer::continuize`] first to
+    /// interpolate between its pixels.
+    fn to_polar(self, center: Place, scale: PolarScale) -> ToPolar<Self>
+    where
+        Self: Sized,
+    {
+        ToPolar::new(self, center, scale)
+    }
+
+    /// Rolls a polar image laid out like [`to_polar`](Self::to_polar)'s back
+    /// up around `center`.
+    // Named as the counterpart of `to_polar`, not as a constructor.
+    #[allow(clippy::wrong_self_convention)]
+    fn from_polar(self, center: Place, scale: PolarScale) -> FromPolar<Self>
+    where
+        Self: Sized,
+    {
+        FromPolar::new(self, center, scale)
+    }
+
+    /// Resamples the image at every place moved by the [`Offset`] that
+    /// `field` holds there: the result at `p` is the source at
+    /// `p + field(p)`.
+    ///
+    /// Unlike [`transform`](Self::transform) and [`warp`](Self::warp) any
+    /// smooth or rough motion works, such as lens distortion from
+    /// [`barrel_distortion`](crate::generators::barrel_distortion), ripples
+    /// built with [`from_fn`](crate::from_fn), or an optical flow field.
+    fn displace<F>(self, field: F) -> Displaced<Self, F>
+    where
+        Self: Sized,
+        F: Image<Pixel = Offset>,
+    {
+        Displaced::new(self, field)
+    }
+
+    /// Erases the adapter type, so images built by different operations can
+    /// be stored together or chained at runtime.
+    fn boxed<'a>(self) -> BoxedImage<'a, Self::Pixel>
+    where
+        Self: Sized + 'a,
+    {
+        BoxedImage::new(self)
+    }
+
+    /// Records how often and how long `self` is sampled under `name`, see
+    /// [`Tracer::report`].
+    #[cfg(feature = "trace")]
+    fn traced(self, name: &str, tracer: &Tracer) -> Traced<Self>
+    where
+        Self: Sized,
+    {
+        Traced::new(self, name, tracer)
+    }
+
+    /// Calls `f` with every place `self` is sampled at and the pixel found
+    /// there, like [`Iterator::inspect`].
+    fn inspect<F>(self, f: F) -> Inspect<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Place, &Self::Pixel),
+    {
+        Inspect::new(self, f)
+    }
+
+    /// Samples `self` on a `width × height` grid and hands it to the
+    /// [`DebugSink`](crate::debug::DebugSink) under `name` if
+    /// [`set_debug`](crate::debug::set_debug) turned debugging on; returns
+    /// `self` unchanged either way.
+    #[cfg(feature = "std")]
+    fn dump_stage(self, name: &str, width: usize, height: usize) -> Self
+    where
+        Self: Sized,
+        Self::Pixel: Pixel,
+        <Self::Pixel as Pixel>::Scalar: SrgbChannel,
+    {
+        crate::debug::dump(&self, name, width, height);
+        self
+    }
 }
@@ -0,0 +1,194 @@
+use space::Place;
+
+use crate::Image;
+use crate::buffer::{ImageBuffer, Layout};
+use crate::pixel::Pixel;
+use crate::static_image::clamped_index;
+
+/// Borrowed `width × height` window into row-major pixels whose rows start
+/// every `stride` pixels.
+///
+/// Sampling follows [`ImageBuffer`]: pixel `(i, j)` of the view covers the unit
+/// cell `[i, i + 1) × [j, j + 1)` and places outside are clamped to the edge of
+/// the view, never reaching into the surrounding pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageView<'a, P> {
+    pixels: &'a [P],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<'a, P: Copy> ImageView<'a, P> {
+    /// Wraps externally owned, possibly row-padded pixels without copying.
+    ///
+    /// Returns `None` if the view would be empty, rows would overlap or
+    /// `pixels` ends before the last row, including when that end overflows.
+    pub fn from_slice(pixels: &'a [P], width: usize, height: usize, stride: usize) -> Option<Self> {
+        let end = stride
+            .checked_mul(height.checked_sub(1)?)?
+            .checked_add(width)?;
+        let valid = width > 0 && stride >= width && pixels.len() >= end;
+        valid.then_some(Self {
+            pixels,
+            width,
+            height,
+            stride,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Distance in pixels between the starts of consecutive rows.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The pixels from the start of the first row on, padding included.
+    pub(crate) fn as_slice(&self) -> &'a [P] {
+        self.pixels
+    }
+
+    pub fn pixel(&self, i: usize, j: usize) -> Option<P> {
+        (i < self.width && j < self.height).then(|| self.pixels[j * self.stride + i])
+    }
+
+    /// Narrows the view to the `width × height` rectangle at `(x, y)`, sharing
+    /// the same pixels; `None` if it does not fit.
+    pub fn view(&self, x: usize, y: usize, width: usize, height: usize) -> Option<Self> {
+        if x.checked_add(width)? > self.width || y.checked_add(height)? > self.height {
+            return None;
+        }
+        let start = y.checked_mul(self.stride)?.checked_add(x)?;
+
+        Self::from_slice(
+            &self.pixels[start.min(self.pixels.len())..],
+            width,
+            height,
+            self.stride,
+        )
+    }
+
+    /// Copies the viewed pixels into an owned buffer.
+    pub fn to_buffer(&self, layout: Layout) -> ImageBuffer<P>
+    where
+        P: Pixel,
+    {
+        ImageBuffer::from_fn(self.width, self.height, layout, |i, j| {
+            self.pixels[j * self.stride + i]
+        })
+    }
+}
+
+impl<P: Copy> Image for ImageView<'_, P> {
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let i = clamped_index(p.x(), self.width);
+        let j = clamped_index(p.y(), self.height);
+
+        self.pixels[j * self.stride + i]
+    }
+}
+
+impl<P: Pixel> ImageBuffer<P> {
+    /// Borrows the `width × height` rectangle at `(x, y)` without copying.
+    ///
+    /// Returns `None` if the rectangle is empty or does not fit, or if the
+    /// buffer is [`Layout::Planar`], whose pixels are not stored contiguously.
+    pub fn view(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Option<ImageView<'_, P>> {
+        ImageView::from_slice(
+            self.as_interleaved()?,
+            self.width(),
+            self.height(),
+            self.width(),
+        )?
+        .view(x, y, width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::ImageView;
+    use crate::buffer::{ImageBuffer, Layout};
+    use crate::tests::place;
+    use crate::{Gray, Image};
+
+    fn numbered() -> ImageBuffer<Gray<u8>> {
+        ImageBuffer::from_fn(5, 4, Layout::Interleaved, |i, j| Gray((10 * j + i) as u8))
+    }
+
+    #[test]
+    fn view_reads_the_rectangle() {
+        let buffer = numbered();
+        let view = buffer.view(1, 2, 3, 2).unwrap();
+        assert_eq!(view.pixel(0, 0), Some(Gray(21)));
+        assert_eq!(view.pixel(2, 1), Some(Gray(33)));
+        assert_eq!(view.pixel(3, 0), None);
+        assert_eq!(view.get(Place::new(10.0, -3.0).unwrap()), Gray(23));
+    }
+
+    #[test]
+    fn views_nest_and_copy_out() {
+        let buffer = numbered();
+        let inner = buffer.view(1, 1, 4, 3).unwrap().view(2, 1, 2, 2).unwrap();
+        assert_eq!(inner.stride(), 5);
+        assert_eq!(
+            inner.to_buffer(Layout::Planar),
+            ImageBuffer::from_fn(2, 2, Layout::Interleaved, |i, j| Gray(
+                (10 * (j + 2) + i + 3) as u8
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_views_are_rejected() {
+        let buffer = numbered();
+        assert_eq!(buffer.view(3, 0, 3, 1), None);
+        assert_eq!(buffer.view(0, 0, 0, 1), None);
+        assert_eq!(
+            buffer.clone().into_layout(Layout::Planar).view(0, 0, 1, 1),
+            None
+        );
+        assert_eq!(ImageView::from_slice(&[0u8; 7], 3, 2, 5), None);
+        assert!(ImageView::from_slice(&[0u8; 8], 3, 2, 5).is_some());
+    }
+
+    #[test]
+    fn overflowing_geometry_is_rejected() {
+        let pixels = [0u8; 8];
+        assert_eq!(ImageView::from_slice(&pixels, 3, 2, usize::MAX), None);
+        assert_eq!(ImageView::from_slice(&pixels, 3, usize::MAX, 3), None);
+        assert_eq!(
+            ImageView::from_slice(&pixels, 2, 3, usize::MAX / 2 + 1),
+            None
+        );
+
+        let buffer = numbered();
+        assert_eq!(buffer.view(usize::MAX, 0, 2, 1), None);
+        assert_eq!(buffer.view(1, 1, 1, usize::MAX), None);
+    }
+
+    proptest! {
+        #[test]
+        fn full_view_samples_like_the_buffer(p in place()) {
+            let buffer = numbered();
+            prop_assert_eq!(buffer.view(0, 0, 5, 4).unwrap().get(p.clone()), buffer.get(p));
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use alloc::vec::Vec;
+
+use space::Place;
+
+use crate::Image;
+
+/// Images stacked along a third axis, depth for volumes or time for frame
+/// sequences, with slices at whole indices `0..depth()`.
+pub trait Volume {
+    type Pixel;
+
+    /// Number of slices.
+    fn depth(&self) -> usize;
+
+    /// Pixel at `p` in slice `z`; slices past the end are clamped to the
+    /// last one, like places past the edge of an [`ImageBuffer`](crate::ImageBuffer).
+    fn get(&self, p: Place, z: usize) -> Self::Pixel;
+
+    /// Slice `z` as an image, so any image operation can read it.
+    fn slice(&self, z: usize) -> Slice<'_, Self> {
+        Slice { volume: self, z }
+    }
+}
+
+/// One slice of a [`Volume`], see [`Volume::slice`].
+#[derive(Debug)]
+pub struct Slice<'a, V: ?Sized> {
+    volume: &'a V,
+    z: usize,
+}
+
+impl<V: ?Sized> Clone for Slice<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V: ?Sized> Copy for Slice<'_, V> {}
+
+impl<V: Volume + ?Sized> Image for Slice<'_, V> {
+    type Pixel = V::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.volume.get(p, self.z)
+    }
+}
+
+/// [`Volume`] made of one image per slice, processed slice by slice with
+/// [`map_slices`](Self::map_slices).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageStack<I> {
+    slices: Vec<I>,
+}
+
+impl<I> ImageStack<I> {
+    /// # Panics
+    ///
+    /// Panics if `slices` is empty.
+    pub fn new(slices: Vec<I>) -> Self {
+        assert!(
+            !slices.is_empty(),
+            "ImageStack must have at least one slice"
+        );
+        Self { slices }
+    }
+
+    /// Stack of `depth` slices, asking `f` for each index in order.
+    pub fn from_fn(depth: usize, f: impl FnMut(usize) -> I) -> Self {
+        Self::new((0..depth).map(f).collect())
+    }
+
+    pub fn slices(&self) -> &[I] {
+        &self.slices
+    }
+
+    pub fn into_slices(self) -> Vec<I> {
+        self.slices
+    }
+
+    /// Applies an image operation to every slice, for example
+    /// `stack.map_slices(|s| s.gaussian_blur(1.0))`.
+    pub fn map_slices<J>(self, f: impl FnMut(I) -> J) -> ImageStack<J> {
+        ImageStack {
+            slices: self.slices.into_iter().map(f).collect(),
+        }
+    }
+}
+
+impl<I: Image> Volume for ImageStack<I> {
+    type Pixel = I::Pixel;
+
+    fn depth(&self) -> usize {
+        self.slices.len()
+    }
+
+    fn get(&self, p: Place, z: usize) -> Self::Pixel {
+        self.slices[z.min(self.slices.len() - 1)].get(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use proptest::{prop_assert_eq, proptest};
+    use space::Place;
+
+    use super::{ImageStack, Volume};
+    use crate::tests::place;
+    use crate::{Gray, Image, ImageBuffer, Layout, MultiChannelPixel, Pixel, from_fn};
+
+    type Bands = MultiChannelPixel<f32, 6>;
+
+    fn bands(z: usize) -> ImageBuffer<Bands> {
+        ImageBuffer::from_fn(4, 4, Layout::Planar, move |i, j| {
+            MultiChannelPixel::from_channels(|c| (i + j + c + z) as f32)
+        })
+    }
+
+    #[test]
+    fn many_channels_work_with_image_operations() {
+        let blurred = bands(0).gaussian_blur(0.0);
+        let p = Place::new(2.5, 1.5).unwrap();
+        assert_eq!(blurred.get(p.clone()), bands(0).get(p));
+        assert_eq!(bands(0).plane(5).map(<[f32]>::len), Some(16));
+    }
+
+    #[test]
+    fn slices_are_processed_independently() {
+        let stack = ImageStack::from_fn(3, bands).map_slices(|slice| {
+            ImageBuffer::sample(&slice.select_channel(2), 4, 4, Layout::Interleaved)
+        });
+        assert_eq!(stack.depth(), 3);
+
+        let p = Place::new(1.5, 0.5).unwrap();
+        let values: Vec<_> = (0..4).map(|z| stack.get(p.clone(), z)).collect();
+        assert_eq!(values, [Gray(3.0), Gray(4.0), Gray(5.0), Gray(5.0)]);
+    }
+
+    proptest! {
+        #[test]
+        fn slices_read_their_image(p in place(), z in 0..3usize) {
+            let stack = ImageStack::from_fn(3, |z| from_fn(move |_| Gray(z)));
+            prop_assert_eq!(stack.slice(z).get(p), Gray(z));
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use space::Place;
+
+use crate::Image;
+use crate::arithmetic::{Overflow, OverflowPolicy, PixelAdd};
+
+/// Pixel-wise combination of two images, see [`Image::zip_with`].
+#[derive(Debug, Clone)]
+pub struct ZipWith<I, J, F> {
+    first: I,
+    second: J,
+    f: F,
+}
+
+impl<I, J, F> ZipWith<I, J, F> {
+    pub(crate) fn new(first: I, second: J, f: F) -> Self {
+        Self { first, second, f }
+    }
+}
+
+impl<I, J, F, P> Image for ZipWith<I, J, F>
+where
+    I: Image,
+    J: Image,
+    F: Fn(I::Pixel, J::Pixel) -> P,
+{
+    type Pixel = P;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        (self.f)(self.first.get(p.clone()), self.second.get(p))
+    }
+}
+
+/// Pixel-wise sum of two images with an explicit overflow policy, see
+/// [`Image::add_with`].
+#[derive(Debug, Clone)]
+pub struct AddWith<I, J> {
+    first: I,
+    second: J,
+    policy: OverflowPolicy,
+}
+
+impl<I, J> AddWith<I, J> {
+    pub(crate) fn new(first: I, second: J, policy: OverflowPolicy) -> Self {
+        Self {
+            first,
+            second,
+            policy,
+        }
+    }
+}
+
+impl<I, J> Image for AddWith<I, J>
+where
+    I: Image,
+    J: Image<Pixel = I::Pixel>,
+    I::Pixel: PixelAdd,
+{
+    type Pixel = Result<I::Pixel, Overflow>;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        let (a, b) = (self.first.get(p.clone()), self.second.get(p));
+        a.add_with(b, self.policy)
+    }
+}
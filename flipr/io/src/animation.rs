@@ -0,0 +1,380 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use flipr::{ChannelOrder, Dithering, Image, ImageBuffer, Layout, Palette, Rgb, Rgba, quantize};
+
+use crate::Error;
+
+/// Where the colors of the frames of a GIF come from, see
+/// [`FrameEncoder::palette`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PaletteMode {
+    /// One palette built from all frames, so that colors shared by the frames
+    /// do not flicker.
+    #[default]
+    Global,
+    /// A palette per frame, which suits frames with very different colors.
+    PerFrame,
+}
+
+/// GIF has no partial transparency: pixels with less alpha are transparent
+/// and the others opaque.
+const GIF_ALPHA_THRESHOLD: u8 = 128;
+
+/// Collects the frames of an animation and writes them as a GIF or an APNG.
+///
+/// Frames are sampled onto the grid of the animation when they are pushed,
+/// so they can be lazy pipelines, for example one per step of a parameter:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use flipr::{Dither, Image, ImageBuffer, Rgba};
+/// # let image: ImageBuffer<Rgba<f32>> = unimplemented!();
+/// let mut animation = flipr_io::FrameEncoder::new(image.width(), image.height());
+/// for step in 1..=10 {
+///     let frame = image.clone().gaussian_blur(step as f64).to_u8(Dither::None);
+///     animation.push(&frame, Duration::from_millis(100));
+/// }
+/// animation.save("blur.gif").expect("animation can be written");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameEncoder {
+    width: usize,
+    height: usize,
+    frames: Vec<(ImageBuffer<Rgba<u8>>, Duration)>,
+    palette: PaletteMode,
+    dithering: Dithering,
+}
+
+impl FrameEncoder {
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is zero.
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(
+            width > 0 && height > 0,
+            "an animation must have at least one pixel"
+        );
+        Self {
+            width,
+            height,
+            frames: Vec::new(),
+            palette: PaletteMode::default(),
+            dithering: Dithering::default(),
+        }
+    }
+
+    /// Whether GIF frames share one palette or get their own; APNG frames
+    /// keep all their colors either way.
+    pub fn palette(mut self, mode: PaletteMode) -> Self {
+        self.palette = mode;
+        self
+    }
+
+    /// Dithering used when mapping GIF frames onto their palette.
+    pub fn dithering(mut self, dithering: Dithering) -> Self {
+        self.dithering = dithering;
+        self
+    }
+
+    /// Appends `frame`, shown for `delay`.
+    pub fn push<I>(&mut self, frame: &I, delay: Duration)
+    where
+        I: Image<Pixel = Rgba<u8>>,
+    {
+        let frame = ImageBuffer::sample(frame, self.width, self.height, Layout::Interleaved);
+        self.frames.push((frame, delay));
+    }
+
+    fn check_frames(&self) -> Result<(), Error> {
+        if self.frames.is_empty() {
+            return Err(Error::Encode(
+                "an animation needs at least one frame".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Writes the frames as a GIF looping forever, with delays rounded up to
+    /// hundredths of a second.
+    pub fn write_gif(&self, writer: impl Write) -> Result<(), Error> {
+        self.check_frames()?;
+        let (Ok(width), Ok(height)) = (u16::try_from(self.width), u16::try_from(self.height))
+        else {
+            return Err(Error::Encode(format!(
+                "GIF cannot store {}×{} pixels",
+                self.width, self.height
+            )));
+        };
+
+        let transparent = self.frames.iter().any(|(frame, _)| {
+            frame
+                .samples(self.width, self.height)
+                .any(|p| p.a < GIF_ALPHA_THRESHOLD)
+        });
+        // The last index stays free for transparent pixels.
+        let colors = if transparent { 255 } else { 256 };
+        let transparent_index =
+            |palette: &Palette| transparent.then_some(palette.colors().len() as u8);
+
+        let global = (self.palette == PaletteMode::Global).then(|| {
+            let stacked = ImageBuffer::from_fn(
+                self.width,
+                self.height * self.frames.len(),
+                Layout::Interleaved,
+                |i, j| opaque(&self.frames[j / self.height].0, i, j % self.height),
+            );
+            Palette::median_cut(&stacked, colors)
+        });
+        let global_colors = global
+            .as_ref()
+            .map(|palette| palette_bytes(palette, transparent))
+            .unwrap_or_default();
+
+        let mut encoder = gif::Encoder::new(writer, width, height, &global_colors)?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+
+        for (frame, delay) in &self.frames {
+            let rgb = ImageBuffer::from_fn(self.width, self.height, Layout::Interleaved, |i, j| {
+                opaque(frame, i, j)
+            });
+            let palette = match &global {
+                Some(palette) => palette.clone(),
+                None => Palette::median_cut(&rgb, colors),
+            };
+            let indexed = quantize(&rgb, &palette, self.dithering);
+            let indices = (0..self.height)
+                .flat_map(|j| (0..self.width).map(move |i| (i, j)))
+                .map(|(i, j)| match transparent_index(&palette) {
+                    Some(index)
+                        if frame.pixel(i, j).expect("in bounds").a < GIF_ALPHA_THRESHOLD =>
+                    {
+                        index
+                    }
+                    _ => indexed.indices().pixel(i, j).expect("in bounds").0,
+                })
+                .collect::<Vec<_>>();
+
+            let mut gif_frame = match global {
+                Some(_) => gif::Frame::from_indexed_pixels(
+                    width,
+                    height,
+                    indices,
+                    transparent_index(&palette),
+                ),
+                None => gif::Frame::from_palette_pixels(
+                    width,
+                    height,
+                    indices,
+                    palette_bytes(&palette, transparent),
+                    transparent_index(&palette),
+                ),
+            };
+            gif_frame.delay = (delay.as_millis().div_ceil(10)).min(u16::MAX as u128) as u16;
+            gif_frame.dispose = gif::DisposalMethod::Background;
+            encoder.write_frame(&gif_frame)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the frames as an 8-bit RGBA APNG looping forever, keeping every
+    /// color and level of transparency.
+    pub fn write_apng(&self, writer: impl Write) -> Result<(), Error> {
+        self.check_frames()?;
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(self.frames.len() as u32, 0)?;
+
+        let mut writer = encoder.write_header()?;
+        for (frame, delay) in &self.frames {
+            let (numerator, denominator) = match u16::try_from(delay.as_millis()) {
+                Ok(milliseconds) => (milliseconds, 1000),
+                Err(_) => (delay.as_secs().min(u16::MAX as u64) as u16, 1),
+            };
+            writer.set_frame_delay(numerator, denominator)?;
+            let bytes = frame
+                .as_bytes(ChannelOrder::Rgba)
+                .expect("RGBA pixels have one byte per channel");
+            writer.write_image_data(&bytes)?;
+        }
+        Ok(writer.finish()?)
+    }
+
+    /// Writes the animation to `path`: a GIF if its extension is `gif` and
+    /// an APNG otherwise.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let gif = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+        let file = BufWriter::new(File::create(path)?);
+        if gif {
+            self.write_gif(file)
+        } else {
+            self.write_apng(file)
+        }
+    }
+}
+
+fn opaque(frame: &ImageBuffer<Rgba<u8>>, i: usize, j: usize) -> Rgb<u8> {
+    let p = frame.pixel(i, j).expect("indices are in bounds");
+    Rgb::new(p.r, p.g, p.b)
+}
+
+/// GIF color table of `palette`, ending with a black entry for transparent
+/// pixels if `transparent`.
+fn palette_bytes(palette: &Palette, transparent: bool) -> Vec<u8> {
+    let mut bytes = palette
+        .colors()
+        .iter()
+        .flat_map(|c| [c.r, c.g, c.b])
+        .collect::<Vec<_>>();
+    if transparent {
+        bytes.extend([0, 0, 0]);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::time::Duration;
+
+    use flipr::{ImageBuffer, Layout, Rgba};
+
+    use super::{FrameEncoder, PaletteMode};
+    use crate::{Error, temp_dir};
+
+    fn filled(color: Rgba<u8>) -> ImageBuffer<Rgba<u8>> {
+        ImageBuffer::filled(4, 3, Layout::Interleaved, color)
+    }
+
+    fn two_frames(mode: PaletteMode) -> FrameEncoder {
+        let mut animation = FrameEncoder::new(4, 3).palette(mode);
+        animation.push(
+            &filled(Rgba::new(255, 0, 0, 255)),
+            Duration::from_millis(100),
+        );
+        let mut blue = filled(Rgba::new(0, 0, 255, 255));
+        blue.set_pixel(1, 1, Rgba::new(0, 0, 0, 0));
+        animation.push(&blue, Duration::from_millis(250));
+        animation
+    }
+
+    fn decode_gif(bytes: &[u8]) -> (Option<Vec<u8>>, Vec<gif::Frame<'static>>) {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(bytes).unwrap();
+        let global = decoder.global_palette().map(<[u8]>::to_vec);
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frames.push(frame.clone());
+        }
+        (global, frames)
+    }
+
+    fn color(palette: &[u8], index: u8) -> [u8; 3] {
+        let start = index as usize * 3;
+        [palette[start], palette[start + 1], palette[start + 2]]
+    }
+
+    #[test]
+    fn gif_frames_share_the_global_palette() {
+        let mut bytes = Vec::new();
+        two_frames(PaletteMode::Global)
+            .write_gif(&mut bytes)
+            .unwrap();
+        let (global, frames) = decode_gif(&bytes);
+        let global = global.expect("global palette is written");
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!((frames[0].delay, frames[1].delay), (10, 25));
+        assert!(frames.iter().all(|frame| frame.palette.is_none()));
+        assert_eq!(color(&global, frames[0].buffer[0]), [255, 0, 0]);
+        assert_eq!(color(&global, frames[1].buffer[0]), [0, 0, 255]);
+
+        let transparent = frames[1].transparent.expect("transparent index is set");
+        assert_eq!(frames[1].buffer[4 + 1], transparent);
+        assert_eq!(
+            frames[1]
+                .buffer
+                .iter()
+                .filter(|&&i| i == transparent)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn gif_frames_can_have_their_own_palettes() {
+        let mut bytes = Vec::new();
+        two_frames(PaletteMode::PerFrame)
+            .write_gif(&mut bytes)
+            .unwrap();
+        let (_, frames) = decode_gif(&bytes);
+
+        for (frame, expected) in frames.iter().zip([[255, 0, 0], [0, 0, 255]]) {
+            let palette = frame.palette.as_ref().expect("local palette is written");
+            assert_eq!(color(palette, frame.buffer[0]), expected);
+        }
+    }
+
+    #[test]
+    fn apng_keeps_every_frame_exactly() {
+        let dir = temp_dir("apng");
+        let path = dir.join("frames.png");
+        let animation = two_frames(PaletteMode::Global);
+        animation.save(&path).unwrap();
+
+        let decoder = png::Decoder::new(BufReader::new(File::open(&path).unwrap()));
+        let mut reader = decoder.read_info().unwrap();
+        let control = reader.info().animation_control.expect("file is animated");
+        assert_eq!((control.num_frames, control.num_plays), (2, 0));
+
+        for (expected, delay) in &animation.frames {
+            let mut bytes = vec![0; reader.output_buffer_size().unwrap()];
+            reader.next_frame(&mut bytes).unwrap();
+            let control = reader
+                .info()
+                .frame_control
+                .expect("frame has a control chunk");
+            assert_eq!(
+                control.delay_num as u128 * 1000 / control.delay_den as u128,
+                delay.as_millis()
+            );
+            let frame = ImageBuffer::<Rgba<u8>>::from_raw(
+                &bytes,
+                4,
+                3,
+                16,
+                flipr::ChannelOrder::Rgba,
+                Layout::Interleaved,
+            )
+            .unwrap();
+            assert_eq!(&frame, expected);
+        }
+    }
+
+    #[test]
+    fn frames_are_sampled_to_the_animation_size() {
+        let mut animation = FrameEncoder::new(2, 2);
+        animation.push(&filled(Rgba::new(1, 2, 3, 4)), Duration::ZERO);
+        assert_eq!(
+            (
+                animation.frames[0].0.width(),
+                animation.frames[0].0.height()
+            ),
+            (2, 2)
+        );
+
+        let path = temp_dir("gif").join("empty.gif");
+        assert!(matches!(
+            FrameEncoder::new(2, 2).save(path),
+            Err(Error::Encode(_))
+        ));
+    }
+}
@@ -0,0 +1,90 @@
+use std::{fmt, io};
+
+/// Reason an image could not be read or written.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    Io(io::Error),
+    /// The file is damaged or not in the format it was read as.
+    Decode(String),
+    /// The format cannot store the image, for example because it is too large.
+    Encode(String),
+    /// The file is valid but its pixels do not fit the requested pixel type.
+    Unsupported(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{error}"),
+            Error::Decode(message) => write!(f, "invalid image: {message}"),
+            Error::Encode(message) => write!(f, "cannot encode image: {message}"),
+            Error::Unsupported(message) => write!(f, "unsupported image: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<png::DecodingError> for Error {
+    fn from(error: png::DecodingError) -> Self {
+        match error {
+            png::DecodingError::IoError(error) => Error::Io(error),
+            error => Error::Decode(error.to_string()),
+        }
+    }
+}
+
+impl From<png::EncodingError> for Error {
+    fn from(error: png::EncodingError) -> Self {
+        match error {
+            png::EncodingError::IoError(error) => Error::Io(error),
+            error => Error::Encode(error.to_string()),
+        }
+    }
+}
+
+impl From<gif::EncodingError> for Error {
+    fn from(error: gif::EncodingError) -> Self {
+        match error {
+            gif::EncodingError::Io(error) => Error::Io(error),
+            error => Error::Encode(error.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "tiff")]
+impl From<tiff::TiffError> for Error {
+    fn from(error: tiff::TiffError) -> Self {
+        match error {
+            tiff::TiffError::IoError(error) => Error::Io(error),
+            tiff::TiffError::UnsupportedError(error) => Error::Unsupported(error.to_string()),
+            tiff::TiffError::FormatError(error) => Error::Decode(error.to_string()),
+            error => Error::Encode(error.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "exr")]
+impl From<exr::error::Error> for Error {
+    fn from(error: exr::error::Error) -> Self {
+        match error {
+            exr::error::Error::Io(error) => Error::Io(error),
+            exr::error::Error::NotSupported(message) => Error::Unsupported(message.into()),
+            error => Error::Decode(error.to_string()),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use exr::prelude::{read_first_rgba_layer_from_file, write_rgba_file};
+use flipr::{ImageBuffer, Layout, Rgba};
+
+use crate::Error;
+
+/// Reads the first RGB or RGBA layer of an OpenEXR file, with alpha `1.0`
+/// if the layer has none.
+pub fn read_exr(path: impl AsRef<Path>) -> Result<ImageBuffer<Rgba<f32>>, Error> {
+    let image = read_first_rgba_layer_from_file(
+        path,
+        |size, _| {
+            (size.width() > 0 && size.height() > 0).then(|| {
+                ImageBuffer::filled(
+                    size.width(),
+                    size.height(),
+                    Layout::Interleaved,
+                    Rgba::default(),
+                )
+            })
+        },
+        |pixels: &mut Option<ImageBuffer<Rgba<f32>>>,
+         position,
+         (r, g, b, a): (f32, f32, f32, f32)| {
+            if let Some(pixels) = pixels {
+                pixels.set_pixel(position.x(), position.y(), Rgba::new(r, g, b, a));
+            }
+        },
+    )?;
+    image
+        .layer_data
+        .channel_data
+        .pixels
+        .ok_or_else(|| Error::Decode("the image has no pixels".into()))
+}
+
+/// Writes `image` as an OpenEXR file with 32-bit float RGBA channels.
+pub fn write_exr(path: impl AsRef<Path>, image: &ImageBuffer<Rgba<f32>>) -> Result<(), Error> {
+    Ok(write_rgba_file(
+        path,
+        image.width(),
+        image.height(),
+        |i, j| {
+            let p = image.pixel(i, j).expect("indices are in bounds");
+            (p.r, p.g, p.b, p.a)
+        },
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use flipr::{ImageBuffer, Layout, Rgba};
+
+    use super::{read_exr, write_exr};
+    use crate::{Error, temp_dir};
+
+    #[test]
+    fn high_dynamic_range_pixels_round_trip() {
+        let dir = temp_dir("exr");
+        let path = dir.join("hdr.exr");
+        let image = ImageBuffer::from_fn(6, 4, Layout::Interleaved, |i, j| {
+            Rgba::new(i as f32 * 250.5, -(j as f32) / 3.0, 1e-6, 0.5)
+        });
+        write_exr(&path, &image).unwrap();
+        assert_eq!(read_exr(&path).unwrap(), image);
+
+        assert!(matches!(
+            read_exr(dir.join("missing.exr")),
+            Err(Error::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,40 @@
+//! Reading and writing flipr images, shared by `flipr-cli` and
+//! `flipr-testing`: PNG in the pixel types it stores, plus animations written
+//! as GIF or APNG. PNG files can carry [`Metadata`] from loading to saving,
+//! and loading turns them upright by their EXIF orientation.
+//!
+//! The `tiff` feature adds multi-page TIFF and the `exr` feature OpenEXR,
+//! both keeping `u16` and `f32` samples without precision loss.
+//!
+//! ```no_run
+//! let image = flipr_io::load_png("photo.png").expect("photo is a PNG");
+//! flipr_io::write_png("copy.png", &image).expect("copy can be written");
+//! ```
+
+mod animation;
+mod error;
+#[cfg(feature = "exr")]
+mod exr;
+mod metadata;
+mod png;
+#[cfg(feature = "tiff")]
+mod tiff;
+
+pub use animation::{FrameEncoder, PaletteMode};
+pub use error::Error;
+pub use metadata::{Metadata, Orientation, WithMetadata};
+
+#[cfg(feature = "exr")]
+pub use self::exr::{read_exr, write_exr};
+pub use self::png::{
+    PngPixel, load_png, load_png_with_metadata, read_png, write_png, write_png_with_metadata,
+};
+#[cfg(feature = "tiff")]
+pub use self::tiff::{TiffPixel, read_tiff, write_tiff};
+
+#[cfg(test)]
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("flipr-io-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+
+use flipr::{AffineTransform, Image, ImageBuffer, Layout, Pixel};
+use space::Place;
+
+/// How the stored pixels of an image are turned to display it upright, as
+/// recorded by the EXIF `Orientation` tag of cameras.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    #[default]
+    Normal,
+    MirrorHorizontal,
+    Rotate180,
+    MirrorVertical,
+    /// Mirrored across the diagonal through the top-left corner.
+    Transpose,
+    /// Rotated a quarter turn clockwise.
+    Rotate90,
+    /// Mirrored across the diagonal through the top-right corner.
+    Transverse,
+    /// Rotated a quarter turn counterclockwise.
+    Rotate270,
+}
+
+impl Orientation {
+    const EXIF: [Orientation; 8] = [
+        Orientation::Normal,
+        Orientation::MirrorHorizontal,
+        Orientation::Rotate180,
+        Orientation::MirrorVertical,
+        Orientation::Transpose,
+        Orientation::Rotate90,
+        Orientation::Transverse,
+        Orientation::Rotate270,
+    ];
+
+    /// The orientation stored as `value` in the EXIF `Orientation` tag.
+    pub fn from_exif(value: u32) -> Option<Self> {
+        let index = usize::try_from(value).ok()?.checked_sub(1)?;
+        Self::EXIF.get(index).copied()
+    }
+
+    /// The value of the EXIF `Orientation` tag, from 1 to 8.
+    pub fn exif(self) -> u16 {
+        Self::EXIF
+            .iter()
+            .position(|&o| o == self)
+            .expect("every orientation has a tag") as u16
+            + 1
+    }
+
+    /// Whether the upright image is the stored one with width and height
+    /// exchanged.
+    pub fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Orientation::Transpose
+                | Orientation::Rotate90
+                | Orientation::Transverse
+                | Orientation::Rotate270
+        )
+    }
+
+    /// Moves the pixels of a stored `width × height` image to where they are
+    /// displayed, with the upright image again starting at the origin.
+    pub fn transform(self, width: usize, height: usize) -> AffineTransform {
+        let (w, h) = (width as f64, height as f64);
+        AffineTransform::new(match self {
+            Orientation::Normal => [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            Orientation::MirrorHorizontal => [[-1.0, 0.0, w], [0.0, 1.0, 0.0]],
+            Orientation::Rotate180 => [[-1.0, 0.0, w], [0.0, -1.0, h]],
+            Orientation::MirrorVertical => [[1.0, 0.0, 0.0], [0.0, -1.0, h]],
+            Orientation::Transpose => [[0.0, 1.0, 0.0], [1.0, 0.0, 0.0]],
+            Orientation::Rotate90 => [[0.0, -1.0, h], [1.0, 0.0, 0.0]],
+            Orientation::Transverse => [[0.0, -1.0, h], [-1.0, 0.0, w]],
+            Orientation::Rotate270 => [[0.0, 1.0, 0.0], [-1.0, 0.0, w]],
+        })
+    }
+
+    /// Turns the stored `image` upright.
+    pub fn apply<P: Pixel>(self, image: ImageBuffer<P>) -> ImageBuffer<P> {
+        if self == Orientation::Normal {
+            return image;
+        }
+        let (width, height) = (image.width(), image.height());
+        let (upright_width, upright_height) = if self.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        let layout = image.layout();
+        ImageBuffer::sample(
+            &image.transform(self.transform(width, height)),
+            upright_width,
+            upright_height,
+            layout,
+        )
+    }
+}
+
+/// Metadata of an image file, carried from loading to saving.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    pub orientation: Orientation,
+    /// Horizontal and vertical resolution in dots per inch.
+    pub dpi: Option<(f64, f64)>,
+    /// ICC profile describing the color space of the pixels.
+    pub icc_profile: Option<Vec<u8>>,
+    /// Text tags such as `Title`, `Author` or `Software`.
+    pub tags: BTreeMap<String, String>,
+}
+
+/// Image together with the [`Metadata`] of the file it was loaded from, so
+/// that processing it keeps the metadata for saving.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithMetadata<I> {
+    pub image: I,
+    pub metadata: Metadata,
+}
+
+impl<I> WithMetadata<I> {
+    pub fn new(image: I, metadata: Metadata) -> Self {
+        Self { image, metadata }
+    }
+
+    /// Processes the image, keeping the metadata.
+    pub fn map<J>(self, f: impl FnOnce(I) -> J) -> WithMetadata<J> {
+        WithMetadata::new(f(self.image), self.metadata)
+    }
+
+    /// Samples the image onto a `width × height` grid, keeping the metadata.
+    pub fn materialize(&self, width: usize, height: usize) -> WithMetadata<ImageBuffer<I::Pixel>>
+    where
+        I: Image,
+        I::Pixel: Pixel,
+    {
+        WithMetadata::new(
+            ImageBuffer::sample(&self.image, width, height, Layout::Interleaved),
+            self.metadata.clone(),
+        )
+    }
+}
+
+impl<I: Image> Image for WithMetadata<I> {
+    type Pixel = I::Pixel;
+
+    fn get(&self, p: Place) -> Self::Pixel {
+        self.image.get(p)
+    }
+
+    fn as_buffer(&self) -> Option<&ImageBuffer<Self::Pixel>>
+    where
+        Self::Pixel: Pixel,
+    {
+        self.image.as_buffer()
+    }
+}
+
+/// EXIF orientation stored in a raw EXIF block, the TIFF structure without
+/// the `Exif\0\0` prefix of JPEG files.
+pub(crate) fn exif_orientation(exif: &[u8]) -> Option<Orientation> {
+    let exif = exif::Reader::new().read_raw(exif.to_vec()).ok()?;
+    let field = exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?;
+    Orientation::from_exif(field.value.get_uint(0)?)
+}
+
+/// Raw EXIF block holding nothing but `orientation`.
+pub(crate) fn exif_block(orientation: Orientation) -> Vec<u8> {
+    const ORIENTATION_TAG: u16 = 0x0112;
+    const SHORT: u16 = 3;
+
+    // Big-endian TIFF header pointing at one directory with one entry.
+    let mut bytes = b"MM\0\x2a\0\0\0\x08".to_vec();
+    bytes.extend(1u16.to_be_bytes());
+    bytes.extend(ORIENTATION_TAG.to_be_bytes());
+    bytes.extend(SHORT.to_be_bytes());
+    bytes.extend(1u32.to_be_bytes());
+    // The value fills the first half of the 4-byte value field.
+    bytes.extend(orientation.exif().to_be_bytes());
+    bytes.extend([0, 0]);
+    // No further directories.
+    bytes.extend(0u32.to_be_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use flipr::{Gray, Image, ImageBuffer, Layout, Lut};
+
+    use super::{Metadata, Orientation, WithMetadata, exif_block, exif_orientation};
+
+    /// Stored pixel shown at upright `(x, y)`, following the EXIF definitions.
+    fn stored(
+        orientation: Orientation,
+        (w, h): (usize, usize),
+        (x, y): (usize, usize),
+    ) -> (usize, usize) {
+        match orientation {
+            Orientation::Normal => (x, y),
+            Orientation::MirrorHorizontal => (w - 1 - x, y),
+            Orientation::Rotate180 => (w - 1 - x, h - 1 - y),
+            Orientation::MirrorVertical => (x, h - 1 - y),
+            Orientation::Transpose => (y, x),
+            Orientation::Rotate90 => (y, h - 1 - x),
+            Orientation::Transverse => (w - 1 - y, h - 1 - x),
+            Orientation::Rotate270 => (w - 1 - y, x),
+        }
+    }
+
+    #[test]
+    fn orientations_turn_images_upright() {
+        let image = ImageBuffer::from_fn(4, 3, Layout::Interleaved, |i, j| Gray((j * 4 + i) as u8));
+
+        for value in 1..=8 {
+            let orientation = Orientation::from_exif(value).unwrap();
+            assert_eq!(orientation.exif() as u32, value);
+
+            let upright = orientation.apply(image.clone());
+            let expected = if orientation.swaps_axes() {
+                (3, 4)
+            } else {
+                (4, 3)
+            };
+            assert_eq!((upright.width(), upright.height()), expected);
+            for y in 0..upright.height() {
+                for x in 0..upright.width() {
+                    let (i, j) = stored(orientation, (4, 3), (x, y));
+                    assert_eq!(
+                        upright.pixel(x, y),
+                        image.pixel(i, j),
+                        "{orientation:?} at {x}, {y}"
+                    );
+                }
+            }
+        }
+        assert_eq!(Orientation::from_exif(0), None);
+        assert_eq!(Orientation::from_exif(9), None);
+    }
+
+    #[test]
+    fn exif_blocks_hold_the_orientation() {
+        for value in 1..=8 {
+            let orientation = Orientation::from_exif(value).unwrap();
+            assert_eq!(
+                exif_orientation(&exif_block(orientation)),
+                Some(orientation)
+            );
+        }
+        assert_eq!(exif_orientation(b"not exif"), None);
+    }
+
+    #[test]
+    fn processing_keeps_the_metadata() {
+        let metadata = Metadata {
+            dpi: Some((300.0, 300.0)),
+            ..Metadata::default()
+        };
+        let loaded = WithMetadata::new(
+            ImageBuffer::filled(4, 4, Layout::Interleaved, Gray(9u8)),
+            metadata.clone(),
+        );
+        let processed = loaded
+            .map(|image| image.apply_lut(Lut::from_fn(|v| 255 - v)))
+            .materialize(2, 2);
+        assert_eq!(processed.metadata, metadata);
+        assert_eq!(processed.image.pixel(1, 1), Some(Gray(246)));
+    }
+}
@@ -0,0 +1,269 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use flipr::{ChannelOrder, Gray, ImageBuffer, Layout, Pixel, Rgb, Rgba};
+
+use crate::metadata::{exif_block, exif_orientation};
+use crate::{Error, Metadata, Orientation, WithMetadata};
+
+const METERS_PER_INCH: f64 = 0.0254;
+
+/// `u8` pixel type that PNG can store without conversion.
+pub trait PngPixel: Pixel<Scalar = u8> {
+    #[doc(hidden)]
+    const COLOR: png::ColorType;
+    #[doc(hidden)]
+    const ORDER: ChannelOrder;
+}
+
+impl PngPixel for Gray<u8> {
+    const COLOR: png::ColorType = png::ColorType::Grayscale;
+    const ORDER: ChannelOrder = ChannelOrder::Gray;
+}
+
+impl PngPixel for Rgb<u8> {
+    const COLOR: png::ColorType = png::ColorType::Rgb;
+    const ORDER: ChannelOrder = ChannelOrder::Rgb;
+}
+
+impl PngPixel for Rgba<u8> {
+    const COLOR: png::ColorType = png::ColorType::Rgba;
+    const ORDER: ChannelOrder = ChannelOrder::Rgba;
+}
+
+/// Writes `image` as an 8-bit PNG.
+pub fn write_png<P: PngPixel>(path: impl AsRef<Path>, image: &ImageBuffer<P>) -> Result<(), Error> {
+    write_png_with_metadata(path, image, &Metadata::default())
+}
+
+/// Writes `image` as an 8-bit PNG recording `metadata`: the orientation as
+/// EXIF, the resolution, the ICC profile and the tags as text chunks.
+pub fn write_png_with_metadata<P: PngPixel>(
+    path: impl AsRef<Path>,
+    image: &ImageBuffer<P>,
+    metadata: &Metadata,
+) -> Result<(), Error> {
+    let mut info = png::Info::with_size(image.width() as u32, image.height() as u32);
+    info.color_type = P::COLOR;
+    info.bit_depth = png::BitDepth::Eight;
+    info.pixel_dims = metadata.dpi.map(|(x, y)| png::PixelDimensions {
+        xppu: (x / METERS_PER_INCH).round() as u32,
+        yppu: (y / METERS_PER_INCH).round() as u32,
+        unit: png::Unit::Meter,
+    });
+    info.icc_profile = metadata.icc_profile.as_deref().map(Cow::Borrowed);
+    info.exif_metadata = (metadata.orientation != Orientation::Normal)
+        .then(|| Cow::Owned(exif_block(metadata.orientation)));
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::with_info(file, info)?;
+    for (key, value) in &metadata.tags {
+        // tEXt is the most widely read but only holds Latin-1.
+        if value.chars().all(|c| (c as u32) <= 0xFF) {
+            encoder.add_text_chunk(key.clone(), value.clone())?;
+        } else {
+            encoder.add_itxt_chunk(key.clone(), value.clone())?;
+        }
+    }
+
+    let bytes = image
+        .as_bytes(P::ORDER)
+        .expect("PNG pixels have one byte per channel");
+    Ok(encoder.write_header()?.write_image_data(&bytes)?)
+}
+
+/// Reads an 8-bit PNG whose color type matches `P`.
+pub fn read_png<P: PngPixel>(path: impl AsRef<Path>) -> Result<ImageBuffer<P>, Error> {
+    let mut reader = png::Decoder::new(BufReader::new(File::open(path)?)).read_info()?;
+    let mut bytes = vec![0; reader.output_buffer_size().unwrap_or(0)];
+    let info = reader.next_frame(&mut bytes)?;
+
+    if info.color_type != P::COLOR || info.bit_depth != png::BitDepth::Eight {
+        return Err(Error::Unsupported(format!(
+            "expected 8-bit {:?}, found {:?}-bit {:?}",
+            P::COLOR,
+            info.bit_depth,
+            info.color_type
+        )));
+    }
+
+    ImageBuffer::from_raw(
+        &bytes,
+        info.width as usize,
+        info.height as usize,
+        info.line_size,
+        P::ORDER,
+        Layout::Interleaved,
+    )
+    .map_err(|error| Error::Decode(error.to_string()))
+}
+
+/// Reads a PNG of any color type and bit depth as straight 8-bit RGBA,
+/// turned upright by its EXIF orientation.
+pub fn load_png(path: impl AsRef<Path>) -> Result<ImageBuffer<Rgba<u8>>, Error> {
+    Ok(load_png_with_metadata(path)?.image)
+}
+
+/// [`load_png`] together with the metadata of the file. The orientation is
+/// applied to the pixels, so the metadata reports [`Orientation::Normal`].
+pub fn load_png_with_metadata(
+    path: impl AsRef<Path>,
+) -> Result<WithMetadata<ImageBuffer<Rgba<u8>>>, Error> {
+    let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+
+    let mut bytes = vec![0; reader.output_buffer_size().unwrap_or(0)];
+    let output = reader.next_frame(&mut bytes)?;
+    let (width, height) = (output.width as usize, output.height as usize);
+    if width == 0 || height == 0 {
+        return Err(Error::Decode("the image has no pixels".into()));
+    }
+    // Text chunks may follow the pixels.
+    reader.finish()?;
+
+    let channels = output.color_type.samples();
+    let stored = ImageBuffer::from_fn(width, height, Layout::Interleaved, |i, j| {
+        let start = j * output.line_size + i * channels;
+        let p = &bytes[start..start + channels];
+        match channels {
+            1 => Rgba::new(p[0], p[0], p[0], u8::MAX),
+            2 => Rgba::new(p[0], p[0], p[0], p[1]),
+            3 => Rgba::new(p[0], p[1], p[2], u8::MAX),
+            _ => Rgba::new(p[0], p[1], p[2], p[3]),
+        }
+    });
+
+    let info = reader.info();
+    let orientation = info
+        .exif_metadata
+        .as_deref()
+        .and_then(exif_orientation)
+        .unwrap_or_default();
+    let dpi = info
+        .pixel_dims
+        .filter(|dims| dims.unit == png::Unit::Meter)
+        .map(|dims| {
+            let (x, y) = (
+                dims.xppu as f64 * METERS_PER_INCH,
+                dims.yppu as f64 * METERS_PER_INCH,
+            );
+            if orientation.swaps_axes() {
+                (y, x)
+            } else {
+                (x, y)
+            }
+        });
+
+    let mut tags = BTreeMap::new();
+    for chunk in &info.uncompressed_latin1_text {
+        tags.insert(chunk.keyword.clone(), chunk.text.clone());
+    }
+    for chunk in &info.compressed_latin1_text {
+        tags.insert(chunk.keyword.clone(), chunk.get_text()?);
+    }
+    for chunk in &info.utf8_text {
+        tags.insert(chunk.keyword.clone(), chunk.get_text()?);
+    }
+
+    let metadata = Metadata {
+        orientation: Orientation::Normal,
+        dpi,
+        icc_profile: info.icc_profile.as_ref().map(|profile| profile.to_vec()),
+        tags,
+    };
+    Ok(WithMetadata::new(orientation.apply(stored), metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use flipr::{Gray, ImageBuffer, Layout, Rgb, Rgba};
+
+    use super::{load_png, load_png_with_metadata, read_png, write_png, write_png_with_metadata};
+    use crate::{Error, Metadata, Orientation, temp_dir};
+
+    #[test]
+    fn pixels_round_trip_in_their_own_color_type() {
+        let dir = temp_dir("png");
+        let image = ImageBuffer::from_fn(5, 3, Layout::Interleaved, |i, j| {
+            Rgb::new(i as u8 * 40, j as u8 * 80, 7)
+        });
+        write_png(dir.join("rgb.png"), &image).unwrap();
+        assert_eq!(read_png::<Rgb<u8>>(dir.join("rgb.png")).unwrap(), image);
+        assert!(matches!(
+            read_png::<Gray<u8>>(dir.join("rgb.png")),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            read_png::<Rgb<u8>>(dir.join("missing.png")),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn any_png_loads_as_rgba() {
+        let dir = temp_dir("load");
+        let gray = ImageBuffer::from_fn(4, 2, Layout::Interleaved, |i, j| {
+            Gray(i as u8 * 60 + j as u8)
+        });
+        write_png(dir.join("gray.png"), &gray).unwrap();
+
+        let rgba = load_png(dir.join("gray.png")).unwrap();
+        assert_eq!((rgba.width(), rgba.height()), (4, 2));
+        assert_eq!(rgba.pixel(3, 1), Some(Rgba::new(181, 181, 181, 255)));
+    }
+
+    #[test]
+    fn metadata_round_trips() {
+        let dir = temp_dir("metadata");
+        let image = ImageBuffer::filled(3, 2, Layout::Interleaved, Gray(40u8));
+        let metadata = Metadata {
+            dpi: Some((300.0, 150.0)),
+            icc_profile: Some(b"not really a profile".to_vec()),
+            tags: [("Title", "Ramp"), ("Author", "Jiří")]
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .into(),
+            ..Metadata::default()
+        };
+        write_png_with_metadata(dir.join("tagged.png"), &image, &metadata).unwrap();
+
+        let loaded = load_png_with_metadata(dir.join("tagged.png")).unwrap();
+        assert_eq!(loaded.image.pixel(2, 1), Some(Rgba::new(40, 40, 40, 255)));
+        let (x, y) = loaded.metadata.dpi.unwrap();
+        assert!((x - 300.0).abs() < 0.02 && (y - 150.0).abs() < 0.02);
+        assert_eq!(loaded.metadata.icc_profile, metadata.icc_profile);
+        assert_eq!(loaded.metadata.tags, metadata.tags);
+    }
+
+    #[test]
+    fn exif_orientation_is_applied_on_load() {
+        let dir = temp_dir("orientation");
+        let stored =
+            ImageBuffer::from_fn(3, 2, Layout::Interleaved, |i, j| Gray((j * 3 + i) as u8));
+        let metadata = Metadata {
+            orientation: Orientation::Rotate90,
+            dpi: Some((300.0, 100.0)),
+            ..Metadata::default()
+        };
+        write_png_with_metadata(dir.join("rotated.png"), &stored, &metadata).unwrap();
+        assert_eq!(
+            read_png::<Gray<u8>>(dir.join("rotated.png")).unwrap(),
+            stored
+        );
+
+        let loaded = load_png_with_metadata(dir.join("rotated.png")).unwrap();
+        assert_eq!(loaded.metadata.orientation, Orientation::Normal);
+        let (x, y) = loaded.metadata.dpi.unwrap();
+        assert!((x - 100.0).abs() < 0.02 && (y - 300.0).abs() < 0.02);
+
+        // A quarter turn clockwise puts the bottom-left pixel at the top-left.
+        let upright = loaded.image;
+        assert_eq!((upright.width(), upright.height()), (2, 3));
+        assert_eq!(upright.pixel(0, 0), Some(Rgba::new(3, 3, 3, 255)));
+        assert_eq!(upright.pixel(1, 2), Some(Rgba::new(2, 2, 2, 255)));
+        assert_eq!(load_png(dir.join("rotated.png")).unwrap(), upright);
+    }
+}
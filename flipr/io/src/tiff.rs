@@ -0,0 +1,175 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Seek, Write};
+use std::path::Path;
+
+use flipr::{Gray, ImageBuffer, Layout, Pixel, Rgb, Rgba};
+use tiff::TiffResult;
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::encoder::{TiffEncoder, colortype};
+
+use crate::Error;
+
+/// Pixel type TIFF can store without conversion: gray, RGB or RGBA with
+/// `u8`, `u16` or `f32` channels.
+pub trait TiffPixel: Pixel {
+    #[doc(hidden)]
+    fn decode(color: tiff::ColorType, samples: DecodingResult) -> Option<Vec<Self::Scalar>>;
+    #[doc(hidden)]
+    fn encode<W: Write + Seek>(
+        encoder: &mut TiffEncoder<W>,
+        width: u32,
+        height: u32,
+        samples: &[Self::Scalar],
+    ) -> TiffResult<()>;
+}
+
+macro_rules! tiff_pixel {
+    ($($pixel:ident<$scalar:ty>: $color:ident, $encoded:ident, $decoded:ident;)*) => {$(
+        impl TiffPixel for $pixel<$scalar> {
+            fn decode(color: tiff::ColorType, samples: DecodingResult) -> Option<Vec<$scalar>> {
+                let bits = 8 * size_of::<$scalar>() as u8;
+                match samples {
+                    DecodingResult::$decoded(samples) if color == tiff::ColorType::$color(bits) => {
+                        Some(samples)
+                    }
+                    _ => None,
+                }
+            }
+
+            fn encode<W: Write + Seek>(
+                encoder: &mut TiffEncoder<W>,
+                width: u32,
+                height: u32,
+                samples: &[$scalar],
+            ) -> TiffResult<()> {
+                encoder.write_image::<colortype::$encoded>(width, height, samples)
+            }
+        }
+    )*};
+}
+
+tiff_pixel! {
+    Gray<u8>: Gray, Gray8, U8;
+    Gray<u16>: Gray, Gray16, U16;
+    Gray<f32>: Gray, Gray32Float, F32;
+    Rgb<u8>: RGB, RGB8, U8;
+    Rgb<u16>: RGB, RGB16, U16;
+    Rgb<f32>: RGB, RGB32Float, F32;
+    Rgba<u8>: RGBA, RGBA8, U8;
+    Rgba<u16>: RGBA, RGBA16, U16;
+    Rgba<f32>: RGBA, RGBA32Float, F32;
+}
+
+/// Reads every page of a TIFF whose samples match `P` exactly, so 16-bit
+/// and float data keep their full precision.
+pub fn read_tiff<P: TiffPixel>(path: impl AsRef<Path>) -> Result<Vec<ImageBuffer<P>>, Error> {
+    let mut decoder = Decoder::new(BufReader::new(File::open(path)?))?;
+    let mut pages = Vec::new();
+
+    loop {
+        let (width, height) = decoder.dimensions()?;
+        let (width, height) = (width as usize, height as usize);
+        let color = decoder.colortype()?;
+        let samples = P::decode(color, decoder.read_image()?).ok_or_else(|| {
+            Error::Unsupported(format!(
+                "page {} holds {color:?} samples of another type",
+                pages.len()
+            ))
+        })?;
+        if width == 0 || height == 0 || samples.len() < width * height * P::CHANNELS {
+            return Err(Error::Decode(format!(
+                "page {} has no pixels or too few samples",
+                pages.len()
+            )));
+        }
+
+        pages.push(ImageBuffer::from_fn(
+            width,
+            height,
+            Layout::Interleaved,
+            |i, j| {
+                let start = (j * width + i) * P::CHANNELS;
+                P::from_channels(|c| samples[start + c])
+            },
+        ));
+
+        if !decoder.more_images() {
+            return Ok(pages);
+        }
+        decoder.next_image()?;
+    }
+}
+
+/// Writes `pages` as the pages of one uncompressed TIFF.
+pub fn write_tiff<P: TiffPixel>(
+    path: impl AsRef<Path>,
+    pages: &[ImageBuffer<P>],
+) -> Result<(), Error> {
+    if pages.is_empty() {
+        return Err(Error::Encode("a TIFF needs at least one page".into()));
+    }
+
+    let mut encoder = TiffEncoder::new(BufWriter::new(File::create(path)?))?;
+    for page in pages {
+        let samples = (0..page.height())
+            .flat_map(|j| (0..page.width()).map(move |i| (i, j)))
+            .flat_map(|(i, j)| {
+                let p = page.pixel(i, j).expect("indices are in bounds");
+                (0..P::CHANNELS).map(move |c| p.channel(c))
+            })
+            .collect::<Vec<_>>();
+        P::encode(
+            &mut encoder,
+            page.width() as u32,
+            page.height() as u32,
+            &samples,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use flipr::{Gray, ImageBuffer, Layout, Rgb, Rgba};
+
+    use super::{read_tiff, write_tiff};
+    use crate::{Error, temp_dir};
+
+    #[test]
+    fn pages_round_trip_without_precision_loss() {
+        let dir = temp_dir("tiff");
+        let path = dir.join("pages.tiff");
+        let pages = [
+            ImageBuffer::from_fn(5, 3, Layout::Interleaved, |i, j| {
+                Rgb::new(i as u16 * 13_000, j as u16 * 30_001, 65_535)
+            }),
+            ImageBuffer::filled(2, 7, Layout::Interleaved, Rgb::new(1, 2, 3)),
+        ];
+        write_tiff(&path, &pages).unwrap();
+        assert_eq!(read_tiff::<Rgb<u16>>(&path).unwrap(), pages);
+
+        assert!(matches!(
+            read_tiff::<Rgb<u8>>(&path),
+            Err(Error::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn float_samples_keep_values_outside_the_unit_range() {
+        let dir = temp_dir("tiff-float");
+        let gray = [ImageBuffer::from_fn(4, 4, Layout::Interleaved, |i, j| {
+            Gray(i as f32 * -0.1 + j as f32 * 1e3)
+        })];
+        write_tiff(dir.join("gray.tiff"), &gray).unwrap();
+        assert_eq!(read_tiff::<Gray<f32>>(dir.join("gray.tiff")).unwrap(), gray);
+
+        let rgba = [ImageBuffer::filled(
+            3,
+            2,
+            Layout::Interleaved,
+            Rgba::new(0.5f32, 2.5, -1.0, 0.25),
+        )];
+        write_tiff(dir.join("rgba.tiff"), &rgba).unwrap();
+        assert_eq!(read_tiff::<Rgba<f32>>(dir.join("rgba.tiff")).unwrap(), rgba);
+    }
+}
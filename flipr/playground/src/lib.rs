@@ -0,0 +1,112 @@
+//! Tunable parameters of the operations of a `flipr-cli` pipeline, which the
+//! `flipr-playground` application, built with the `gui` feature, shows as
+//! sliders and re-runs the pipeline with whenever one moves.
+
+use std::ops::RangeInclusive;
+
+use flipr_cli::Op;
+
+/// One adjustable number of an operation and the range it may take.
+#[derive(Debug)]
+pub enum Knob<'a> {
+    Float(&'static str, &'a mut f32, RangeInclusive<f32>),
+    Double(&'static str, &'a mut f64, RangeInclusive<f64>),
+    Count(&'static str, &'a mut usize, RangeInclusive<usize>),
+}
+
+impl Knob<'_> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Knob::Float(name, ..) | Knob::Double(name, ..) | Knob::Count(name, ..) => name,
+        }
+    }
+}
+
+/// Something with numbers worth tuning by hand.
+///
+/// Every value within the ranges of [`knobs`](Self::knobs) must be valid,
+/// so ranges may depend on the other values, like the black point of
+/// [`Op::Levels`] staying below its white point.
+pub trait Tunable {
+    fn knobs(&mut self) -> Vec<Knob<'_>>;
+}
+
+/// Largest width or height the resizing operations offer.
+const MAX_SIZE: usize = 4096;
+
+impl Tunable for Op {
+    fn knobs(&mut self) -> Vec<Knob<'_>> {
+        match self {
+            Op::Resize(width, height) | Op::Carve(width, height) => vec![
+                Knob::Count("width", width, 1..=MAX_SIZE),
+                Knob::Count("height", height, 1..=MAX_SIZE),
+            ],
+            Op::Blur(sigma) | Op::Sharpen(sigma) => vec![Knob::Double("sigma", sigma, 0.0..=20.0)],
+            Op::Saturate(factor) | Op::Vibrance(factor) => {
+                vec![Knob::Float("factor", factor, 0.0..=3.0)]
+            }
+            Op::Rotate(degrees) => vec![Knob::Double("degrees", degrees, -180.0..=180.0)],
+            Op::Hue(degrees) => vec![Knob::Float("degrees", degrees, -180.0..=180.0)],
+            Op::Levels(black, white, gamma) => {
+                const GAP: f32 = 0.01;
+                let (b, w) = (*black, *white);
+                vec![
+                    Knob::Float("black", black, 0.0..=w - GAP),
+                    Knob::Float("white", white, b + GAP..=1.0),
+                    Knob::Float("gamma", gamma, 0.1..=5.0),
+                ]
+            }
+            Op::ToneMap(_) => Vec::new(),
+        }
+    }
+}
+
+impl<T: Tunable> Tunable for [T] {
+    fn knobs(&mut self) -> Vec<Knob<'_>> {
+        self.iter_mut().flat_map(Tunable::knobs).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flipr::ToneMap;
+    use flipr_cli::{Op, parse_ops};
+
+    use super::{Knob, Tunable};
+
+    #[test]
+    fn knobs_edit_the_operation_in_place() {
+        let mut op = Op::Levels(0.1, 0.9, 1.0);
+        if let [_, Knob::Float(name, white, range), _] = &mut op.knobs()[..] {
+            assert_eq!(*name, "white");
+            assert_eq!(*range.start(), 0.11);
+            **white = 0.8;
+        } else {
+            panic!("levels has three float knobs");
+        }
+        assert_eq!(op, Op::Levels(0.1, 0.8, 1.0));
+        assert!(Op::ToneMap(ToneMap::Aces).knobs().is_empty());
+    }
+
+    #[test]
+    fn defaults_of_a_pipeline_lie_in_their_ranges() {
+        let mut ops =
+            parse_ops("resize=640x480,blur=2,hue=30,levels=0.1:0.9:1.2,tonemap=aces").unwrap();
+        let knobs = ops.knobs();
+        let names: Vec<_> = knobs.iter().map(Knob::name).collect();
+        assert_eq!(
+            names,
+            [
+                "width", "height", "sigma", "degrees", "black", "white", "gamma"
+            ]
+        );
+        for knob in knobs {
+            let inside = match knob {
+                Knob::Float(_, value, range) => range.contains(value),
+                Knob::Double(_, value, range) => range.contains(value),
+                Knob::Count(_, value, range) => range.contains(value),
+            };
+            assert!(inside);
+        }
+    }
+}
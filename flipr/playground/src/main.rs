@@ -0,0 +1,166 @@
+//! Interactive playground: loads a PNG, shows every operation of a pipeline
+//! with a slider per parameter and re-runs the pipeline whenever one moves.
+//!
+//! Usage: `flipr-playground INPUT.png [OPS]`, with `OPS` in the `--ops`
+//! language of `flipr-cli`, such as `blur=2,saturate=1.2`.
+
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use flipr::{Dither, Image, ImageBuffer, Layout, Rgba};
+use flipr_cli::{Op, load_png, parse_ops};
+use flipr_playground::{Knob, Tunable};
+
+/// Operations the "add" menu offers, with their starting parameters.
+const TEMPLATES: [Op; 8] = [
+    Op::Rotate(15.0),
+    Op::Blur(2.0),
+    Op::Sharpen(1.0),
+    Op::Saturate(1.2),
+    Op::Vibrance(1.2),
+    Op::Hue(30.0),
+    Op::Levels(0.05, 0.95, 1.0),
+    Op::ToneMap(flipr::ToneMap::Reinhard),
+];
+
+struct Playground {
+    source: ImageBuffer<Rgba<f32>>,
+    ops: Vec<Op>,
+    texture: Option<egui::TextureHandle>,
+    elapsed: Duration,
+    dirty: bool,
+}
+
+impl Playground {
+    /// Runs the pipeline and uploads the result.
+    fn render(&mut self, ctx: &egui::Context) {
+        let start = Instant::now();
+        let result = self
+            .ops
+            .iter()
+            .fold(self.source.clone(), |image, op| op.apply(image));
+        let (width, height) = (result.width(), result.height());
+        let bytes = ImageBuffer::sample(
+            &result.to_u8(Dither::None),
+            width,
+            height,
+            Layout::Interleaved,
+        )
+        .pixels()
+        .flat_map(|Rgba { r, g, b, a }| [r, g, b, a])
+        .collect::<Vec<u8>>();
+        self.elapsed = start.elapsed();
+
+        let image = egui::ColorImage::from_rgba_unmultiplied([width, height], &bytes);
+        match &mut self.texture {
+            Some(texture) => texture.set(image, egui::TextureOptions::NEAREST),
+            None => {
+                self.texture =
+                    Some(ctx.load_texture("result", image, egui::TextureOptions::NEAREST));
+            }
+        }
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        let mut removed = None;
+        for (n, op) in self.ops.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.strong(op.name());
+                if ui.small_button("✖").clicked() {
+                    removed = Some(n);
+                }
+            });
+            for knob in op.knobs() {
+                let name = knob.name();
+                let slider = match knob {
+                    Knob::Float(_, value, range) => egui::Slider::new(value, range),
+                    Knob::Double(_, value, range) => egui::Slider::new(value, range),
+                    Knob::Count(_, value, range) => egui::Slider::new(value, range),
+                };
+                self.dirty |= ui.add(slider.text(name)).changed();
+            }
+            ui.separator();
+        }
+        if let Some(n) = removed {
+            self.ops.remove(n);
+            self.dirty = true;
+        }
+
+        ui.menu_button("add", |ui| {
+            for template in TEMPLATES {
+                if ui.button(template.name()).clicked() {
+                    self.ops.push(template);
+                    self.dirty = true;
+                    ui.close();
+                }
+            }
+        });
+        ui.label(format!(
+            "rendered in {:.1} ms",
+            self.elapsed.as_secs_f64() * 1e3
+        ));
+    }
+}
+
+impl eframe::App for Playground {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::SidePanel::left("pipeline").show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| self.controls(ui));
+        });
+        if self.dirty || self.texture.is_none() {
+            self.dirty = false;
+            self.render(ctx);
+        }
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(texture) = &self.texture {
+                egui::ScrollArea::both().show(ui, |ui| ui.image(texture));
+            }
+        });
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (input, script) = match &args[..] {
+        [input] => (input, ""),
+        [input, script] => (input, script.as_str()),
+        _ => {
+            eprintln!("usage: flipr-playground INPUT.png [OPS]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let setup = load_png(input.as_ref()).and_then(|source| {
+        let ops = parse_ops(script)?;
+        let (width, height) = (source.width(), source.height());
+        let source = ImageBuffer::sample(&source.to_float(), width, height, Layout::Interleaved);
+        Ok(Playground {
+            source,
+            ops,
+            texture: None,
+            elapsed: Duration::ZERO,
+            dirty: true,
+        })
+    });
+    let playground = match setup {
+        Ok(playground) => playground,
+        Err(message) => {
+            eprintln!("error: {message}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let outcome = eframe::run_native(
+        "flipr playground",
+        eframe::NativeOptions::default(),
+        Box::new(|_| Ok(Box::new(playground))),
+    );
+    match outcome {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
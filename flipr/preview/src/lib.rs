@@ -0,0 +1,215 @@
+//! Window showing the output of a flipr pipeline and re-rendering it
+//! whenever its parameters change, for tuning sigmas and thresholds by eye.
+//!
+//! ```no_run
+//! use flipr::{Gray, ImageBuffer, Layout};
+//! use flipr_preview::{Key, Params, Preview};
+//!
+//! let params = Params::new(1.0f64);
+//! Preview::new("threshold", params)
+//!     .on_key(Key::Up, |t| *t += 0.05)
+//!     .on_key(Key::Down, |t| *t -= 0.05)
+//!     .run(|&t| {
+//!         ImageBuffer::from_fn(256, 64, Layout::Interleaved, |i, _| {
+//!             Gray(if i as f64 / 256.0 < t { 0u8 } else { 255 })
+//!         })
+//!     })
+//!     .unwrap();
+//! ```
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use flipr::{ImageBuffer, Pixel, SrgbChannel};
+pub use minifb::{Error, Key};
+use minifb::{KeyRepeat, Window, WindowOptions};
+
+/// Parameters of a previewed pipeline, shared between the window and
+/// whatever tunes them: key bindings, another thread or a file watcher.
+///
+/// Clones share the same value; every [`update`](Self::update) makes the
+/// window render again.
+#[derive(Debug)]
+pub struct Params<S> {
+    shared: Arc<Mutex<(S, u64)>>,
+}
+
+impl<S> Clone for Params<S> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<S: Clone> Params<S> {
+    pub fn new(value: S) -> Self {
+        Self {
+            shared: Arc::new(Mutex::new((value, 0))),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, (S, u64)> {
+        self.shared.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// A copy of the current value.
+    pub fn get(&self) -> S {
+        self.lock().0.clone()
+    }
+
+    /// Changes the value with `f`.
+    pub fn update(&self, f: impl FnOnce(&mut S)) {
+        let mut guard = self.lock();
+        f(&mut guard.0);
+        guard.1 += 1;
+    }
+
+    /// The current value and how many updates led to it.
+    fn snapshot(&self) -> (S, u64) {
+        let guard = self.lock();
+        (guard.0.clone(), guard.1)
+    }
+}
+
+/// `image` as the `0RGB` words of a window framebuffer, with alpha
+/// composited over black.
+///
+/// Pixels with fewer than three channels show their first channel as gray;
+/// those with two or four take the last channel as alpha.
+pub fn framebuffer<P>(image: &ImageBuffer<P>) -> Vec<u32>
+where
+    P: Pixel,
+    P::Scalar: SrgbChannel,
+{
+    let byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u32;
+    image
+        .pixels()
+        .map(|pixel| {
+            let channel = |c: usize| pixel.channel(c).normalized();
+            let alpha = match P::CHANNELS {
+                2 | 4 => channel(P::CHANNELS - 1),
+                _ => 1.0,
+            };
+            let [r, g, b] = if P::CHANNELS < 3 {
+                [channel(0); 3]
+            } else {
+                [channel(0), channel(1), channel(2)]
+            };
+            (byte(r * alpha) << 16) | (byte(g * alpha) << 8) | byte(b * alpha)
+        })
+        .collect()
+}
+
+type Binding<S> = (Key, Box<dyn Fn(&mut S)>);
+
+/// Preview window, see the [crate documentation](crate).
+pub struct Preview<S> {
+    title: String,
+    params: Params<S>,
+    bindings: Vec<Binding<S>>,
+}
+
+impl<S: Clone> Preview<S> {
+    pub fn new(title: impl Into<String>, params: Params<S>) -> Self {
+        Self {
+            title: title.into(),
+            params,
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Changes the parameters with `f` whenever `key` is pressed or held.
+    pub fn on_key(mut self, key: Key, f: impl Fn(&mut S) + 'static) -> Self {
+        self.bindings.push((key, Box::new(f)));
+        self
+    }
+
+    /// Opens the window and shows `render` of the parameters, rendering
+    /// again after every change, until the window is closed or Escape is
+    /// pressed.
+    ///
+    /// The window takes the size of the first image; later images of other
+    /// sizes are stretched to it. The title shows how long the last render
+    /// took.
+    pub fn run<P, F>(self, mut render: F) -> Result<(), Error>
+    where
+        P: Pixel,
+        P::Scalar: SrgbChannel,
+        F: FnMut(&S) -> ImageBuffer<P>,
+    {
+        let (mut params, mut generation) = self.params.snapshot();
+        let start = Instant::now();
+        let mut image = render(&params);
+        let mut elapsed = start.elapsed();
+
+        let mut window = Window::new(
+            &self.title,
+            image.width(),
+            image.height(),
+            WindowOptions {
+                resize: true,
+                ..WindowOptions::default()
+            },
+        )?;
+        window.set_target_fps(60);
+
+        let mut pixels = framebuffer(&image);
+        while window.is_open() && !window.is_key_down(Key::Escape) {
+            for key in window.get_keys_pressed(KeyRepeat::Yes) {
+                for (_, f) in self.bindings.iter().filter(|(k, _)| *k == key) {
+                    self.params.update(f);
+                }
+            }
+
+            let (latest, latest_generation) = self.params.snapshot();
+            if latest_generation != generation {
+                (params, generation) = (latest, latest_generation);
+                let start = Instant::now();
+                image = render(&params);
+                elapsed = start.elapsed();
+                pixels = framebuffer(&image);
+            }
+
+            window.set_title(&format!(
+                "{} ({:.1} ms)",
+                self.title,
+                elapsed.as_secs_f64() * 1e3
+            ));
+            window.update_with_buffer(&pixels, image.width(), image.height())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flipr::{Gray, ImageBuffer, Layout, Rgb, Rgba};
+
+    use super::{Params, framebuffer};
+
+    #[test]
+    fn updates_are_shared_between_clones() {
+        let params = Params::new(2.0f32);
+        let tuner = params.clone();
+        assert_eq!(params.snapshot(), (2.0, 0));
+
+        tuner.update(|sigma| *sigma *= 1.5);
+        assert_eq!(params.get(), 3.0);
+        assert_eq!(params.snapshot().1, 1);
+    }
+
+    #[test]
+    fn framebuffers_hold_0rgb_words() {
+        let color = ImageBuffer::from_fn(2, 1, Layout::Planar, |i, _| {
+            Rgb::new(0x12u8, 0x34, if i == 0 { 0x56 } else { 0xff })
+        });
+        assert_eq!(framebuffer(&color), [0x123456, 0x1234ff]);
+
+        let gray = ImageBuffer::filled(1, 1, Layout::Interleaved, Gray(1.0f32));
+        assert_eq!(framebuffer(&gray), [0xffffff]);
+
+        let half = ImageBuffer::filled(1, 1, Layout::Interleaved, Rgba::new(255u8, 0, 0, 128));
+        assert_eq!(framebuffer(&half), [0x800000]);
+    }
+}
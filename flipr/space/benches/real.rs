@@ -0,0 +1,82 @@
+//! Exact arithmetic on pixel-scale coordinates, comparing `Real` with the
+//! plain `Ratio<BigInt>` it used to wrap: `cargo bench -p space`.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use num::BigInt;
+use num::rational::Ratio;
+use space::Real;
+
+const SIZES: [usize; 3] = [64, 256, 1024];
+
+/// Maps each pixel index of a row through `x · scale + offset`, the way
+/// samplers place pixel centers, and sums the results.
+fn row_centers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("row_centers");
+    for size in SIZES {
+        group.bench_with_input(BenchmarkId::new("real", size), &size, |b, &size| {
+            let scale = Real::from_f64(0.75).unwrap();
+            let offset = Real::from_f64(0.5).unwrap();
+            b.iter(|| {
+                (0..size).fold(Real::zero(), |sum, i| {
+                    let x = Real::from_f64(i as f64).unwrap();
+                    sum + (x * &scale + &offset)
+                })
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("ratio_bigint", size), &size, |b, &size| {
+            let scale = Ratio::<BigInt>::from_float(0.75).unwrap();
+            let offset = Ratio::<BigInt>::from_float(0.5).unwrap();
+            b.iter(|| {
+                (0..size).fold(Ratio::from_integer(BigInt::from(0)), |sum, i| {
+                    let x = Ratio::from_integer(BigInt::from(i));
+                    sum + (x * &scale + &offset)
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Compares and floors coordinates, as done when finding the pixel a place
+/// falls into.
+fn containing_pixel(c: &mut Criterion) {
+    let places: Vec<f64> = (0..1024).map(|i| i as f64 * 0.37).collect();
+
+    let mut group = c.benchmark_group("containing_pixel");
+    group.bench_function("real", |b| {
+        let reals: Vec<_> = places.iter().map(|&x| Real::from_f64(x).unwrap()).collect();
+        b.iter(|| {
+            reals
+                .iter()
+                .filter(|x| **x >= Real::zero())
+                .map(|x| x.floor().to_i64())
+                .for_each(|i| {
+                    black_box(i);
+                })
+        })
+    });
+
+    group.bench_function("ratio_bigint", |b| {
+        let ratios: Vec<_> = places
+            .iter()
+            .map(|&x| Ratio::<BigInt>::from_float(x).unwrap())
+            .collect();
+        let zero = Ratio::from_integer(BigInt::from(0));
+        b.iter(|| {
+            ratios
+                .iter()
+                .filter(|x| **x >= zero)
+                .map(|x| num::ToPrimitive::to_i64(&x.floor()))
+                .for_each(|i| {
+                    black_box(i);
+                })
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, row_centers, containing_pixel);
+criterion_main!(benches);
@@ -0,0 +1,265 @@
+use alloc::string::ToString;
+
+use crate::angle::Angle;
+use crate::matrix::Matrix2;
+use crate::offset::Offset;
+use crate::place::Place;
+use crate::real::Real;
+use crate::scale::Scale;
+use crate::vector::Vector2;
+
+/// Exact affine map of the plane, `p ↦ linear · p + translation`.
+///
+/// Composition and inversion stay exact, so a chain of maps with rational
+/// parameters lands exactly where the parameters say.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct AffineMap {
+    pub(super) linear: Matrix2,
+    pub(super) translation: Vector2,
+}
+
+impl core::fmt::Display for AffineMap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entry(&"linear", &self.linear.to_string())
+            .entry(&"translation", &self.translation.to_string())
+            .finish()
+    }
+}
+
+impl AffineMap {
+    pub fn identity() -> Self {
+        Self::new(Matrix2::identity(), Vector2::zero())
+    }
+
+    pub fn new(linear: Matrix2, translation: Vector2) -> Self {
+        Self {
+            linear,
+            translation,
+        }
+    }
+
+    pub fn translation(offset: Offset) -> Self {
+        Self::new(Matrix2::identity(), offset.into())
+    }
+
+    /// Scales x by `sx` and y by `sy` about the origin.
+    pub fn scaling(sx: Scale, sy: Scale) -> Self {
+        Self::new(Matrix2::diagonal(sx.0, sy.0), Vector2::zero())
+    }
+
+    /// Rotation about the origin, exact for quarter turns.
+    pub fn rotation(angle: &Angle) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let linear = Matrix2::from_rows([[cos.clone(), -&sin], [sin, cos]]);
+        Self::new(linear, Vector2::zero())
+    }
+
+    /// Rotation that keeps `center` in place.
+    pub fn rotation_about(center: &Place, angle: &Angle) -> Self {
+        let center = Vector2::from(center.clone());
+        Self::new(Matrix2::identity(), -&center)
+            .then(&Self::rotation(angle))
+            .then(&Self::new(Matrix2::identity(), center))
+    }
+
+    pub fn linear(&self) -> &Matrix2 {
+        &self.linear
+    }
+
+    pub fn translation_part(&self) -> &Vector2 {
+        &self.translation
+    }
+
+    /// Applies `self` first and `next` second.
+    pub fn then(&self, next: &Self) -> Self {
+        Self::new(
+            &next.linear * &self.linear,
+            &(&next.linear * &self.translation) + &next.translation,
+        )
+    }
+
+    /// The exact inverse, or `None` if the linear part is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let linear = self.linear.inverse()?;
+        let translation = -(&linear * &self.translation);
+
+        Some(Self::new(linear, translation))
+    }
+
+    pub fn apply(&self, place: &Place) -> Place {
+        let moved = &(&self.linear * &Vector2::from(place.clone())) + &self.translation;
+        Place::from_reals(moved.x, moved.y)
+    }
+
+    /// Maps a displacement, which the translation does not affect.
+    pub fn apply_offset(&self, offset: &Offset) -> Offset {
+        (&self.linear * &Vector2::from(offset.clone())).into()
+    }
+
+    /// Whether the map is a pure translation.
+    pub fn is_translation(&self) -> bool {
+        self.linear == Matrix2::identity()
+    }
+
+    /// Determinant of the linear part, the factor by which areas change.
+    pub fn determinant(&self) -> Real {
+        self.linear.determinant()
+    }
+}
+
+#[cfg(test)]
+pub mod gens {
+    use proptest::prelude::Strategy;
+
+    use super::AffineMap;
+    use crate::matrix::gens::matrix;
+    use crate::tests::sampler;
+    use crate::vector::gens::vector;
+
+    pub fn affine_map() -> impl Strategy<Value = AffineMap> {
+        (matrix(), vector()).prop_map(|(linear, translation)| AffineMap::new(linear, translation))
+    }
+
+    #[test]
+    #[ignore = "just examples of AffineMap"]
+    fn print_affine_maps() {
+        sampler(affine_map()).take(10).for_each(|m| {
+            println!("AffineMap: {m:#}");
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::array::{uniform2, uniform3};
+    use proptest::prelude::ProptestConfig;
+    #[cfg(feature = "fast")]
+    use proptest::prelude::Strategy;
+    #[cfg(feature = "fast")]
+    use proptest::prop_assert;
+    #[cfg(not(feature = "fast"))]
+    use proptest::prop_assume;
+    use proptest::{prop_assert_eq, proptest};
+
+    use super::AffineMap;
+    use super::gens::affine_map;
+    use crate::angle::Angle;
+    use crate::offset::Offset;
+    use crate::offset::gens::offset;
+    use crate::place::Place;
+    use crate::place::gens::place;
+    use crate::real::Real;
+    use crate::scale::Scale;
+    #[cfg(feature = "fast")]
+    use crate::tests::{BOUND, close};
+
+    proptest! {
+        // Products of arbitrary f64 ratios grow large, so keep the case count low.
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn then_applies_in_order([f, g] in uniform2(affine_map()), p in place()) {
+            prop_assert_eq!(f.then(&g).apply(&p), g.apply(&f.apply(&p)));
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn then_applies_in_order_up_to_rounding([f, g] in uniform2(affine_map()), p in place()) {
+            let magnitude = 8.0 * BOUND.powi(3);
+            prop_assert!(close(&f.then(&g).apply(&p), &g.apply(&f.apply(&p)), magnitude));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn then_is_associative([f, g, h] in uniform3(affine_map())) {
+            prop_assert_eq!(f.then(&g).then(&h), f.then(&g.then(&h)));
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn then_is_associative_up_to_rounding([f, g, h] in uniform3(affine_map())) {
+            let magnitude = 8.0 * BOUND.powi(3);
+            prop_assert!(close(&f.then(&g).then(&h), &f.then(&g.then(&h)), magnitude));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn inverse_undoes_exactly(f in affine_map(), p in place()) {
+            prop_assume!(f.determinant() != Real::zero());
+            let inverse = f.inverse().expect("determinant is not zero");
+            prop_assert_eq!(inverse.apply(&f.apply(&p)), p);
+            prop_assert_eq!(f.then(&inverse), AffineMap::identity());
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn inverse_undoes_up_to_rounding(
+            f in affine_map().prop_filter("nearly singular maps amplify rounding", |f| {
+                f.determinant().to_f64().unwrap().abs() >= 1.0
+            }),
+            p in place(),
+        ) {
+            let inverse = f.inverse().expect("determinant is not zero");
+            let magnitude = 8.0 * BOUND.powi(3);
+            prop_assert!(close(&inverse.apply(&f.apply(&p)), &p, magnitude));
+            prop_assert!(close(&f.then(&inverse), &AffineMap::identity(), magnitude));
+        }
+
+        #[test]
+        fn translations_move_places_by_their_offset(a in offset(), p in place()) {
+            prop_assert_eq!(AffineMap::translation(a.clone()).apply(&p), &p + &a);
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn maps_preserve_differences(f in affine_map(), [p, q] in uniform2(place())) {
+            prop_assert_eq!(f.apply(&q) - f.apply(&p), f.apply_offset(&(&q - &p)));
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn maps_preserve_differences_up_to_rounding(
+            f in affine_map(),
+            [p, q] in uniform2(place()),
+        ) {
+            let magnitude = 4.0 * BOUND.powi(2);
+            prop_assert!(close(
+                &(f.apply(&q) - f.apply(&p)),
+                &f.apply_offset(&(&q - &p)),
+                magnitude
+            ));
+        }
+    }
+
+    #[test]
+    fn rational_chains_stay_exact() {
+        let third = Scale::from_real(Real::one() / Real::from_f64(3.0).unwrap());
+        let shrink = AffineMap::scaling(third.clone(), third);
+        let shift = AffineMap::translation(Offset::new(1.0, 2.0).unwrap());
+        let there_and_back = shrink
+            .then(&shift)
+            .then(&shift.inverse().unwrap())
+            .then(&shrink.inverse().unwrap());
+
+        assert_eq!(there_and_back, AffineMap::identity());
+        assert!(
+            AffineMap::scaling(Scale::zero(), Scale::one())
+                .inverse()
+                .is_none()
+        );
+
+        let center = Place::new(2.0, 1.0).unwrap();
+        let quarter = AffineMap::rotation_about(&center, &Angle::quarter_turns(1));
+        assert_eq!(quarter.apply(&center), center);
+        assert_eq!(
+            quarter.apply(&Place::new(3.0, 1.0).unwrap()),
+            Place::new(2.0, 2.0).unwrap()
+        );
+        assert_eq!(
+            shrink.apply(&Place::new(3.0, 6.0).unwrap()),
+            Place::new(1.0, 2.0).unwrap()
+        );
+    }
+}
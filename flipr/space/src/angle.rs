@@ -0,0 +1,245 @@
+use core::f64::consts::{FRAC_PI_2, TAU};
+
+use crate::offset::Offset;
+use crate::real::Real;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Repr {
+    /// Counter-clockwise quarter turns, always in `0..4`.
+    Quarters(u8),
+    /// Any other angle, in radians.
+    Radians(f64),
+}
+
+/// Rotation angle that is exact for multiples of 90°.
+///
+/// Quarter turns rotate offsets exactly; every other angle goes through
+/// `f64` sine and cosine and is only as exact as they are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Angle(Repr);
+
+impl core::fmt::Display for Angle {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.0 {
+            Repr::Quarters(n) => write!(f, "{}°", u16::from(n) * 90),
+            Repr::Radians(radians) => write!(f, "{radians} rad"),
+        }
+    }
+}
+
+impl Angle {
+    pub fn zero() -> Self {
+        Self::quarter_turns(0)
+    }
+
+    pub fn quarter_turns(n: i64) -> Self {
+        Self(Repr::Quarters(n.rem_euclid(4) as u8))
+    }
+
+    /// Exact whenever `degrees` is a whole multiple of 90, `None` if it is
+    /// not finite.
+    pub fn degrees(degrees: f64) -> Option<Self> {
+        if !degrees.is_finite() {
+            return None;
+        }
+
+        let quarters = degrees / 90.0;
+        if quarters == libm::trunc(quarters) && quarters.abs() < i64::MAX as f64 {
+            Some(Self::quarter_turns(quarters as i64))
+        } else {
+            Self::radians(degrees.to_radians())
+        }
+    }
+
+    /// `None` if `radians` is not finite; never exact, as no multiple of π
+    /// is an `f64`.
+    pub fn radians(radians: f64) -> Option<Self> {
+        radians.is_finite().then(|| {
+            // `f64::rem_euclid` needs std.
+            let radians = radians % TAU;
+            Self(Repr::Radians(if radians < 0.0 {
+                radians + TAU
+            } else {
+                radians
+            }))
+        })
+    }
+
+    /// Number of quarter turns in `0..4`, if the angle is exact.
+    pub fn as_quarter_turns(&self) -> Option<u8> {
+        match self.0 {
+            Repr::Quarters(n) => Some(n),
+            Repr::Radians(_) => None,
+        }
+    }
+
+    pub fn to_radians(&self) -> f64 {
+        match self.0 {
+            Repr::Quarters(n) => f64::from(n) * FRAC_PI_2,
+            Repr::Radians(radians) => radians,
+        }
+    }
+
+    /// Sine and cosine, exact for quarter turns.
+    pub fn sin_cos(&self) -> (Real, Real) {
+        let (zero, one) = (Real::zero(), Real::one());
+        match self.0 {
+            Repr::Quarters(0) => (zero, one),
+            Repr::Quarters(1) => (one, zero),
+            Repr::Quarters(2) => (zero, -one),
+            Repr::Quarters(_) => (-one, zero),
+            Repr::Radians(radians) => {
+                let (sin, cos) = libm::sincos(radians);
+                let real = |v| Real::from_f64(v).expect("sine and cosine are finite");
+                (real(sin), real(cos))
+            }
+        }
+    }
+
+    /// Rotates `offset` counter-clockwise, from the x axis toward the y axis.
+    pub fn rotate(&self, offset: &Offset) -> Offset {
+        let (dx, dy) = (&offset.dx, &offset.dy);
+        match self.0 {
+            Repr::Quarters(0) => offset.clone(),
+            Repr::Quarters(1) => Offset::from_reals(-dy, dx.clone()),
+            Repr::Quarters(2) => -offset,
+            Repr::Quarters(_) => Offset::from_reals(dy.clone(), -dx),
+            Repr::Radians(_) => {
+                let (sin, cos) = self.sin_cos();
+                Offset::from_reals(dx * &cos - dy * &sin, dx * &sin + dy * &cos)
+            }
+        }
+    }
+}
+
+///////////
+// Addition
+///////////
+
+impl core::ops::Add for Angle {
+    type Output = Angle;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        match (self.0, rhs.0) {
+            (Repr::Quarters(a), Repr::Quarters(b)) => Self::quarter_turns(i64::from(a + b)),
+            (a, b) => Self::radians(Angle(a).to_radians() + Angle(b).to_radians())
+                .expect("sum of two angles below 2π is finite"),
+        }
+    }
+}
+
+impl core::ops::Add for &Angle {
+    type Output = Angle;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.clone() + rhs.clone()
+    }
+}
+
+///////////
+// Negation
+///////////
+
+impl core::ops::Neg for Angle {
+    type Output = Angle;
+
+    fn neg(self) -> Self::Output {
+        match self.0 {
+            Repr::Quarters(n) => Self::quarter_turns(-i64::from(n)),
+            Repr::Radians(radians) => {
+                Self::radians(-radians).expect("negated finite angle is finite")
+            }
+        }
+    }
+}
+
+impl core::ops::Neg for &Angle {
+    type Output = Angle;
+
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+#[cfg(test)]
+pub mod gens {
+    use proptest::prelude::Strategy;
+    use proptest::prop_oneof;
+
+    use super::Angle;
+    use crate::tests::sampler;
+
+    /// Generates exact quarter turns as well as arbitrary angles.
+    pub fn angle() -> impl Strategy<Value = Angle> {
+        prop_oneof![
+            (-10..10i64).prop_map(Angle::quarter_turns),
+            (-10.0..10.0f64).prop_map(|r| Angle::radians(r).expect("range is finite")),
+        ]
+    }
+
+    #[test]
+    #[ignore = "just examples of Angle"]
+    fn print_angles() {
+        sampler(angle()).take(10).for_each(|a| {
+            println!("Angle: {a:#}");
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+
+    use super::Angle;
+    use super::gens::angle;
+    use crate::offset::Offset;
+    use crate::offset::gens::offset;
+
+    fn close(a: &Offset, b: &Offset) -> bool {
+        let d = a + &-b;
+        let norm = |o: &Offset| o.dx.to_f64().unwrap().abs() + o.dy.to_f64().unwrap().abs();
+        norm(&d) <= 1e-9 * (1.0 + norm(a) + norm(b))
+    }
+
+    proptest! {
+        #[test]
+        fn four_quarter_turns_are_identity(a in offset()) {
+            let quarter = Angle::degrees(90.0).unwrap();
+            let turned = (0..4).fold(a.clone(), |o, _| quarter.rotate(&o));
+            prop_assert_eq!(turned, a);
+        }
+
+        #[test]
+        fn quarter_turn_sums_stay_exact(n in -100..100i64, m in -100..100i64) {
+            prop_assert_eq!(
+                Angle::quarter_turns(n) + Angle::quarter_turns(m),
+                Angle::quarter_turns(n + m)
+            );
+        }
+
+        #[test]
+        fn rotation_by_the_negation_undoes(theta in angle(), a in offset()) {
+            let back = (-&theta).rotate(&theta.rotate(&a));
+            prop_assert!(close(&back, &a));
+        }
+
+        #[test]
+        fn rotations_compose_like_addition(theta in angle(), phi in angle(), a in offset()) {
+            let one_by_one = phi.rotate(&theta.rotate(&a));
+            prop_assert!(close(&(&theta + &phi).rotate(&a), &one_by_one));
+        }
+    }
+
+    #[test]
+    fn right_angles_are_exact() {
+        let x = Offset::new(1.0, 0.0).unwrap();
+        assert_eq!(
+            Angle::degrees(90.0).unwrap().rotate(&x),
+            Offset::new(0.0, 1.0).unwrap()
+        );
+        assert_eq!(Angle::degrees(-270.0), Some(Angle::quarter_turns(1)));
+        assert_eq!(Angle::degrees(540.0).unwrap().as_quarter_turns(), Some(2));
+        assert_eq!(Angle::degrees(45.0).unwrap().as_quarter_turns(), None);
+        assert_eq!(Angle::degrees(f64::NAN), None);
+    }
+}
@@ -0,0 +1,303 @@
+use alloc::string::ToString;
+
+use crate::affine::AffineMap;
+use crate::offset::Offset;
+use crate::place::Place;
+use crate::real::Real;
+use crate::scale::Scale;
+
+/// Axis-aligned rectangle `[min.x, max.x) × [min.y, max.y)` of places.
+///
+/// An extent whose `max` does not exceed its `min` along some axis is empty.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Extent {
+    pub(super) min: Place,
+    pub(super) max: Place,
+}
+
+impl core::fmt::Display for Extent {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entry(&"min", &self.min.to_string())
+            .entry(&"max", &self.max.to_string())
+            .finish()
+    }
+}
+
+fn min(a: &Real, b: &Real) -> Real {
+    a.min(b).clone()
+}
+
+fn max(a: &Real, b: &Real) -> Real {
+    a.max(b).clone()
+}
+
+impl Extent {
+    /// Rectangle spanned by two opposite corners, in any order.
+    pub fn new(a: &Place, b: &Place) -> Self {
+        Self {
+            min: Place::from_reals(min(&a.x, &b.x), min(&a.y, &b.y)),
+            max: Place::from_reals(max(&a.x, &b.x), max(&a.y, &b.y)),
+        }
+    }
+
+    /// Rectangle at `origin` reaching `size` to the right and down.
+    pub fn from_origin(origin: Place, size: Offset) -> Self {
+        let far = &origin + &size;
+        Self::new(&origin, &far)
+    }
+
+    /// Smallest extent containing every place, `None` if there are none.
+    ///
+    /// Places with the largest coordinates end up on the excluded edges of the
+    /// half-open result.
+    pub fn bounding<'a>(places: impl IntoIterator<Item = &'a Place>) -> Option<Self> {
+        let mut places = places.into_iter();
+        let first = places.next()?;
+
+        Some(places.fold(Self::new(first, first), |extent, p| Self {
+            min: Place::from_reals(min(&extent.min.x, &p.x), min(&extent.min.y, &p.y)),
+            max: Place::from_reals(max(&extent.max.x, &p.x), max(&extent.max.y, &p.y)),
+        }))
+    }
+
+    pub fn min(&self) -> &Place {
+        &self.min
+    }
+
+    pub fn max(&self) -> &Place {
+        &self.max
+    }
+
+    pub fn size(&self) -> Offset {
+        &self.max - &self.min
+    }
+
+    pub fn width(&self) -> Real {
+        &self.max.x - &self.min.x
+    }
+
+    pub fn height(&self) -> Real {
+        &self.max.y - &self.min.y
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.max.x <= self.min.x || self.max.y <= self.min.y
+    }
+
+    pub fn contains(&self, place: &Place) -> bool {
+        self.min.x <= place.x
+            && place.x < self.max.x
+            && self.min.y <= place.y
+            && place.y < self.max.y
+    }
+
+    /// Whether every place of `other` lies in `self`; empty extents lie
+    /// everywhere.
+    pub fn contains_extent(&self, other: &Self) -> bool {
+        other.is_empty()
+            || (self.min.x <= other.min.x
+                && other.max.x <= self.max.x
+                && self.min.y <= other.min.y
+                && other.max.y <= self.max.y)
+    }
+
+    /// Places in both extents, `None` if they do not overlap.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let overlap = Self {
+            min: Place::from_reals(
+                max(&self.min.x, &other.min.x),
+                max(&self.min.y, &other.min.y),
+            ),
+            max: Place::from_reals(
+                min(&self.max.x, &other.max.x),
+                min(&self.max.y, &other.max.y),
+            ),
+        };
+        (!overlap.is_empty()).then_some(overlap)
+    }
+
+    /// Smallest extent containing both, ignoring empty ones.
+    pub fn union(&self, other: &Self) -> Self {
+        match (self.is_empty(), other.is_empty()) {
+            (_, true) => self.clone(),
+            (true, false) => other.clone(),
+            (false, false) => Self {
+                min: Place::from_reals(
+                    min(&self.min.x, &other.min.x),
+                    min(&self.min.y, &other.min.y),
+                ),
+                max: Place::from_reals(
+                    max(&self.max.x, &other.max.x),
+                    max(&self.max.y, &other.max.y),
+                ),
+            },
+        }
+    }
+
+    /// Bounding box of the extent after `map`, exact when `map` only scales
+    /// and translates.
+    pub fn transform(&self, map: &AffineMap) -> Self {
+        let corners = [
+            self.min.clone(),
+            Place::from_reals(self.max.x.clone(), self.min.y.clone()),
+            Place::from_reals(self.min.x.clone(), self.max.y.clone()),
+            self.max.clone(),
+        ]
+        .map(|corner| map.apply(&corner));
+
+        Self::bounding(&corners).expect("there are four corners")
+    }
+}
+
+///////////
+// Addition
+///////////
+
+impl core::ops::Add<&Offset> for &Extent {
+    type Output = Extent;
+
+    fn add(self, rhs: &Offset) -> Self::Output {
+        Extent {
+            min: &self.min + rhs,
+            max: &self.max + rhs,
+        }
+    }
+}
+
+impl core::ops::Add<Offset> for Extent {
+    type Output = Extent;
+
+    fn add(self, rhs: Offset) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+/////////////////
+// Multiplication
+/////////////////
+
+impl core::ops::Mul<&Scale> for &Extent {
+    type Output = Extent;
+
+    /// Scales about the origin; negative factors mirror the extent.
+    fn mul(self, rhs: &Scale) -> Self::Output {
+        let scale = |p: &Place| Place::from_reals(&p.x * &rhs.0, &p.y * &rhs.0);
+        Extent::new(&scale(&self.min), &scale(&self.max))
+    }
+}
+
+impl core::ops::Mul<Scale> for Extent {
+    type Output = Extent;
+
+    fn mul(self, rhs: Scale) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+#[cfg(test)]
+pub mod gens {
+    use proptest::prelude::Strategy;
+
+    use super::Extent;
+    use crate::place::gens::place;
+    use crate::tests::sampler;
+
+    pub fn extent() -> impl Strategy<Value = Extent> {
+        (place(), place()).prop_map(|(a, b)| Extent::new(&a, &b))
+    }
+
+    #[test]
+    #[ignore = "just examples of Extent"]
+    fn print_extents() {
+        sampler(extent()).take(10).for_each(|e| {
+            println!("Extent: {e:#}");
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::array::uniform2;
+    #[cfg(feature = "fast")]
+    use proptest::prop_assume;
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+
+    use super::Extent;
+    use super::gens::extent;
+    use crate::affine::AffineMap;
+    use crate::angle::Angle;
+    use crate::offset::gens::offset;
+    use crate::place::Place;
+    use crate::place::gens::place;
+    #[cfg(feature = "fast")]
+    use crate::real::Real;
+    use crate::scale::gens::scale;
+    #[cfg(feature = "fast")]
+    use crate::tests::BOUND;
+
+    proptest! {
+        #[test]
+        fn intersection_is_contained_in_both([a, b] in uniform2(extent())) {
+            if let Some(overlap) = a.intersection(&b) {
+                prop_assert!(a.contains_extent(&overlap) && b.contains_extent(&overlap));
+            }
+        }
+
+        #[test]
+        fn places_in_both_are_in_the_intersection([a, b] in uniform2(extent()), p in place()) {
+            let in_both = a.contains(&p) && b.contains(&p);
+            prop_assert_eq!(in_both, a.intersection(&b).is_some_and(|o| o.contains(&p)));
+        }
+
+        #[test]
+        fn union_contains_both([a, b] in uniform2(extent())) {
+            let union = a.union(&b);
+            prop_assert!(union.contains_extent(&a) && union.contains_extent(&b));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn translation_moves_membership(e in extent(), o in offset(), p in place()) {
+            prop_assert_eq!((&e + &o).contains(&(&p + &o)), e.contains(&p));
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn translation_moves_membership_away_from_edges(
+            e in extent(),
+            o in offset(),
+            p in place(),
+        ) {
+            let margin = Real::from_f64(1e-12 * 4.0 * BOUND).unwrap();
+            let clear = |a: &Real, b: &Real| (a - b).abs() > margin;
+            prop_assume!(clear(&p.x, &e.min.x) && clear(&p.x, &e.max.x));
+            prop_assume!(clear(&p.y, &e.min.y) && clear(&p.y, &e.max.y));
+            prop_assert_eq!((&e + &o).contains(&(&p + &o)), e.contains(&p));
+        }
+
+        #[test]
+        fn scaling_matches_the_affine_map(e in extent(), s in scale()) {
+            let map = AffineMap::scaling(s.clone(), s.clone());
+            prop_assert_eq!(&e * &s, e.transform(&map));
+        }
+    }
+
+    #[test]
+    fn quarter_turns_keep_boxes_tight() {
+        let e = Extent::new(
+            &Place::new(0.0, 0.0).unwrap(),
+            &Place::new(4.0, 2.0).unwrap(),
+        );
+        let turned = e.transform(&AffineMap::rotation(&Angle::quarter_turns(1)));
+        assert_eq!(
+            turned,
+            Extent::new(
+                &Place::new(-2.0, 0.0).unwrap(),
+                &Place::new(0.0, 4.0).unwrap()
+            )
+        );
+        assert!(!e.contains(&Place::new(4.0, 1.0).unwrap()));
+        assert!(e.contains(&Place::new(0.0, 1.0).unwrap()));
+    }
+}
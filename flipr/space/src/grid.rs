@@ -0,0 +1,229 @@
+use alloc::string::ToString;
+
+use crate::affine::AffineMap;
+use crate::offset::Offset;
+use crate::place::Place;
+use crate::real::Real;
+use crate::scale::Scale;
+
+/// How [`Grid::place_to_index`] turns a fractional index into a whole one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Largest index not above the fractional one, the cell a place lies in
+    /// when the grid marks cell corners.
+    Floor,
+    /// Closest index, ties rounding up, the nearest sample when the grid
+    /// marks sample positions.
+    Nearest,
+}
+
+/// Regular lattice of places, index `(x, y)` sitting at
+/// `origin + (x · spacing_x, y · spacing_y)`.
+///
+/// Describes where the pixels of an image are sampled; spacings may be
+/// negative to flip an axis, but never zero.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Grid {
+    origin: Place,
+    spacing_x: Scale,
+    spacing_y: Scale,
+}
+
+impl core::fmt::Display for Grid {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entry(&"origin", &self.origin.to_string())
+            .entry(&"spacing_x", &self.spacing_x.to_string())
+            .entry(&"spacing_y", &self.spacing_y.to_string())
+            .finish()
+    }
+}
+
+impl Grid {
+    /// `None` if either spacing is zero.
+    pub fn new(origin: Place, spacing_x: Scale, spacing_y: Scale) -> Option<Self> {
+        (spacing_x != Scale::zero() && spacing_y != Scale::zero()).then_some(Self {
+            origin,
+            spacing_x,
+            spacing_y,
+        })
+    }
+
+    /// Index `(x, y)` at place `(x, y)`, the corners of unit pixel cells.
+    pub fn unit() -> Self {
+        Self {
+            origin: Place::origin(),
+            spacing_x: Scale::one(),
+            spacing_y: Scale::one(),
+        }
+    }
+
+    /// Centers of unit pixel cells, where image buffers sample.
+    pub fn pixel_centers() -> Self {
+        let half = Real::one() / Real::from(2);
+        Self {
+            origin: Place::from_reals(half.clone(), half),
+            ..Self::unit()
+        }
+    }
+
+    pub fn origin(&self) -> &Place {
+        &self.origin
+    }
+
+    pub fn spacing_x(&self) -> &Scale {
+        &self.spacing_x
+    }
+
+    pub fn spacing_y(&self) -> &Scale {
+        &self.spacing_y
+    }
+
+    pub fn index_to_place(&self, x: i64, y: i64) -> Place {
+        Place::from_reals(
+            &self.origin.x + Real::from(x) * &self.spacing_x.0,
+            &self.origin.y + Real::from(y) * &self.spacing_y.0,
+        )
+    }
+
+    /// Index of `place` after rounding `(place - origin) / spacing`, or `None`
+    /// if it does not fit `i64`.
+    pub fn place_to_index(&self, place: &Place, rounding: Rounding) -> Option<(i64, i64)> {
+        let round = |fraction: Real| match rounding {
+            Rounding::Floor => fraction.floor(),
+            Rounding::Nearest => (fraction + Real::one() / Real::from(2)).floor(),
+        };
+        let index = |p: &Real, o: &Real, spacing: &Scale| round((p - o) / &spacing.0).to_i64();
+
+        Some((
+            index(&place.x, &self.origin.x, &self.spacing_x)?,
+            index(&place.y, &self.origin.y, &self.spacing_y)?,
+        ))
+    }
+
+    /// Map taking indices, as places, to the places they sit at.
+    pub fn to_map(&self) -> AffineMap {
+        AffineMap::scaling(self.spacing_x.clone(), self.spacing_y.clone()).then(
+            &AffineMap::translation(Offset::from_reals(
+                self.origin.x.clone(),
+                self.origin.y.clone(),
+            )),
+        )
+    }
+}
+
+#[cfg(test)]
+pub mod gens {
+    use proptest::prelude::Strategy;
+
+    use super::Grid;
+    use crate::place::gens::place;
+    use crate::scale::gens::scale;
+    use crate::tests::sampler;
+
+    pub fn grid() -> impl Strategy<Value = Grid> {
+        (place(), scale(), scale())
+            .prop_filter_map("spacings must not be zero", |(o, x, y)| Grid::new(o, x, y))
+    }
+
+    #[test]
+    #[ignore = "just examples of Grid"]
+    fn print_grids() {
+        sampler(grid()).take(10).for_each(|g| {
+            println!("Grid: {g:#}");
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+
+    use super::gens::grid;
+    use super::{Grid, Rounding};
+    #[cfg(feature = "fast")]
+    use crate::offset::Offset;
+    use crate::place::Place;
+    use crate::place::gens::place;
+    #[cfg(feature = "fast")]
+    use crate::real::Real;
+    use crate::scale::Scale;
+
+    proptest! {
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn indices_round_trip(g in grid(), x in -1000..1000i64, y in -1000..1000i64) {
+            let p = g.index_to_place(x, y);
+            prop_assert_eq!(g.place_to_index(&p, Rounding::Floor), Some((x, y)));
+            prop_assert_eq!(g.place_to_index(&p, Rounding::Nearest), Some((x, y)));
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn indices_round_trip_up_to_rounding(
+            g in grid(),
+            x in -1000..1000i64,
+            y in -1000..1000i64,
+        ) {
+            let p = g.index_to_place(x, y);
+            prop_assert_eq!(g.place_to_index(&p, Rounding::Nearest), Some((x, y)));
+            // Flooring the place itself could land in the previous cell.
+            let half = Real::one() / Real::from(2);
+            let middle = &p + Offset::from_reals(&half * &g.spacing_x.0, &half * &g.spacing_y.0);
+            prop_assert_eq!(g.place_to_index(&middle, Rounding::Floor), Some((x, y)));
+        }
+
+        #[test]
+        fn map_agrees_with_indices(g in grid(), x in -1000..1000i64, y in -1000..1000i64) {
+            let index = Place::from_reals(x.into(), y.into());
+            prop_assert_eq!(g.to_map().apply(&index), g.index_to_place(x, y));
+        }
+
+        #[test]
+        fn nearest_index_is_within_half_a_spacing(p in place()) {
+            let g = Grid::pixel_centers();
+            if let Some((x, y)) = g.place_to_index(&p, Rounding::Nearest) {
+                let sample = g.index_to_place(x, y);
+                let half = Scale::new(0.5).unwrap();
+                prop_assert!((p.x() - sample.x()).abs() <= *half.value());
+                prop_assert!((p.y() - sample.y()).abs() <= *half.value());
+            }
+        }
+    }
+
+    #[test]
+    fn unit_cells_and_pixel_centers() {
+        let p = Place::new(2.7, -0.2).unwrap();
+        assert_eq!(
+            Grid::unit().place_to_index(&p, Rounding::Floor),
+            Some((2, -1))
+        );
+        assert_eq!(
+            Grid::pixel_centers().place_to_index(&p, Rounding::Nearest),
+            Some((2, -1))
+        );
+        assert_eq!(
+            Grid::pixel_centers().index_to_place(1, 0),
+            Place::new(1.5, 0.5).unwrap()
+        );
+        assert_eq!(
+            Grid::new(Place::origin(), Scale::zero(), Scale::one()),
+            None
+        );
+    }
+
+    #[test]
+    fn negative_spacing_flips_the_axis() {
+        let g = Grid::new(
+            Place::new(0.0, 10.0).unwrap(),
+            Scale::one(),
+            Scale::new(-2.0).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(g.index_to_place(3, 2), Place::new(3.0, 6.0).unwrap());
+        assert_eq!(
+            g.place_to_index(&Place::new(3.2, 6.5).unwrap(), Rounding::Nearest),
+            Some((3, 2))
+        );
+    }
+}
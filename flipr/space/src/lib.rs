@@ -1,11 +1,27 @@
-mod real;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+pub mod affine;
+pub mod angle;
+pub mod extent;
+pub mod grid;
+pub mod matrix;
 pub mod offset;
 pub mod place;
+pub mod real;
 pub mod scale;
+pub mod vector;
+pub use affine::AffineMap;
+pub use angle::Angle;
+pub use extent::Extent;
+pub use grid::{Grid, Rounding};
+pub use matrix::Matrix2;
 pub use offset::Offset;
 pub use place::Place;
+pub use real::{ParseRealError, Real};
 pub use scale::Scale;
+pub use vector::Vector2;
 
 #[cfg(test)]
 pub mod tests;
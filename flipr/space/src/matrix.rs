@@ -0,0 +1,250 @@
+use alloc::string::ToString;
+
+use crate::real::Real;
+use crate::vector::Vector2;
+
+/// 2×2 matrix of exact reals, stored row by row.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Matrix2 {
+    pub(super) rows: [[Real; 2]; 2],
+}
+
+impl core::fmt::Display for Matrix2 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let [[a, b], [c, d]] = &self.rows;
+        f.debug_list()
+            .entry(&[a.to_string(), b.to_string()])
+            .entry(&[c.to_string(), d.to_string()])
+            .finish()
+    }
+}
+
+impl Matrix2 {
+    pub fn identity() -> Self {
+        Self::diagonal(Real::one(), Real::one())
+    }
+
+    pub fn zero() -> Self {
+        Self::diagonal(Real::zero(), Real::zero())
+    }
+
+    pub fn diagonal(a: Real, d: Real) -> Self {
+        Self::from_rows([[a, Real::zero()], [Real::zero(), d]])
+    }
+
+    pub fn new(rows: [[f64; 2]; 2]) -> Option<Self> {
+        let [[a, b], [c, d]] = rows;
+        Some(Self::from_rows([
+            [Real::from_f64(a)?, Real::from_f64(b)?],
+            [Real::from_f64(c)?, Real::from_f64(d)?],
+        ]))
+    }
+
+    pub fn from_rows(rows: [[Real; 2]; 2]) -> Self {
+        Self { rows }
+    }
+
+    pub fn rows(&self) -> &[[Real; 2]; 2] {
+        &self.rows
+    }
+
+    pub fn determinant(&self) -> Real {
+        let [[a, b], [c, d]] = &self.rows;
+        a * d - b * c
+    }
+
+    pub fn transpose(&self) -> Self {
+        let [[a, b], [c, d]] = self.rows.clone();
+        Self::from_rows([[a, c], [b, d]])
+    }
+
+    /// The exact inverse, or `None` if the determinant is exactly zero.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == Real::zero() {
+            return None;
+        }
+
+        let [[a, b], [c, d]] = self.rows.clone();
+        Some(Self::from_rows([
+            [&d / &det, -b / &det],
+            [-c / &det, a / det],
+        ]))
+    }
+}
+
+/////////////////
+// Multiplication
+/////////////////
+
+impl core::ops::Mul for &Matrix2 {
+    type Output = Matrix2;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let [[a, b], [c, d]] = &self.rows;
+        let [[e, f], [g, h]] = &rhs.rows;
+
+        Matrix2::from_rows([
+            [a * e + b * g, a * f + b * h],
+            [c * e + d * g, c * f + d * h],
+        ])
+    }
+}
+
+impl core::ops::Mul for Matrix2 {
+    type Output = Matrix2;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl core::ops::Mul<&Vector2> for &Matrix2 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: &Vector2) -> Self::Output {
+        let [[a, b], [c, d]] = &self.rows;
+
+        Vector2 {
+            x: a * &rhs.x + b * &rhs.y,
+            y: c * &rhs.x + d * &rhs.y,
+        }
+    }
+}
+
+impl core::ops::Mul<Vector2> for Matrix2 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: Vector2) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+#[cfg(test)]
+pub mod gens {
+    use proptest::array::uniform4;
+    use proptest::prelude::Strategy;
+
+    use super::Matrix2;
+    use crate::real::gens::real;
+    use crate::tests::sampler;
+
+    pub fn matrix() -> impl Strategy<Value = Matrix2> {
+        uniform4(real()).prop_map(|[a, b, c, d]| Matrix2::from_rows([[a, b], [c, d]]))
+    }
+
+    #[test]
+    #[ignore = "just examples of Matrix2"]
+    fn print_matrices() {
+        sampler(matrix()).take(10).for_each(|m| {
+            println!("Matrix2: {m:#}");
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::array::{uniform2, uniform3};
+    use proptest::prelude::ProptestConfig;
+    #[cfg(feature = "fast")]
+    use proptest::prelude::Strategy;
+    #[cfg(feature = "fast")]
+    use proptest::prop_assert;
+    #[cfg(not(feature = "fast"))]
+    use proptest::prop_assume;
+    use proptest::{prop_assert_eq, proptest};
+
+    use super::Matrix2;
+    use super::gens::matrix;
+    #[cfg(not(feature = "fast"))]
+    use crate::real::Real;
+    #[cfg(feature = "fast")]
+    use crate::tests::{BOUND, close};
+    use crate::vector::gens::vector;
+
+    proptest! {
+        // Products of arbitrary f64 ratios grow large, so keep the case count low.
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn matrix_mul_associative([m, n, o] in uniform3(matrix())) {
+            prop_assert_eq!(&m * &(&n * &o), &(&m * &n) * &o);
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn matrix_mul_associative_up_to_rounding([m, n, o] in uniform3(matrix())) {
+            let magnitude = 8.0 * BOUND.powi(3);
+            prop_assert!(close(&(&m * &(&n * &o)), &(&(&m * &n) * &o), magnitude));
+        }
+
+        #[test]
+        fn identity_is_neutral(m in matrix()) {
+            prop_assert_eq!(&Matrix2::identity() * &m, m.clone());
+            prop_assert_eq!(&m * &Matrix2::identity(), m);
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn matrix_mul_vector_compatible([m, n] in uniform2(matrix()), v in vector()) {
+            prop_assert_eq!(&(&m * &n) * &v, &m * &(&n * &v));
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn matrix_mul_vector_compatible_up_to_rounding(
+            [m, n] in uniform2(matrix()),
+            v in vector(),
+        ) {
+            let magnitude = 8.0 * BOUND.powi(3);
+            prop_assert!(close(&(&(&m * &n) * &v), &(&m * &(&n * &v)), magnitude));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn determinant_is_multiplicative([m, n] in uniform2(matrix())) {
+            prop_assert_eq!((&m * &n).determinant(), m.determinant() * n.determinant());
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn determinant_is_multiplicative_up_to_rounding([m, n] in uniform2(matrix())) {
+            let magnitude = 8.0 * BOUND.powi(4);
+            prop_assert!(close(
+                &(&m * &n).determinant(),
+                &(m.determinant() * n.determinant()),
+                magnitude
+            ));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn inverse_is_exact(m in matrix()) {
+            prop_assume!(m.determinant() != Real::zero());
+            let inverse = m.inverse().expect("determinant is not zero");
+            prop_assert_eq!(&m * &inverse, Matrix2::identity());
+            prop_assert_eq!(&inverse * &m, Matrix2::identity());
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn inverse_is_exact_up_to_rounding(
+            m in matrix().prop_filter("nearly singular matrices amplify rounding", |m| {
+                m.determinant().to_f64().unwrap().abs() >= 1.0
+            }),
+        ) {
+            let inverse = m.inverse().expect("determinant is not zero");
+            let magnitude = 2.0 * BOUND.powi(2);
+            prop_assert!(close(&(&m * &inverse), &Matrix2::identity(), magnitude));
+            prop_assert!(close(&(&inverse * &m), &Matrix2::identity(), magnitude));
+        }
+    }
+
+    #[test]
+    fn singular_matrices_have_no_inverse() {
+        let m = Matrix2::new([[1.0, 2.0], [0.5, 1.0]]).unwrap();
+        assert_eq!(m.inverse(), None);
+        assert_eq!(Matrix2::zero().inverse(), None);
+    }
+}
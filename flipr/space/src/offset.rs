@@ -1,14 +1,17 @@
-use crate::real::Real;
+use alloc::string::ToString;
+
+use crate::real::{ParseRealError, Real, parse_entries};
 use crate::scale::Scale;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Offset {
     pub(super) dx: Real,
     pub(super) dy: Real,
 }
 
-impl std::fmt::Display for Offset {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Offset {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_map()
             .entry(&"dx", &self.dx.to_string())
             .entry(&"dy", &self.dy.to_string())
@@ -16,6 +19,16 @@ impl std::fmt::Display for Offset {
     }
 }
 
+/// Parses the displayed form, `{"dx": "1/2", "dy": "-3"}`.
+impl core::str::FromStr for Offset {
+    type Err = ParseRealError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [dx, dy] = parse_entries(s, ["dx", "dy"])?;
+        Ok(Self { dx, dy })
+    }
+}
+
 impl Offset {
     pub fn zero() -> Self {
         Self {
@@ -30,13 +43,66 @@ impl Offset {
 
         Some(Self { dx, dy })
     }
+
+    pub fn from_reals(dx: Real, dy: Real) -> Self {
+        Self { dx, dy }
+    }
+
+    pub fn dx(&self) -> &Real {
+        &self.dx
+    }
+
+    pub fn dy(&self) -> &Real {
+        &self.dy
+    }
+
+    pub fn dot(&self, other: &Self) -> Real {
+        &self.dx * &other.dx + &self.dy * &other.dy
+    }
+
+    /// Squared Euclidean length, exact unlike the length itself.
+    pub fn norm_squared(&self) -> Real {
+        self.dot(self)
+    }
+
+    /// `|dx| + |dy|`, the number of unit steps along the axes.
+    pub fn manhattan_norm(&self) -> Real {
+        self.dx.abs() + self.dy.abs()
+    }
+
+    /// `max(|dx|, |dy|)`, the number of king moves on a grid.
+    pub fn chebyshev_norm(&self) -> Real {
+        self.dx.abs().max(self.dy.abs())
+    }
+
+    /// Orders by `dx`, then by `dy`, a total order suited to sorting.
+    pub fn cmp_lexicographic(&self, other: &Self) -> core::cmp::Ordering {
+        (&self.dx, &self.dy).cmp(&(&other.dx, &other.dy))
+    }
+
+    /// Component-wise order: `Less` only if neither component is greater,
+    /// `None` if the components disagree.
+    pub fn partial_cmp_componentwise(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        use core::cmp::Ordering::*;
+
+        match (self.dx.cmp(&other.dx), self.dy.cmp(&other.dy)) {
+            (x, y) if x == y => Some(x),
+            (Equal, other) | (other, Equal) => Some(other),
+            _ => None,
+        }
+    }
+
+    /// Whether `self` is shorter than `other`, without taking square roots.
+    pub fn is_shorter_than(&self, other: &Self) -> bool {
+        self.norm_squared() < other.norm_squared()
+    }
 }
 
 ///////////
 // Addition
 ///////////
 
-impl std::ops::Add for Offset {
+impl core::ops::Add for Offset {
     type Output = Offset;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -47,7 +113,7 @@ impl std::ops::Add for Offset {
     }
 }
 
-impl std::ops::Add for &Offset {
+impl core::ops::Add for &Offset {
     type Output = Offset;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -55,7 +121,7 @@ impl std::ops::Add for &Offset {
     }
 }
 
-impl std::ops::Add<&Offset> for Offset {
+impl core::ops::Add<&Offset> for Offset {
     type Output = Offset;
 
     fn add(self, rhs: &Offset) -> Self::Output {
@@ -63,7 +129,7 @@ impl std::ops::Add<&Offset> for Offset {
     }
 }
 
-impl std::ops::Add<Offset> for &Offset {
+impl core::ops::Add<Offset> for &Offset {
     type Output = Offset;
 
     fn add(self, rhs: Offset) -> Self::Output {
@@ -75,7 +141,7 @@ impl std::ops::Add<Offset> for &Offset {
 // Negation
 ///////////
 
-impl std::ops::Neg for Offset {
+impl core::ops::Neg for Offset {
     type Output = Offset;
 
     fn neg(self) -> Self::Output {
@@ -86,7 +152,7 @@ impl std::ops::Neg for Offset {
     }
 }
 
-impl std::ops::Neg for &Offset {
+impl core::ops::Neg for &Offset {
     type Output = Offset;
 
     fn neg(self) -> Self::Output {
@@ -98,7 +164,7 @@ impl std::ops::Neg for &Offset {
 // Multiplication
 /////////////////
 
-impl std::ops::Mul<Scale> for Offset {
+impl core::ops::Mul<Scale> for Offset {
     type Output = Offset;
 
     fn mul(self, rhs: Scale) -> Self::Output {
@@ -109,7 +175,7 @@ impl std::ops::Mul<Scale> for Offset {
     }
 }
 
-impl std::ops::Mul<&Scale> for Offset {
+impl core::ops::Mul<&Scale> for Offset {
     type Output = Offset;
 
     fn mul(self, rhs: &Scale) -> Self::Output {
@@ -117,7 +183,7 @@ impl std::ops::Mul<&Scale> for Offset {
     }
 }
 
-impl std::ops::Mul<Scale> for &Offset {
+impl core::ops::Mul<Scale> for &Offset {
     type Output = Offset;
 
     fn mul(self, rhs: Scale) -> Self::Output {
@@ -125,7 +191,7 @@ impl std::ops::Mul<Scale> for &Offset {
     }
 }
 
-impl std::ops::Mul<&Scale> for &Offset {
+impl core::ops::Mul<&Scale> for &Offset {
     type Output = Offset;
 
     fn mul(self, rhs: &Scale) -> Self::Output {
@@ -133,6 +199,48 @@ impl std::ops::Mul<&Scale> for &Offset {
     }
 }
 
+///////////
+// Division
+///////////
+
+impl core::ops::Div<Scale> for Offset {
+    type Output = Offset;
+
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    fn div(self, rhs: Scale) -> Self::Output {
+        let dx = self.dx / &rhs.0;
+        let dy = self.dy / rhs.0;
+
+        Self { dx, dy }
+    }
+}
+
+impl core::ops::Div<&Scale> for Offset {
+    type Output = Offset;
+
+    fn div(self, rhs: &Scale) -> Self::Output {
+        self / rhs.clone()
+    }
+}
+
+impl core::ops::Div<Scale> for &Offset {
+    type Output = Offset;
+
+    fn div(self, rhs: Scale) -> Self::Output {
+        self.clone() / rhs
+    }
+}
+
+impl core::ops::Div<&Scale> for &Offset {
+    type Output = Offset;
+
+    fn div(self, rhs: &Scale) -> Self::Output {
+        self.clone() / rhs.clone()
+    }
+}
+
 #[cfg(test)]
 pub mod gens {
     use proptest::prelude::Strategy;
@@ -157,18 +265,34 @@ pub mod gens {
 #[cfg(test)]
 mod tests {
     use proptest::array::{uniform2, uniform3};
-    use proptest::proptest;
+    #[cfg(feature = "fast")]
+    use proptest::prop_assert;
+    use proptest::{prop_assert_eq, prop_assume, proptest};
 
     use super::gens::offset;
     use super::*;
     use crate::scale::gens::scale;
+    #[cfg(feature = "fast")]
+    use crate::tests::{BOUND, close};
 
     proptest! {
+        #[test]
+        fn display_round_trips(a in offset()) {
+            prop_assert_eq!(a.to_string().parse(), Ok(a));
+        }
+
+        #[cfg(not(feature = "fast"))]
         #[test]
         fn offset_add_associative([a, b, c] in uniform3(offset())) {
             assert_eq!(&a + (&b + &c), (&a + &b) + &c)
         }
 
+        #[cfg(feature = "fast")]
+        #[test]
+        fn offset_add_associative_up_to_rounding([a, b, c] in uniform3(offset())) {
+            prop_assert!(close(&(&a + (&b + &c)), &((&a + &b) + &c), 3.0 * BOUND));
+        }
+
         #[test]
         fn offset_add_commutative([a, b] in uniform2(offset())) {
             assert_eq!(&a + &b, &b + &a)
@@ -189,24 +313,151 @@ mod tests {
             assert_eq!(&a + -&a, Offset::zero())
         }
 
+        #[cfg(not(feature = "fast"))]
         #[test]
         fn offset_mul_scale_mul_associative(a in offset(), [m, n] in uniform2(scale())) {
             assert_eq!((&a * &m) * &n, &a * (&m * &n))
         }
 
+        #[cfg(feature = "fast")]
+        #[test]
+        fn offset_mul_scale_mul_associative_up_to_rounding(
+            a in offset(),
+            [m, n] in uniform2(scale()),
+        ) {
+            prop_assert!(close(&((&a * &m) * &n), &(&a * (&m * &n)), BOUND.powi(3)));
+        }
+
         #[test]
         fn scale_one_offset_mul_right_identity(a in offset()) {
             assert_eq!(&a * Scale::one(), a);
         }
 
+        #[cfg(not(feature = "fast"))]
         #[test]
         fn offset_mul_distributive_over_offset_add([a, b] in uniform2(offset()), m in scale()) {
             assert_eq!((&a + &b) * &m, &a * &m + &b * &m)
         }
 
+        #[cfg(feature = "fast")]
+        #[test]
+        fn offset_mul_distributive_over_offset_add_up_to_rounding(
+            [a, b] in uniform2(offset()),
+            m in scale(),
+        ) {
+            let magnitude = 2.0 * BOUND.powi(2);
+            prop_assert!(close(&((&a + &b) * &m), &(&a * &m + &b * &m), magnitude));
+        }
+
+        #[cfg(not(feature = "fast"))]
         #[test]
         fn offset_mul_distributive_over_scale_add(a in offset(), [m, n] in uniform2(scale())) {
             assert_eq!(&a * (&m + &n), &a * &m + &a * &n)
         }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn offset_mul_distributive_over_scale_add_up_to_rounding(
+            a in offset(),
+            [m, n] in uniform2(scale()),
+        ) {
+            let magnitude = 2.0 * BOUND.powi(2);
+            prop_assert!(close(&(&a * (&m + &n)), &(&a * &m + &a * &n), magnitude));
+        }
+
+        #[test]
+        fn offset_dot_commutative([a, b] in uniform2(offset())) {
+            assert_eq!(a.dot(&b), b.dot(&a))
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn offset_dot_distributive_over_offset_add([a, b, c] in uniform3(offset())) {
+            assert_eq!(a.dot(&(&b + &c)), a.dot(&b) + a.dot(&c))
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn offset_dot_distributive_over_offset_add_up_to_rounding(
+            [a, b, c] in uniform3(offset()),
+        ) {
+            let magnitude = 4.0 * BOUND.powi(2);
+            prop_assert!(close(&a.dot(&(&b + &c)), &(a.dot(&b) + a.dot(&c)), magnitude));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn offset_norm_squared_scales_quadratically(a in offset(), m in scale()) {
+            assert_eq!((&a * &m).norm_squared(), a.norm_squared() * &m.0 * &m.0)
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn offset_norm_squared_scales_quadratically_up_to_rounding(a in offset(), m in scale()) {
+            prop_assert!(close(
+                &(&a * &m).norm_squared(),
+                &(a.norm_squared() * &m.0 * &m.0),
+                2.0 * BOUND.powi(4)
+            ));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn offset_norms_satisfy_triangle_inequality([a, b] in uniform2(offset())) {
+            assert!((&a + &b).manhattan_norm() <= a.manhattan_norm() + b.manhattan_norm());
+            assert!((&a + &b).chebyshev_norm() <= a.chebyshev_norm() + b.chebyshev_norm());
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn offset_norms_satisfy_triangle_inequality_up_to_rounding(
+            [a, b] in uniform2(offset()),
+        ) {
+            let slack = Real::from_f64(1e-12 * 4.0 * BOUND).unwrap();
+            let sum = &a + &b;
+            prop_assert!(sum.manhattan_norm() <= a.manhattan_norm() + b.manhattan_norm() + &slack);
+            prop_assert!(sum.chebyshev_norm() <= a.chebyshev_norm() + b.chebyshev_norm() + &slack);
+        }
+
+        #[test]
+        fn offset_norms_are_ordered(a in offset()) {
+            assert!(a.chebyshev_norm() <= a.manhattan_norm());
+            assert!(a.manhattan_norm() <= &a.chebyshev_norm() + &a.chebyshev_norm());
+        }
+
+        #[test]
+        fn offset_componentwise_order_refines_lexicographic([a, b] in uniform2(offset())) {
+            if let Some(order) = a.partial_cmp_componentwise(&b) {
+                assert_eq!(order, a.cmp_lexicographic(&b))
+            }
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn offset_div_scale_undoes_offset_mul_scale(a in offset(), m in scale()) {
+            prop_assume!(m != Scale::zero());
+            assert_eq!((&a * &m) / &m, a)
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn offset_div_scale_undoes_offset_mul_scale_up_to_rounding(a in offset(), m in scale()) {
+            prop_assume!(m != Scale::zero());
+            prop_assert!(close(&((&a * &m) / &m), &a, BOUND));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn offset_div_scale_is_mul_by_inverse(a in offset(), m in scale()) {
+            prop_assume!(m != Scale::zero());
+            assert_eq!(&a / &m, &a * m.inverse().unwrap())
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn offset_div_scale_is_mul_by_inverse_up_to_rounding(a in offset(), m in scale()) {
+            prop_assume!(m != Scale::zero());
+            prop_assert!(close(&(&a / &m), &(&a * m.inverse().unwrap()), BOUND.powi(2)));
+        }
     }
 }
@@ -1,14 +1,17 @@
+use alloc::string::ToString;
+
 use crate::offset::Offset;
-use crate::real::Real;
+use crate::real::{ParseRealError, Real, parse_entries};
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Place {
     pub(super) x: Real,
     pub(super) y: Real,
 }
 
-impl std::fmt::Display for Place {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Place {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_map()
             .entry(&"x", &self.x.to_string())
             .entry(&"y", &self.y.to_string())
@@ -16,6 +19,16 @@ impl std::fmt::Display for Place {
     }
 }
 
+/// Parses the displayed form, `{"x": "1/2", "y": "-3"}`.
+impl core::str::FromStr for Place {
+    type Err = ParseRealError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y] = parse_entries(s, ["x", "y"])?;
+        Ok(Self { x, y })
+    }
+}
+
 impl Place {
     pub fn new(x: f64, y: f64) -> Option<Self> {
         let x = Real::from_f64(x)?;
@@ -24,6 +37,10 @@ impl Place {
         Some(Self { x, y })
     }
 
+    pub fn from_reals(x: Real, y: Real) -> Self {
+        Self { x, y }
+    }
+
     pub fn origin() -> Self {
         Self {
             x: Real::zero(),
@@ -31,6 +48,14 @@ impl Place {
         }
     }
 
+    pub fn x(&self) -> &Real {
+        &self.x
+    }
+
+    pub fn y(&self) -> &Real {
+        &self.y
+    }
+
     pub fn offset_to(self, other: Self) -> Offset {
         other - self
     }
@@ -40,7 +65,7 @@ impl Place {
 // Addition
 ///////////
 
-impl std::ops::Add<Offset> for Place {
+impl core::ops::Add<Offset> for Place {
     type Output = Place;
 
     fn add(self, rhs: Offset) -> Self::Output {
@@ -51,7 +76,7 @@ impl std::ops::Add<Offset> for Place {
     }
 }
 
-impl std::ops::Add<&Offset> for Place {
+impl core::ops::Add<&Offset> for Place {
     type Output = Place;
 
     fn add(self, rhs: &Offset) -> Self::Output {
@@ -59,7 +84,7 @@ impl std::ops::Add<&Offset> for Place {
     }
 }
 
-impl std::ops::Add<Offset> for &Place {
+impl core::ops::Add<Offset> for &Place {
     type Output = Place;
 
     fn add(self, rhs: Offset) -> Self::Output {
@@ -67,7 +92,7 @@ impl std::ops::Add<Offset> for &Place {
     }
 }
 
-impl std::ops::Add<&Offset> for &Place {
+impl core::ops::Add<&Offset> for &Place {
     type Output = Place;
 
     fn add(self, rhs: &Offset) -> Self::Output {
@@ -79,7 +104,7 @@ impl std::ops::Add<&Offset> for &Place {
 // Subtraction
 //////////////
 
-impl std::ops::Sub for Place {
+impl core::ops::Sub for Place {
     type Output = Offset;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -90,7 +115,7 @@ impl std::ops::Sub for Place {
     }
 }
 
-impl std::ops::Sub for &Place {
+impl core::ops::Sub for &Place {
     type Output = Offset;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -98,7 +123,7 @@ impl std::ops::Sub for &Place {
     }
 }
 
-impl std::ops::Sub<&Place> for Place {
+impl core::ops::Sub<&Place> for Place {
     type Output = Offset;
 
     fn sub(self, rhs: &Place) -> Self::Output {
@@ -106,7 +131,7 @@ impl std::ops::Sub<&Place> for Place {
     }
 }
 
-impl std::ops::Sub<Place> for &Place {
+impl core::ops::Sub<Place> for &Place {
     type Output = Offset;
 
     fn sub(self, rhs: Place) -> Self::Output {
@@ -138,26 +163,76 @@ pub mod gens {
 #[cfg(test)]
 mod tests {
     use proptest::array::uniform2;
-    use proptest::proptest;
+    #[cfg(feature = "fast")]
+    use proptest::prop_assert;
+    use proptest::{prop_assert_eq, proptest};
 
-    use crate::offset::gens::offset;
     use crate::offset::Offset;
+    use crate::offset::gens::offset;
+    use crate::place::Place;
     use crate::place::gens::place;
+    use crate::real::Real;
+    #[cfg(feature = "fast")]
+    use crate::tests::{BOUND, close};
+
+    #[test]
+    fn places_parse_with_or_without_quotes() {
+        let p = Place::from_reals(Real::one() / Real::from_f64(2.0).unwrap(), -Real::one());
+        assert_eq!(r#"{"x": "1/2", "y": "-1"}"#.parse(), Ok(p.clone()));
+        assert_eq!("{ y: -1, x: 0.5 }".parse(), Ok(p));
+        assert!("{x: 1}".parse::<Place>().is_err());
+        assert!("{x: 1, x: 2}".parse::<Place>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn places_serialize_as_exact_strings() {
+        let p = Place::from_reals(Real::one() / Real::from_f64(3.0).unwrap(), Real::zero());
+        let json = serde_json::to_string(&p).unwrap();
+        assert_eq!(json, r#"{"x":"1/3","y":"0"}"#);
+        assert_eq!(serde_json::from_str::<Place>(&json).unwrap(), p);
+        assert_eq!(
+            serde_json::from_str::<Place>(r#"{"x": 0.25, "y": -2}"#).unwrap(),
+            Place::new(0.25, -2.0).unwrap()
+        );
+    }
 
     proptest! {
+        #[test]
+        fn display_round_trips(p in place()) {
+            prop_assert_eq!(p.to_string().parse(), Ok(p));
+        }
+
         #[test]
         fn offset_zero_place_add_right_identity(p in place()) {
             assert_eq!(&p + Offset::zero(), p)
         }
 
+        #[cfg(not(feature = "fast"))]
         #[test]
         fn place_add_offset_add_associative(p in place(), [a, b] in uniform2(offset())) {
             assert_eq!((&p + &a) + &b, &p + (&a + &b))
         }
 
+        #[cfg(feature = "fast")]
+        #[test]
+        fn place_add_offset_add_associative_up_to_rounding(
+            p in place(),
+            [a, b] in uniform2(offset()),
+        ) {
+            prop_assert!(close(&((&p + &a) + &b), &(&p + (&a + &b)), 3.0 * BOUND));
+        }
+
+        #[cfg(not(feature = "fast"))]
         #[test]
         fn place_add_place_sub([p, q] in uniform2(place())) {
             assert_eq!(&p + (&q - &p), q)
         }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn place_add_place_sub_up_to_rounding([p, q] in uniform2(place())) {
+            prop_assert!(close(&(&p + (&q - &p)), &q, 3.0 * BOUND));
+        }
     }
 }
@@ -1,46 +1,180 @@
-use num::rational::Ratio;
-use num::{BigInt, ToPrimitive};
-
+#[cfg(not(feature = "fast"))]
+mod exact;
+#[cfg(feature = "fast")]
+mod fast;
+
+#[cfg(not(feature = "fast"))]
+use exact::Repr;
+#[cfg(feature = "fast")]
+use fast::Repr;
+
+/// Exact rational number, or a plain `f64` when the `fast` feature is
+/// enabled.
+///
+/// Both modes share this API, so geometry code compiles unchanged in either;
+/// only the exact mode guarantees the algebraic laws tested below.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-pub struct Real(Ratio<BigInt>);
+pub struct Real(Repr);
 
-impl std::fmt::Display for Real {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Real {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
 
+impl core::str::FromStr for Real {
+    type Err = ParseRealError;
+
+    /// Parses an integer, a decimal such as `-1.25` or a fraction `n/d`, so
+    /// every displayed `Real` parses back to itself.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim().parse().map(Self)
+    }
+}
+
+impl From<i64> for Real {
+    fn from(value: i64) -> Self {
+        Self(Repr::from_integer(value))
+    }
+}
+
+/// Error returned when a string is not a [`Real`], or not one of the types
+/// built from reals, such as [`Place`](crate::Place).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRealError;
+
+impl core::fmt::Display for ParseRealError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "expected an integer, a decimal or a fraction n/d")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseRealError {}
+
+/// Parses the `{"a": "1/2", "b": "3"}` form that pairs of reals display as,
+/// returning the values in the order of `keys`. Quotes are optional.
+pub(crate) fn parse_entries(s: &str, keys: [&str; 2]) -> Result<[Real; 2], ParseRealError> {
+    fn unquote(s: &str) -> &str {
+        let s = s.trim();
+        s.strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .unwrap_or(s)
+    }
+
+    let entries = s
+        .trim()
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .ok_or(ParseRealError)?;
+
+    let mut values = [None, None];
+    for entry in entries.split(',') {
+        let (key, value) = entry.split_once(':').ok_or(ParseRealError)?;
+        let index = keys
+            .iter()
+            .position(|k| unquote(key) == *k)
+            .ok_or(ParseRealError)?;
+        if values[index].replace(unquote(value).parse()?).is_some() {
+            return Err(ParseRealError);
+        }
+    }
+
+    match values {
+        [Some(a), Some(b)] => Ok([a, b]),
+        _ => Err(ParseRealError),
+    }
+}
+
+/// Serialized as its display string, such as `"-3/4"`, so no precision is
+/// lost. Numbers are accepted too when deserializing.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Real {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Real {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Real;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "a finite number or a string such as \"-3/4\"")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Real, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Real, E> {
+                self.visit_str(&alloc::format!("{v}"))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Real, E> {
+                self.visit_str(&alloc::format!("{v}"))
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Real, E> {
+                Real::from_f64(v).ok_or_else(|| E::custom("expected a finite number"))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
 impl Real {
     pub fn one() -> Self {
-        Self(Ratio::from_integer(BigInt::from(1)))
+        Self(Repr::from_integer(1))
     }
 
     pub fn zero() -> Self {
-        Self(Ratio::from_integer(BigInt::from(0)))
+        Self(Repr::from_integer(0))
     }
 
     pub fn from_f64(value: f64) -> Option<Self> {
-        Ratio::from_float(value).map(|r| Self(r))
+        Repr::from_f64(value).map(Self)
     }
 
     pub fn to_f64(&self) -> Option<f64> {
         self.0.to_f64()
     }
 
+    pub fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    pub fn abs(&self) -> Self {
+        if *self < Self::zero() {
+            -self
+        } else {
+            self.clone()
+        }
+    }
+
+    pub fn floor(&self) -> Self {
+        Self(self.0.floor())
+    }
+
+    #[cfg(feature = "std")]
     pub fn sin(&self) -> Self {
         Self::from_f64(
-            self.0
-                .to_f64()
+            self.to_f64()
                 .expect("current implementation of sin can work only with finite f64")
                 .sin(),
         )
         .expect("sin of finite f64 should produce finite f64")
     }
 
+    #[cfg(feature = "std")]
     pub fn cos(&self) -> Self {
         Self::from_f64(
-            self.0
-                .to_f64()
+            self.to_f64()
                 .expect("current implementation of cos can work only with finite f64")
                 .cos(),
         )
@@ -52,7 +186,7 @@ impl Real {
 // Addition
 ///////////
 
-impl std::ops::Add for Real {
+impl core::ops::Add for Real {
     type Output = Real;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -60,7 +194,7 @@ impl std::ops::Add for Real {
     }
 }
 
-impl std::ops::Add for &Real {
+impl core::ops::Add for &Real {
     type Output = Real;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -68,7 +202,7 @@ impl std::ops::Add for &Real {
     }
 }
 
-impl std::ops::Add<&Real> for Real {
+impl core::ops::Add<&Real> for Real {
     type Output = Real;
 
     fn add(self, rhs: &Real) -> Self::Output {
@@ -76,7 +210,7 @@ impl std::ops::Add<&Real> for Real {
     }
 }
 
-impl std::ops::Add<Real> for &Real {
+impl core::ops::Add<Real> for &Real {
     type Output = Real;
 
     fn add(self, rhs: Real) -> Self::Output {
@@ -88,7 +222,7 @@ impl std::ops::Add<Real> for &Real {
 // Subtraction
 //////////////
 
-impl std::ops::Sub for Real {
+impl core::ops::Sub for Real {
     type Output = Real;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -96,7 +230,7 @@ impl std::ops::Sub for Real {
     }
 }
 
-impl std::ops::Sub for &Real {
+impl core::ops::Sub for &Real {
     type Output = Real;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -104,7 +238,7 @@ impl std::ops::Sub for &Real {
     }
 }
 
-impl std::ops::Sub<&Real> for Real {
+impl core::ops::Sub<&Real> for Real {
     type Output = Real;
 
     fn sub(self, rhs: &Real) -> Self::Output {
@@ -112,7 +246,7 @@ impl std::ops::Sub<&Real> for Real {
     }
 }
 
-impl std::ops::Sub<Real> for &Real {
+impl core::ops::Sub<Real> for &Real {
     type Output = Real;
 
     fn sub(self, rhs: Real) -> Self::Output {
@@ -124,7 +258,7 @@ impl std::ops::Sub<Real> for &Real {
 // Multiplication
 /////////////////
 
-impl std::ops::Mul for Real {
+impl core::ops::Mul for Real {
     type Output = Real;
 
     fn mul(self, rhs: Self) -> Self::Output {
@@ -132,7 +266,7 @@ impl std::ops::Mul for Real {
     }
 }
 
-impl std::ops::Mul for &Real {
+impl core::ops::Mul for &Real {
     type Output = Real;
 
     fn mul(self, rhs: Self) -> Self::Output {
@@ -140,7 +274,7 @@ impl std::ops::Mul for &Real {
     }
 }
 
-impl std::ops::Mul<&Real> for Real {
+impl core::ops::Mul<&Real> for Real {
     type Output = Real;
 
     fn mul(self, rhs: &Real) -> Self::Output {
@@ -148,7 +282,7 @@ impl std::ops::Mul<&Real> for Real {
     }
 }
 
-impl std::ops::Mul<Real> for &Real {
+impl core::ops::Mul<Real> for &Real {
     type Output = Real;
 
     fn mul(self, rhs: Real) -> Self::Output {
@@ -160,7 +294,7 @@ impl std::ops::Mul<Real> for &Real {
 // Negation
 ///////////
 
-impl std::ops::Neg for Real {
+impl core::ops::Neg for Real {
     type Output = Real;
 
     fn neg(self) -> Self::Output {
@@ -168,11 +302,11 @@ impl std::ops::Neg for Real {
     }
 }
 
-impl std::ops::Neg for &Real {
+impl core::ops::Neg for &Real {
     type Output = Real;
 
     fn neg(self) -> Self::Output {
-        Real(-self.0.clone())
+        -self.clone()
     }
 }
 
@@ -180,7 +314,7 @@ impl std::ops::Neg for &Real {
 // Division
 ///////////
 
-impl std::ops::Div for Real {
+impl core::ops::Div for Real {
     type Output = Real;
 
     fn div(self, rhs: Self) -> Self::Output {
@@ -188,7 +322,7 @@ impl std::ops::Div for Real {
     }
 }
 
-impl std::ops::Div for &Real {
+impl core::ops::Div for &Real {
     type Output = Real;
 
     fn div(self, rhs: Self) -> Self::Output {
@@ -196,7 +330,7 @@ impl std::ops::Div for &Real {
     }
 }
 
-impl std::ops::Div<&Real> for Real {
+impl core::ops::Div<&Real> for Real {
     type Output = Real;
 
     fn div(self, rhs: &Real) -> Self::Output {
@@ -204,7 +338,7 @@ impl std::ops::Div<&Real> for Real {
     }
 }
 
-impl std::ops::Div<Real> for &Real {
+impl core::ops::Div<Real> for &Real {
     type Output = Real;
 
     fn div(self, rhs: Real) -> Self::Output {
@@ -221,6 +355,7 @@ pub mod gens {
     use crate::tests::sampler;
 
     /// Generates arbitrary Real values for testing.
+    #[cfg(not(feature = "fast"))]
     pub fn real() -> impl Strategy<Value = Real> {
         (proptest::num::f64::NORMAL
             | proptest::num::f64::NEGATIVE
@@ -229,6 +364,21 @@ pub mod gens {
             .prop_map(|f| Real::from_f64(f).expect("any finite f64 should be a valid Real"))
     }
 
+    /// Generates Real values for testing, within [`BOUND`](crate::tests::BOUND)
+    /// so that the law tests can bound the rounding of `f64` arithmetic.
+    #[cfg(feature = "fast")]
+    pub fn real() -> impl Strategy<Value = Real> {
+        small_real()
+    }
+
+    /// Generates ratios of small integers, which stay in the inline
+    /// representation.
+    pub fn small_real() -> impl Strategy<Value = Real> {
+        (-1000..1000i32, 1..1000i32).prop_map(|(numer, denom)| {
+            Real::from_f64(numer.into()).unwrap() / Real::from_f64(denom.into()).unwrap()
+        })
+    }
+
     #[test]
     #[ignore = "just examples of Real"]
     fn print_reals() {
@@ -246,13 +396,20 @@ pub mod gens {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "fast")))]
 mod tests {
     use proptest::array::{uniform2, uniform3};
-    use proptest::{prop_assert_eq, prop_assume, proptest};
+    use proptest::{prop_assert, prop_assert_eq, prop_assume, prop_oneof, proptest};
 
-    use super::gens::real;
-    use super::Real;
+    use super::gens::{real, small_real};
+    use super::{ParseRealError, Real};
+
+    #[test]
+    fn malformed_reals_are_rejected() {
+        for s in ["", "1/0", "one", "1.2.3", "--1", "1/2/3"] {
+            assert_eq!(s.parse::<Real>(), Err(ParseRealError), "{s:?}");
+        }
+    }
 
     proptest! {
         #[test]
@@ -325,6 +482,32 @@ mod tests {
             prop_assert_eq!(-(-&a), a);
         }
 
+        #[test]
+        fn display_round_trips(a in prop_oneof![small_real(), real()]) {
+            prop_assert_eq!(a.to_string().parse(), Ok(a));
+        }
+
+        #[test]
+        fn floor_is_at_most_value(a in real()) {
+            prop_assert!(a.floor() <= a);
+        }
+
+        #[test]
+        fn floor_is_within_one_of_value(a in real()) {
+            prop_assert!(&a - a.floor() < Real::one());
+        }
+
+        #[test]
+        fn floor_is_idempotent(a in real()) {
+            prop_assert_eq!(a.floor().floor(), a.floor());
+        }
+
+        #[test]
+        fn abs_is_non_negative_and_symmetric(a in real()) {
+            prop_assert!(a.abs() >= Real::zero());
+            prop_assert_eq!(a.abs(), (-&a).abs());
+        }
+
         #[test]
         fn division_by_nonzero_is_valid([a, b] in uniform2(real())) {
             prop_assume!(b != Real::zero());
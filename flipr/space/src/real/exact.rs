@@ -0,0 +1,305 @@
+use alloc::borrow::Cow;
+use core::cmp::Ordering;
+
+use num::rational::Ratio;
+use num::traits::float::FloatCore;
+use num::{BigInt, Integer, ToPrimitive};
+
+use super::ParseRealError;
+
+/// Exact rational value of a [`Real`](super::Real).
+///
+/// Values whose reduced numerator and denominator fit in `i64` are kept
+/// inline and computed with `i128` intermediates, so coordinate arithmetic
+/// only allocates once results outgrow them. Every value has exactly one
+/// representation, so the derived equality and hash agree across both
+/// variants.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) enum Repr {
+    /// Reduced, with a positive denominator and a numerator other than
+    /// `i64::MIN`, so negation cannot overflow.
+    Small(Ratio<i64>),
+    /// Only used when the reduced value does not fit `Small`.
+    Big(Ratio<BigInt>),
+}
+
+impl core::fmt::Display for Repr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Repr::Small(r) => write!(f, "{r}"),
+            Repr::Big(r) => write!(f, "{r}"),
+        }
+    }
+}
+
+impl core::str::FromStr for Repr {
+    type Err = ParseRealError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ratio = match s.split_once('.') {
+            Some((whole, fraction)) => {
+                if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(ParseRealError);
+                }
+                let digits = [whole, fraction].concat();
+                let denom = num::pow(BigInt::from(10), fraction.len());
+                Ratio::new(digits.parse().map_err(|_| ParseRealError)?, denom)
+            }
+            None => s.parse().map_err(|_| ParseRealError)?,
+        };
+
+        Ok(Self::from_big(ratio))
+    }
+}
+
+impl PartialOrd for Repr {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Repr {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Repr::Small(a), Repr::Small(b)) => (i128::from(*a.numer()) * i128::from(*b.denom()))
+                .cmp(&(i128::from(*b.numer()) * i128::from(*a.denom()))),
+            _ => self.to_big().cmp(&other.to_big()),
+        }
+    }
+}
+
+impl Repr {
+    pub(super) fn from_integer(value: i64) -> Self {
+        match value {
+            i64::MIN => Repr::Big(Ratio::from_integer(value.into())),
+            _ => Repr::Small(Ratio::from_integer(value)),
+        }
+    }
+
+    pub(super) fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        // value = sign · mantissa · 2^exponent, with mantissa < 2^53.
+        let (mantissa, exponent, sign) = value.integer_decode();
+        let small = if mantissa == 0 {
+            Some((0, 1))
+        } else if exponent >= 0 {
+            (mantissa.leading_zeros() > exponent as u32).then(|| (mantissa << exponent, 1))
+        } else {
+            let shift = mantissa
+                .trailing_zeros()
+                .min(exponent.unsigned_abs().into());
+            let exponent = u32::from(exponent.unsigned_abs()) - shift;
+            (exponent < 63).then(|| (mantissa >> shift, 1 << exponent))
+        };
+
+        match small {
+            Some((numer, denom)) => Some(Repr::Small(Ratio::new_raw(
+                i64::from(sign) * numer as i64,
+                denom,
+            ))),
+            None => Ratio::from_float(value).map(Self::from_big),
+        }
+    }
+
+    pub(super) fn to_f64(&self) -> Option<f64> {
+        match self {
+            Repr::Small(r) => r.to_f64(),
+            Repr::Big(r) => r.to_f64(),
+        }
+    }
+
+    pub(super) fn to_i64(&self) -> Option<i64> {
+        match self {
+            Repr::Small(r) => r.to_i64(),
+            Repr::Big(r) => r.to_i64(),
+        }
+    }
+
+    pub(super) fn floor(&self) -> Self {
+        match self {
+            Repr::Small(r) => Repr::Small(Ratio::from_integer(r.numer().div_euclid(*r.denom()))),
+            Repr::Big(r) => Self::from_big(r.floor()),
+        }
+    }
+
+    /// Canonical form of a reduced big rational.
+    fn from_big(r: Ratio<BigInt>) -> Self {
+        match (r.numer().to_i64(), r.denom().to_i64()) {
+            (Some(numer), Some(denom)) if numer != i64::MIN => {
+                Repr::Small(Ratio::new_raw(numer, denom))
+            }
+            _ => Repr::Big(r),
+        }
+    }
+
+    /// Canonical form of `numer / denom`, for the `i128` results of combining
+    /// two small values. Panics if `denom` is zero.
+    fn from_wide(numer: i128, denom: i128) -> Self {
+        assert!(denom != 0, "denominator == 0");
+
+        let gcd = numer.gcd(&denom) * denom.signum();
+        let (numer, denom) = (numer / gcd, denom / gcd);
+        match (i64::try_from(numer), i64::try_from(denom)) {
+            (Ok(numer), Ok(denom)) if numer != i64::MIN => {
+                Repr::Small(Ratio::new_raw(numer, denom))
+            }
+            _ => Repr::Big(Ratio::new_raw(numer.into(), denom.into())),
+        }
+    }
+
+    fn to_big(&self) -> Cow<'_, Ratio<BigInt>> {
+        match self {
+            Repr::Small(r) => Cow::Owned(widen(r)),
+            Repr::Big(r) => Cow::Borrowed(r),
+        }
+    }
+
+    fn into_big(self) -> Ratio<BigInt> {
+        match self {
+            Repr::Small(r) => widen(&r),
+            Repr::Big(r) => r,
+        }
+    }
+}
+
+fn widen(r: &Ratio<i64>) -> Ratio<BigInt> {
+    Ratio::new_raw(BigInt::from(*r.numer()), BigInt::from(*r.denom()))
+}
+
+/// Numerators and denominators of two small values as `i128`, which holds
+/// any sum of two of their products without overflow.
+fn wide(a: &Ratio<i64>, b: &Ratio<i64>) -> (i128, i128, i128, i128) {
+    (
+        i128::from(*a.numer()),
+        i128::from(*a.denom()),
+        i128::from(*b.numer()),
+        i128::from(*b.denom()),
+    )
+}
+
+impl core::ops::Add for Repr {
+    type Output = Repr;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        match (&self, &rhs) {
+            (Repr::Small(a), Repr::Small(b)) => {
+                let (an, ad, bn, bd) = wide(a, b);
+                Repr::from_wide(an * bd + bn * ad, ad * bd)
+            }
+            _ => Repr::from_big(self.into_big() + rhs.into_big()),
+        }
+    }
+}
+
+impl core::ops::Sub for Repr {
+    type Output = Repr;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        match (&self, &rhs) {
+            (Repr::Small(a), Repr::Small(b)) => {
+                let (an, ad, bn, bd) = wide(a, b);
+                Repr::from_wide(an * bd - bn * ad, ad * bd)
+            }
+            _ => Repr::from_big(self.into_big() - rhs.into_big()),
+        }
+    }
+}
+
+impl core::ops::Mul for Repr {
+    type Output = Repr;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        match (&self, &rhs) {
+            (Repr::Small(a), Repr::Small(b)) => {
+                let (an, ad, bn, bd) = wide(a, b);
+                Repr::from_wide(an * bn, ad * bd)
+            }
+            _ => Repr::from_big(self.into_big() * rhs.into_big()),
+        }
+    }
+}
+
+impl core::ops::Neg for Repr {
+    type Output = Repr;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Repr::Small(r) => Repr::Small(-r),
+            Repr::Big(r) => Repr::Big(-r),
+        }
+    }
+}
+
+impl core::ops::Div for Repr {
+    type Output = Repr;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        match (&self, &rhs) {
+            (Repr::Small(a), Repr::Small(b)) => {
+                let (an, ad, bn, bd) = wide(a, b);
+                Repr::from_wide(an * bd, ad * bn)
+            }
+            _ => Repr::from_big(self.into_big() / rhs.into_big()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::rational::Ratio;
+    use proptest::array::uniform2;
+    use proptest::{prop_assert_eq, prop_oneof, proptest};
+
+    use super::super::gens::{real, small_real};
+    use super::super::{ParseRealError, Real};
+    use super::Repr;
+
+    fn big(value: i64) -> Real {
+        Real::from(value)
+    }
+
+    #[test]
+    fn results_move_between_representations() {
+        let large = big(i64::MAX) * big(4);
+        assert!(large > big(i64::MAX));
+        assert_eq!(&large / big(8), big(i64::MAX) / big(2));
+        assert_eq!(large.clone() - large, Real::zero());
+        assert_eq!(-big(i64::MIN), big(i64::MAX) + Real::one());
+    }
+
+    #[test]
+    fn from_f64_is_exact() {
+        for value in [0.0, -0.0, 0.1, -2.5, 1e18, 1e300, 5e-324, f64::MAX] {
+            let real = Real::from_f64(value).unwrap();
+            assert_eq!(real.to_f64(), Some(value));
+            assert_eq!(real.0, Repr::from_big(Ratio::from_float(value).unwrap()));
+        }
+    }
+
+    #[test]
+    fn decimals_parse_exactly() {
+        let tenth = Real::one() / Real::from_f64(10.0).unwrap();
+        assert_eq!("0.1".parse(), Ok(tenth.clone()));
+        assert_eq!("-.1".parse(), Ok(-&tenth));
+        assert_eq!("2/20".parse(), Ok(tenth));
+        assert_eq!("1e3".parse::<Real>(), Err(ParseRealError));
+    }
+
+    proptest! {
+        #[test]
+        fn arithmetic_matches_big_rationals([a, b] in uniform2(prop_oneof![small_real(), real()])) {
+            let (x, y) = (a.0.to_big().into_owned(), b.0.to_big().into_owned());
+            prop_assert_eq!((&a + &b).0.into_big(), &x + &y);
+            prop_assert_eq!((&a - &b).0.into_big(), &x - &y);
+            prop_assert_eq!((&a * &b).0.into_big(), &x * &y);
+            prop_assert_eq!(a.cmp(&b), x.cmp(&y));
+            prop_assert_eq!(a.floor().0.into_big(), x.floor());
+            if b != Real::zero() {
+                prop_assert_eq!((&a / &b).0.into_big(), &x / &y);
+            }
+        }
+    }
+}
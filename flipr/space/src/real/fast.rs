@@ -0,0 +1,183 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+use num::ToPrimitive;
+
+use super::ParseRealError;
+
+/// Approximate value of a [`Real`](super::Real), ordered by
+/// [`f64::total_cmp`] so it can still be `Eq`, `Ord` and `Hash`. Zero is
+/// always stored as `+0.0`, so both zeros compare equal.
+#[derive(Debug, Clone)]
+pub(super) struct Repr(f64);
+
+impl core::fmt::Display for Repr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl core::str::FromStr for Repr {
+    type Err = ParseRealError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |s: &str| s.parse::<f64>().map_err(|_| ParseRealError);
+        let value = match s.split_once('/') {
+            Some((numer, denom)) => {
+                let denom = number(denom)?;
+                if denom == 0.0 {
+                    return Err(ParseRealError);
+                }
+                number(numer)? / denom
+            }
+            None => number(s)?,
+        };
+
+        Repr::from_f64(value).ok_or(ParseRealError)
+    }
+}
+
+impl PartialEq for Repr {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Repr {}
+
+impl PartialOrd for Repr {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Repr {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Hash for Repr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl Repr {
+    fn new(value: f64) -> Self {
+        // -0.0 + 0.0 is +0.0, every other value is unchanged.
+        Repr(value + 0.0)
+    }
+
+    /// Rounds integers beyond 2^53 to the nearest `f64`.
+    pub(super) fn from_integer(value: i64) -> Self {
+        Repr::new(value as f64)
+    }
+
+    pub(super) fn from_f64(value: f64) -> Option<Self> {
+        value.is_finite().then(|| Repr::new(value))
+    }
+
+    /// `None` once a result has overflowed to infinity.
+    pub(super) fn to_f64(&self) -> Option<f64> {
+        self.0.is_finite().then_some(self.0)
+    }
+
+    pub(super) fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    pub(super) fn floor(&self) -> Self {
+        Repr::new(libm::floor(self.0))
+    }
+}
+
+impl core::ops::Add for Repr {
+    type Output = Repr;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Repr::new(self.0 + rhs.0)
+    }
+}
+
+impl core::ops::Sub for Repr {
+    type Output = Repr;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Repr::new(self.0 - rhs.0)
+    }
+}
+
+impl core::ops::Mul for Repr {
+    type Output = Repr;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Repr::new(self.0 * rhs.0)
+    }
+}
+
+impl core::ops::Neg for Repr {
+    type Output = Repr;
+
+    fn neg(self) -> Self::Output {
+        Repr::new(-self.0)
+    }
+}
+
+impl core::ops::Div for Repr {
+    type Output = Repr;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        // Exact reals panic on division by zero, so fast ones do too.
+        assert!(rhs.0 != 0.0, "denominator == 0");
+        Repr::new(self.0 / rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::super::Real;
+
+    fn real(value: f64) -> Real {
+        Real::from_f64(value).unwrap()
+    }
+
+    #[test]
+    fn zeros_are_one_value() {
+        assert_eq!(real(-0.0), Real::zero());
+        assert_eq!(
+            HashSet::from([real(0.0), real(-0.0), -Real::zero()]).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn arithmetic_rounds_like_f64() {
+        assert_eq!((real(0.1) + real(0.2)).to_f64(), Some(0.1 + 0.2));
+        assert_eq!(real(-2.5).floor(), real(-3.0));
+        assert_eq!(real(7.9).to_i64(), Some(7));
+        assert!(real(-1.0) < Real::zero());
+    }
+
+    #[test]
+    fn overflow_is_not_finite() {
+        assert_eq!((real(f64::MAX) * real(2.0)).to_f64(), None);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for value in [0.1, -2.5, 1e300, 5e-324] {
+            assert_eq!(real(value).to_string().parse(), Ok(real(value)));
+        }
+        assert_eq!("3/4".parse(), Ok(real(0.75)));
+        assert!("1/0".parse::<Real>().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "denominator == 0")]
+    fn division_by_zero_panics() {
+        let _ = Real::one() / Real::zero();
+    }
+}
@@ -1,14 +1,28 @@
-use crate::real::Real;
+use crate::real::{ParseRealError, Real};
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Scale(pub(super) Real);
 
-impl std::fmt::Display for Scale {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Scale {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
 
+/// Parses the factor like a [`Real`].
+impl core::str::FromStr for Scale {
+    type Err = ParseRealError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
 impl Scale {
     pub fn one() -> Self {
         Self(Real::one())
@@ -17,13 +31,30 @@ impl Scale {
     pub fn zero() -> Self {
         Self(Real::zero())
     }
+
+    pub fn new(factor: f64) -> Option<Self> {
+        Real::from_f64(factor).map(Self)
+    }
+
+    pub fn from_real(factor: Real) -> Self {
+        Self(factor)
+    }
+
+    pub fn value(&self) -> &Real {
+        &self.0
+    }
+
+    /// The factor undoing `self`, or `None` for zero.
+    pub fn inverse(&self) -> Option<Self> {
+        (self.0 != Real::zero()).then(|| Self(Real::one() / &self.0))
+    }
 }
 
 /////////////////
 // Multiplication
 /////////////////
 
-impl std::ops::Mul for Scale {
+impl core::ops::Mul for Scale {
     type Output = Scale;
 
     fn mul(self, rhs: Scale) -> Self::Output {
@@ -31,7 +62,7 @@ impl std::ops::Mul for Scale {
     }
 }
 
-impl std::ops::Mul for &Scale {
+impl core::ops::Mul for &Scale {
     type Output = Scale;
 
     fn mul(self, rhs: &Scale) -> Self::Output {
@@ -39,7 +70,7 @@ impl std::ops::Mul for &Scale {
     }
 }
 
-impl std::ops::Mul<&Scale> for Scale {
+impl core::ops::Mul<&Scale> for Scale {
     type Output = Scale;
 
     fn mul(self, rhs: &Scale) -> Self::Output {
@@ -47,7 +78,7 @@ impl std::ops::Mul<&Scale> for Scale {
     }
 }
 
-impl std::ops::Mul<Scale> for &Scale {
+impl core::ops::Mul<Scale> for &Scale {
     type Output = Scale;
 
     fn mul(self, rhs: Scale) -> Self::Output {
@@ -59,7 +90,7 @@ impl std::ops::Mul<Scale> for &Scale {
 // Addition
 ///////////
 
-impl std::ops::Add for Scale {
+impl core::ops::Add for Scale {
     type Output = Scale;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -67,7 +98,7 @@ impl std::ops::Add for Scale {
     }
 }
 
-impl std::ops::Add for &Scale {
+impl core::ops::Add for &Scale {
     type Output = Scale;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -75,7 +106,7 @@ impl std::ops::Add for &Scale {
     }
 }
 
-impl std::ops::Add<&Scale> for Scale {
+impl core::ops::Add<&Scale> for Scale {
     type Output = Scale;
 
     fn add(self, rhs: &Scale) -> Self::Output {
@@ -83,7 +114,7 @@ impl std::ops::Add<&Scale> for Scale {
     }
 }
 
-impl std::ops::Add<Scale> for &Scale {
+impl core::ops::Add<Scale> for &Scale {
     type Output = Scale;
 
     fn add(self, rhs: Scale) -> Self::Output {
@@ -95,7 +126,7 @@ impl std::ops::Add<Scale> for &Scale {
 // Negation
 ///////////
 
-impl std::ops::Neg for Scale {
+impl core::ops::Neg for Scale {
     type Output = Scale;
 
     fn neg(self) -> Self::Output {
@@ -103,7 +134,7 @@ impl std::ops::Neg for Scale {
     }
 }
 
-impl std::ops::Neg for &Scale {
+impl core::ops::Neg for &Scale {
     type Output = Scale;
 
     fn neg(self) -> Self::Output {
@@ -111,6 +142,45 @@ impl std::ops::Neg for &Scale {
     }
 }
 
+///////////
+// Division
+///////////
+
+impl core::ops::Div for Scale {
+    type Output = Scale;
+
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    fn div(self, rhs: Scale) -> Self::Output {
+        Self(self.0 / rhs.0)
+    }
+}
+
+impl core::ops::Div for &Scale {
+    type Output = Scale;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.clone() / rhs.clone()
+    }
+}
+
+impl core::ops::Div<&Scale> for Scale {
+    type Output = Scale;
+
+    fn div(self, rhs: &Scale) -> Self::Output {
+        self / rhs.clone()
+    }
+}
+
+impl core::ops::Div<Scale> for &Scale {
+    type Output = Scale;
+
+    fn div(self, rhs: Scale) -> Self::Output {
+        self.clone() / rhs
+    }
+}
+
 #[cfg(test)]
 pub mod gens {
     use proptest::prelude::Strategy;
@@ -121,7 +191,7 @@ pub mod gens {
 
     /// Generates arbitrary Scale values for testing.
     pub fn scale() -> impl Strategy<Value = Scale> {
-        real().prop_map(|d| Scale(d))
+        real().prop_map(Scale)
     }
 
     #[test]
@@ -136,17 +206,33 @@ pub mod gens {
 #[cfg(test)]
 mod tests {
     use proptest::array::{uniform2, uniform3};
-    use proptest::{prop_assert_eq, proptest};
+    #[cfg(feature = "fast")]
+    use proptest::prop_assert;
+    use proptest::{prop_assert_eq, prop_assume, proptest};
 
-    use super::gens::scale;
     use super::Scale;
+    use super::gens::scale;
+    #[cfg(feature = "fast")]
+    use crate::tests::{BOUND, close};
 
     proptest! {
+        #[test]
+        fn display_round_trips(m in scale()) {
+            prop_assert_eq!(m.to_string().parse(), Ok(m));
+        }
+
+        #[cfg(not(feature = "fast"))]
         #[test]
         fn scale_add_associative([m, n, o] in uniform3(scale())) {
             prop_assert_eq!(&m + (&n + &o), (&m + &n) + &o);
         }
 
+        #[cfg(feature = "fast")]
+        #[test]
+        fn scale_add_associative_up_to_rounding([m, n, o] in uniform3(scale())) {
+            prop_assert!(close(&(&m + (&n + &o)), &((&m + &n) + &o), 3.0 * BOUND));
+        }
+
         #[test]
         fn scale_add_commutative([m, n] in uniform2(scale())) {
             prop_assert_eq!(&m + &n, &n + &m);
@@ -167,11 +253,18 @@ mod tests {
             prop_assert_eq!(&m + (-&m), Scale::zero());
         }
 
+        #[cfg(not(feature = "fast"))]
         #[test]
         fn scale_mul_associative([m, n, o] in uniform3(scale())) {
             prop_assert_eq!(&m * (&n * &o), (&m * &n) * &o);
         }
 
+        #[cfg(feature = "fast")]
+        #[test]
+        fn scale_mul_associative_up_to_rounding([m, n, o] in uniform3(scale())) {
+            prop_assert!(close(&(&m * (&n * &o)), &((&m * &n) * &o), BOUND.powi(3)));
+        }
+
         #[test]
         fn scale_mul_commutative([m, n] in uniform2(scale())) {
             prop_assert_eq!(&m * &n, &n * &m);
@@ -197,9 +290,78 @@ mod tests {
             prop_assert_eq!(&m * Scale::one(), m);
         }
 
+        #[cfg(not(feature = "fast"))]
         #[test]
         fn scale_mul_distributes_over_scale_add([m, n, o] in uniform3(scale())) {
             prop_assert_eq!(&m * (&n + &o), &m * &n + &m * &o);
         }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn scale_mul_distributes_over_scale_add_up_to_rounding([m, n, o] in uniform3(scale())) {
+            let magnitude = 2.0 * BOUND.powi(2);
+            prop_assert!(close(&(&m * (&n + &o)), &(&m * &n + &m * &o), magnitude));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn scale_mul_inverse(m in scale()) {
+            prop_assume!(m != Scale::zero());
+            prop_assert_eq!(&m * m.inverse().unwrap(), Scale::one());
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn scale_mul_inverse_up_to_rounding(m in scale()) {
+            prop_assume!(m != Scale::zero());
+            prop_assert!(close(&(&m * m.inverse().unwrap()), &Scale::one(), 1.0));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn scale_inverse_involutive(m in scale()) {
+            prop_assume!(m != Scale::zero());
+            prop_assert_eq!(m.inverse().unwrap().inverse().unwrap(), m);
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn scale_inverse_involutive_up_to_rounding(m in scale()) {
+            prop_assume!(m != Scale::zero());
+            prop_assert!(close(&m.inverse().unwrap().inverse().unwrap(), &m, BOUND));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn scale_div_is_mul_by_inverse([m, n] in uniform2(scale())) {
+            prop_assume!(n != Scale::zero());
+            prop_assert_eq!(&m / &n, &m * n.inverse().unwrap());
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn scale_div_is_mul_by_inverse_up_to_rounding([m, n] in uniform2(scale())) {
+            prop_assume!(n != Scale::zero());
+            prop_assert!(close(&(&m / &n), &(&m * n.inverse().unwrap()), BOUND.powi(2)));
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn scale_div_undoes_scale_mul([m, n] in uniform2(scale())) {
+            prop_assume!(n != Scale::zero());
+            prop_assert_eq!((&m * &n) / &n, m);
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn scale_div_undoes_scale_mul_up_to_rounding([m, n] in uniform2(scale())) {
+            prop_assume!(n != Scale::zero());
+            prop_assert!(close(&((&m * &n) / &n), &m, BOUND));
+        }
+    }
+
+    #[test]
+    fn scale_zero_has_no_inverse() {
+        assert_eq!(Scale::zero().inverse(), None);
     }
 }
@@ -2,7 +2,82 @@ use proptest::prelude::Strategy;
 use proptest::strategy::ValueTree;
 use proptest::test_runner::TestRunner;
 
+#[cfg(feature = "fast")]
+pub use self::approx::{BOUND, Coordinates, close};
+
 pub fn sampler<T>(strategy: impl Strategy<Value = T>) -> impl Iterator<Item = T> {
     let mut runner = TestRunner::default();
     std::iter::from_fn(move || strategy.new_tree(&mut runner).ok().map(|v| v.current()))
 }
+
+/// Tolerances for checking algebraic laws on `fast` reals, whose `f64`
+/// arithmetic only satisfies them up to rounding.
+#[cfg(feature = "fast")]
+mod approx {
+    use alloc::vec::Vec;
+
+    use crate::{AffineMap, Matrix2, Offset, Place, Real, Scale, Vector2};
+
+    /// Largest magnitude of the reals generated in `fast` mode.
+    pub const BOUND: f64 = 1e3;
+
+    /// The reals a value is made of, compared one by one by [`close`].
+    pub trait Coordinates {
+        fn coordinates(&self) -> Vec<Real>;
+    }
+
+    impl Coordinates for Real {
+        fn coordinates(&self) -> Vec<Real> {
+            alloc::vec![self.clone()]
+        }
+    }
+
+    impl Coordinates for Scale {
+        fn coordinates(&self) -> Vec<Real> {
+            alloc::vec![self.0.clone()]
+        }
+    }
+
+    impl Coordinates for Offset {
+        fn coordinates(&self) -> Vec<Real> {
+            alloc::vec![self.dx.clone(), self.dy.clone()]
+        }
+    }
+
+    impl Coordinates for Place {
+        fn coordinates(&self) -> Vec<Real> {
+            alloc::vec![self.x.clone(), self.y.clone()]
+        }
+    }
+
+    impl Coordinates for Vector2 {
+        fn coordinates(&self) -> Vec<Real> {
+            alloc::vec![self.x.clone(), self.y.clone()]
+        }
+    }
+
+    impl Coordinates for Matrix2 {
+        fn coordinates(&self) -> Vec<Real> {
+            self.rows.iter().flatten().cloned().collect()
+        }
+    }
+
+    impl Coordinates for AffineMap {
+        fn coordinates(&self) -> Vec<Real> {
+            let mut coordinates = self.linear.coordinates();
+            coordinates.extend(self.translation.coordinates());
+            coordinates
+        }
+    }
+
+    /// Whether every coordinate of `a` is within rounding error of the one of
+    /// `b`, for results of a few operations on reals that never grow beyond
+    /// `magnitude`, intermediate results included.
+    pub fn close<T: Coordinates>(a: &T, b: &T, magnitude: f64) -> bool {
+        let tolerance = 1e-12 * magnitude;
+        a.coordinates()
+            .iter()
+            .zip(b.coordinates())
+            .all(|(a, b)| (a - b).to_f64().is_some_and(|d| d.abs() <= tolerance))
+    }
+}
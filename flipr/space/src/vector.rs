@@ -0,0 +1,228 @@
+use alloc::string::ToString;
+
+use crate::offset::Offset;
+use crate::place::Place;
+use crate::real::Real;
+
+/// Column vector of two exact reals, the operand of [`Matrix2`](crate::Matrix2).
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Vector2 {
+    pub(super) x: Real,
+    pub(super) y: Real,
+}
+
+impl core::fmt::Display for Vector2 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entry(&self.x.to_string())
+            .entry(&self.y.to_string())
+            .finish()
+    }
+}
+
+impl Vector2 {
+    pub fn zero() -> Self {
+        Self {
+            x: Real::zero(),
+            y: Real::zero(),
+        }
+    }
+
+    pub fn new(x: f64, y: f64) -> Option<Self> {
+        let x = Real::from_f64(x)?;
+        let y = Real::from_f64(y)?;
+
+        Some(Self { x, y })
+    }
+
+    pub fn from_reals(x: Real, y: Real) -> Self {
+        Self { x, y }
+    }
+
+    pub fn x(&self) -> &Real {
+        &self.x
+    }
+
+    pub fn y(&self) -> &Real {
+        &self.y
+    }
+
+    pub fn dot(&self, other: &Self) -> Real {
+        &self.x * &other.x + &self.y * &other.y
+    }
+}
+
+impl From<Offset> for Vector2 {
+    fn from(offset: Offset) -> Self {
+        Self {
+            x: offset.dx,
+            y: offset.dy,
+        }
+    }
+}
+
+impl From<Vector2> for Offset {
+    fn from(vector: Vector2) -> Self {
+        Offset {
+            dx: vector.x,
+            dy: vector.y,
+        }
+    }
+}
+
+impl From<Place> for Vector2 {
+    /// The position of the place relative to the origin.
+    fn from(place: Place) -> Self {
+        Self {
+            x: place.x,
+            y: place.y,
+        }
+    }
+}
+
+///////////
+// Addition
+///////////
+
+impl core::ops::Add for Vector2 {
+    type Output = Vector2;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl core::ops::Add for &Vector2 {
+    type Output = Vector2;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.clone() + rhs.clone()
+    }
+}
+
+///////////
+// Negation
+///////////
+
+impl core::ops::Neg for Vector2 {
+    type Output = Vector2;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl core::ops::Neg for &Vector2 {
+    type Output = Vector2;
+
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+/////////////////
+// Multiplication
+/////////////////
+
+impl core::ops::Mul<Real> for Vector2 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: Real) -> Self::Output {
+        Self {
+            x: self.x * &rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl core::ops::Mul<&Real> for &Vector2 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: &Real) -> Self::Output {
+        self.clone() * rhs.clone()
+    }
+}
+
+#[cfg(test)]
+pub mod gens {
+    use proptest::prelude::Strategy;
+
+    use super::Vector2;
+    use crate::real::gens::real;
+    use crate::tests::sampler;
+
+    pub fn vector() -> impl Strategy<Value = Vector2> {
+        (real(), real()).prop_map(|(x, y)| Vector2 { x, y })
+    }
+
+    #[test]
+    #[ignore = "just examples of Vector2"]
+    fn print_vectors() {
+        sampler(vector()).take(10).for_each(|v| {
+            println!("Vector2: {v:#}");
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::array::uniform3;
+    #[cfg(feature = "fast")]
+    use proptest::prop_assert;
+    use proptest::{prop_assert_eq, proptest};
+
+    use super::Vector2;
+    use super::gens::vector;
+    use crate::offset::Offset;
+    use crate::offset::gens::offset;
+    use crate::real::gens::real;
+    #[cfg(feature = "fast")]
+    use crate::tests::{BOUND, close};
+
+    proptest! {
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn vector_add_associative([u, v, w] in uniform3(vector())) {
+            prop_assert_eq!(&u + &(&v + &w), &(&u + &v) + &w);
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn vector_add_associative_up_to_rounding([u, v, w] in uniform3(vector())) {
+            prop_assert!(close(&(&u + &(&v + &w)), &(&(&u + &v) + &w), 3.0 * BOUND));
+        }
+
+        #[test]
+        fn vector_add_inverse(v in vector()) {
+            prop_assert_eq!(&v + &-&v, Vector2::zero());
+        }
+
+        #[cfg(not(feature = "fast"))]
+        #[test]
+        fn dot_is_bilinear([u, v, w] in uniform3(vector()), r in real()) {
+            prop_assert_eq!((&u * &r).dot(&(&v + &w)), (u.dot(&v) + u.dot(&w)) * r);
+        }
+
+        #[cfg(feature = "fast")]
+        #[test]
+        fn dot_is_bilinear_up_to_rounding([u, v, w] in uniform3(vector()), r in real()) {
+            let magnitude = 4.0 * BOUND.powi(3);
+            prop_assert!(close(
+                &(&u * &r).dot(&(&v + &w)),
+                &((u.dot(&v) + u.dot(&w)) * r),
+                magnitude
+            ));
+        }
+
+        #[test]
+        fn offsets_round_trip(a in offset()) {
+            prop_assert_eq!(Offset::from(Vector2::from(a.clone())), a);
+        }
+    }
+}
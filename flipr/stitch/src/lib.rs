@@ -0,0 +1,441 @@
+//! Panorama stitching built from flipr's pieces: FAST corners and oriented
+//! BRIEF descriptors find matching points, RANSAC fits the homography
+//! between neighboring images, and Laplacian pyramids blend the warped
+//! images without visible seams.
+//!
+//! ```no_run
+//! # let images: Vec<flipr::ImageBuffer<flipr::Rgb<f32>>> = Vec::new();
+//! let panorama = flipr_stitch::stitch(&images).expect("neighboring images overlap");
+//! ```
+
+use std::fmt;
+
+use flipr::analysis::{describe, fast_corners, match_features};
+use flipr::{
+    AffineTransform, DownsampleFilter, GaussianPyramid, Gray, Homography, Image, ImageBuffer,
+    Interpolation, LaplacianPyramid, Layout, Pixel, Ransac, estimate_homography, from_fn,
+};
+use space::Place;
+
+/// Reason [`Stitcher::stitch`] could not align the images.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StitchError {
+    NoImages,
+    /// Image `image` shares too few reliable matches with image `image - 1`.
+    NotEnoughMatches {
+        image: usize,
+        inliers: usize,
+    },
+    /// The estimated placement of image `image` is degenerate, for example
+    /// because it folds the image over the horizon or stretches it over a
+    /// canvas many times larger than all images together.
+    Degenerate {
+        image: usize,
+    },
+}
+
+impl fmt::Display for StitchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StitchError::NoImages => write!(f, "nothing to stitch"),
+            StitchError::NotEnoughMatches { image, inliers } => write!(
+                f,
+                "image {image} has only {inliers} consistent matches with image {}",
+                image - 1
+            ),
+            StitchError::Degenerate { image } => {
+                write!(f, "image {image} cannot be placed in the panorama")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StitchError {}
+
+/// Stitches images taken in order, each overlapping the previous one, into
+/// the frame of the first image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stitcher {
+    corner_threshold: f64,
+    max_features: usize,
+    ratio: f64,
+    min_inliers: usize,
+    ransac: Ransac,
+    levels: usize,
+}
+
+impl Default for Stitcher {
+    fn default() -> Self {
+        Self {
+            corner_threshold: 0.05,
+            max_features: 500,
+            ratio: 0.8,
+            min_inliers: 8,
+            ransac: Ransac::default(),
+            levels: 5,
+        }
+    }
+}
+
+/// [`Stitcher::stitch`] with the default settings.
+pub fn stitch<P>(images: &[ImageBuffer<P>]) -> Result<ImageBuffer<P>, StitchError>
+where
+    P: Pixel<Scalar = f32>,
+{
+    Stitcher::new().stitch(images)
+}
+
+/// Mean of the channels, blurred a little so descriptors are stable.
+fn luminance<P>(image: &ImageBuffer<P>) -> ImageBuffer<Gray<f32>>
+where
+    P: Pixel<Scalar = f32>,
+{
+    let gray = ImageBuffer::from_fn(
+        image.width(),
+        image.height(),
+        Layout::Interleaved,
+        |i, j| {
+            let p = image.pixel(i, j).expect("indices are in bounds");
+            Gray((0..P::CHANNELS).map(|c| p.channel(c)).sum::<f32>() / P::CHANNELS as f32)
+        },
+    );
+    ImageBuffer::sample(
+        &gray.gaussian_blur(1.0),
+        image.width(),
+        image.height(),
+        Layout::Interleaved,
+    )
+}
+
+fn corner(x: usize, y: usize) -> (f64, f64) {
+    (x as f64, y as f64)
+}
+
+/// Largest canvas accepted, as a multiple of the total area of the images.
+/// Nearly degenerate placements stay finite but throw corners arbitrarily
+/// far, asking for canvases no machine can allocate.
+const MAX_CANVAS_GROWTH: f64 = 16.0;
+
+/// Left and top edges, width and height of the pixel canvas holding every
+/// image of size `sizes` placed by `placements`.
+fn canvas(
+    placements: &[Homography],
+    sizes: &[(usize, usize)],
+) -> Result<(f64, f64, usize, usize), StitchError> {
+    let total: f64 = sizes.iter().map(|&(w, h)| w as f64 * h as f64).sum();
+    let (mut min, mut max) = ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN));
+    for (image, (placement, &(w, h))) in placements.iter().zip(sizes).enumerate() {
+        for (x, y) in [corner(0, 0), corner(w, 0), corner(0, h), corner(w, h)] {
+            let (x, y) = placement
+                .transform_point(x, y)
+                .filter(|(x, y)| x.is_finite() && y.is_finite())
+                .ok_or(StitchError::Degenerate { image })?;
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+        if (max.0 - min.0) * (max.1 - min.1) > MAX_CANVAS_GROWTH * total {
+            return Err(StitchError::Degenerate { image });
+        }
+    }
+
+    // Estimated corners land a rounding error away from whole pixels.
+    let (left, top) = ((min.0 + 1e-6).floor(), (min.1 + 1e-6).floor());
+    let (right, bottom) = ((max.0 - 1e-6).ceil(), (max.1 - 1e-6).ceil());
+    Ok((left, top, (right - left) as usize, (bottom - top) as usize))
+}
+
+impl Stitcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Smallest brightness difference, in channel units, for a FAST corner.
+    pub fn corner_threshold(mut self, threshold: f64) -> Self {
+        self.corner_threshold = threshold;
+        self
+    }
+
+    /// Strongest corners described per image.
+    pub fn max_features(mut self, count: usize) -> Self {
+        self.max_features = count;
+        self
+    }
+
+    /// Lowe's ratio for [`match_features`].
+    pub fn ratio(mut self, ratio: f64) -> Self {
+        self.ratio = ratio;
+        self
+    }
+
+    /// Fewest RANSAC inliers accepted between neighboring images.
+    pub fn min_inliers(mut self, count: usize) -> Self {
+        self.min_inliers = count;
+        self
+    }
+
+    pub fn ransac(mut self, ransac: Ransac) -> Self {
+        self.ransac = ransac;
+        self
+    }
+
+    /// Pyramid levels used for blending; more levels blend low frequencies
+    /// over wider seams.
+    pub fn levels(mut self, levels: usize) -> Self {
+        self.levels = levels.max(1);
+        self
+    }
+
+    /// Homographies placing every image in the frame of the first one.
+    fn register<P>(&self, images: &[ImageBuffer<P>]) -> Result<Vec<Homography>, StitchError>
+    where
+        P: Pixel<Scalar = f32>,
+    {
+        let features: Vec<_> = images
+            .iter()
+            .map(|image| {
+                let gray = luminance(image);
+                let mut corners = fast_corners(
+                    gray.clone(),
+                    gray.width(),
+                    gray.height(),
+                    self.corner_threshold,
+                );
+                corners.truncate(self.max_features);
+                describe(&gray, corners.into_iter().map(|(place, _)| place))
+            })
+            .collect();
+
+        let mut placements = vec![Homography::identity()];
+        for image in 1..images.len() {
+            let pairs = match_features(&features[image], &features[image - 1], self.ratio);
+            let estimate = estimate_homography(&pairs, &self.ransac);
+            let inliers = estimate.as_ref().map_or(0, |e| e.inlier_count());
+            let estimate = estimate
+                .filter(|_| inliers >= self.min_inliers)
+                .ok_or(StitchError::NotEnoughMatches { image, inliers })?;
+
+            placements.push(estimate.transform.then(placements[image - 1]));
+        }
+        Ok(placements)
+    }
+
+    /// Aligns and blends `images` into one panorama in the frame of the first
+    /// image, enlarged to hold all of them. Pixels no image covers are zero.
+    pub fn stitch<P>(&self, images: &[ImageBuffer<P>]) -> Result<ImageBuffer<P>, StitchError>
+    where
+        P: Pixel<Scalar = f32>,
+    {
+        if images.is_empty() {
+            return Err(StitchError::NoImages);
+        }
+        let placements = self.register(images)?;
+
+        let sizes: Vec<_> = images.iter().map(|b| (b.width(), b.height())).collect();
+        let (left, top, width, height) = canvas(&placements, &sizes)?;
+        let shift = Homography::from(AffineTransform::translation(-left, -top));
+
+        // Where each canvas place comes from in every image, with the distance
+        // to that image's border, or `None` outside of it.
+        let sources: Vec<_> = placements
+            .iter()
+            .zip(images)
+            .enumerate()
+            .map(|(image, (placement, buffer))| {
+                let inverse = placement
+                    .then(shift)
+                    .inverse()
+                    .ok_or(StitchError::Degenerate { image })?;
+                let (w, h) = (buffer.width() as f64, buffer.height() as f64);
+                Ok(move |p: &Place| {
+                    let source = inverse.transform_place(p)?;
+                    let (x, y) = (source.x().to_f64()?, source.y().to_f64()?);
+                    let inside = (0.0..w).contains(&x) && (0.0..h).contains(&y);
+                    let margin = x.min(y).min(w - x).min(h - y);
+                    Some((source, inside.then_some(margin)))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let nearest_to_center = |p: &Place| {
+            sources
+                .iter()
+                .enumerate()
+                .filter_map(|(k, source)| Some((k, source(p)?.1?)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(k, _)| k)
+        };
+
+        let zero = P::from_channels(|_| 0.0);
+        let mut blended: Option<Vec<ImageBuffer<P>>> = None;
+        let mut weights: Option<Vec<ImageBuffer<Gray<f32>>>> = None;
+        for (k, (buffer, source)) in images.iter().zip(&sources).enumerate() {
+            let continuous = buffer.clone().continuize(Interpolation::Bilinear);
+            let warped =
+                from_fn(|p: Place| source(&p).map_or(zero, |(place, _)| continuous.get(place)));
+            let mask = from_fn(|p: Place| Gray((nearest_to_center(&p) == Some(k)) as u8 as f32));
+
+            let bands = LaplacianPyramid::new(
+                &warped,
+                width,
+                height,
+                self.levels,
+                DownsampleFilter::Binomial,
+            );
+            let mask = GaussianPyramid::new(
+                &mask,
+                width,
+                height,
+                self.levels,
+                DownsampleFilter::Binomial,
+            );
+
+            let weighted: Vec<_> = bands
+                .levels()
+                .iter()
+                .zip(mask.levels())
+                .map(|(band, mask)| {
+                    ImageBuffer::from_fn(
+                        band.width(),
+                        band.height(),
+                        Layout::Interleaved,
+                        |i, j| {
+                            let Gray(m) = mask.pixel(i, j).expect("levels have equal sizes");
+                            band.pixel(i, j)
+                                .expect("indices are in bounds")
+                                .map(|v| v * m)
+                        },
+                    )
+                })
+                .collect();
+
+            blended = Some(match blended {
+                None => weighted,
+                Some(sums) => sums.iter().zip(&weighted).map(|(a, b)| add(a, b)).collect(),
+            });
+            weights = Some(match weights {
+                None => mask.levels().to_vec(),
+                Some(sums) => sums
+                    .iter()
+                    .zip(mask.levels())
+                    .map(|(a, b)| add(a, b))
+                    .collect(),
+            });
+        }
+
+        let blended = blended.expect("there is at least one image");
+        let weights = weights.expect("there is at least one image");
+        let levels = blended
+            .iter()
+            .zip(&weights)
+            .map(|(band, weight)| {
+                ImageBuffer::from_fn(band.width(), band.height(), Layout::Interleaved, |i, j| {
+                    let Gray(w) = weight.pixel(i, j).expect("levels have equal sizes");
+                    let p = band.pixel(i, j).expect("indices are in bounds");
+                    if w > 0.0 { p.map(|v| v / w) } else { zero }
+                })
+            })
+            .collect();
+        let panorama = LaplacianPyramid::from_levels(levels).reconstruct();
+
+        Ok(ImageBuffer::from_fn(
+            width,
+            height,
+            Layout::Interleaved,
+            |i, j| {
+                let center =
+                    Place::new(i as f64 + 0.5, j as f64 + 0.5).expect("indices are finite");
+                match nearest_to_center(&center) {
+                    Some(_) => panorama.pixel(i, j).expect("indices are in bounds"),
+                    None => zero,
+                }
+            },
+        ))
+    }
+}
+
+fn add<P: Pixel<Scalar = f32>>(a: &ImageBuffer<P>, b: &ImageBuffer<P>) -> ImageBuffer<P> {
+    ImageBuffer::from_fn(a.width(), a.height(), Layout::Interleaved, |i, j| {
+        let (a, b) = (a.pixel(i, j), b.pixel(i, j));
+        a.expect("indices are in bounds")
+            .zip_map(b.expect("levels have equal sizes"), |a, b| a + b)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use flipr::{AffineTransform, Gray, Homography, Image, ImageBuffer, Layout, Noise, Rgb, noise};
+
+    use super::{StitchError, Stitcher, canvas, stitch};
+
+    fn scene() -> impl Image<Pixel = Gray<f32>> + Clone {
+        noise(Noise::Value { scale: 5.0 }, 3)
+    }
+
+    fn view(dx: f64, dy: f64) -> ImageBuffer<Gray<f32>> {
+        let moved = scene().transform(AffineTransform::translation(-dx, -dy));
+        ImageBuffer::sample(&moved, 48, 40, Layout::Interleaved)
+    }
+
+    #[test]
+    fn overlapping_views_rebuild_the_scene() {
+        let panorama = stitch(&[view(0.0, 0.0), view(20.0, 4.0)]).unwrap();
+        assert_eq!((panorama.width(), panorama.height()), (68, 44));
+
+        let truth = ImageBuffer::sample(&scene(), 68, 44, Layout::Interleaved);
+        let covered = |i: usize, j: usize| (i < 48 && j < 40) || (i >= 20 && j >= 4);
+        let errors: Vec<f32> = (0..44)
+            .flat_map(|j| (0..68).map(move |i| (i, j)))
+            .filter(|&(i, j)| covered(i, j))
+            .map(|(i, j)| (panorama.pixel(i, j).unwrap().0 - truth.pixel(i, j).unwrap().0).abs())
+            .collect();
+        let mean = errors.iter().sum::<f32>() / errors.len() as f32;
+        assert!(mean < 0.01, "mean error {mean}");
+        assert_eq!(panorama.pixel(60, 1), Some(Gray(0.0)));
+    }
+
+    #[test]
+    fn color_images_are_stitched_too() {
+        let color = |dx: f64| {
+            let gray = view(dx, 0.0);
+            ImageBuffer::from_fn(48, 40, Layout::Planar, |i, j| {
+                let Gray(v) = gray.pixel(i, j).unwrap();
+                Rgb::new(v, v * v, 0.5)
+            })
+        };
+        let panorama = Stitcher::new()
+            .levels(3)
+            .stitch(&[color(0.0), color(24.0)])
+            .unwrap();
+        assert_eq!((panorama.width(), panorama.height()), (72, 40));
+    }
+
+    #[test]
+    fn unrelated_images_are_rejected() {
+        let flat = ImageBuffer::filled(32, 32, Layout::Interleaved, Gray(0.5f32));
+        assert_eq!(
+            stitch(&[view(0.0, 0.0), flat]),
+            Err(StitchError::NotEnoughMatches {
+                image: 1,
+                inliers: 0
+            })
+        );
+        assert_eq!(stitch::<Gray<f32>>(&[]), Err(StitchError::NoImages));
+    }
+
+    #[test]
+    fn huge_canvases_are_rejected() {
+        let shifted = Homography::from(AffineTransform::translation(-5.0, 8.0));
+        let sizes = [(10, 10), (10, 10)];
+        assert_eq!(
+            canvas(&[Homography::identity(), shifted], &sizes),
+            Ok((-5.0, 0.0, 15, 18))
+        );
+
+        // Finite, but sends the right edge ten thousand pixels away.
+        let vanishing = Homography::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [-0.0999, 0.0, 1.0]]);
+        assert_eq!(
+            canvas(&[Homography::identity(), vanishing], &sizes),
+            Err(StitchError::Degenerate { image: 1 })
+        );
+    }
+}
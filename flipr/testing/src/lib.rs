@@ -0,0 +1,201 @@
+//! Helpers for testing code built on flipr: exact and tolerant image
+//! assertions, similarity metrics and PNG golden-file snapshots.
+
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+
+pub use flipr::analysis::{mse, psnr, ssim};
+use flipr::{Channel, ImageBuffer, Pixel};
+pub use flipr_io::{PngPixel, read_png, write_png};
+
+/// Environment variable that makes [`assert_snapshot`] write golden files
+/// instead of comparing with them.
+pub const UPDATE_SNAPSHOTS: &str = "FLIPR_UPDATE_SNAPSHOTS";
+
+fn coords(width: usize, height: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..height).flat_map(move |j| (0..width).map(move |i| (i, j)))
+}
+
+fn assert_same_size<P: Pixel>(actual: &ImageBuffer<P>, expected: &ImageBuffer<P>) {
+    assert!(
+        actual.width() == expected.width() && actual.height() == expected.height(),
+        "image sizes differ: actual {}×{}, expected {}×{}",
+        actual.width(),
+        actual.height(),
+        expected.width(),
+        expected.height()
+    );
+}
+
+/// Panics with the first differing pixel unless both images are identical.
+#[track_caller]
+pub fn assert_images_equal<P>(actual: &ImageBuffer<P>, expected: &ImageBuffer<P>)
+where
+    P: Pixel + PartialEq + Debug,
+{
+    assert_same_size(actual, expected);
+
+    if let Some((i, j)) = coords(actual.width(), actual.height())
+        .find(|&(i, j)| actual.pixel(i, j) != expected.pixel(i, j))
+    {
+        panic!(
+            "images differ at ({i}, {j}): actual {:?}, expected {:?}",
+            actual.pixel(i, j).expect("in bounds"),
+            expected.pixel(i, j).expect("in bounds")
+        );
+    }
+}
+
+/// Panics with the worst pixel unless every channel of `actual` is within
+/// `tolerance` of `expected`, in channel units.
+#[track_caller]
+pub fn assert_images_close<P>(actual: &ImageBuffer<P>, expected: &ImageBuffer<P>, tolerance: f64)
+where
+    P: Pixel + Debug,
+    P::Scalar: Channel,
+{
+    assert_same_size(actual, expected);
+
+    let error = |i: usize, j: usize| {
+        let (a, e) = (
+            actual.pixel(i, j).expect("in bounds"),
+            expected.pixel(i, j).expect("in bounds"),
+        );
+        (0..P::CHANNELS)
+            .map(|c| (a.channel(c).to_f64() - e.channel(c).to_f64()).abs())
+            .fold(0.0, f64::max)
+    };
+    let worst = coords(actual.width(), actual.height())
+        .max_by(|&(ai, aj), &(bi, bj)| error(ai, aj).total_cmp(&error(bi, bj)));
+
+    if let Some((i, j)) = worst.filter(|&(i, j)| error(i, j) > tolerance) {
+        panic!(
+            "images differ by {} at ({i}, {j}), more than {tolerance}: actual {:?}, expected {:?}",
+            error(i, j),
+            actual.pixel(i, j).expect("in bounds"),
+            expected.pixel(i, j).expect("in bounds")
+        );
+    }
+}
+
+fn actual_path(golden: &Path) -> PathBuf {
+    golden.with_extension("actual.png")
+}
+
+/// Compares `image` with the PNG golden file at `path`.
+///
+/// When the [`UPDATE_SNAPSHOTS`] environment variable is set, the golden file
+/// is written from `image` instead, creating it if needed. Otherwise a missing
+/// golden file or a mismatch fails the assertion, and the actual image is
+/// saved next to the golden one as `*.actual.png` for inspection.
+#[track_caller]
+pub fn assert_snapshot<P: PngPixel + PartialEq + Debug>(
+    path: impl AsRef<Path>,
+    image: &ImageBuffer<P>,
+) {
+    let path = path.as_ref();
+
+    if std::env::var_os(UPDATE_SNAPSHOTS).is_some() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("snapshot directory can be created");
+        }
+        write_png(path, image).expect("golden file can be written");
+        return;
+    }
+
+    let problem = if path.exists() {
+        let golden: ImageBuffer<P> = read_png(path).expect("golden file can be read");
+        let matches = golden.width() == image.width()
+            && golden.height() == image.height()
+            && coords(image.width(), image.height())
+                .all(|(i, j)| golden.pixel(i, j) == image.pixel(i, j));
+        (!matches).then_some("does not match")
+    } else {
+        Some("has no")
+    };
+
+    if let Some(problem) = problem {
+        let actual = actual_path(path);
+        if let Some(parent) = actual.parent() {
+            std::fs::create_dir_all(parent).expect("snapshot directory can be created");
+        }
+        write_png(&actual, image).expect("actual image can be written");
+        panic!(
+            "image {problem} snapshot {}, see {}; set {UPDATE_SNAPSHOTS} to accept it",
+            path.display(),
+            actual.display()
+        );
+    }
+
+    // Stale output from an earlier failure.
+    let _ = std::fs::remove_file(actual_path(path));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::catch_unwind;
+
+    use flipr::{Gray, ImageBuffer, Layout, Rgb};
+
+    use super::{
+        assert_images_close, assert_images_equal, assert_snapshot, mse, psnr, read_png, ssim,
+        write_png,
+    };
+
+    fn ramp(offset: u8) -> ImageBuffer<Gray<u8>> {
+        ImageBuffer::from_fn(8, 8, Layout::Interleaved, |i, j| {
+            Gray((i * 16 + j * 8) as u8 + offset)
+        })
+    }
+
+    #[test]
+    fn equal_images_pass_and_different_ones_fail() {
+        assert_images_equal(&ramp(0), &ramp(0).into_layout(Layout::Planar));
+        assert_images_close(&ramp(0), &ramp(2), 2.0);
+        assert!(catch_unwind(|| assert_images_equal(&ramp(0), &ramp(1))).is_err());
+        assert!(catch_unwind(|| assert_images_close(&ramp(0), &ramp(3), 2.0)).is_err());
+    }
+
+    #[test]
+    fn metrics_follow_their_definitions() {
+        assert_eq!(mse(&ramp(0), &ramp(2)), 4.0);
+        assert!(
+            (psnr(&ramp(0), &ramp(2), 255.0) - 10.0 * (255.0f64 * 255.0 / 4.0).log10()).abs()
+                < 1e-9
+        );
+        assert_eq!(psnr(&ramp(0), &ramp(0), 255.0), f64::INFINITY);
+        assert!((ssim(&ramp(0), &ramp(0), 255.0) - 1.0).abs() < 1e-12);
+        let flat = ImageBuffer::filled(8, 8, Layout::Interleaved, Gray(100u8));
+        assert!(ssim(&ramp(0), &flat, 255.0) < ssim(&ramp(0), &ramp(5), 255.0));
+    }
+
+    #[test]
+    fn snapshots_are_compared_with_existing_golden_files() {
+        let dir = std::env::temp_dir().join(format!("flipr-testing-{}", std::process::id()));
+        let golden = dir.join("ramp.png");
+        let image = ImageBuffer::from_fn(5, 3, Layout::Planar, |i, j| {
+            Rgb::new(i as u8 * 50, j as u8 * 100, 7)
+        });
+
+        assert!(catch_unwind(|| assert_snapshot(&golden, &image)).is_err());
+        assert!(!golden.exists());
+        assert_eq!(
+            read_png::<Rgb<u8>>(dir.join("ramp.actual.png")).unwrap(),
+            image
+        );
+
+        write_png(&golden, &image).unwrap();
+        assert_snapshot(&golden, &image);
+        assert!(!dir.join("ramp.actual.png").exists());
+
+        let other = ImageBuffer::filled(5, 3, Layout::Interleaved, Rgb::new(0, 0, 0));
+        assert!(catch_unwind(|| assert_snapshot(&golden, &other)).is_err());
+        assert_eq!(
+            read_png::<Rgb<u8>>(dir.join("ramp.actual.png")).unwrap(),
+            other
+        );
+        assert!(read_png::<Gray<u8>>(&golden).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
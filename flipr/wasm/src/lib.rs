@@ -0,0 +1,122 @@
+//! WebAssembly bindings for browser image editors: applies operations of the
+//! `flipr-cli` `--ops` language to the RGBA bytes of a canvas `ImageData`.
+//!
+//! ```js
+//! const result = process(image.data, image.width, image.height, "blur=2,rotate=30");
+//! context.putImageData(new ImageData(result.data, result.width, result.height), 0, 0);
+//! ```
+
+use flipr::{ChannelOrder, Dither, Image, ImageBuffer, Layout, Rgba};
+use flipr_cli::parse_ops;
+use wasm_bindgen::Clamped;
+use wasm_bindgen::prelude::{JsError, wasm_bindgen};
+
+/// Straight-alpha RGBA pixels in the row-major layout of `ImageData`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Processed {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Processed {
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// A copy of the pixels, ready for `new ImageData(data, width, height)`.
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Clamped<Vec<u8>> {
+        Clamped(self.data.clone())
+    }
+}
+
+/// Applies `ops`, a pipeline script like `"resize=400x300,blur=2.0"`, to the
+/// `width × height` RGBA bytes of an `ImageData`.
+#[wasm_bindgen]
+pub fn process(data: &[u8], width: usize, height: usize, ops: &str) -> Result<Processed, JsError> {
+    apply(data, width, height, ops).map_err(|e| JsError::new(&e))
+}
+
+/// [`process`] with plain errors, so it also runs outside a JavaScript host.
+pub fn apply(data: &[u8], width: usize, height: usize, ops: &str) -> Result<Processed, String> {
+    if width == 0 || height == 0 {
+        return Err("ImageData must have at least one pixel".into());
+    }
+    let ops = parse_ops(ops)?;
+    let stride = width
+        .checked_mul(4)
+        .ok_or_else(|| "ImageData is too wide".to_string())?;
+    let source: ImageBuffer<Rgba<u8>> = ImageBuffer::from_raw(
+        data,
+        width,
+        height,
+        stride,
+        ChannelOrder::Rgba,
+        Layout::Interleaved,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let float = ImageBuffer::sample(&source.to_float(), width, height, Layout::Interleaved);
+    let result = ops.iter().fold(float, |image, op| op.apply(image));
+    let (width, height) = (result.width(), result.height());
+    let quantized = ImageBuffer::sample(
+        &result.to_u8(Dither::None),
+        width,
+        height,
+        Layout::Interleaved,
+    );
+
+    Ok(Processed {
+        width,
+        height,
+        data: quantized
+            .as_bytes(ChannelOrder::Rgba)
+            .map_err(|e| e.to_string())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply;
+
+    fn gradient(width: usize, height: usize) -> Vec<u8> {
+        (0..width * height)
+            .flat_map(|k| [(k % 256) as u8, 128, (255 - k % 256) as u8, 255])
+            .collect()
+    }
+
+    #[test]
+    fn image_data_round_trips_without_ops() {
+        let data = gradient(5, 3);
+        let result = apply(&data, 5, 3, "").unwrap();
+        assert_eq!((result.width, result.height), (5, 3));
+        assert_eq!(result.data, data);
+    }
+
+    #[test]
+    fn ops_can_change_the_size() {
+        let result = apply(&gradient(8, 4), 8, 4, "blur=1.0, resize=4x2").unwrap();
+        assert_eq!((result.width, result.height), (4, 2));
+        assert_eq!(result.data.len(), 4 * 2 * 4);
+    }
+
+    #[test]
+    fn bad_input_is_reported() {
+        assert!(
+            apply(&gradient(4, 4), 4, 4, "shear=3")
+                .unwrap_err()
+                .contains("unknown")
+        );
+        assert!(apply(&gradient(4, 3), 4, 4, "").is_err());
+        assert!(apply(&[], 0, 4, "").is_err());
+    }
+}